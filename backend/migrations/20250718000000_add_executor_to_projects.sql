@@ -0,0 +1,7 @@
+PRAGMA foreign_keys = ON;
+
+-- Per-project default executor (overrides the global config executor)
+ALTER TABLE projects ADD COLUMN executor TEXT;
+
+-- Extra CLI arguments appended to the coding agent command for this project
+ALTER TABLE projects ADD COLUMN executor_args TEXT;
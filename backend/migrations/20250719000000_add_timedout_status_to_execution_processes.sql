@@ -0,0 +1,52 @@
+PRAGMA foreign_keys = ON;
+
+-- Allow the 'timedout' status and track when a process last wrote to stdout,
+-- so the execution monitor can enforce runtime and idle timeouts.
+
+-- SQLite can't alter CHECK constraints, so we need to recreate the table
+CREATE TABLE execution_processes_new (
+    id                BLOB PRIMARY KEY,
+    task_attempt_id   BLOB NOT NULL,
+    process_type      TEXT NOT NULL DEFAULT 'setupscript'
+                         CHECK (process_type IN ('setupscript','codingagent','devserver')),
+    executor_type     TEXT,
+    status            TEXT NOT NULL DEFAULT 'running'
+                         CHECK (status IN ('running','completed','failed','killed','timedout')),
+    command           TEXT NOT NULL,
+    args              TEXT,  -- JSON array of arguments
+    working_directory TEXT NOT NULL,
+    stdout            TEXT,
+    stderr            TEXT,
+    exit_code         INTEGER,
+    started_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    completed_at      TEXT,
+    last_stdout_at    TEXT,
+    created_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
+
+INSERT INTO execution_processes_new (
+    id, task_attempt_id, process_type, executor_type, status, command, args,
+    working_directory, stdout, stderr, exit_code, started_at, completed_at,
+    created_at, updated_at
+)
+SELECT id, task_attempt_id, process_type, executor_type, status, command, args,
+       working_directory, stdout, stderr, exit_code, started_at, completed_at,
+       created_at, updated_at
+FROM execution_processes;
+
+-- Dropping the old table cascades into executor_sessions, so keep a copy aside
+CREATE TEMP TABLE executor_sessions_backup AS SELECT * FROM executor_sessions;
+
+DROP TABLE execution_processes;
+
+ALTER TABLE execution_processes_new RENAME TO execution_processes;
+
+INSERT INTO executor_sessions SELECT * FROM executor_sessions_backup;
+
+DROP TABLE executor_sessions_backup;
+
+CREATE INDEX idx_execution_processes_task_attempt_id ON execution_processes(task_attempt_id);
+CREATE INDEX idx_execution_processes_status ON execution_processes(status);
+CREATE INDEX idx_execution_processes_type ON execution_processes(process_type);
@@ -0,0 +1,15 @@
+PRAGMA foreign_keys = ON;
+
+CREATE TABLE execution_usage (
+    id                          BLOB PRIMARY KEY,
+    execution_process_id        BLOB NOT NULL UNIQUE,
+    model                       TEXT,
+    input_tokens                INTEGER NOT NULL DEFAULT 0,
+    output_tokens               INTEGER NOT NULL DEFAULT 0,
+    cache_creation_input_tokens INTEGER NOT NULL DEFAULT 0,
+    cache_read_input_tokens     INTEGER NOT NULL DEFAULT 0,
+    cost_usd                    REAL,  -- Only set when the executor reports cost
+    created_at                  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at                  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
@@ -0,0 +1,30 @@
+PRAGMA foreign_keys = ON;
+
+-- Tool permission requests raised by a coding agent, awaiting a human decision
+CREATE TABLE tool_approvals (
+    id                   BLOB PRIMARY KEY,
+    execution_process_id BLOB NOT NULL,
+    tool_name            TEXT NOT NULL,
+    tool_input           TEXT NOT NULL,  -- JSON object passed to the tool
+    status               TEXT NOT NULL DEFAULT 'pending'
+                            CHECK (status IN ('pending','approved','denied','timedout')),
+    reason               TEXT,           -- Why the request was approved or denied
+    decided_at           TEXT,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_tool_approvals_execution_process_id ON tool_approvals(execution_process_id);
+
+-- Commands and paths that are approved automatically for a project
+CREATE TABLE project_tool_allowlist (
+    id         BLOB PRIMARY KEY,
+    project_id BLOB NOT NULL,
+    kind       TEXT NOT NULL CHECK (kind IN ('command','path')),
+    pattern    TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_project_tool_allowlist_project_id ON project_tool_allowlist(project_id);
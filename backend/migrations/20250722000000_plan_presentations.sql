@@ -0,0 +1,17 @@
+PRAGMA foreign_keys = ON;
+
+-- Versions of the plan presented by a plan mode attempt. The agent's plan is version 1;
+-- edits before approval add new versions.
+CREATE TABLE plan_presentations (
+    id                   BLOB PRIMARY KEY,
+    task_attempt_id      BLOB NOT NULL,
+    execution_process_id BLOB,            -- Process that presented the plan, NULL for edits
+    version              INTEGER NOT NULL,
+    plan                 TEXT NOT NULL,
+    author               TEXT NOT NULL CHECK (author IN ('agent','user')),
+    approved_at          TEXT,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE SET NULL,
+    UNIQUE (task_attempt_id, version)
+);
@@ -0,0 +1,15 @@
+PRAGMA foreign_keys = ON;
+
+-- Environment variables injected into the processes spawned for a project.
+-- Secret values are never returned by the API and are redacted from stored output.
+CREATE TABLE project_env_vars (
+    id         BLOB PRIMARY KEY,
+    project_id BLOB NOT NULL,
+    key        TEXT NOT NULL,
+    value      TEXT NOT NULL,
+    secret     BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    UNIQUE (project_id, key)
+);
@@ -0,0 +1,10 @@
+PRAGMA foreign_keys = ON;
+
+-- Confine coding agents and setup scripts to their worktree (Linux only)
+ALTER TABLE projects ADD COLUMN sandbox BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Whether sandboxed processes may use the network
+ALTER TABLE projects ADD COLUMN sandbox_network BOOLEAN NOT NULL DEFAULT TRUE;
+
+-- JSON sandbox policy a process ran under, NULL when it wasn't sandboxed
+ALTER TABLE execution_processes ADD COLUMN sandbox_policy TEXT;
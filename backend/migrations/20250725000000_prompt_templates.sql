@@ -0,0 +1,7 @@
+PRAGMA foreign_keys = ON;
+
+-- Template for the prompt coding agents start tasks with, NULL uses the global default
+ALTER TABLE projects ADD COLUMN prompt_template TEXT;
+
+-- House rules for coding agents, available to prompt templates as {{project_instructions}}
+ALTER TABLE projects ADD COLUMN instructions TEXT;
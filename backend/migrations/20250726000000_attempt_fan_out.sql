@@ -0,0 +1,75 @@
+PRAGMA foreign_keys = ON;
+
+-- Command that checks an attempt's changes (e.g. `cargo test`), run after each coding agent
+ALTER TABLE projects ADD COLUMN test_script TEXT;
+
+-- Attempts started together to race on a task share a fan-out id
+ALTER TABLE task_attempts ADD COLUMN fan_out_id BLOB;
+
+CREATE INDEX idx_task_attempts_fan_out_id ON task_attempts(fan_out_id);
+
+-- Allow the 'testscript' process type.
+-- SQLite can't alter CHECK constraints, so we need to recreate the table
+CREATE TABLE execution_processes_new (
+    id                BLOB PRIMARY KEY,
+    task_attempt_id   BLOB NOT NULL,
+    process_type      TEXT NOT NULL DEFAULT 'setupscript'
+                         CHECK (process_type IN ('setupscript','codingagent','devserver','testscript')),
+    executor_type     TEXT,
+    status            TEXT NOT NULL DEFAULT 'running'
+                         CHECK (status IN ('running','completed','failed','killed','timedout')),
+    command           TEXT NOT NULL,
+    args              TEXT,  -- JSON array of arguments
+    working_directory TEXT NOT NULL,
+    stdout            TEXT,
+    stderr            TEXT,
+    exit_code         INTEGER,
+    started_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    completed_at      TEXT,
+    last_stdout_at    TEXT,
+    sandbox_policy    TEXT,  -- JSON sandbox policy, NULL when not sandboxed
+    created_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
+
+INSERT INTO execution_processes_new (
+    id, task_attempt_id, process_type, executor_type, status, command, args,
+    working_directory, stdout, stderr, exit_code, started_at, completed_at,
+    last_stdout_at, sandbox_policy, created_at, updated_at
+)
+SELECT id, task_attempt_id, process_type, executor_type, status, command, args,
+       working_directory, stdout, stderr, exit_code, started_at, completed_at,
+       last_stdout_at, sandbox_policy, created_at, updated_at
+FROM execution_processes;
+
+-- Dropping the old table cascades into the tables referencing it, so keep copies aside
+CREATE TEMP TABLE executor_sessions_backup AS SELECT * FROM executor_sessions;
+CREATE TEMP TABLE execution_usage_backup AS SELECT * FROM execution_usage;
+CREATE TEMP TABLE tool_approvals_backup AS SELECT * FROM tool_approvals;
+CREATE TEMP TABLE plan_presentations_backup AS SELECT * FROM plan_presentations;
+
+DROP TABLE execution_processes;
+
+ALTER TABLE execution_processes_new RENAME TO execution_processes;
+
+INSERT INTO executor_sessions SELECT * FROM executor_sessions_backup;
+INSERT INTO execution_usage SELECT * FROM execution_usage_backup;
+INSERT INTO tool_approvals SELECT * FROM tool_approvals_backup;
+
+-- Plan presentations survive the drop with their process cleared, so restore the link
+UPDATE plan_presentations
+SET execution_process_id = (
+    SELECT backup.execution_process_id
+    FROM plan_presentations_backup backup
+    WHERE backup.id = plan_presentations.id
+);
+
+DROP TABLE executor_sessions_backup;
+DROP TABLE execution_usage_backup;
+DROP TABLE tool_approvals_backup;
+DROP TABLE plan_presentations_backup;
+
+CREATE INDEX idx_execution_processes_task_attempt_id ON execution_processes(task_attempt_id);
+CREATE INDEX idx_execution_processes_status ON execution_processes(status);
+CREATE INDEX idx_execution_processes_type ON execution_processes(process_type);
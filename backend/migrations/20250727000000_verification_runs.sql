@@ -0,0 +1,20 @@
+PRAGMA foreign_keys = ON;
+
+-- Follow-ups sent to the coding agent with the output of a failing test script, 0 is off
+ALTER TABLE projects ADD COLUMN max_fix_iterations INTEGER NOT NULL DEFAULT 0;
+
+-- Test script runs of the verify-and-fix loop. The run after an agent started by the user is
+-- iteration 1; each run after a fix the loop requested counts up from there.
+CREATE TABLE verification_runs (
+    id                   BLOB PRIMARY KEY,
+    task_attempt_id      BLOB NOT NULL,
+    execution_process_id BLOB NOT NULL UNIQUE,  -- The test script run
+    iteration            INTEGER NOT NULL,
+    passed               BOOLEAN NOT NULL,
+    fix_requested        BOOLEAN NOT NULL,      -- Whether the output was sent back as a follow-up
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_verification_runs_task_attempt_id ON verification_runs(task_attempt_id);
@@ -0,0 +1,6 @@
+PRAGMA foreign_keys = ON;
+
+-- OS process and process group of a spawned execution, so a restarted server can find
+-- processes that kept running without it
+ALTER TABLE execution_processes ADD COLUMN pid INTEGER;
+ALTER TABLE execution_processes ADD COLUMN pgid INTEGER;
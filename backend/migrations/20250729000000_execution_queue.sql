@@ -0,0 +1,14 @@
+PRAGMA foreign_keys = ON;
+
+-- Most of a project's attempts running a setup script or coding agent at once, 0 for no limit
+ALTER TABLE projects ADD COLUMN max_concurrent_agents INTEGER NOT NULL DEFAULT 0;
+
+-- Attempts waiting for a free slot to start; higher priority first, then oldest first
+CREATE TABLE execution_queue (
+    task_attempt_id BLOB PRIMARY KEY,
+    priority        INTEGER NOT NULL DEFAULT 0,
+    queued_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_execution_queue_order ON execution_queue(priority DESC, queued_at ASC);
@@ -0,0 +1,32 @@
+PRAGMA foreign_keys = ON;
+
+-- Store process output as append-only chunks instead of TEXT columns that are rewritten on
+-- every line. A chunk starts at `byte_offset` within its stream, so any range of the output
+-- can be read without loading the rest.
+
+CREATE TABLE execution_output_chunks (
+    id                   INTEGER PRIMARY KEY AUTOINCREMENT,
+    execution_process_id BLOB NOT NULL,
+    stream               TEXT NOT NULL CHECK (stream IN ('stdout','stderr')),
+    byte_offset          INTEGER NOT NULL,
+    content              TEXT NOT NULL,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX idx_execution_output_chunks_range
+    ON execution_output_chunks(execution_process_id, stream, byte_offset);
+
+-- Existing output becomes a single chunk per stream
+INSERT INTO execution_output_chunks (execution_process_id, stream, byte_offset, content, created_at)
+SELECT id, 'stdout', 0, stdout, COALESCE(last_stdout_at, updated_at)
+FROM execution_processes
+WHERE stdout IS NOT NULL AND stdout != '';
+
+INSERT INTO execution_output_chunks (execution_process_id, stream, byte_offset, content, created_at)
+SELECT id, 'stderr', 0, stderr, updated_at
+FROM execution_processes
+WHERE stderr IS NOT NULL AND stderr != '';
+
+ALTER TABLE execution_processes DROP COLUMN stdout;
+ALTER TABLE execution_processes DROP COLUMN stderr;
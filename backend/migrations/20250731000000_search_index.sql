@@ -0,0 +1,65 @@
+PRAGMA foreign_keys = ON;
+
+-- Full-text index over tasks, executor session prompts and summaries, and the normalized
+-- conversations of coding agents. `source_id` is the task's id for `task` rows and the
+-- execution process's id for all others. Only tasks have a title, which ranks above bodies.
+CREATE VIRTUAL TABLE search_index USING fts5(
+    kind UNINDEXED,
+    source_id UNINDEXED,
+    title,
+    body,
+    tokenize = 'porter unicode61'
+);
+
+CREATE TRIGGER search_index_task_insert AFTER INSERT ON tasks BEGIN
+    INSERT INTO search_index (kind, source_id, title, body)
+    VALUES ('task', new.id, new.title, new.description);
+END;
+
+CREATE TRIGGER search_index_task_update AFTER UPDATE OF title, description ON tasks BEGIN
+    DELETE FROM search_index WHERE kind = 'task' AND source_id = old.id;
+    INSERT INTO search_index (kind, source_id, title, body)
+    VALUES ('task', new.id, new.title, new.description);
+END;
+
+CREATE TRIGGER search_index_task_delete AFTER DELETE ON tasks BEGIN
+    DELETE FROM search_index WHERE kind = 'task' AND source_id = old.id;
+END;
+
+CREATE TRIGGER search_index_session_insert AFTER INSERT ON executor_sessions BEGIN
+    INSERT INTO search_index (kind, source_id, body)
+    SELECT 'prompt', new.execution_process_id, new.prompt WHERE new.prompt IS NOT NULL;
+    INSERT INTO search_index (kind, source_id, body)
+    SELECT 'summary', new.execution_process_id, new.summary WHERE new.summary IS NOT NULL;
+END;
+
+CREATE TRIGGER search_index_session_update AFTER UPDATE OF prompt, summary ON executor_sessions BEGIN
+    DELETE FROM search_index
+    WHERE kind IN ('prompt', 'summary') AND source_id = old.execution_process_id;
+    INSERT INTO search_index (kind, source_id, body)
+    SELECT 'prompt', new.execution_process_id, new.prompt WHERE new.prompt IS NOT NULL;
+    INSERT INTO search_index (kind, source_id, body)
+    SELECT 'summary', new.execution_process_id, new.summary WHERE new.summary IS NOT NULL;
+END;
+
+CREATE TRIGGER search_index_session_delete AFTER DELETE ON executor_sessions BEGIN
+    DELETE FROM search_index
+    WHERE kind IN ('prompt', 'summary') AND source_id = old.execution_process_id;
+END;
+
+-- Conversations are normalized and indexed by the server once their process has finished
+ALTER TABLE execution_processes ADD COLUMN conversation_indexed_at TEXT;
+
+CREATE TRIGGER search_index_conversation_delete AFTER DELETE ON execution_processes BEGIN
+    DELETE FROM search_index WHERE kind = 'conversation' AND source_id = old.id;
+END;
+
+-- Index what already exists
+INSERT INTO search_index (kind, source_id, title, body)
+SELECT 'task', id, title, description FROM tasks;
+
+INSERT INTO search_index (kind, source_id, body)
+SELECT 'prompt', execution_process_id, prompt FROM executor_sessions WHERE prompt IS NOT NULL;
+
+INSERT INTO search_index (kind, source_id, body)
+SELECT 'summary', execution_process_id, summary FROM executor_sessions WHERE summary IS NOT NULL;
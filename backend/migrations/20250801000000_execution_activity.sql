@@ -0,0 +1,4 @@
+PRAGMA foreign_keys = ON;
+
+-- Idle timeouts count output on either stream and input sent to the process, not just stdout
+ALTER TABLE execution_processes RENAME COLUMN last_stdout_at TO last_activity_at;
@@ -0,0 +1,6 @@
+PRAGMA foreign_keys = ON;
+
+-- Start time (clock ticks since boot) and boot id of an execution's process group leader, so a
+-- restarted server only reattaches to or stops the group if its pid wasn't reused since
+ALTER TABLE execution_processes ADD COLUMN process_start_time INTEGER;
+ALTER TABLE execution_processes ADD COLUMN boot_id TEXT;
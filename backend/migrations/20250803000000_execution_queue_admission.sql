@@ -0,0 +1,14 @@
+PRAGMA foreign_keys = ON;
+
+-- Attempts stay in the queue once started: an admitted attempt holds a slot until none of its
+-- setup script, coding agent or test script runs any more, gaps between them included
+ALTER TABLE execution_queue ADD COLUMN admitted_at TEXT;
+
+-- Prompt of a queued follow-up, NULL for an attempt's first run
+ALTER TABLE execution_queue ADD COLUMN follow_up_prompt TEXT;
+
+-- Attempts already running hold their slots as well
+INSERT OR IGNORE INTO execution_queue (task_attempt_id, admitted_at)
+SELECT DISTINCT task_attempt_id, datetime('now', 'subsec')
+FROM execution_processes
+WHERE status = 'running' AND process_type IN ('setupscript', 'codingagent', 'testscript');
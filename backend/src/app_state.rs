@@ -5,20 +5,38 @@ use nix::{sys::signal::Signal, unistd::Pid};
 use tokio::sync::{Mutex, RwLock as TokioRwLock};
 use uuid::Uuid;
 
-use crate::services::{generate_user_id, AnalyticsConfig, AnalyticsService};
+#[cfg(unix)]
+use crate::utils::process_group::ProcessIdentity;
+use crate::{
+    models::task::{Task, TaskStatus},
+    services::{generate_user_id, AnalyticsConfig, AnalyticsService, BoardEvent, EventBus},
+};
 
 #[derive(Debug)]
 pub enum ExecutionType {
     SetupScript,
     CodingAgent,
     DevServer,
+    TestScript,
 }
 
 #[derive(Debug)]
 pub struct RunningExecution {
     pub task_attempt_id: Uuid,
     pub _execution_type: ExecutionType,
-    pub child: command_group::AsyncGroupChild,
+    pub child: ExecutionChild,
+}
+
+#[derive(Debug)]
+pub enum ExecutionChild {
+    Spawned(command_group::AsyncGroupChild),
+    /// Kept running from before a server restart; it isn't our child, so only its process
+    /// group is known and its exit status is lost
+    #[cfg(unix)]
+    Reattached {
+        pgid: i32,
+        identity: ProcessIdentity,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -27,6 +45,8 @@ pub struct AppState {
     pub db_pool: sqlx::SqlitePool,
     config: Arc<tokio::sync::RwLock<crate::models::config::Config>>,
     pub analytics: Arc<TokioRwLock<AnalyticsService>>,
+    /// Board changes, for clients watching projects live
+    pub events: EventBus,
     user_id: String,
 }
 
@@ -49,6 +69,7 @@ impl AppState {
             db_pool,
             config,
             analytics,
+            events: EventBus::new(),
             user_id: generate_user_id(),
         }
     }
@@ -71,6 +92,19 @@ impl AppState {
         }
     }
 
+    /// Move a task to another status and tell the board
+    pub async fn set_task_status(
+        &self,
+        task_id: Uuid,
+        project_id: Uuid,
+        status: TaskStatus,
+    ) -> Result<(), sqlx::Error> {
+        Task::update_status(&self.db_pool, task_id, project_id, status.clone()).await?;
+        self.events
+            .publish(project_id, BoardEvent::TaskUpdated { task_id, status });
+        Ok(())
+    }
+
     // Running executions getters
     pub async fn has_running_execution(&self, attempt_id: Uuid) -> bool {
         let executions = self.running_executions.lock().await;
@@ -84,7 +118,24 @@ impl AppState {
         let mut completed_executions = Vec::new();
 
         for (execution_id, running_exec) in executions.iter_mut() {
-            match running_exec.child.try_wait() {
+            let child = match &mut running_exec.child {
+                ExecutionChild::Spawned(child) => child,
+                #[cfg(unix)]
+                ExecutionChild::Reattached { pgid, identity } => {
+                    // A reattached process leaves no exit status, so there's no telling whether
+                    // it succeeded. It is marked failed, without an exit code.
+                    if !crate::utils::process_group::is_running_as(*pgid, identity) {
+                        completed_executions.push((
+                            *execution_id,
+                            running_exec.task_attempt_id,
+                            false,
+                            None,
+                        ));
+                    }
+                    continue;
+                }
+            };
+            match child.try_wait() {
                 Ok(Some(status)) => {
                     let success = status.success();
                     let exit_code = status.code().map(|c| c as i64);
@@ -133,24 +184,37 @@ impl AppState {
             return Ok(false);
         };
 
+        let child = match &mut exec.child {
+            ExecutionChild::Spawned(child) => child,
+            #[cfg(unix)]
+            ExecutionChild::Reattached { pgid, identity } => {
+                // Once the group is gone its id may be someone else's
+                if crate::utils::process_group::is_running_as(*pgid, identity) {
+                    crate::utils::process_group::terminate(*pgid).await?;
+                }
+                executions.remove(&execution_id);
+                return Ok(true);
+            }
+        };
+
         // hit the whole process group, not just the leader
         #[cfg(unix)]
         {
             use nix::{sys::signal::killpg, unistd::getpgid};
 
-            let pgid = getpgid(Some(Pid::from_raw(exec.child.id().unwrap() as i32)))?;
+            let pgid = getpgid(Some(Pid::from_raw(child.id().unwrap() as i32)))?;
             for sig in [Signal::SIGINT, Signal::SIGTERM, Signal::SIGKILL] {
                 killpg(pgid, sig)?;
                 tokio::time::sleep(Duration::from_secs(2)).await;
-                if exec.child.try_wait()?.is_some() {
+                if child.try_wait()?.is_some() {
                     break; // gone!
                 }
             }
         }
 
         // final fallback – command_group already targets the group
-        exec.child.kill().await.ok();
-        exec.child.wait().await.ok(); // reap
+        child.kill().await.ok();
+        child.wait().await.ok(); // reap
 
         // only NOW remove it
         executions.remove(&execution_id);
@@ -3,20 +3,74 @@ use std::{env, fs, path::Path};
 use ts_rs::TS;
 // in [build-dependencies]
 
-fn generate_constants() -> String {
-    r#"// Generated constants
-export const EXECUTOR_TYPES: string[] = [
-    "echo",
-    "claude",
-    "claude-plan",
-    "amp",
-    "gemini",
-    "charm-opencode",
-    "claude-code-router",
-    "sst-opencode"
+/// Executor constants, generated from the capability registry
+fn generate_executor_constants() -> String {
+    let mut executors = vibe_kanban::executor::ExecutorConstants::new(
+        &vibe_kanban::models::config::Config::default(),
+    )
+    .executors;
+    // Config file locations depend on the machine, so they're only served by the API
+    for capabilities in &mut executors {
+        if let Some(mcp) = &mut capabilities.mcp {
+            mcp.config_path = None;
+        }
+    }
+
+    let quoted_list = |types: Vec<&str>| {
+        types
+            .iter()
+            .map(|t| format!("    \"{t}\""))
+            .collect::<Vec<_>>()
+            .join(",\n")
+    };
+    let (plan_types, executor_types): (Vec<_>, Vec<_>) = executors
+        .iter()
+        .map(|c| c.executor_type.as_str())
+        .partition(|t| t.starts_with(vibe_kanban::executor::PLAN_EXECUTOR_PREFIX));
+    let labels = executors
+        .iter()
+        .map(|c| format!("    \"{}\": \"{}\"", c.executor_type, c.label))
+        .collect::<Vec<_>>()
+        .join(",\n");
+    let capabilities = executors
+        .iter()
+        .map(|c| {
+            format!(
+                "    \"{}\": {}",
+                c.executor_type,
+                serde_json::to_string(c).expect("capabilities serialize")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",\n");
+
+    format!(
+        r#"export const EXECUTOR_TYPES: string[] = [
+{}
+];
+
+// Executors run in plan mode, selectable when starting an attempt
+export const PLAN_EXECUTOR_TYPES: string[] = [
+{}
 ];
 
-export const EDITOR_TYPES: EditorType[] = [
+export const EXECUTOR_LABELS: Record<string, string> = {{
+{}
+}};
+
+export const EXECUTOR_CAPABILITIES: Record<string, ExecutorCapabilities> = {{
+{}
+}};"#,
+        quoted_list(executor_types),
+        quoted_list(plan_types),
+        labels,
+        capabilities
+    )
+}
+
+fn generate_constants() -> String {
+    let executor_constants = generate_executor_constants();
+    let other_constants = r#"export const EDITOR_TYPES: EditorType[] = [
     "vscode",
     "cursor", 
     "windsurf",
@@ -25,17 +79,6 @@ export const EDITOR_TYPES: EditorType[] = [
     "custom"
 ];
 
-export const EXECUTOR_LABELS: Record<string, string> = {
-    "echo": "Echo (Test Mode)",
-    "claude": "Claude",
-    "claude-plan": "Claude Plan",
-    "amp": "Amp",
-    "gemini": "Gemini",
-    "charm-opencode": "Charm Opencode",
-    "claude-code-router": "Claude Code Router",
-    "sst-opencode": "SST Opencode"
-};
-
 export const EDITOR_LABELS: Record<string, string> = {
     "vscode": "VS Code",
     "cursor": "Cursor",
@@ -63,8 +106,19 @@ export const SOUND_LABELS: Record<string, string> = {
     "cow-mooing": "Cow Mooing",
     "phone-vibration": "Phone Vibration",
     "rooster": "Rooster Call"
-};"#
-    .to_string()
+};"#;
+
+    let prompt_constants = format!(
+        "export const DEFAULT_PROMPT_TEMPLATE: string = {};\n\nexport const PROMPT_TEMPLATE_VARIABLES: string[] = {};",
+        serde_json::to_string(vibe_kanban::executors::prompt::DEFAULT_PROMPT_TEMPLATE)
+            .expect("template serializes"),
+        serde_json::to_string(vibe_kanban::executors::prompt::PROMPT_TEMPLATE_VARIABLES)
+            .expect("variables serialize")
+    );
+
+    format!(
+        "// Generated constants\n{executor_constants}\n\n{other_constants}\n\n{prompt_constants}"
+    )
 }
 
 fn generate_types_content() -> String {
@@ -81,12 +135,24 @@ fn generate_types_content() -> String {
         vibe_kanban::models::config::ThemeMode::decl(),
         vibe_kanban::models::config::EditorConfig::decl(),
         vibe_kanban::models::config::GitHubConfig::decl(),
+        vibe_kanban::models::config::ExecutionTimeouts::decl(),
+        vibe_kanban::models::config::ProcessTimeouts::decl(),
+        vibe_kanban::models::config::ToolApprovalConfig::decl(),
+        vibe_kanban::models::config::SandboxConfig::decl(),
         vibe_kanban::models::config::EditorType::decl(),
         vibe_kanban::models::config::EditorConstants::decl(),
         vibe_kanban::models::config::SoundFile::decl(),
         vibe_kanban::models::config::SoundConstants::decl(),
         vibe_kanban::routes::config::ConfigConstants::decl(),
         vibe_kanban::executor::ExecutorConfig::decl(),
+        vibe_kanban::executor::PlanModeSupport::decl(),
+        vibe_kanban::executor::McpConfigFormat::decl(),
+        vibe_kanban::executor::McpConfigLocation::decl(),
+        vibe_kanban::executor::ExecutorCapabilities::decl(),
+        vibe_kanban::executors::custom::CustomExecutorConfig::decl(),
+        vibe_kanban::executors::custom::PromptDelivery::decl(),
+        vibe_kanban::executors::custom::SessionIdExtractor::decl(),
+        vibe_kanban::executors::custom::CustomLogNormalizer::decl(),
         vibe_kanban::executor::ExecutorConstants::decl(),
         vibe_kanban::models::project::CreateProject::decl(),
         vibe_kanban::models::project::Project::decl(),
@@ -110,6 +176,10 @@ fn generate_types_content() -> String {
         vibe_kanban::models::task_attempt::CreateTaskAttempt::decl(),
         vibe_kanban::models::task_attempt::UpdateTaskAttempt::decl(),
         vibe_kanban::models::task_attempt::CreateFollowUpAttempt::decl(),
+        vibe_kanban::models::fan_out::CreateFanOut::decl(),
+        vibe_kanban::models::fan_out::DiffStats::decl(),
+        vibe_kanban::models::fan_out::AttemptComparison::decl(),
+        vibe_kanban::models::fan_out::FanOutComparison::decl(),
         vibe_kanban::routes::filesystem::DirectoryEntry::decl(),
         vibe_kanban::routes::filesystem::DirectoryListResponse::decl(),
         vibe_kanban::routes::auth::DeviceStartResponse::decl(),
@@ -121,12 +191,27 @@ fn generate_types_content() -> String {
         vibe_kanban::models::task_attempt::BranchStatus::decl(),
         vibe_kanban::models::task_attempt::ExecutionState::decl(),
         vibe_kanban::models::task_attempt::TaskAttemptState::decl(),
+        vibe_kanban::models::execution_output::OutputStream::decl(),
+        vibe_kanban::models::execution_output::OutputRange::decl(),
         vibe_kanban::models::execution_process::ExecutionProcess::decl(),
         vibe_kanban::models::execution_process::ExecutionProcessSummary::decl(),
         vibe_kanban::models::execution_process::ExecutionProcessStatus::decl(),
         vibe_kanban::models::execution_process::ExecutionProcessType::decl(),
         vibe_kanban::models::execution_process::CreateExecutionProcess::decl(),
         vibe_kanban::models::execution_process::UpdateExecutionProcess::decl(),
+        vibe_kanban::models::execution_process::SendExecutionInput::decl(),
+        vibe_kanban::models::execution_usage::ExecutionUsage::decl(),
+        vibe_kanban::models::execution_usage::UsageTotals::decl(),
+        vibe_kanban::models::tool_approval::ToolApproval::decl(),
+        vibe_kanban::models::tool_approval::ToolApprovalStatus::decl(),
+        vibe_kanban::models::tool_approval::RespondToolApproval::decl(),
+        vibe_kanban::models::tool_allowlist::ToolAllowlist::decl(),
+        vibe_kanban::models::verification_run::VerificationRun::decl(),
+        vibe_kanban::models::project_env_var::ProjectEnvVar::decl(),
+        vibe_kanban::utils::sandbox::SandboxPolicy::decl(),
+        vibe_kanban::models::plan_presentation::PlanPresentation::decl(),
+        vibe_kanban::models::plan_presentation::PlanAuthor::decl(),
+        vibe_kanban::models::plan_presentation::UpdatePlanPresentation::decl(),
         vibe_kanban::models::executor_session::ExecutorSession::decl(),
         vibe_kanban::models::executor_session::CreateExecutorSession::decl(),
         vibe_kanban::models::executor_session::UpdateExecutorSession::decl(),
@@ -134,6 +219,20 @@ fn generate_types_content() -> String {
         vibe_kanban::executor::NormalizedEntry::decl(),
         vibe_kanban::executor::NormalizedEntryType::decl(),
         vibe_kanban::executor::ActionType::decl(),
+        vibe_kanban::executor::FileEditHunk::decl(),
+        vibe_kanban::executor::TodoItem::decl(),
+        vibe_kanban::services::transcript::Transcript::decl(),
+        vibe_kanban::services::transcript::TranscriptProject::decl(),
+        vibe_kanban::services::transcript::TranscriptTask::decl(),
+        vibe_kanban::services::transcript::TranscriptAttempt::decl(),
+        vibe_kanban::services::transcript::TranscriptProcess::decl(),
+        vibe_kanban::services::transcript::TranscriptEntry::decl(),
+        vibe_kanban::services::transcript::TranscriptEntryKind::decl(),
+        vibe_kanban::services::transcript::TranscriptFileDiff::decl(),
+        vibe_kanban::models::search::SearchHitKind::decl(),
+        vibe_kanban::models::search::SearchHit::decl(),
+        vibe_kanban::services::event_bus::BoardEvent::decl(),
+        vibe_kanban::services::event_bus::ProjectEvent::decl(),
     ];
 
     let body = decls
@@ -1,9 +1,44 @@
-use std::str::FromStr;
+use std::{path::PathBuf, str::FromStr, time::Duration};
 
 use rmcp::{transport::stdio, ServiceExt};
 use sqlx::{sqlite::SqliteConnectOptions, SqlitePool};
 use tracing_subscriber::{prelude::*, EnvFilter};
-use vibe_kanban::{mcp::task_server::TaskServer, sentry_layer, utils::asset_dir};
+use vibe_kanban::{
+    mcp::{permission_server::PermissionServer, task_server::TaskServer},
+    sentry_layer,
+    utils::asset_dir,
+};
+
+/// Arguments selecting the permission prompt server instead of the task server:
+/// `--permission-prompt --worktree <path> --timeout-seconds <n>`
+struct PermissionPromptArgs {
+    worktree: PathBuf,
+    timeout: Duration,
+}
+
+fn parse_permission_prompt_args() -> anyhow::Result<Option<PermissionPromptArgs>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.iter().any(|arg| arg == "--permission-prompt") {
+        return Ok(None);
+    }
+
+    let value_of = |flag: &str| {
+        args.iter()
+            .position(|arg| arg == flag)
+            .and_then(|i| args.get(i + 1))
+    };
+    let worktree = value_of("--worktree")
+        .ok_or_else(|| anyhow::anyhow!("--permission-prompt requires --worktree <path>"))?;
+    let timeout_seconds = match value_of("--timeout-seconds") {
+        Some(value) => value.parse::<u64>()?,
+        None => 600,
+    };
+
+    Ok(Some(PermissionPromptArgs {
+        worktree: PathBuf::from(worktree),
+        timeout: Duration::from_secs(timeout_seconds),
+    }))
+}
 
 fn main() -> anyhow::Result<()> {
     let environment = if cfg!(debug_assertions) {
@@ -19,6 +54,7 @@ fn main() -> anyhow::Result<()> {
     sentry::configure_scope(|scope| {
         scope.set_tag("source", "mcp");
     });
+    let permission_prompt = parse_permission_prompt_args()?;
     tokio::runtime::Builder::new_multi_thread()
         .enable_all()
         .build()
@@ -44,6 +80,23 @@ fn main() -> anyhow::Result<()> {
             let options = SqliteConnectOptions::from_str(&database_url)?.create_if_missing(false);
             let pool = SqlitePool::connect_with(options).await?;
 
+            if let Some(args) = permission_prompt {
+                tracing::debug!(
+                    "[MCP] Serving permission prompts for {}",
+                    args.worktree.display()
+                );
+                let service = PermissionServer::new(pool, args.worktree, args.timeout)
+                    .serve(stdio())
+                    .await
+                    .inspect_err(|e| {
+                        tracing::error!("serving error: {:?}", e);
+                        sentry::capture_error(e);
+                    })?;
+
+                service.waiting().await?;
+                return Ok(());
+            }
+
             let service = TaskServer::new(pool)
                 .serve(stdio())
                 .await
@@ -3,12 +3,19 @@ use uuid::Uuid;
 
 use crate::{
     app_state::AppState,
+    executor::ExecutorConfig,
     models::{
+        config::Config,
+        execution_output::{ExecutionOutput, OutputStream},
         execution_process::{ExecutionProcess, ExecutionProcessStatus, ExecutionProcessType},
+        executor_session::ExecutorSession,
+        project::Project,
+        search::SearchIndex,
         task::{Task, TaskStatus},
         task_attempt::TaskAttempt,
+        verification_run::{fix_prompt, VerificationRun},
     },
-    services::{NotificationConfig, NotificationService, ProcessService},
+    services::{ExecutionQueue, NotificationConfig, NotificationService, ProcessService},
     utils::worktree_manager::WorktreeManager,
 };
 
@@ -249,9 +256,10 @@ async fn delete_worktree(
 
 /// Clean up all worktrees for a specific task (immediate cleanup)
 pub async fn cleanup_task_worktrees(
-    pool: &sqlx::SqlitePool,
+    app_state: &AppState,
     task_id: Uuid,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let pool = &app_state.db_pool;
     let task_attempts_with_project =
         TaskAttempt::find_by_task_id_with_project(pool, task_id).await?;
 
@@ -270,28 +278,11 @@ pub async fn cleanup_task_worktrees(
     let mut failed_count = 0;
 
     for (attempt_id, worktree_path, git_repo_path) in task_attempts_with_project {
-        if let Err(e) = delete_worktree(&worktree_path, &git_repo_path, attempt_id).await {
-            tracing::error!(
-                "Failed to cleanup worktree for attempt {}: {}",
-                attempt_id,
-                e
-            );
-            failed_count += 1;
-            // Continue with other attempts even if one fails
+        // Continue with other attempts even if one fails
+        if cleanup_attempt_worktree(app_state, attempt_id, &worktree_path, &git_repo_path).await {
+            cleaned_count += 1;
         } else {
-            // Mark worktree as deleted in database after successful cleanup
-            if let Err(e) =
-                crate::models::task_attempt::TaskAttempt::mark_worktree_deleted(pool, attempt_id)
-                    .await
-            {
-                tracing::error!(
-                    "Failed to mark worktree as deleted in database for attempt {}: {}",
-                    attempt_id,
-                    e
-                );
-            } else {
-                cleaned_count += 1;
-            }
+            failed_count += 1;
         }
     }
 
@@ -305,6 +296,40 @@ pub async fn cleanup_task_worktrees(
     Ok(())
 }
 
+/// Delete one attempt's worktree and mark it as deleted, logging failures.
+///
+/// Returns whether the worktree is gone and recorded as such.
+pub async fn cleanup_attempt_worktree(
+    app_state: &AppState,
+    attempt_id: Uuid,
+    worktree_path: &str,
+    git_repo_path: &str,
+) -> bool {
+    if let Err(e) = delete_worktree(worktree_path, git_repo_path, attempt_id).await {
+        tracing::error!(
+            "Failed to cleanup worktree for attempt {}: {}",
+            attempt_id,
+            e
+        );
+        return false;
+    }
+
+    // Mark worktree as deleted in database after successful cleanup
+    if let Err(e) = TaskAttempt::mark_worktree_deleted(&app_state.db_pool, attempt_id).await {
+        tracing::error!(
+            "Failed to mark worktree as deleted in database for attempt {}: {}",
+            attempt_id,
+            e
+        );
+        return false;
+    }
+    app_state
+        .events
+        .publish_worktree_cleaned_up(&app_state.db_pool, attempt_id)
+        .await;
+    true
+}
+
 /// Defensively check for externally deleted worktrees and mark them as deleted in the database
 async fn check_externally_deleted_worktrees(pool: &sqlx::SqlitePool) {
     let active_attempts = match sqlx::query!(
@@ -512,6 +537,8 @@ async fn cleanup_orphaned_worktree_directory(
 }
 
 pub async fn execution_monitor(app_state: AppState) {
+    recover_interrupted_executions(&app_state).await;
+
     let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(5));
     let mut cleanup_interval = tokio::time::interval(tokio::time::Duration::from_secs(1800)); // 30 minutes
 
@@ -561,6 +588,11 @@ pub async fn execution_monitor(app_state: AppState) {
                             execution_process_id,
                             e
                         );
+                    } else {
+                        app_state
+                            .events
+                            .publish_process_status(&app_state.db_pool, execution_process_id)
+                            .await;
                     }
 
                     // Get the execution process to determine next steps
@@ -599,6 +631,16 @@ pub async fn execution_monitor(app_state: AppState) {
                                 )
                                 .await;
                             }
+                            ExecutionProcessType::TestScript => {
+                                handle_test_script_completion(
+                                    &app_state,
+                                    task_attempt_id,
+                                    execution_process,
+                                    success,
+                                    exit_code,
+                                )
+                                .await;
+                            }
                         }
                     } else {
                         tracing::error!(
@@ -608,6 +650,24 @@ pub async fn execution_monitor(app_state: AppState) {
                     }
                 }
 
+                // Kill executions that exceeded their runtime or idle limits
+                enforce_execution_timeouts(&app_state).await;
+
+                // Start queued attempts in the slots freed by the completions above
+                if let Err(e) = ExecutionQueue::release_finished(&app_state).await {
+                    tracing::error!("Failed to free execution slots: {}", e);
+                }
+                if let Err(e) = ExecutionQueue::dispatch(&app_state).await {
+                    tracing::error!("Failed to start queued task attempts: {}", e);
+                }
+
+                // Make the conversations of finished coding agents searchable
+                let config = app_state.get_config().read().await.clone();
+                if let Err(e) = SearchIndex::index_conversations(&app_state.db_pool, &config).await
+                {
+                    tracing::error!("Failed to index conversations for search: {}", e);
+                }
+
                 // Check for orphaned execution processes AFTER handling completions
                 // Add a small delay to ensure completed processes are properly handled first
                 tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
@@ -665,6 +725,10 @@ pub async fn execution_monitor(app_state: AppState) {
                             );
                             continue;
                         }
+                        app_state
+                            .events
+                            .publish_process_status(&app_state.db_pool, process.id)
+                            .await;
 
                         // Process marked as failed
 
@@ -681,13 +745,9 @@ pub async fn execution_monitor(app_state: AppState) {
                                 if let Ok(Some(task)) =
                                     Task::find_by_id(&app_state.db_pool, task_attempt.task_id).await
                                 {
-                                    if let Err(e) = Task::update_status(
-                                        &app_state.db_pool,
-                                        task.id,
-                                        task.project_id,
-                                        TaskStatus::InReview,
-                                    )
-                                    .await
+                                    if let Err(e) = app_state
+                                        .set_task_status(task.id, task.project_id, TaskStatus::InReview)
+                                        .await
                                     {
                                         tracing::error!("Failed to update task status to InReview for orphaned attempt: {}", e);
                                     }
@@ -722,6 +782,7 @@ pub async fn execution_monitor(app_state: AppState) {
                                         tracing::error!("Failed to mark worktree as deleted in database for attempt {}: {}", attempt_id, e);
                                     } else {
                                         tracing::info!("Successfully marked worktree as deleted for attempt {}", attempt_id);
+                                        app_state.events.publish_worktree_cleaned_up(&app_state.db_pool, attempt_id).await;
                                     }
                                 }
                             }
@@ -736,6 +797,311 @@ pub async fn execution_monitor(app_state: AppState) {
     }
 }
 
+/// Follow-up prompt for a coding agent whose process ended with the server that ran it
+const RESUME_AFTER_RESTART_PROMPT: &str = "The server running you was restarted and your previous run was interrupted. Continue the task where you left off; if it's already done, summarize what you did.";
+
+/// Pick up the executions a previous server run left `Running`. Processes that kept running
+/// are reattached; coding agents that are gone, or can't be reattached, resume their session
+/// with a follow-up. Anything else is marked failed, as the orphan check in the monitor loop
+/// would.
+///
+/// A process only counts as still running while its pid leads a group as the same process
+/// it was spawned as, on the same boot. Otherwise the pid may be someone else's by now, so it
+/// is neither reattached to nor stopped.
+async fn recover_interrupted_executions(app_state: &AppState) {
+    let pool = &app_state.db_pool;
+    let processes = match ExecutionProcess::find_running(pool).await {
+        Ok(processes) => processes,
+        Err(e) => {
+            tracing::error!("Failed to query interrupted execution processes: {}", e);
+            return;
+        }
+    };
+
+    let config = app_state.get_config().read().await.clone();
+
+    // Only interrupted executions still get their logs read. Any other log holds output
+    // that was never redacted and stored.
+    if let Err(e) = crate::utils::output_log::remove_leftovers(|id| {
+        processes.iter().any(|process| process.id == id)
+    }) {
+        tracing::warn!("Failed to remove leftover output logs: {}", e);
+    }
+
+    for process in processes {
+        let (task, project) =
+            match load_attempt_task_and_project(pool, process.task_attempt_id).await {
+                Ok(Some(loaded)) => loaded,
+                // The orphan check marks the process failed
+                Ok(None) => continue,
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to load task of interrupted execution {}: {}",
+                        process.id,
+                        e
+                    );
+                    continue;
+                }
+            };
+
+        #[cfg(unix)]
+        if let Some(pgid) = process.pgid.map(|pgid| pgid as i32) {
+            let identity = process
+                .process_identity()
+                .filter(|identity| crate::utils::process_group::is_running_as(pgid, identity));
+            if let Some(identity) = identity {
+                if can_reattach(&process, &config) {
+                    match ProcessService::reattach_execution(
+                        app_state, &process, project.id, pgid, identity,
+                    )
+                    .await
+                    {
+                        Ok(()) => {
+                            tracing::info!(
+                                "Reattached to execution {} still running as process group {}",
+                                process.id,
+                                pgid
+                            );
+                            continue;
+                        }
+                        Err(e) => {
+                            tracing::warn!("Failed to reattach to execution {}: {}", process.id, e)
+                        }
+                    }
+                }
+                if let Err(e) = crate::utils::process_group::terminate(pgid).await {
+                    tracing::error!(
+                        "Failed to stop process group {} of execution {}: {}",
+                        pgid,
+                        process.id,
+                        e
+                    );
+                }
+            }
+
+            // Store what the process printed while no server was reading, e.g. its session ID
+            match ProcessService::stream_remaining_output(pool, &config, &process, project.id, None)
+                .await
+            {
+                Ok(stdout) => {
+                    stdout.await.ok();
+                }
+                Err(e) => tracing::debug!(
+                    "No output left to store for execution {}: {}",
+                    process.id,
+                    e
+                ),
+            }
+        }
+
+        if let Err(e) = ExecutionProcess::update_completion(
+            pool,
+            process.id,
+            ExecutionProcessStatus::Failed,
+            None,
+        )
+        .await
+        {
+            tracing::error!(
+                "Failed to update interrupted execution process {} status: {}",
+                process.id,
+                e
+            );
+            continue;
+        }
+        app_state
+            .events
+            .publish_process_status(pool, process.id)
+            .await;
+
+        if process.process_type == ExecutionProcessType::CodingAgent {
+            let session_id = ExecutorSession::find_by_execution_process_id(pool, process.id)
+                .await
+                .ok()
+                .flatten()
+                .and_then(|session| session.session_id);
+            if session_id.is_some() {
+                match TaskAttempt::start_followup_execution(
+                    app_state,
+                    process.task_attempt_id,
+                    task.id,
+                    project.id,
+                    RESUME_AFTER_RESTART_PROMPT,
+                )
+                .await
+                {
+                    Ok(_) => {
+                        tracing::info!(
+                            "Resumed session of interrupted execution {} for task attempt {}",
+                            process.id,
+                            process.task_attempt_id
+                        );
+                        continue;
+                    }
+                    Err(e) => tracing::error!(
+                        "Failed to resume session of interrupted execution {}: {}",
+                        process.id,
+                        e
+                    ),
+                }
+            }
+        }
+
+        tracing::info!(
+            "Marked interrupted execution process {} as failed",
+            process.id
+        );
+        if matches!(
+            process.process_type,
+            ExecutionProcessType::CodingAgent | ExecutionProcessType::SetupScript
+        ) {
+            if let Err(e) = app_state
+                .set_task_status(task.id, project.id, TaskStatus::InReview)
+                .await
+            {
+                tracing::error!(
+                    "Failed to update task status to InReview for interrupted attempt: {}",
+                    e
+                );
+            }
+        }
+    }
+}
+
+/// Whether a still running process can be monitored again after a restart. Its exit status
+/// is lost, so scripts whose result decides what happens next are rerun by hand instead.
+fn can_reattach(process: &ExecutionProcess, config: &Config) -> bool {
+    match process.process_type {
+        ExecutionProcessType::DevServer => true,
+        ExecutionProcessType::CodingAgent => process
+            .executor_type
+            .as_deref()
+            .and_then(|executor| executor.parse::<ExecutorConfig>().ok())
+            .is_some_and(|executor| executor.create_executor(config).stores_stdout_lines()),
+        ExecutionProcessType::SetupScript | ExecutionProcessType::TestScript => false,
+    }
+}
+
+/// Kill running executions that exceeded their configured timeouts and record them as timed out
+async fn enforce_execution_timeouts(app_state: &AppState) {
+    let timeouts = app_state
+        .get_config()
+        .read()
+        .await
+        .execution_timeouts
+        .clone();
+
+    let processes = match ExecutionProcess::find_running_activity(&app_state.db_pool).await {
+        Ok(processes) => processes,
+        Err(e) => {
+            tracing::error!(
+                "Failed to query running execution processes for timeouts: {}",
+                e
+            );
+            return;
+        }
+    };
+
+    let now = chrono::Utc::now();
+    for process in processes {
+        let Some(reason) = timeouts.for_process_type(&process.process_type).check(
+            process.started_at,
+            process.last_activity(now),
+            now,
+        ) else {
+            continue;
+        };
+
+        tracing::warn!("Execution process {} {}, stopping it", process.id, reason);
+
+        // Only processes owned by this server can be killed; the orphan check handles the rest
+        match app_state.stop_running_execution_by_id(process.id).await {
+            Ok(true) => {}
+            Ok(false) => continue,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to stop timed out execution process {}: {}",
+                    process.id,
+                    e
+                );
+                continue;
+            }
+        }
+
+        let message = format!("\nExecution timed out: {}\n", reason);
+        if let Err(e) =
+            ExecutionProcess::append_stderr(&app_state.db_pool, process.id, &message).await
+        {
+            tracing::error!(
+                "Failed to record timeout reason for execution process {}: {}",
+                process.id,
+                e
+            );
+        }
+
+        if let Err(e) = ExecutionProcess::update_completion(
+            &app_state.db_pool,
+            process.id,
+            ExecutionProcessStatus::TimedOut,
+            None,
+        )
+        .await
+        {
+            tracing::error!(
+                "Failed to mark execution process {} as timed out: {}",
+                process.id,
+                e
+            );
+            continue;
+        }
+        app_state
+            .events
+            .publish_process_status(&app_state.db_pool, process.id)
+            .await;
+
+        let Ok(Some(execution_process)) =
+            ExecutionProcess::find_by_id(&app_state.db_pool, process.id).await
+        else {
+            tracing::error!(
+                "Failed to find execution process {} for timeout handling",
+                process.id
+            );
+            continue;
+        };
+
+        let task_attempt_id = execution_process.task_attempt_id;
+        match execution_process.process_type {
+            ExecutionProcessType::SetupScript => {
+                handle_setup_completion(app_state, task_attempt_id, execution_process, false).await;
+            }
+            ExecutionProcessType::CodingAgent => {
+                handle_coding_agent_completion(
+                    app_state,
+                    task_attempt_id,
+                    process.id,
+                    execution_process,
+                    false,
+                    None,
+                )
+                .await;
+            }
+            ExecutionProcessType::DevServer => {
+                // Nothing follows a dev server; the timed out status is all we record
+            }
+            ExecutionProcessType::TestScript => {
+                handle_test_script_completion(
+                    app_state,
+                    task_attempt_id,
+                    execution_process,
+                    false,
+                    None,
+                )
+                .await;
+            }
+        }
+    }
+}
+
 /// Handle setup script completion
 async fn handle_setup_completion(
     app_state: &AppState,
@@ -801,13 +1167,9 @@ async fn handle_setup_completion(
         {
             if let Ok(Some(task)) = Task::find_by_id(&app_state.db_pool, task_attempt.task_id).await
             {
-                if let Err(e) = Task::update_status(
-                    &app_state.db_pool,
-                    task.id,
-                    task.project_id,
-                    TaskStatus::InReview,
-                )
-                .await
+                if let Err(e) = app_state
+                    .set_task_status(task.id, task.project_id, TaskStatus::InReview)
+                    .await
                 {
                     tracing::error!(
                         "Failed to update task status to InReview after setup failure: {}",
@@ -828,8 +1190,40 @@ async fn handle_coding_agent_completion(
     success: bool,
     exit_code: Option<i64>,
 ) {
+    let timed_out = execution_process.status == ExecutionProcessStatus::TimedOut;
+
+    let config = app_state.get_config().read().await.clone();
+    if let Err(e) =
+        ProcessService::record_execution_usage(&app_state.db_pool, &config, execution_process_id)
+            .await
+    {
+        tracing::error!(
+            "Failed to record usage for execution process {}: {}",
+            execution_process_id,
+            e
+        );
+    }
+
+    // Plan mode executions may be killed once the plan is presented, so record it regardless
+    if let Err(e) =
+        ProcessService::record_plan_presentation(&app_state.db_pool, &config, execution_process_id)
+            .await
+    {
+        tracing::error!(
+            "Failed to record plan for execution process {}: {}",
+            execution_process_id,
+            e
+        );
+    }
+
     // Extract and store assistant message from execution logs
-    let summary = if let Some(stdout) = &execution_process.stdout {
+    let stdout = ExecutionOutput::read_to_string(
+        &app_state.db_pool,
+        execution_process_id,
+        OutputStream::Stdout,
+    )
+    .await;
+    let summary = if let Ok(stdout) = &stdout {
         if let Some(assistant_message) = crate::executor::parse_assistant_message_from_logs(stdout)
         {
             if let Err(e) = crate::models::executor_session::ExecutorSession::update_summary(
@@ -878,8 +1272,19 @@ async fn handle_coding_agent_completion(
         {
             if let Ok(Some(task)) = Task::find_by_id(&app_state.db_pool, task_attempt.task_id).await
             {
-                let title = format!("Task Complete: {}", task.title);
-                let message = if success {
+                let title = if timed_out {
+                    format!("Task Timed Out: {}", task.title)
+                } else {
+                    format!("Task Complete: {}", task.title)
+                };
+                let message = if timed_out {
+                    format!(
+                        "⏱️ '{}' timed out and was stopped\nBranch: {}\nExecutor: {}",
+                        task.title,
+                        task_attempt.branch,
+                        task_attempt.executor.as_deref().unwrap_or("default")
+                    )
+                } else if success {
                     format!(
                         "✅ '{}' completed successfully\nBranch: {}\nExecutor: {}",
                         task.title,
@@ -897,23 +1302,11 @@ async fn handle_coding_agent_completion(
                 (title, message)
             } else {
                 // Fallback if task not found
-                let title = "Task Complete";
-                let message = if success {
-                    "Task execution completed successfully"
-                } else {
-                    "Task execution failed"
-                };
-                (title.to_string(), message.to_string())
+                fallback_notification(success, timed_out)
             }
         } else {
             // Fallback if task attempt not found
-            let title = "Task Complete";
-            let message = if success {
-                "Task execution completed successfully"
-            } else {
-                "Task execution failed"
-            };
-            (title.to_string(), message.to_string())
+            fallback_notification(success, timed_out)
         };
 
         notification_service
@@ -962,24 +1355,40 @@ async fn handle_coding_agent_completion(
                         "attempt_id": task_attempt_id.to_string(),
                         "execution_success": success,
                         "exit_code": exit_code,
+                        "timed_out": timed_out,
                     })),
                 )
                 .await;
 
             // Update task status to InReview
-            if let Err(e) = Task::update_status(
-                &app_state.db_pool,
-                task.id,
-                task.project_id,
-                TaskStatus::InReview,
-            )
-            .await
+            if let Err(e) = app_state
+                .set_task_status(task.id, task.project_id, TaskStatus::InReview)
+                .await
             {
                 tracing::error!(
                     "Failed to update task status to InReview for completed attempt: {}",
                     e
                 );
             }
+
+            // Check the committed changes with the project's test script, if it has one
+            if success && !timed_out {
+                if let Err(e) = ProcessService::start_test_script(
+                    &app_state.db_pool,
+                    app_state,
+                    task_attempt_id,
+                    task.id,
+                    task.project_id,
+                )
+                .await
+                {
+                    tracing::error!(
+                        "Failed to start test script for attempt {}: {}",
+                        task_attempt_id,
+                        e
+                    );
+                }
+            }
         }
     } else {
         tracing::error!(
@@ -989,6 +1398,158 @@ async fn handle_coding_agent_completion(
     }
 }
 
+/// Notification title and message used when the task details can't be loaded
+fn fallback_notification(success: bool, timed_out: bool) -> (String, String) {
+    let message = if timed_out {
+        "Task execution timed out"
+    } else if success {
+        "Task execution completed successfully"
+    } else {
+        "Task execution failed"
+    };
+    let title = if timed_out {
+        "Task Timed Out"
+    } else {
+        "Task Complete"
+    };
+    (title.to_string(), message.to_string())
+}
+
+/// Handle test script completion: record the run and, while the project's verify-and-fix loop
+/// has iterations left, send the failing output back to the coding agent
+async fn handle_test_script_completion(
+    app_state: &AppState,
+    task_attempt_id: Uuid,
+    execution_process: ExecutionProcess,
+    success: bool,
+    exit_code: Option<i64>,
+) {
+    tracing::info!(
+        "Test script for task attempt {} {}{}",
+        task_attempt_id,
+        if success { "passed" } else { "failed" },
+        exit_code
+            .map(|code| format!(" with exit code {}", code))
+            .unwrap_or_default()
+    );
+
+    let pool = &app_state.db_pool;
+    let (task, project) = match load_attempt_task_and_project(pool, task_attempt_id).await {
+        Ok(Some(found)) => found,
+        Ok(None) => {
+            tracing::error!(
+                "Failed to find task or project of attempt {} for test script completion",
+                task_attempt_id
+            );
+            return;
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to load attempt {} for test script completion: {}",
+                task_attempt_id,
+                e
+            );
+            return;
+        }
+    };
+
+    let iteration = match (
+        VerificationRun::find_by_task_attempt_id(pool, task_attempt_id).await,
+        ExecutionProcess::find_summaries_by_task_attempt_id(pool, task_attempt_id).await,
+    ) {
+        (Ok(runs), Ok(processes)) => {
+            VerificationRun::next_iteration(runs.last(), &processes, execution_process.id)
+        }
+        (Err(e), _) | (_, Err(e)) => {
+            tracing::error!(
+                "Failed to load verification history of attempt {}: {}",
+                task_attempt_id,
+                e
+            );
+            return;
+        }
+    };
+
+    let mut fix_requested = false;
+    if !success && iteration <= project.max_fix_iterations {
+        let mut streams = Vec::new();
+        for stream in [OutputStream::Stdout, OutputStream::Stderr] {
+            match ExecutionOutput::read(pool, execution_process.id, stream, 0).await {
+                Ok(range) if !range.content.is_empty() => streams.push(range.content),
+                Ok(_) => {}
+                Err(e) => tracing::error!(
+                    "Failed to read {} of test run {}: {}",
+                    stream.as_str(),
+                    execution_process.id,
+                    e
+                ),
+            }
+        }
+        let output = streams.join("\n");
+        let prompt = fix_prompt(
+            project.test_script.as_deref().unwrap_or_default(),
+            exit_code,
+            &output,
+        );
+        match TaskAttempt::start_followup_execution(
+            app_state,
+            task_attempt_id,
+            task.id,
+            project.id,
+            &prompt,
+        )
+        .await
+        {
+            Ok(_) => {
+                fix_requested = true;
+                tracing::info!(
+                    "Sent failing test output to attempt {} (fix {} of {})",
+                    task_attempt_id,
+                    iteration,
+                    project.max_fix_iterations
+                );
+            }
+            Err(e) => tracing::error!(
+                "Failed to start fix follow-up for attempt {}: {}",
+                task_attempt_id,
+                e
+            ),
+        }
+    }
+
+    if let Err(e) = VerificationRun::create(
+        pool,
+        task_attempt_id,
+        execution_process.id,
+        iteration,
+        success,
+        fix_requested,
+    )
+    .await
+    {
+        tracing::error!(
+            "Failed to record verification run for attempt {}: {}",
+            task_attempt_id,
+            e
+        );
+    }
+}
+
+async fn load_attempt_task_and_project(
+    pool: &sqlx::SqlitePool,
+    task_attempt_id: Uuid,
+) -> Result<Option<(Task, Project)>, sqlx::Error> {
+    let Some(attempt) = TaskAttempt::find_by_id(pool, task_attempt_id).await? else {
+        return Ok(None);
+    };
+    let Some(task) = Task::find_by_id(pool, attempt.task_id).await? else {
+        return Ok(None);
+    };
+    Ok(Project::find_by_id(pool, task.project_id)
+        .await?
+        .map(|project| (task, project)))
+}
+
 /// Handle dev server completion (future functionality)
 async fn handle_dev_server_completion(
     app_state: &AppState,
@@ -1,14 +1,28 @@
 use std::str::FromStr;
 
 use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, Utc};
 use serde::{Deserialize, Serialize};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use ts_rs::TS;
 use uuid::Uuid;
 
-use crate::executors::{
-    AmpExecutor, CCRExecutor, CharmOpencodeExecutor, ClaudeExecutor, EchoExecutor, GeminiExecutor,
-    SetupScriptExecutor, SstOpencodeExecutor,
+use crate::{
+    executors::{
+        custom::{
+            find_custom_executor, usable_custom_executors, CustomLogNormalizer,
+            CUSTOM_EXECUTOR_PREFIX,
+        },
+        replay::{
+            parse_replay_executor, ReplayFixture, REPLAY_EXECUTOR_PREFIX, REPLAY_SPEED_SEPARATOR,
+        },
+        AmpExecutor, CCRExecutor, CharmOpencodeExecutor, ClaudeExecutor, CustomExecutor,
+        EchoExecutor, GeminiExecutor, ReplayExecutor, SetupScriptExecutor, SstOpencodeExecutor,
+    },
+    models::{
+        config::Config,
+        execution_output::{OutputStream, TimedOutput},
+    },
 };
 
 // Constants for database streaming - fast for near-real-time updates
@@ -35,10 +49,16 @@ pub struct NormalizedEntry {
     pub content: String,
     #[ts(skip)]
     pub metadata: Option<serde_json::Value>,
+    /// Time since the previous entry, i.e. how long this one took to arrive
+    #[serde(default)]
+    pub duration_ms: Option<u32>,
+    /// For a tool call, how long it ran
+    #[serde(default)]
+    pub tool_duration_ms: Option<u32>,
 }
 
 /// Types of entries in a normalized conversation
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 #[ts(export)]
 pub enum NormalizedEntryType {
@@ -47,6 +67,18 @@ pub enum NormalizedEntryType {
     ToolUse {
         tool_name: String,
         action_type: ActionType,
+        /// The ID the CLI gave the call, which its `ToolResult` refers to
+        #[serde(default)]
+        tool_use_id: Option<String>,
+    },
+    /// What a tool call returned, with the output as the entry's content. Not every CLI
+    /// prints these: Gemini and SST Opencode only report their tool calls.
+    ToolResult {
+        tool_use_id: String,
+        #[serde(default)]
+        is_error: bool,
+        #[serde(default)]
+        exit_code: Option<i32>,
     },
     SystemMessage,
     ErrorMessage,
@@ -54,18 +86,387 @@ pub enum NormalizedEntryType {
 }
 
 /// Types of tool actions that can be performed
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(tag = "action", rename_all = "snake_case")]
 #[ts(export)]
 pub enum ActionType {
-    FileRead { path: String },
-    FileWrite { path: String },
-    CommandRun { command: String },
-    Search { query: String },
-    WebFetch { url: String },
-    TaskCreate { description: String },
-    PlanPresentation { plan: String },
-    Other { description: String },
+    FileRead {
+        path: String,
+    },
+    FileWrite {
+        path: String,
+    },
+    /// A change to part of an existing file
+    FileEdit {
+        path: String,
+        edits: Vec<FileEditHunk>,
+    },
+    /// A shell command, with its output and exit code once its result is known
+    CommandRun {
+        command: String,
+        #[serde(default)]
+        output: Option<String>,
+        #[serde(default)]
+        exit_code: Option<i32>,
+    },
+    Search {
+        query: String,
+    },
+    WebFetch {
+        url: String,
+    },
+    TaskCreate {
+        description: String,
+    },
+    PlanPresentation {
+        plan: String,
+    },
+    /// The agent's todo list, as it stands after the call
+    TodoUpdate {
+        todos: Vec<TodoItem>,
+    },
+    Other {
+        description: String,
+    },
+}
+
+impl ActionType {
+    /// Fallback for a tool the normalizer doesn't recognize
+    pub fn unknown_tool(tool_name: &str) -> Self {
+        ActionType::Other {
+            description: format!("Tool: {}", tool_name),
+        }
+    }
+
+    /// A command whose result isn't known yet
+    pub fn command_run(command: &str) -> Self {
+        ActionType::CommandRun {
+            command: command.to_string(),
+            output: None,
+            exit_code: None,
+        }
+    }
+}
+
+/// Text an edit replaced, and what it was replaced with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct FileEditHunk {
+    pub old: String,
+    pub new: String,
+}
+
+impl FileEditHunk {
+    /// Read a hunk from tool input that names its old and new text with the given keys
+    pub fn from_input(input: &serde_json::Value, old_key: &str, new_key: &str) -> Option<Self> {
+        Some(FileEditHunk {
+            old: input.get(old_key)?.as_str()?.to_string(),
+            new: input.get(new_key)?.as_str()?.to_string(),
+        })
+    }
+}
+
+/// One item of an agent's todo list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TodoItem {
+    pub content: String,
+    /// As the CLI reports it, e.g. `pending`, `in_progress` or `completed`
+    pub status: String,
+    pub priority: Option<String>,
+}
+
+impl TodoItem {
+    /// The items of a todo tool's `todos` input, skipping any without content
+    pub fn from_input(input: &serde_json::Value) -> Vec<Self> {
+        input
+            .get("todos")
+            .and_then(|todos| todos.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|todo| {
+                let text = |key: &str| todo.get(key).and_then(|v| v.as_str()).map(str::to_string);
+                Some(TodoItem {
+                    content: text("content")?,
+                    status: text("status").unwrap_or_else(|| "pending".to_string()),
+                    priority: text("priority"),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Tool use content for a todo list, one item per line
+pub fn format_todo_list(todos: &[TodoItem]) -> String {
+    if todos.is_empty() {
+        return "Managing TODO list".to_string();
+    }
+
+    let items = todos
+        .iter()
+        .map(|todo| {
+            let emoji = match todo.status.as_str() {
+                "completed" => "✅",
+                "in_progress" | "in-progress" => "🔄",
+                "pending" | "todo" => "⏳",
+                _ => "📝",
+            };
+            format!(
+                "{} {} ({})",
+                emoji,
+                todo.content,
+                todo.priority.as_deref().unwrap_or("medium")
+            )
+        })
+        .collect::<Vec<_>>();
+    format!("TODO List:\n{}", items.join("\n"))
+}
+
+/// Fill in each command's output and exit code from its tool result, for executors whose
+/// CLI reports them separately
+pub fn link_tool_results(entries: &mut [NormalizedEntry]) {
+    let results = entries
+        .iter()
+        .filter_map(|entry| match &entry.entry_type {
+            NormalizedEntryType::ToolResult { tool_use_id, .. } => {
+                Some((tool_use_id.clone(), entry.clone()))
+            }
+            _ => None,
+        })
+        .collect::<std::collections::HashMap<_, _>>();
+
+    for entry in entries.iter_mut() {
+        let result = match &entry.entry_type {
+            NormalizedEntryType::ToolUse {
+                tool_use_id: Some(id),
+                ..
+            } => results.get(id),
+            _ => None,
+        };
+        if let Some(result) = result {
+            link_tool_result(entry, result);
+        }
+    }
+}
+
+/// Fill in a command's output and exit code from a tool result. Returns whether the result
+/// belongs to that command.
+pub fn link_tool_result(entry: &mut NormalizedEntry, result: &NormalizedEntry) -> bool {
+    let NormalizedEntryType::ToolResult {
+        tool_use_id: result_id,
+        exit_code: result_exit_code,
+        ..
+    } = &result.entry_type
+    else {
+        return false;
+    };
+
+    match &mut entry.entry_type {
+        NormalizedEntryType::ToolUse {
+            action_type:
+                ActionType::CommandRun {
+                    output, exit_code, ..
+                },
+            tool_use_id: Some(id),
+            ..
+        } if id == result_id => {
+            *output = Some(result.content.clone());
+            *exit_code = *result_exit_code;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Format a capture time as an entry timestamp
+pub fn format_timestamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// Lines of output, each with the time it was captured where that's known
+#[derive(Debug, Clone, Default)]
+pub struct TimedLines<'a> {
+    lines: Vec<(&'a str, Option<DateTime<Utc>>)>,
+}
+
+impl<'a> TimedLines<'a> {
+    /// Lines of output whose capture times aren't known
+    pub fn untimed(logs: &'a str) -> Self {
+        Self {
+            lines: logs.lines().map(|line| (line, None)).collect(),
+        }
+    }
+
+    /// Lines of captured output, `line_times` holding the capture time of each
+    pub fn new(content: &'a str, line_times: &[DateTime<Utc>]) -> Self {
+        Self {
+            lines: content
+                .lines()
+                .enumerate()
+                .map(|(index, line)| (line, line_times.get(index).copied()))
+                .collect(),
+        }
+    }
+
+    /// Each line with the timestamp of the entries it gives
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, Option<String>)> + '_ {
+        self.lines
+            .iter()
+            .map(|(line, time)| (*line, time.map(format_timestamp)))
+    }
+
+    /// Only the lines `keep` accepts, each still with its own capture time
+    pub fn filter(self, keep: impl Fn(&str) -> bool) -> Self {
+        Self {
+            lines: self
+                .lines
+                .into_iter()
+                .filter(|(line, _)| keep(line))
+                .collect(),
+        }
+    }
+}
+
+/// Derive durations from the entries' timestamps: each entry's time since the previous one,
+/// and each tool call's time until its result. For CLIs that report no results, a tool call
+/// lasts until the next entry.
+pub fn fill_durations(entries: &mut [NormalizedEntry]) {
+    let times = entries
+        .iter()
+        .map(|entry| {
+            entry
+                .timestamp
+                .as_deref()
+                .and_then(|timestamp| DateTime::parse_from_rfc3339(timestamp).ok())
+                .map(|time| time.with_timezone(&Utc))
+        })
+        .collect::<Vec<_>>();
+    let result_times = entries
+        .iter()
+        .zip(&times)
+        .filter_map(|(entry, time)| match &entry.entry_type {
+            NormalizedEntryType::ToolResult { tool_use_id, .. } => {
+                Some((tool_use_id.clone(), (*time)?))
+            }
+            _ => None,
+        })
+        .collect::<std::collections::HashMap<_, _>>();
+    let reports_results = entries
+        .iter()
+        .any(|entry| matches!(entry.entry_type, NormalizedEntryType::ToolResult { .. }));
+
+    let mut previous = None;
+    for (index, entry) in entries.iter_mut().enumerate() {
+        let Some(time) = times[index] else {
+            continue;
+        };
+        entry.duration_ms = previous.map(|previous| millis_between(previous, time));
+        previous = Some(time);
+
+        if let NormalizedEntryType::ToolUse { tool_use_id, .. } = &entry.entry_type {
+            let end = match tool_use_id.as_ref().and_then(|id| result_times.get(id)) {
+                Some(end) => Some(*end),
+                None if !reports_results => times.get(index + 1).copied().flatten(),
+                None => None,
+            };
+            entry.tool_duration_ms = end.map(|end| millis_between(time, end));
+        }
+    }
+}
+
+fn millis_between(start: DateTime<Utc>, end: DateTime<Utc>) -> u32 {
+    (end - start).num_milliseconds().clamp(0, u32::MAX as i64) as u32
+}
+
+/// How an executor presents a plan before changing the worktree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum PlanModeSupport {
+    /// The CLI has its own plan mode
+    Native,
+    /// The prompt is wrapped in planning instructions
+    Scaffolded,
+    Unsupported,
+}
+
+const PLAN_OPEN_TAG: &str = "<plan>";
+const PLAN_CLOSE_TAG: &str = "</plan>";
+
+/// Wrap a prompt in instructions that make an agent without a native plan mode
+/// explore, then present its plan between `<plan>` tags without changing anything
+pub fn plan_mode_prompt(prompt: &str) -> String {
+    format!(
+        r#"You are in planning mode. Investigate the codebase as needed, but do not create, edit or delete any files and do not run commands that change state.
+
+When you are done, reply with a step-by-step implementation plan in markdown, wrapped in {PLAN_OPEN_TAG} and {PLAN_CLOSE_TAG} tags. The plan will be reviewed before any changes are made.
+
+{prompt}"#
+    )
+}
+
+/// The last plan an agent wrapped in `<plan>` tags, if any
+pub fn extract_tagged_plan(text: &str) -> Option<String> {
+    let start = text.rfind(PLAN_OPEN_TAG)? + PLAN_OPEN_TAG.len();
+    let end = start + text[start..].find(PLAN_CLOSE_TAG)?;
+    let plan = text[start..end].trim();
+    (!plan.is_empty()).then(|| plan.to_string())
+}
+
+/// Token usage and cost reported by an executor for a single execution process
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TokenUsage {
+    pub model: Option<String>,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_creation_input_tokens: i64,
+    pub cache_read_input_tokens: i64,
+    /// Only set when the executor reports cost
+    pub cost_usd: Option<f64>,
+}
+
+impl TokenUsage {
+    /// Read token counts from a usage object, accepting both snake_case (Claude)
+    /// and camelCase (Amp) field names
+    pub fn from_usage_json(usage: &serde_json::Value) -> Self {
+        let count = |snake: &str, camel: &str| {
+            usage
+                .get(snake)
+                .or_else(|| usage.get(camel))
+                .and_then(|v| v.as_i64())
+                .unwrap_or(0)
+        };
+
+        Self {
+            model: usage
+                .get("model")
+                .and_then(|m| m.as_str())
+                .map(|m| m.to_string()),
+            input_tokens: count("input_tokens", "inputTokens"),
+            output_tokens: count("output_tokens", "outputTokens"),
+            cache_creation_input_tokens: count(
+                "cache_creation_input_tokens",
+                "cacheCreationInputTokens",
+            ),
+            cache_read_input_tokens: count("cache_read_input_tokens", "cacheReadInputTokens"),
+            cost_usd: None,
+        }
+    }
+
+    /// Accumulate another usage report into this one
+    pub fn add(&mut self, other: &TokenUsage) {
+        if self.model.is_none() {
+            self.model = other.model.clone();
+        }
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_creation_input_tokens += other.cache_creation_input_tokens;
+        self.cache_read_input_tokens += other.cache_read_input_tokens;
+        self.cost_usd = match (self.cost_usd, other.cost_usd) {
+            (Some(a), Some(b)) => Some(a + b),
+            (a, b) => a.or(b),
+        };
+    }
 }
 
 /// Context information for spawn failures to provide comprehensive error details
@@ -254,6 +655,41 @@ impl ExecutorError {
     }
 }
 
+/// Executors whose commands take extra CLI arguments from the user
+pub trait ExtraArgs: Sized {
+    /// Where the executor keeps its extra arguments
+    fn extra_args_mut(&mut self) -> &mut Option<String>;
+
+    /// Append extra CLI arguments (e.g. `--model opus`) to every command this executor runs
+    fn with_extra_args(mut self, extra_args: Option<String>) -> Self {
+        *self.extra_args_mut() = extra_args;
+        self
+    }
+}
+
+/// Executors that start a task with a prompt rendered from a template
+pub trait PromptTemplate: Sized {
+    /// Where the executor keeps the prompt template from the user config
+    fn prompt_template_mut(&mut self) -> &mut Option<String>;
+
+    /// Render task prompts with the template from the user config, for projects without one
+    fn with_prompt_template(mut self, template: Option<String>) -> Self {
+        *self.prompt_template_mut() = template;
+        self
+    }
+}
+
+/// A coding agent executor with the extra arguments and prompt template it runs with
+fn configured<E: ExtraArgs + PromptTemplate>(
+    executor: E,
+    config: &Config,
+    extra_args: Option<String>,
+) -> E {
+    executor
+        .with_extra_args(extra_args)
+        .with_prompt_template(config.prompt_template.clone())
+}
+
 /// Trait for coding agents that can execute tasks, normalize logs, and support follow-up sessions
 #[async_trait]
 pub trait Executor: Send + Sync {
@@ -284,7 +720,18 @@ pub trait Executor: Send + Sync {
     /// Normalize executor logs into a standard format
     fn normalize_logs(
         &self,
-        _logs: &str,
+        logs: &str,
+        worktree_path: &str,
+    ) -> Result<NormalizedConversation, String> {
+        self.normalize_lines(TimedLines::untimed(logs), worktree_path)
+    }
+
+    /// Normalize lines of executor output into a standard format. Entries take their
+    /// timestamp from the capture time of the line they come from, unless the output
+    /// carries its own.
+    fn normalize_lines(
+        &self,
+        _lines: TimedLines<'_>,
         _worktree_path: &str,
     ) -> Result<NormalizedConversation, String> {
         // Default implementation returns empty conversation
@@ -297,43 +744,100 @@ pub trait Executor: Send + Sync {
         })
     }
 
+    /// Normalize a single line of output as it arrives, for live streaming.
+    ///
+    /// The default runs `normalize_logs` on the line alone, which suits executors that
+    /// write one self-contained message per line.
+    fn normalize_line(&self, line: &str, worktree_path: &str) -> Vec<NormalizedEntry> {
+        self.normalize_logs(line, worktree_path)
+            .map(|conversation| conversation.entries)
+            .unwrap_or_default()
+    }
+
+    /// Normalize logs whose lines carry the time they were captured, deriving the entries'
+    /// durations from their timestamps
+    fn normalize_timed_logs(
+        &self,
+        output: &TimedOutput,
+        worktree_path: &str,
+    ) -> Result<NormalizedConversation, String> {
+        let mut conversation = self.normalize_lines(
+            TimedLines::new(&output.content, &output.line_times),
+            worktree_path,
+        )?;
+        fill_durations(&mut conversation.entries);
+        Ok(conversation)
+    }
+
+    /// Extract token usage and cost from executor logs.
+    ///
+    /// Executors whose output doesn't report usage keep the default, which returns `None`.
+    fn extract_usage(&self, _logs: &str) -> Option<TokenUsage> {
+        None
+    }
+
+    /// Whether and how this executor can plan before making changes
+    fn plan_mode_support(&self) -> PlanModeSupport {
+        PlanModeSupport::Unsupported
+    }
+
+    /// Extract the plan presented by a plan mode execution.
+    ///
+    /// The default takes the last `PlanPresentation` tool use, falling back to the last
+    /// plan the assistant wrapped in `<plan>` tags as asked by the scaffolded prompt.
+    /// Assistant messages are joined first, since some executors split them by line.
+    fn extract_plan(&self, logs: &str, worktree_path: &str) -> Option<String> {
+        let conversation = self.normalize_logs(logs, worktree_path).ok()?;
+        let entries = &conversation.entries;
+
+        entries
+            .iter()
+            .rev()
+            .find_map(|entry| match &entry.entry_type {
+                NormalizedEntryType::ToolUse {
+                    action_type: ActionType::PlanPresentation { plan },
+                    ..
+                } => Some(plan.clone()),
+                _ => None,
+            })
+            .or_else(|| {
+                let assistant_text = entries
+                    .iter()
+                    .filter(|entry| {
+                        matches!(entry.entry_type, NormalizedEntryType::AssistantMessage)
+                    })
+                    .map(|entry| entry.content.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                extract_tagged_plan(&assistant_text)
+            })
+    }
+
+    /// Format a message for the stdin of a running process, for executors whose CLI reads
+    /// further input while it works. `None` means the executor can't be steered.
+    fn format_input_message(&self, _message: &str) -> Option<String> {
+        None
+    }
+
+    /// Whether stdout is stored line for line as the process prints it. Only then can a
+    /// restarted server tell from the stored lines where to continue a still running
+    /// process's log; other executors' processes are stopped and resumed instead.
+    fn stores_stdout_lines(&self) -> bool {
+        true
+    }
+
     #[allow(clippy::result_large_err)]
+    /// Stream the child's output to the database. `config` is the user config the output is
+    /// normalized with as it arrives.
     fn setup_streaming(
         &self,
         child: &mut command_group::AsyncGroupChild,
         pool: &sqlx::SqlitePool,
+        config: &Config,
         attempt_id: Uuid,
         execution_process_id: Uuid,
     ) -> Result<(), ExecutorError> {
-        let stdout = child
-            .inner()
-            .stdout
-            .take()
-            .expect("Failed to take stdout from child process");
-        let stderr = child
-            .inner()
-            .stderr
-            .take()
-            .expect("Failed to take stderr from child process");
-
-        let pool_clone1 = pool.clone();
-        let pool_clone2 = pool.clone();
-
-        tokio::spawn(stream_output_to_db(
-            stdout,
-            pool_clone1,
-            attempt_id,
-            execution_process_id,
-            true,
-        ));
-        tokio::spawn(stream_output_to_db(
-            stderr,
-            pool_clone2,
-            attempt_id,
-            execution_process_id,
-            false,
-        ));
-
+        spawn_output_streaming(child, pool, config, attempt_id, execution_process_id);
         Ok(())
     }
 
@@ -341,13 +845,21 @@ pub trait Executor: Send + Sync {
     async fn execute_streaming(
         &self,
         pool: &sqlx::SqlitePool,
+        config: &Config,
         task_id: Uuid,
         attempt_id: Uuid,
         execution_process_id: Uuid,
         worktree_path: &str,
     ) -> Result<command_group::AsyncGroupChild, ExecutorError> {
         let mut child = self.spawn(pool, task_id, worktree_path).await?;
-        Self::setup_streaming(self, &mut child, pool, attempt_id, execution_process_id)?;
+        Self::setup_streaming(
+            self,
+            &mut child,
+            pool,
+            config,
+            attempt_id,
+            execution_process_id,
+        )?;
         Ok(child)
     }
 
@@ -356,6 +868,7 @@ pub trait Executor: Send + Sync {
     async fn execute_followup_streaming(
         &self,
         pool: &sqlx::SqlitePool,
+        config: &Config,
         task_id: Uuid,
         attempt_id: Uuid,
         execution_process_id: Uuid,
@@ -366,7 +879,14 @@ pub trait Executor: Send + Sync {
         let mut child = self
             .spawn_followup(pool, task_id, session_id, prompt, worktree_path)
             .await?;
-        Self::setup_streaming(self, &mut child, pool, attempt_id, execution_process_id)?;
+        Self::setup_streaming(
+            self,
+            &mut child,
+            pool,
+            config,
+            attempt_id,
+            execution_process_id,
+        )?;
         Ok(child)
     }
 }
@@ -376,9 +896,12 @@ pub trait Executor: Send + Sync {
 pub enum ExecutorType {
     SetupScript(String),
     DevServer(String),
+    TestScript(String),
     CodingAgent {
         config: ExecutorConfig,
         follow_up: Option<FollowUpInfo>,
+        /// Extra CLI arguments from the project settings
+        extra_args: Option<String>,
     },
 }
 
@@ -408,14 +931,101 @@ pub enum ExecutorConfig {
     CharmOpencode,
     #[serde(alias = "opencode")]
     SstOpencode,
+    /// A user-defined executor from `Config::custom_executors`, referenced by name
+    Custom {
+        name: String,
+    },
+    /// Another executor run in plan mode, referenced as `plan:<executor>`
+    Plan {
+        executor: Box<ExecutorConfig>,
+    },
+    /// Plays back a recorded run, referenced as `replay:<fixture>[?speed=<n>]`
+    Replay {
+        fixture: String,
+        /// How many times faster than recorded to play back; 0 plays back without waiting
+        speed: Option<u32>,
+    },
+}
+
+/// Prefix used when an executor is run in plan mode (e.g. `plan:gemini`)
+pub const PLAN_EXECUTOR_PREFIX: &str = "plan:";
+
+/// How MCP servers are stored in an executor's config file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum McpConfigFormat {
+    /// Nested JSON objects following the attribute path, e.g. `{"mcpServers": {...}}`
+    Nested,
+    /// A single top-level key joining the attribute path with dots, e.g. `{"amp.mcpServers": {...}}`
+    DottedKey,
+}
+
+/// Where an executor reads its MCP servers from
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct McpConfigLocation {
+    /// Config file on this machine. Left out of the generated TypeScript constants.
+    pub config_path: Option<String>,
+    pub attribute_path: Vec<String>,
+    pub format: McpConfigFormat,
+}
+
+/// What an executor supports, so callers can check before starting something that would fail
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ExecutorCapabilities {
+    /// Executor string accepted wherever an executor is configured, e.g. `claude` or `plan:amp`
+    pub executor_type: String,
+    pub label: String,
+    /// Follow-up prompts can be sent once an execution finishes
+    pub follow_up: bool,
+    /// Output is normalized into a conversation while the execution runs
+    pub streaming: bool,
+    /// Messages can be sent to the agent while it runs
+    pub send_input: bool,
+    pub plan_mode: PlanModeSupport,
+    /// The agent can read images referenced in the prompt
+    pub image_input: bool,
+    /// None if the executor has no MCP configuration we can edit
+    pub mcp: Option<McpConfigLocation>,
+    /// Programs that must be on the PATH to start the executor
+    pub required_binaries: Vec<String>,
 }
 
 // Constants for frontend
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct ExecutorConstants {
-    pub executor_types: Vec<ExecutorConfig>,
-    pub executor_labels: Vec<String>,
+    pub executors: Vec<ExecutorCapabilities>,
+}
+
+impl ExecutorConstants {
+    /// Built-in executors, their plan mode variants, then the custom executors of `config`
+    pub fn new(config: &Config) -> Self {
+        let builtin = ExecutorConfig::builtin();
+        let plan_variants = builtin
+            .iter()
+            .filter(|executor| {
+                executor.create_executor(config).plan_mode_support() == PlanModeSupport::Scaffolded
+            })
+            .map(|executor| ExecutorConfig::plan(executor.clone()))
+            .collect::<Vec<_>>();
+        let custom = usable_custom_executors(&config.custom_executors)
+            .into_iter()
+            .map(|definition| ExecutorConfig::Custom {
+                name: definition.name.clone(),
+            });
+
+        Self {
+            executors: builtin
+                .into_iter()
+                .chain(plan_variants)
+                .chain(custom)
+                .map(|executor| executor.capabilities(config))
+                .collect(),
+        }
+    }
 }
 
 impl FromStr for ExecutorConfig {
@@ -434,25 +1044,220 @@ impl FromStr for ExecutorConfig {
             "setup-script" => Ok(ExecutorConfig::SetupScript {
                 script: "setup script".to_string(),
             }),
-            _ => Err(format!("Unknown executor type: {}", s)),
+            _ => {
+                if let Some(spec) = s.strip_prefix(REPLAY_EXECUTOR_PREFIX) {
+                    let (fixture, speed) = parse_replay_executor(spec)?;
+                    return Ok(ExecutorConfig::Replay { fixture, speed });
+                }
+                if let Some(executor) = s.strip_prefix(PLAN_EXECUTOR_PREFIX) {
+                    return Ok(ExecutorConfig::plan(executor.parse()?));
+                }
+                match s.strip_prefix(CUSTOM_EXECUTOR_PREFIX) {
+                    Some(name) if !name.is_empty() => Ok(ExecutorConfig::Custom {
+                        name: name.to_string(),
+                    }),
+                    _ => Err(format!("Unknown executor type: {}", s)),
+                }
+            }
         }
     }
 }
 
 impl ExecutorConfig {
-    pub fn create_executor(&self) -> Box<dyn Executor> {
+    /// Run an executor in plan mode. Claude keeps its native `claude-plan` form.
+    pub fn plan(executor: ExecutorConfig) -> Self {
+        match executor {
+            ExecutorConfig::Claude | ExecutorConfig::ClaudePlan => ExecutorConfig::ClaudePlan,
+            ExecutorConfig::Plan { executor } => ExecutorConfig::plan(*executor),
+            executor => ExecutorConfig::Plan {
+                executor: Box::new(executor),
+            },
+        }
+    }
+
+    /// Built-in coding agents in the order they're offered to users
+    pub fn builtin() -> Vec<ExecutorConfig> {
+        vec![
+            ExecutorConfig::Echo,
+            ExecutorConfig::Claude,
+            ExecutorConfig::ClaudePlan,
+            ExecutorConfig::Amp,
+            ExecutorConfig::Gemini,
+            ExecutorConfig::CharmOpencode,
+            ExecutorConfig::ClaudeCodeRouter,
+            ExecutorConfig::SstOpencode,
+        ]
+    }
+
+    /// Describe what this executor supports, with the custom executors of `config`
+    pub fn capabilities(&self, config: &Config) -> ExecutorCapabilities {
+        let executor = self.create_executor(config);
+        let custom = match self {
+            ExecutorConfig::Custom { name } => find_custom_executor(&config.custom_executors, name),
+            _ => None,
+        };
+
+        let (follow_up, streaming, image_input, required_binaries) = match self {
+            ExecutorConfig::Echo | ExecutorConfig::SetupScript { .. } => {
+                (false, false, false, vec![])
+            }
+            ExecutorConfig::Claude
+            | ExecutorConfig::ClaudePlan
+            | ExecutorConfig::ClaudeCodeRouter => (true, true, true, vec!["npx".to_string()]),
+            ExecutorConfig::Gemini => (true, true, true, vec!["npx".to_string()]),
+            ExecutorConfig::Amp | ExecutorConfig::SstOpencode => {
+                (true, true, false, vec!["npx".to_string()])
+            }
+            // Follow-ups start a fresh run, since the CLI can't resume sessions
+            ExecutorConfig::CharmOpencode => (true, true, false, vec!["opencode".to_string()]),
+            ExecutorConfig::Custom { .. } => match custom {
+                Some(definition) => (
+                    definition.follow_up_command.is_some(),
+                    definition.normalizer != CustomLogNormalizer::PlainText,
+                    false,
+                    definition
+                        .command
+                        .split_whitespace()
+                        .next()
+                        .map(|program| vec![program.to_string()])
+                        .unwrap_or_default(),
+                ),
+                None => (false, false, false, vec![]),
+            },
+            ExecutorConfig::Plan { executor } => {
+                let inner = executor.capabilities(config);
+                (
+                    inner.follow_up,
+                    inner.streaming,
+                    inner.image_input,
+                    inner.required_binaries,
+                )
+            }
+            // Streams like the recorded executor, without needing its CLI
+            ExecutorConfig::Replay { fixture, .. } => {
+                let streaming = ReplayFixture::load(fixture)
+                    .and_then(|fixture| fixture.recorded_executor())
+                    .map(|recorded| recorded.capabilities(config).streaming)
+                    .unwrap_or(false);
+                (true, streaming, false, vec![])
+            }
+        };
+
+        let label = match self {
+            ExecutorConfig::Custom { name } => custom
+                .map(|definition| definition.display_label().to_string())
+                .unwrap_or_else(|| name.clone()),
+            ExecutorConfig::Plan { executor } => {
+                format!("{} Plan", executor.capabilities(config).label)
+            }
+            other => other.display_name().to_string(),
+        };
+
+        ExecutorCapabilities {
+            executor_type: self.to_string(),
+            label,
+            follow_up,
+            streaming,
+            // Only executors that read stdin while running format input messages
+            send_input: executor.format_input_message("").is_some(),
+            plan_mode: executor.plan_mode_support(),
+            image_input,
+            mcp: self.mcp_config(),
+            required_binaries,
+        }
+    }
+
+    /// Whether executions of this executor present a plan instead of making changes
+    pub fn is_plan_mode(&self) -> bool {
+        matches!(
+            self,
+            ExecutorConfig::ClaudePlan | ExecutorConfig::Plan { .. }
+        )
+    }
+
+    /// Create the executor with the custom executors, prompt template and tool approvals
+    /// of `config`
+    pub fn create_executor(&self, config: &Config) -> Box<dyn Executor> {
+        self.create_executor_with_args(config, None)
+    }
+
+    /// Create the executor with extra CLI arguments appended to its command line.
+    /// Echo and setup scripts ignore the arguments.
+    pub fn create_executor_with_args(
+        &self,
+        config: &Config,
+        extra_args: Option<String>,
+    ) -> Box<dyn Executor> {
         match self {
             ExecutorConfig::Echo => Box::new(EchoExecutor),
-            ExecutorConfig::Claude => Box::new(ClaudeExecutor::new()),
-            ExecutorConfig::ClaudePlan => Box::new(ClaudeExecutor::new_plan_mode()),
-            ExecutorConfig::Amp => Box::new(AmpExecutor),
-            ExecutorConfig::Gemini => Box::new(GeminiExecutor),
-            ExecutorConfig::ClaudeCodeRouter => Box::new(CCRExecutor::new()),
-            ExecutorConfig::CharmOpencode => Box::new(CharmOpencodeExecutor),
-            ExecutorConfig::SstOpencode => Box::new(SstOpencodeExecutor::new()),
+            ExecutorConfig::Claude => Box::new(
+                configured(ClaudeExecutor::new(), config, extra_args)
+                    .with_tool_approvals(config.tool_approvals.clone()),
+            ),
+            ExecutorConfig::ClaudePlan => Box::new(
+                configured(ClaudeExecutor::new_plan_mode(), config, extra_args)
+                    .with_tool_approvals(config.tool_approvals.clone()),
+            ),
+            ExecutorConfig::Amp => Box::new(configured(AmpExecutor::default(), config, extra_args)),
+            ExecutorConfig::Gemini => {
+                Box::new(configured(GeminiExecutor::default(), config, extra_args))
+            }
+            ExecutorConfig::ClaudeCodeRouter => Box::new(
+                configured(CCRExecutor::new(), config, extra_args)
+                    .with_tool_approvals(config.tool_approvals.clone()),
+            ),
+            ExecutorConfig::CharmOpencode => Box::new(configured(
+                CharmOpencodeExecutor::default(),
+                config,
+                extra_args,
+            )),
+            ExecutorConfig::SstOpencode => {
+                Box::new(configured(SstOpencodeExecutor::new(), config, extra_args))
+            }
             ExecutorConfig::SetupScript { script } => {
                 Box::new(SetupScriptExecutor::new(script.clone()))
             }
+            ExecutorConfig::Custom { name } => {
+                let definition = find_custom_executor(&config.custom_executors, name).cloned();
+                Box::new(configured(
+                    CustomExecutor::new(name.clone(), definition),
+                    config,
+                    extra_args,
+                ))
+            }
+            ExecutorConfig::Plan { executor } => executor.create_plan_executor(config, extra_args),
+            ExecutorConfig::Replay { fixture, speed } => {
+                Box::new(ReplayExecutor::new(fixture.clone(), *speed, config))
+            }
+        }
+    }
+
+    /// Create the executor in plan mode. Executors that can't plan are created as usual;
+    /// starting them in plan mode is rejected before it gets here.
+    fn create_plan_executor(
+        &self,
+        config: &Config,
+        extra_args: Option<String>,
+    ) -> Box<dyn Executor> {
+        match self {
+            ExecutorConfig::Claude | ExecutorConfig::ClaudePlan => Box::new(
+                configured(ClaudeExecutor::new_plan_mode(), config, extra_args)
+                    .with_tool_approvals(config.tool_approvals.clone()),
+            ),
+            ExecutorConfig::Amp => {
+                Box::new(configured(AmpExecutor::default(), config, extra_args).with_plan_mode())
+            }
+            ExecutorConfig::Gemini => {
+                Box::new(configured(GeminiExecutor::default(), config, extra_args).with_plan_mode())
+            }
+            ExecutorConfig::CharmOpencode => Box::new(
+                configured(CharmOpencodeExecutor::default(), config, extra_args).with_plan_mode(),
+            ),
+            ExecutorConfig::SstOpencode => Box::new(
+                configured(SstOpencodeExecutor::new(), config, extra_args).with_plan_mode(),
+            ),
+            ExecutorConfig::Plan { executor } => executor.create_plan_executor(config, extra_args),
+            other => other.create_executor_with_args(config, extra_args),
         }
     }
 
@@ -484,30 +1289,41 @@ impl ExecutorConfig {
                 }
             }
             ExecutorConfig::SetupScript { .. } => None,
+            ExecutorConfig::Custom { .. } => None,
+            ExecutorConfig::Plan { executor } => executor.config_path(),
+            ExecutorConfig::Replay { .. } => None,
         }
     }
 
-    /// Get the JSON attribute path for MCP servers in the config file
-    pub fn mcp_attribute_path(&self) -> Option<Vec<&'static str>> {
-        match self {
-            ExecutorConfig::Echo => None, // Echo doesn't support MCP
-            ExecutorConfig::CharmOpencode => Some(vec!["mcpServers"]),
-            ExecutorConfig::SstOpencode => Some(vec!["mcp"]),
-            ExecutorConfig::Claude => Some(vec!["mcpServers"]),
-            ExecutorConfig::ClaudePlan => Some(vec!["mcpServers"]),
-            ExecutorConfig::Amp => Some(vec!["amp", "mcpServers"]), // Nested path for Amp
-            ExecutorConfig::Gemini => Some(vec!["mcpServers"]),
-            ExecutorConfig::ClaudeCodeRouter => Some(vec!["mcpServers"]),
-            ExecutorConfig::SetupScript { .. } => None, // Setup scripts don't support MCP
-        }
+    /// Where MCP servers are configured for this executor, if we know how to edit them
+    pub fn mcp_config(&self) -> Option<McpConfigLocation> {
+        let (attribute_path, format): (&[&str], _) = match self {
+            ExecutorConfig::Echo => return None, // Echo doesn't support MCP
+            ExecutorConfig::CharmOpencode => (&["mcpServers"], McpConfigFormat::Nested),
+            ExecutorConfig::SstOpencode => (&["mcp"], McpConfigFormat::Nested),
+            ExecutorConfig::Claude
+            | ExecutorConfig::ClaudePlan
+            | ExecutorConfig::ClaudeCodeRouter
+            | ExecutorConfig::Gemini => (&["mcpServers"], McpConfigFormat::Nested),
+            ExecutorConfig::Amp => (&["amp", "mcpServers"], McpConfigFormat::DottedKey),
+            ExecutorConfig::SetupScript { .. } => return None, // Setup scripts don't support MCP
+            ExecutorConfig::Custom { .. } => return None, // No known config file to write MCP servers to
+            ExecutorConfig::Plan { executor } => return executor.mcp_config(),
+            ExecutorConfig::Replay { .. } => return None, // Nothing runs that could use MCP servers
+        };
+
+        Some(McpConfigLocation {
+            config_path: self
+                .config_path()
+                .map(|path| path.to_string_lossy().to_string()),
+            attribute_path: attribute_path.iter().map(|part| part.to_string()).collect(),
+            format,
+        })
     }
 
     /// Check if this executor supports MCP configuration
     pub fn supports_mcp(&self) -> bool {
-        !matches!(
-            self,
-            ExecutorConfig::Echo | ExecutorConfig::SetupScript { .. }
-        )
+        self.mcp_config().is_some()
     }
 
     /// Get the display name for this executor
@@ -522,6 +1338,9 @@ impl ExecutorConfig {
             ExecutorConfig::Gemini => "Gemini",
             ExecutorConfig::ClaudeCodeRouter => "Claude Code Router",
             ExecutorConfig::SetupScript { .. } => "Setup Script",
+            ExecutorConfig::Custom { .. } => "Custom",
+            ExecutorConfig::Plan { .. } => "Plan Mode",
+            ExecutorConfig::Replay { .. } => "Replay",
         }
     }
 }
@@ -538,40 +1357,110 @@ impl std::fmt::Display for ExecutorConfig {
             ExecutorConfig::CharmOpencode => "charm-opencode",
             ExecutorConfig::ClaudeCodeRouter => "claude-code-router",
             ExecutorConfig::SetupScript { .. } => "setup-script",
+            ExecutorConfig::Custom { name } => {
+                return write!(f, "{}{}", CUSTOM_EXECUTOR_PREFIX, name);
+            }
+            ExecutorConfig::Plan { executor } => {
+                return write!(f, "{}{}", PLAN_EXECUTOR_PREFIX, executor);
+            }
+            ExecutorConfig::Replay { fixture, speed } => {
+                write!(f, "{}{}", REPLAY_EXECUTOR_PREFIX, fixture)?;
+                if let Some(speed) = speed {
+                    write!(f, "{}{}", REPLAY_SPEED_SEPARATOR, speed)?;
+                }
+                return Ok(());
+            }
         };
         write!(f, "{}", s)
     }
 }
 
+/// Stream a child's stdout and stderr to the database in background tasks
+pub fn spawn_output_streaming(
+    child: &mut command_group::AsyncGroupChild,
+    pool: &sqlx::SqlitePool,
+    config: &Config,
+    attempt_id: Uuid,
+    execution_process_id: Uuid,
+) {
+    let stdout = child
+        .inner()
+        .stdout
+        .take()
+        .expect("Failed to take stdout from child process");
+    let stderr = child
+        .inner()
+        .stderr
+        .take()
+        .expect("Failed to take stderr from child process");
+
+    let pool_clone1 = pool.clone();
+    let pool_clone2 = pool.clone();
+
+    tokio::spawn(stream_output_to_db(
+        stdout,
+        pool_clone1,
+        config.clone(),
+        attempt_id,
+        execution_process_id,
+        true,
+    ));
+    tokio::spawn(stream_output_to_db(
+        stderr,
+        pool_clone2,
+        config.clone(),
+        attempt_id,
+        execution_process_id,
+        false,
+    ));
+}
+
 /// Stream output from a child process to the database
 pub async fn stream_output_to_db(
     output: impl tokio::io::AsyncRead + Unpin,
     pool: sqlx::SqlitePool,
+    config: Config,
     attempt_id: Uuid,
     execution_process_id: Uuid,
     is_stdout: bool,
 ) {
     if is_stdout {
-        stream_stdout_to_db(output, pool, attempt_id, execution_process_id).await;
+        stream_stdout_to_db(
+            output,
+            pool,
+            config,
+            attempt_id,
+            execution_process_id,
+            parse_session_id_from_line,
+        )
+        .await;
     } else {
         stream_stderr_to_db(output, pool, attempt_id, execution_process_id).await;
     }
 }
 
 /// Stream stdout from a child process to the database (immediate updates)
-async fn stream_stdout_to_db(
+///
+/// `parse_session_id` is tried on each line until it yields the executor's session ID.
+pub async fn stream_stdout_to_db(
     output: impl tokio::io::AsyncRead + Unpin,
     pool: sqlx::SqlitePool,
+    config: Config,
     attempt_id: Uuid,
     execution_process_id: Uuid,
+    parse_session_id: impl Fn(&str) -> Option<String>,
 ) {
-    use crate::models::{execution_process::ExecutionProcess, executor_session::ExecutorSession};
+    use crate::{
+        executors::{input::ExecutionInput, streaming::EntryPatcher},
+        models::{execution_process::ExecutionProcess, executor_session::ExecutorSession},
+    };
 
     let mut reader = BufReader::new(output);
     let mut line = String::new();
     let mut accumulated_output = String::new();
     let mut update_counter = 0;
     let mut session_id_parsed = false;
+    let patcher = EntryPatcher::for_process(&pool, &config, execution_process_id).await;
 
     loop {
         line.clear();
@@ -580,7 +1469,7 @@ async fn stream_stdout_to_db(
             Ok(_) => {
                 // Parse session ID from the first JSONL line
                 if !session_id_parsed {
-                    if let Some(external_session_id) = parse_session_id_from_line(&line) {
+                    if let Some(external_session_id) = parse_session_id(&line) {
                         if let Err(e) = ExecutorSession::update_session_id(
                             &pool,
                             execution_process_id,
@@ -603,6 +1492,10 @@ async fn stream_stdout_to_db(
                         session_id_parsed = true;
                     }
                 }
+                if let Some(patcher) = patcher.as_ref() {
+                    patcher.push_line(&line);
+                }
+                ExecutionInput::observe_output(execution_process_id, &line).await;
                 accumulated_output.push_str(&line);
                 update_counter += 1;
 
@@ -648,6 +1541,17 @@ async fn stream_stdout_to_db(
             tracing::error!("Failed to flush stdout for attempt {}: {}", attempt_id, e);
         }
     }
+    if let Err(e) =
+        ExecutionProcess::flush_output(&pool, execution_process_id, OutputStream::Stdout).await
+    {
+        tracing::error!("Failed to flush stdout for attempt {}: {}", attempt_id, e);
+    }
+
+    // The process has exited, so release stdin if it was still open
+    ExecutionInput::close(execution_process_id).await;
+    if let Some(patcher) = patcher {
+        patcher.finish();
+    }
 }
 
 /// Stream stderr from a child process to the database (buffered with timeout)
@@ -704,6 +1608,15 @@ async fn stream_stderr_to_db(
     if !accumulated_output.is_empty() {
         flush_stderr_chunk(&pool, execution_process_id, &accumulated_output, attempt_id).await;
     }
+    if let Err(e) = crate::models::execution_process::ExecutionProcess::flush_output(
+        &pool,
+        execution_process_id,
+        OutputStream::Stderr,
+    )
+    .await
+    {
+        tracing::error!("Failed to flush stderr for attempt {}: {}", attempt_id, e);
+    }
 }
 
 /// Flush a chunk of stderr output to the database
@@ -930,7 +1843,7 @@ mod tests {
 
     #[test]
     fn test_amp_log_normalization() {
-        let amp_executor = AmpExecutor;
+        let amp_executor = AmpExecutor::default();
         let amp_logs = r#"{"type":"initial","threadID":"T-f8f7fec0-b330-47ab-b63a-b72c42f1ef6a"}
 {"type":"messages","messages":[[0,{"role":"user","content":[{"type":"text","text":"Task title: Create and start should open task\nTask description: When I press 'create & start' on task creation dialog it should then open the task in the sidebar"}],"meta":{"sentAt":1751544747623}}]],"toolResults":[]}
 {"type":"messages","messages":[[1,{"role":"assistant","content":[{"type":"thinking","thinking":"The user wants to implement a feature where pressing \"create & start\" on the task creation dialog should open the task in the sidebar."},{"type":"text","text":"I'll help you implement the \"create & start\" functionality. Let me explore the codebase to understand the current task creation and sidebar structure."},{"type":"tool_use","id":"toolu_01FQqskzGAhZaZu8H6qSs5pV","name":"todo_write","input":{"todos":[{"id":"1","content":"Explore task creation dialog component","status":"todo","priority":"high"}]}}],"state":{"type":"complete","stopReason":"tool_use"}}]],"toolResults":[]}"#;
@@ -1039,4 +1952,209 @@ mod tests {
         // Should be the task description, not "Tool: Task with input: ..."
         assert_eq!(task_tool_use.content, "Find vibe-kanban projects");
     }
+
+    #[test]
+    fn test_extract_tagged_plan() {
+        let text =
+            "Scratch <plan>draft</plan>\nFinal answer:\n<plan>\n1. Add route\n2. Add test\n</plan>";
+        assert_eq!(
+            extract_tagged_plan(text),
+            Some("1. Add route\n2. Add test".to_string())
+        );
+        assert_eq!(extract_tagged_plan("<plan>unterminated"), None);
+        assert_eq!(extract_tagged_plan("<plan>  </plan>"), None);
+        assert_eq!(extract_tagged_plan("no plan here"), None);
+    }
+
+    #[test]
+    fn test_plan_executor_config_round_trip() {
+        for name in [
+            "plan:amp",
+            "plan:gemini",
+            "plan:charm-opencode",
+            "plan:sst-opencode",
+        ] {
+            let config: ExecutorConfig = name.parse().unwrap();
+            assert!(config.is_plan_mode());
+            assert_eq!(config.to_string(), name);
+        }
+
+        // Claude has a native plan mode, and nesting plan mode is a no-op
+        assert!(matches!(
+            "plan:claude".parse::<ExecutorConfig>(),
+            Ok(ExecutorConfig::ClaudePlan)
+        ));
+        assert_eq!(
+            ExecutorConfig::plan(ExecutorConfig::plan(ExecutorConfig::Amp)).to_string(),
+            "plan:amp"
+        );
+        assert!("plan:unknown".parse::<ExecutorConfig>().is_err());
+    }
+
+    #[test]
+    fn test_plan_mode_support() {
+        let support = |name: &str| {
+            name.parse::<ExecutorConfig>()
+                .unwrap()
+                .create_executor(&Config::default())
+                .plan_mode_support()
+        };
+        assert_eq!(support("claude-plan"), PlanModeSupport::Native);
+        assert_eq!(support("plan:gemini"), PlanModeSupport::Scaffolded);
+        assert_eq!(support("plan:echo"), PlanModeSupport::Unsupported);
+        assert_eq!(support("amp"), PlanModeSupport::Scaffolded);
+    }
+
+    #[test]
+    fn test_extract_scaffolded_plan_from_amp_logs() {
+        let amp_executor = AmpExecutor::default().with_plan_mode();
+        let amp_logs = r#"{"type":"initial","threadID":"T-f8f7fec0-b330-47ab-b63a-b72c42f1ef6a"}
+{"type":"messages","messages":[[1,{"role":"assistant","content":[{"type":"text","text":"Here is my plan.\n<plan>\n1. Add a route\n2. Cover it with a test\n</plan>"}],"state":{"type":"complete","stopReason":"end_turn"}}]],"toolResults":[]}"#;
+
+        assert_eq!(
+            amp_executor.extract_plan(amp_logs, "/tmp/test-worktree"),
+            Some("1. Add a route\n2. Cover it with a test".to_string())
+        );
+    }
+
+    #[test]
+    fn test_executor_capabilities() {
+        let constants = ExecutorConstants::new(&Config::default());
+        let capabilities = |executor_type: &str| {
+            constants
+                .executors
+                .iter()
+                .find(|c| c.executor_type == executor_type)
+                .cloned()
+                .unwrap()
+        };
+
+        let echo = capabilities("echo");
+        assert!(!echo.follow_up);
+        assert!(echo.mcp.is_none());
+
+        let claude = capabilities("claude");
+        assert!(claude.follow_up && claude.streaming && claude.send_input);
+        assert_eq!(claude.plan_mode, PlanModeSupport::Native);
+
+        let amp = capabilities("amp");
+        assert_eq!(amp.mcp.unwrap().format, McpConfigFormat::DottedKey);
+
+        // Plan variants are listed for scaffolded executors and inherit their capabilities
+        let gemini_plan = capabilities("plan:gemini");
+        assert_eq!(gemini_plan.label, "Gemini Plan");
+        assert!(gemini_plan.follow_up);
+        assert_eq!(gemini_plan.required_binaries, vec!["npx".to_string()]);
+        assert!(!constants
+            .executors
+            .iter()
+            .any(|c| c.executor_type == "plan:echo" || c.executor_type == "plan:claude"));
+    }
+
+    #[test]
+    fn test_normalize_timed_logs_fills_timestamps_and_durations() {
+        let logs = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Running the tests."}]}}
+{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"toolu_1","name":"Bash","input":{"command":"cargo test"}}]}}
+{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"toolu_1","content":"ok"}]}}
+{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"All tests pass."}]}}
+"#;
+        let start = DateTime::parse_from_rfc3339("2025-07-30T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let output = TimedOutput {
+            content: logs.to_string(),
+            line_times: [0, 2, 47, 50]
+                .into_iter()
+                .map(|seconds| start + chrono::Duration::seconds(seconds))
+                .collect(),
+        };
+
+        let conversation = ClaudeExecutor::new()
+            .normalize_timed_logs(&output, "/tmp/test-worktree")
+            .unwrap();
+        let entries = &conversation.entries;
+        assert_eq!(entries.len(), 4);
+        assert_eq!(
+            entries[0].timestamp.as_deref(),
+            Some("2025-07-30T10:00:00.000Z")
+        );
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| entry.duration_ms)
+                .collect::<Vec<_>>(),
+            vec![None, Some(2_000), Some(45_000), Some(3_000)]
+        );
+        // The test run took from the call until its result
+        assert!(matches!(
+            entries[1].entry_type,
+            NormalizedEntryType::ToolUse { .. }
+        ));
+        assert_eq!(entries[1].tool_duration_ms, Some(45_000));
+        assert_eq!(entries[0].tool_duration_ms, None);
+    }
+
+    #[test]
+    fn test_normalize_timed_logs_stamps_entries_with_their_own_line() {
+        // Repeated short messages, after a line the normalizer drops
+        let logs = r#"Service not running, starting service...
+{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"ok"}]}}
+{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"ok"}]}}
+"#;
+        let start = DateTime::parse_from_rfc3339("2025-07-30T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let output = TimedOutput {
+            content: logs.to_string(),
+            line_times: [0, 5, 9]
+                .into_iter()
+                .map(|seconds| start + chrono::Duration::seconds(seconds))
+                .collect(),
+        };
+
+        let conversation = CCRExecutor::new()
+            .normalize_timed_logs(&output, "/tmp/test-worktree")
+            .unwrap();
+        assert_eq!(
+            conversation
+                .entries
+                .iter()
+                .map(|entry| entry.timestamp.as_deref())
+                .collect::<Vec<_>>(),
+            vec![
+                Some("2025-07-30T10:00:05.000Z"),
+                Some("2025-07-30T10:00:09.000Z")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tool_calls_without_results_last_until_the_next_entry() {
+        let entry = |timestamp: &str, entry_type: NormalizedEntryType| NormalizedEntry {
+            timestamp: Some(timestamp.to_string()),
+            entry_type,
+            content: String::new(),
+            metadata: None,
+            duration_ms: None,
+            tool_duration_ms: None,
+        };
+        let mut entries = vec![
+            entry(
+                "2025-07-30T10:00:00.000Z",
+                NormalizedEntryType::ToolUse {
+                    tool_name: "run_shell_command".to_string(),
+                    action_type: ActionType::command_run("npm test"),
+                    tool_use_id: None,
+                },
+            ),
+            entry(
+                "2025-07-30T10:00:12.500Z",
+                NormalizedEntryType::AssistantMessage,
+            ),
+        ];
+
+        fill_durations(&mut entries);
+        assert_eq!(entries[0].tool_duration_ms, Some(12_500));
+        assert_eq!(entries[1].duration_ms, Some(12_500));
+    }
 }
@@ -1,21 +1,29 @@
-use std::path::Path;
+use std::{collections::BTreeMap, path::Path};
 
 use async_trait::async_trait;
-use command_group::{AsyncCommandGroup, AsyncGroupChild};
+use command_group::AsyncGroupChild;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
     executor::{
-        ActionType, Executor, ExecutorError, NormalizedConversation, NormalizedEntry,
-        NormalizedEntryType,
+        format_todo_list, link_tool_results, plan_mode_prompt, ActionType, Executor, ExecutorError,
+        ExtraArgs, FileEditHunk, NormalizedConversation, NormalizedEntry, NormalizedEntryType,
+        PlanModeSupport, PromptTemplate, TimedLines, TodoItem, TokenUsage,
     },
+    executors::prompt::task_prompt,
     models::task::Task,
-    utils::shell::get_shell_command,
+    utils::shell::{append_extra_args, get_shell_command},
 };
 
 /// An executor that uses Amp to process tasks
-pub struct AmpExecutor;
+#[derive(Default)]
+pub struct AmpExecutor {
+    extra_args: Option<String>,
+    prompt_template: Option<String>,
+    /// Ask for a plan through prompt scaffolding instead of making changes
+    plan_mode: bool,
+}
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 #[serde(tag = "type")]
@@ -105,13 +113,19 @@ impl AmpJson {
         }
     }
 
+    /// Entries of the message, stamped with `timestamp` where the message doesn't say when it
+    /// was sent
     pub fn to_normalized_entries(
         &self,
         executor: &AmpExecutor,
         worktree_path: &str,
+        timestamp: Option<String>,
     ) -> Vec<NormalizedEntry> {
         match self {
-            AmpJson::Messages { messages, .. } => {
+            AmpJson::Messages {
+                messages,
+                tool_results,
+            } => {
                 if self.has_streaming_content() {
                     return vec![];
                 }
@@ -120,13 +134,26 @@ impl AmpJson {
                 for (_index, message) in messages {
                     let role = &message.role;
                     for content_item in &message.content {
-                        if let Some(entry) =
-                            content_item.to_normalized_entry(role, message, executor, worktree_path)
-                        {
+                        if let Some(entry) = content_item.to_normalized_entry(
+                            role,
+                            message,
+                            executor,
+                            worktree_path,
+                            timestamp.clone(),
+                        ) {
                             entries.push(entry);
                         }
                     }
                 }
+                // Results of tools that ran during this turn: `{"toolUseID": ..., "run": {...}}`
+                for result in tool_results {
+                    if let (Some(tool_use_id), Some(run)) = (
+                        result.get("toolUseID").and_then(|id| id.as_str()),
+                        result.get("run"),
+                    ) {
+                        entries.push(tool_result_entry(tool_use_id, run, timestamp.clone()));
+                    }
+                }
                 entries
             }
             _ => vec![],
@@ -141,10 +168,15 @@ impl AmpContentItem {
         message: &AmpMessage,
         executor: &AmpExecutor,
         worktree_path: &str,
+        line_timestamp: Option<String>,
     ) -> Option<NormalizedEntry> {
         use serde_json::Value;
 
-        let timestamp = message.meta.as_ref().map(|meta| meta.sent_at.to_string());
+        let timestamp = message
+            .meta
+            .as_ref()
+            .map(|meta| meta.sent_at.to_string())
+            .or(line_timestamp);
 
         match self {
             AmpContentItem::Text { text } => {
@@ -158,6 +190,8 @@ impl AmpContentItem {
                     entry_type,
                     content: text.clone(),
                     metadata: Some(serde_json::to_value(self).unwrap_or(Value::Null)),
+                    duration_ms: None,
+                    tool_duration_ms: None,
                 })
             }
             AmpContentItem::Thinking { thinking } => Some(NormalizedEntry {
@@ -165,8 +199,10 @@ impl AmpContentItem {
                 entry_type: NormalizedEntryType::Thinking,
                 content: thinking.clone(),
                 metadata: Some(serde_json::to_value(self).unwrap_or(Value::Null)),
+                duration_ms: None,
+                tool_duration_ms: None,
             }),
-            AmpContentItem::ToolUse { name, input, .. } => {
+            AmpContentItem::ToolUse { id, name, input } => {
                 let action_type = executor.extract_action_type(name, input, worktree_path);
                 let content =
                     executor.generate_concise_content(name, input, &action_type, worktree_path);
@@ -176,16 +212,80 @@ impl AmpContentItem {
                     entry_type: NormalizedEntryType::ToolUse {
                         tool_name: name.clone(),
                         action_type,
+                        tool_use_id: Some(id.clone()),
                     },
                     content,
                     metadata: Some(serde_json::to_value(self).unwrap_or(Value::Null)),
+                    duration_ms: None,
+                    tool_duration_ms: None,
                 })
             }
-            AmpContentItem::ToolResult { .. } => None,
+            AmpContentItem::ToolResult { tool_use_id, run } => {
+                Some(tool_result_entry(tool_use_id, run, timestamp))
+            }
         }
     }
 }
 
+/// A tool result from its `run`: `{"status": "done" | "error" | ..., "result": ..., "error": ...}`.
+/// Commands report `{"output": ..., "exitCode": ...}` as their result.
+fn tool_result_entry(
+    tool_use_id: &str,
+    run: &serde_json::Value,
+    timestamp: Option<String>,
+) -> NormalizedEntry {
+    use serde_json::Value;
+
+    let is_error = run.get("status").and_then(|s| s.as_str()) == Some("error");
+    let result = run.get("result").unwrap_or(&Value::Null);
+    let detail = if is_error {
+        run.get("error").unwrap_or(result)
+    } else {
+        result
+    };
+    let content = match detail {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        value => match value
+            .get("output")
+            .or_else(|| value.get("message"))
+            .and_then(|o| o.as_str())
+        {
+            Some(text) => text.to_string(),
+            None => value.to_string(),
+        },
+    };
+    let exit_code = result
+        .get("exitCode")
+        .and_then(|code| code.as_i64())
+        .map(|code| code as i32);
+
+    NormalizedEntry {
+        timestamp,
+        entry_type: NormalizedEntryType::ToolResult {
+            tool_use_id: tool_use_id.to_string(),
+            is_error: is_error || exit_code.is_some_and(|code| code != 0),
+            exit_code,
+        },
+        content,
+        metadata: Some(run.clone()),
+        duration_ms: None,
+        tool_duration_ms: None,
+    }
+}
+
+impl ExtraArgs for AmpExecutor {
+    fn extra_args_mut(&mut self) -> &mut Option<String> {
+        &mut self.extra_args
+    }
+}
+
+impl PromptTemplate for AmpExecutor {
+    fn prompt_template_mut(&mut self) -> &mut Option<String> {
+        &mut self.prompt_template
+    }
+}
+
 #[async_trait]
 impl Executor for AmpExecutor {
     async fn spawn(
@@ -203,27 +303,17 @@ impl Executor for AmpExecutor {
 
         use tokio::{io::AsyncWriteExt, process::Command};
 
-        let prompt = if let Some(task_description) = task.description {
-            format!(
-                r#"project_id: {}
-            
-Task title: {}
-Task description: {}"#,
-                task.project_id, task.title, task_description
-            )
-        } else {
-            format!(
-                r#"project_id: {}
-            
-Task title: {}"#,
-                task.project_id, task.title
-            )
-        };
+        let prompt =
+            task_prompt(pool, &task, worktree_path, self.prompt_template.as_deref()).await?;
+        let prompt = self.scaffold_prompt(prompt);
 
         // Use shell command for cross-platform compatibility
         let (shell_cmd, shell_arg) = get_shell_command();
         // --format=jsonl is deprecated in latest versions of Amp CLI
-        let amp_command = "npx @sourcegraph/amp@0.0.1752148945-gd8844f --format=jsonl";
+        let amp_command = append_extra_args(
+            "npx @sourcegraph/amp@0.0.1752148945-gd8844f --format=jsonl",
+            self.extra_args.as_deref(),
+        );
 
         let mut command = Command::new(shell_cmd);
         command
@@ -233,16 +323,15 @@ Task title: {}"#,
             .stderr(Stdio::piped())
             .current_dir(worktree_path)
             .arg(shell_arg)
-            .arg(amp_command);
+            .arg(&amp_command);
 
-        let mut child = command
-            .group_spawn() // Create new process group so we can kill entire tree
-            .map_err(|e| {
-                crate::executor::SpawnContext::from_command(&command, "Amp")
-                    .with_task(task_id, Some(task.title.clone()))
-                    .with_context("Amp CLI execution for new task")
-                    .spawn_error(e)
-            })?;
+        // Create new process group so we can kill entire tree
+        let mut child = crate::utils::process_env::group_spawn(&mut command).map_err(|e| {
+            crate::executor::SpawnContext::from_command(&command, "Amp")
+                .with_task(task_id, Some(task.title.clone()))
+                .with_context("Amp CLI execution for new task")
+                .spawn_error(e)
+        })?;
 
         // feed the prompt in, then close the pipe so `amp` sees EOF
         if let Some(mut stdin) = child.inner().stdin.take() {
@@ -267,9 +356,12 @@ Task title: {}"#,
 
         // Use shell command for cross-platform compatibility
         let (shell_cmd, shell_arg) = get_shell_command();
-        let amp_command = format!(
-            "npx @sourcegraph/amp@0.0.1752148945-gd8844f threads continue {} --format=jsonl",
-            session_id
+        let amp_command = append_extra_args(
+            &format!(
+                "npx @sourcegraph/amp@0.0.1752148945-gd8844f threads continue {} --format=jsonl",
+                session_id
+            ),
+            self.extra_args.as_deref(),
         );
 
         let mut command = Command::new(shell_cmd);
@@ -282,7 +374,7 @@ Task title: {}"#,
             .arg(shell_arg)
             .arg(&amp_command);
 
-        let mut child = command.group_spawn().map_err(|e| {
+        let mut child = crate::utils::process_env::group_spawn(&mut command).map_err(|e| {
             crate::executor::SpawnContext::from_command(&command, "Amp")
                 .with_context(format!(
                     "Amp CLI followup execution for thread {}",
@@ -292,6 +384,7 @@ Task title: {}"#,
         })?;
 
         // Feed the prompt in, then close the pipe so amp sees EOF
+        let prompt = self.scaffold_prompt(prompt.to_string());
         if let Some(mut stdin) = child.inner().stdin.take() {
             stdin.write_all(prompt.as_bytes()).await.map_err(|e| {
                 crate::executor::SpawnContext::from_command(&command, "Amp")
@@ -314,15 +407,19 @@ Task title: {}"#,
         Ok(child)
     }
 
-    fn normalize_logs(
+    fn plan_mode_support(&self) -> PlanModeSupport {
+        PlanModeSupport::Scaffolded
+    }
+
+    fn normalize_lines(
         &self,
-        logs: &str,
+        lines: TimedLines<'_>,
         worktree_path: &str,
     ) -> Result<NormalizedConversation, String> {
-        let mut entries = Vec::new();
+        let mut entries: Vec<NormalizedEntry> = Vec::new();
         let mut session_id = None;
 
-        for line in logs.lines() {
+        for (line, timestamp) in lines.iter() {
             let trimmed = line.trim();
             if trimmed.is_empty() {
                 continue;
@@ -334,10 +431,12 @@ Task title: {}"#,
                 Err(_) => {
                     // If line isn't valid JSON, add it as raw text
                     entries.push(NormalizedEntry {
-                        timestamp: None,
+                        timestamp,
                         entry_type: NormalizedEntryType::SystemMessage,
                         content: format!("Raw output: {}", trimmed),
                         metadata: None,
+                        duration_ms: None,
+                        tool_duration_ms: None,
                     });
                     continue;
                 }
@@ -352,11 +451,13 @@ Task title: {}"#,
 
             // Process the message if it's a type we care about
             if amp_message.should_process() {
-                let new_entries = amp_message.to_normalized_entries(self, worktree_path);
+                let new_entries = amp_message.to_normalized_entries(self, worktree_path, timestamp);
                 entries.extend(new_entries);
             }
         }
 
+        link_tool_results(&mut entries);
+
         Ok(NormalizedConversation {
             entries,
             session_id,
@@ -365,9 +466,69 @@ Task title: {}"#,
             summary: None,
         })
     }
+
+    fn extract_usage(&self, logs: &str) -> Option<TokenUsage> {
+        use serde_json::Value;
+
+        // Messages are re-sent while streaming, so keep the latest usage per message index
+        let mut message_usage: BTreeMap<u64, TokenUsage> = BTreeMap::new();
+        let mut event_usage: Option<TokenUsage> = None;
+
+        for line in logs.lines() {
+            let Ok(json) = serde_json::from_str::<Value>(line.trim()) else {
+                continue;
+            };
+
+            match json.get("type").and_then(|t| t.as_str()) {
+                Some("messages") => {
+                    let Some(messages) = json.get("messages").and_then(|m| m.as_array()) else {
+                        continue;
+                    };
+                    for entry in messages {
+                        let index = entry.get(0).and_then(|i| i.as_u64());
+                        let usage = entry.get(1).and_then(|m| m.get("usage"));
+                        if let (Some(index), Some(usage)) = (index, usage) {
+                            message_usage.insert(index, TokenUsage::from_usage_json(usage));
+                        }
+                    }
+                }
+                Some("token-usage") => {
+                    let usage = json.get("usage").unwrap_or(&json);
+                    event_usage
+                        .get_or_insert_with(TokenUsage::default)
+                        .add(&TokenUsage::from_usage_json(usage));
+                }
+                _ => {}
+            }
+        }
+
+        if message_usage.is_empty() {
+            return event_usage;
+        }
+
+        let mut total = TokenUsage::default();
+        for usage in message_usage.values() {
+            total.add(usage);
+        }
+        Some(total)
+    }
 }
 
 impl AmpExecutor {
+    /// Present a plan instead of making changes
+    pub fn with_plan_mode(mut self) -> Self {
+        self.plan_mode = true;
+        self
+    }
+
+    fn scaffold_prompt(&self, prompt: String) -> String {
+        if self.plan_mode {
+            plan_mode_prompt(&prompt)
+        } else {
+            prompt
+        }
+    }
+
     /// Convert absolute paths to relative paths based on worktree path
     fn make_path_relative(&self, path: &str, worktree_path: &str) -> String {
         let path_obj = Path::new(path);
@@ -397,44 +558,18 @@ impl AmpExecutor {
         match action_type {
             ActionType::FileRead { path } => format!("`{}`", path),
             ActionType::FileWrite { path } => format!("`{}`", path),
-            ActionType::CommandRun { command } => format!("`{}`", command),
+            ActionType::FileEdit { path, .. } => format!("`{}`", path),
+            ActionType::CommandRun { command, .. } => format!("`{}`", command),
             ActionType::Search { query } => format!("`{}`", query),
             ActionType::WebFetch { url } => format!("`{}`", url),
             ActionType::PlanPresentation { plan } => format!("Plan Presentation: `{}`", plan),
             ActionType::TaskCreate { description } => description.clone(),
+            ActionType::TodoUpdate { todos } => format_todo_list(todos),
             ActionType::Other { description: _ } => {
                 // For other tools, try to extract key information or fall back to tool name
                 match tool_name.to_lowercase().as_str() {
                     "todowrite" | "todoread" | "todo_write" | "todo_read" => {
-                        if let Some(todos) = input.get("todos").and_then(|t| t.as_array()) {
-                            let mut todo_items = Vec::new();
-                            for todo in todos {
-                                if let (Some(content), Some(status)) = (
-                                    todo.get("content").and_then(|c| c.as_str()),
-                                    todo.get("status").and_then(|s| s.as_str()),
-                                ) {
-                                    let emoji = match status {
-                                        "completed" => "✅",
-                                        "in_progress" | "in-progress" => "🔄",
-                                        "pending" | "todo" => "⏳",
-                                        _ => "📝",
-                                    };
-                                    let priority = todo
-                                        .get("priority")
-                                        .and_then(|p| p.as_str())
-                                        .unwrap_or("medium");
-                                    todo_items
-                                        .push(format!("{} {} ({})", emoji, content, priority));
-                                }
-                            }
-                            if !todo_items.is_empty() {
-                                format!("TODO List:\n{}", todo_items.join("\n"))
-                            } else {
-                                "Managing TODO list".to_string()
-                            }
-                        } else {
-                            "Managing TODO list".to_string()
-                        }
+                        format_todo_list(&TodoItem::from_input(input))
                     }
                     "ls" => {
                         if let Some(path) = input.get("path").and_then(|p| p.as_str()) {
@@ -559,29 +694,39 @@ impl AmpExecutor {
                 }
             }
             "edit_file" | "write" | "create_file" | "edit" | "multiedit" => {
-                if let Some(path) = input.get("path").and_then(|p| p.as_str()) {
-                    ActionType::FileWrite {
-                        path: self.make_path_relative(path, worktree_path),
-                    }
-                } else if let Some(file_path) = input.get("file_path").and_then(|p| p.as_str()) {
-                    ActionType::FileWrite {
-                        path: self.make_path_relative(file_path, worktree_path),
-                    }
-                } else {
-                    ActionType::Other {
+                let Some(path) = input
+                    .get("path")
+                    .or_else(|| input.get("file_path"))
+                    .and_then(|p| p.as_str())
+                else {
+                    return ActionType::Other {
                         description: "File write operation".to_string(),
-                    }
+                    };
+                };
+                let path = self.make_path_relative(path, worktree_path);
+
+                // edit_file names its text old_str/new_str, the Claude-style tools old_string/new_string
+                let hunk = |edit: &serde_json::Value| {
+                    FileEditHunk::from_input(edit, "old_str", "new_str")
+                        .or_else(|| FileEditHunk::from_input(edit, "old_string", "new_string"))
+                };
+                let edits = match input.get("edits").and_then(|edits| edits.as_array()) {
+                    Some(edits) => edits.iter().filter_map(hunk).collect(),
+                    None => hunk(input).into_iter().collect::<Vec<_>>(),
+                };
+                if edits.is_empty() {
+                    ActionType::FileWrite { path }
+                } else {
+                    ActionType::FileEdit { path, edits }
                 }
             }
             "bash" | "run_command" => {
-                if let Some(cmd) = input.get("cmd").and_then(|c| c.as_str()) {
-                    ActionType::CommandRun {
-                        command: cmd.to_string(),
-                    }
-                } else if let Some(command) = input.get("command").and_then(|c| c.as_str()) {
-                    ActionType::CommandRun {
-                        command: command.to_string(),
-                    }
+                if let Some(command) = input
+                    .get("cmd")
+                    .or_else(|| input.get("command"))
+                    .and_then(|c| c.as_str())
+                {
+                    ActionType::command_run(command)
                 } else {
                     ActionType::Other {
                         description: "Command execution".to_string(),
@@ -635,12 +780,13 @@ impl AmpExecutor {
             "ls" => ActionType::Other {
                 description: "List directory".to_string(),
             },
-            "todowrite" | "todoread" | "todo_write" | "todo_read" => ActionType::Other {
-                description: "Manage TODO list".to_string(),
+            "todowrite" | "todo_write" => ActionType::TodoUpdate {
+                todos: TodoItem::from_input(input),
             },
-            _ => ActionType::Other {
-                description: format!("Tool: {}", tool_name),
+            "todoread" | "todo_read" => ActionType::Other {
+                description: "Manage TODO list".to_string(),
             },
+            _ => ActionType::unknown_tool(tool_name),
         }
     }
 }
@@ -652,7 +798,7 @@ mod tests {
     #[test]
     fn test_filter_streaming_messages() {
         // Test logs that simulate the actual normalize_logs behavior
-        let amp_executor = AmpExecutor;
+        let amp_executor = AmpExecutor::default();
         let logs = r#"{"type":"messages","messages":[[7,{"role":"assistant","content":[{"type":"text","text":"Created all three files: test1.txt, test2.txt, and test3.txt"}],"state":{"type":"streaming"}}]],"toolResults":[]}
 {"type":"messages","messages":[[7,{"role":"assistant","content":[{"type":"text","text":"Created all three files: test1.txt, test2.txt, and test3.txt, each with a line of text."}],"state":{"type":"streaming"}}]],"toolResults":[]}
 {"type":"messages","messages":[[7,{"role":"assistant","content":[{"type":"text","text":"Created all three files: test1.txt, test2.txt, and test3.txt, each with a line of text."}],"state":{"type":"complete","stopReason":"end_turn"}}]],"toolResults":[]}"#;
@@ -673,10 +819,27 @@ mod tests {
         assert_eq!(assistant_messages[0].content, "Created all three files: test1.txt, test2.txt, and test3.txt, each with a line of text.");
     }
 
+    #[test]
+    fn test_extract_usage_keeps_latest_per_message() {
+        let amp_executor = AmpExecutor::default();
+        let logs = r#"{"type":"initial","threadID":"T-1"}
+{"type":"messages","messages":[[1,{"role":"assistant","content":[{"type":"text","text":"Work"}],"state":{"type":"streaming"},"usage":{"model":"claude-sonnet-4","inputTokens":5,"outputTokens":10,"cacheReadInputTokens":100}}]],"toolResults":[]}
+{"type":"messages","messages":[[1,{"role":"assistant","content":[{"type":"text","text":"Work done"}],"state":{"type":"complete"},"usage":{"model":"claude-sonnet-4","inputTokens":5,"outputTokens":40,"cacheReadInputTokens":100}}]],"toolResults":[]}
+{"type":"messages","messages":[[3,{"role":"assistant","content":[{"type":"text","text":"Done"}],"usage":{"model":"claude-sonnet-4","inputTokens":7,"outputTokens":3,"cacheCreationInputTokens":20}}]],"toolResults":[]}"#;
+
+        let usage = amp_executor.extract_usage(logs).unwrap();
+        assert_eq!(usage.model.as_deref(), Some("claude-sonnet-4"));
+        assert_eq!(usage.input_tokens, 12);
+        assert_eq!(usage.output_tokens, 43);
+        assert_eq!(usage.cache_creation_input_tokens, 20);
+        assert_eq!(usage.cache_read_input_tokens, 100);
+        assert_eq!(usage.cost_usd, None);
+    }
+
     #[test]
     fn test_filter_preserves_messages_without_state() {
         // Test that messages without state metadata are preserved (for compatibility)
-        let amp_executor = AmpExecutor;
+        let amp_executor = AmpExecutor::default();
         let logs = r#"{"type":"messages","messages":[[1,{"role":"assistant","content":[{"type":"text","text":"Regular message"}]}]],"toolResults":[]}"#;
 
         let result = amp_executor.normalize_logs(logs, "/tmp/test");
@@ -694,4 +857,36 @@ mod tests {
         assert_eq!(assistant_messages.len(), 1);
         assert_eq!(assistant_messages[0].content, "Regular message");
     }
+
+    #[test]
+    fn test_command_result_fills_in_output_and_exit_code() {
+        let amp_executor = AmpExecutor::default();
+        let logs = r#"{"type":"messages","messages":[[1,{"role":"assistant","content":[{"type":"tool_use","id":"toolu_1","name":"Bash","input":{"cmd":"npm test"}}],"state":{"type":"complete","stopReason":"tool_use"}}]],"toolResults":[]}
+{"type":"messages","messages":[[2,{"role":"user","content":[{"type":"tool_result","toolUseID":"toolu_1","run":{"status":"done","result":{"output":"1 failing","exitCode":1}}}]}]],"toolResults":[]}"#;
+
+        let conversation = amp_executor.normalize_logs(logs, "/tmp/test").unwrap();
+
+        assert_eq!(conversation.entries.len(), 2);
+        assert_eq!(
+            conversation.entries[0].entry_type,
+            NormalizedEntryType::ToolUse {
+                tool_name: "Bash".to_string(),
+                action_type: ActionType::CommandRun {
+                    command: "npm test".to_string(),
+                    output: Some("1 failing".to_string()),
+                    exit_code: Some(1),
+                },
+                tool_use_id: Some("toolu_1".to_string()),
+            }
+        );
+        assert_eq!(
+            conversation.entries[1].entry_type,
+            NormalizedEntryType::ToolResult {
+                tool_use_id: "toolu_1".to_string(),
+                is_error: true,
+                exit_code: Some(1),
+            }
+        );
+        assert_eq!(conversation.entries[1].content, "1 failing");
+    }
 }
@@ -3,8 +3,12 @@ use command_group::AsyncGroupChild;
 use uuid::Uuid;
 
 use crate::{
-    executor::{Executor, ExecutorError, NormalizedConversation},
+    executor::{
+        Executor, ExecutorError, ExtraArgs, NormalizedConversation, PromptTemplate, TimedLines,
+        TokenUsage,
+    },
     executors::ClaudeExecutor,
+    models::config::ToolApprovalConfig,
 };
 
 /// An executor that uses Claude Code Router (CCR) to process tasks
@@ -24,6 +28,23 @@ impl CCRExecutor {
             "npx -y @musistudio/claude-code-router code -p --dangerously-skip-permissions --verbose --output-format=stream-json".to_string(),
         ))
     }
+
+    /// Apply tool approval settings from the user config to the executions this starts
+    pub fn with_tool_approvals(self, tool_approvals: ToolApprovalConfig) -> Self {
+        Self(self.0.with_tool_approvals(tool_approvals))
+    }
+}
+
+impl ExtraArgs for CCRExecutor {
+    fn extra_args_mut(&mut self) -> &mut Option<String> {
+        self.0.extra_args_mut()
+    }
+}
+
+impl PromptTemplate for CCRExecutor {
+    fn prompt_template_mut(&mut self) -> &mut Option<String> {
+        self.0.prompt_template_mut()
+    }
 }
 
 #[async_trait]
@@ -50,42 +71,42 @@ impl Executor for CCRExecutor {
             .await
     }
 
-    fn normalize_logs(
+    fn normalize_lines(
         &self,
-        logs: &str,
+        lines: TimedLines<'_>,
         worktree_path: &str,
     ) -> Result<NormalizedConversation, String> {
-        let filtered_logs = filter_ccr_service_messages(logs);
-        let mut result = self.0.normalize_logs(&filtered_logs, worktree_path)?;
+        let filtered_lines = lines.filter(|line| !is_ccr_service_message(line));
+        let mut result = self.0.normalize_lines(filtered_lines, worktree_path)?;
         result.executor_type = "claude-code-router".to_string();
         Ok(result)
     }
+
+    fn extract_usage(&self, logs: &str) -> Option<TokenUsage> {
+        self.0.extract_usage(&filter_ccr_service_messages(logs))
+    }
 }
 
 /// Filter out CCR service messages that appear in stdout but shouldn't be shown to users
 /// These are informational messages from the CCR wrapper itself
 fn filter_ccr_service_messages(logs: &str) -> String {
     logs.lines()
-        .filter(|line| {
-            let trimmed = line.trim();
+        .filter(|line| !is_ccr_service_message(line))
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
 
-            // Filter out known CCR service messages
-            if trimmed.eq("Service not running, starting service...")
-                || trimmed.eq("claude code router service has been successfully stopped.")
-            {
-                return false;
-            }
+fn is_ccr_service_message(line: &str) -> bool {
+    let trimmed = line.trim();
 
-            // Filter out system init JSON that contains misleading model information
-            // CCR delegates to different models, so the init model info is incorrect
-            if trimmed.starts_with(r#"{"type":"system","subtype":"init""#)
-                && trimmed.contains(r#""model":"#)
-            {
-                return false;
-            }
+    // Filter out known CCR service messages
+    if trimmed.eq("Service not running, starting service...")
+        || trimmed.eq("claude code router service has been successfully stopped.")
+    {
+        return true;
+    }
 
-            true
-        })
-        .collect::<Vec<&str>>()
-        .join("\n")
+    // Filter out system init JSON that contains misleading model information
+    // CCR delegates to different models, so the init model info is incorrect
+    trimmed.starts_with(r#"{"type":"system","subtype":"init""#) && trimmed.contains(r#""model":"#)
 }
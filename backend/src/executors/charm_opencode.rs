@@ -1,15 +1,224 @@
 use async_trait::async_trait;
-use command_group::{AsyncCommandGroup, AsyncGroupChild};
+use command_group::AsyncGroupChild;
+use serde_json::Value;
+use tokio::io::AsyncReadExt;
 use uuid::Uuid;
 
 use crate::{
-    executor::{Executor, ExecutorError},
-    models::task::Task,
-    utils::shell::get_shell_command,
+    executor::{
+        link_tool_results, plan_mode_prompt, stream_output_to_db, Executor, ExecutorError,
+        ExtraArgs, NormalizedConversation, NormalizedEntry, NormalizedEntryType, PlanModeSupport,
+        PromptTemplate, TimedLines,
+    },
+    executors::{prompt::task_prompt, streaming::EntryPatcher},
+    models::{
+        config::Config, execution_output::OutputStream, execution_process::ExecutionProcess,
+        executor_session::ExecutorSession, task::Task,
+    },
+    utils::shell::{append_extra_args, get_shell_command},
 };
 
+// Sub-modules for utilities
+pub mod session;
+pub mod tools;
+
+use self::tools::{determine_action_type, generate_tool_content};
+
 /// An executor that uses OpenCode to process tasks
-pub struct CharmOpencodeExecutor;
+#[derive(Default)]
+pub struct CharmOpencodeExecutor {
+    extra_args: Option<String>,
+    prompt_template: Option<String>,
+    /// Ask for a plan through prompt scaffolding instead of making changes
+    plan_mode: bool,
+}
+
+impl CharmOpencodeExecutor {
+    /// Present a plan instead of making changes
+    pub fn with_plan_mode(mut self) -> Self {
+        self.plan_mode = true;
+        self
+    }
+
+    fn scaffold_prompt(&self, prompt: String) -> String {
+        if self.plan_mode {
+            plan_mode_prompt(&prompt)
+        } else {
+            prompt
+        }
+    }
+}
+
+/// Normalize one part of a session message, as opencode stores it: `{"type": ..., "data": {...}}`
+fn normalize_part(role: &str, part: &Value, worktree_path: &str) -> Option<NormalizedEntry> {
+    let data = part.get("data")?;
+    let text = |key: &str| data.get(key).and_then(|v| v.as_str());
+
+    let (entry_type, content) = match (role, part.get("type")?.as_str()?) {
+        ("user", "text") => (NormalizedEntryType::UserMessage, text("text")?.to_string()),
+        ("assistant", "text") => (
+            NormalizedEntryType::AssistantMessage,
+            text("text")?.to_string(),
+        ),
+        ("assistant", "reasoning") => {
+            (NormalizedEntryType::Thinking, text("thinking")?.to_string())
+        }
+        ("assistant", "tool_call") => {
+            let tool_name = text("name")?;
+            // Tool input is stored as the JSON string the model produced
+            let input = text("input")
+                .and_then(|input| serde_json::from_str(input).ok())
+                .unwrap_or(Value::Null);
+            let action_type = determine_action_type(tool_name, &input, worktree_path);
+            let content = generate_tool_content(tool_name, &input, &action_type, worktree_path);
+            (
+                NormalizedEntryType::ToolUse {
+                    tool_name: tool_name.to_string(),
+                    action_type,
+                    tool_use_id: text("id").map(str::to_string),
+                },
+                content,
+            )
+        }
+        ("tool", "tool_result") => (
+            NormalizedEntryType::ToolResult {
+                tool_use_id: text("tool_call_id")?.to_string(),
+                is_error: data
+                    .get("is_error")
+                    .and_then(|e| e.as_bool())
+                    .unwrap_or(false),
+                exit_code: None,
+            },
+            text("content").unwrap_or_default().to_string(),
+        ),
+        ("assistant", "finish") => match text("reason")? {
+            "canceled" => (
+                NormalizedEntryType::ErrorMessage,
+                "The run was canceled".to_string(),
+            ),
+            "error" => (
+                NormalizedEntryType::ErrorMessage,
+                "The run ended with an error".to_string(),
+            ),
+            "permission_denied" => (
+                NormalizedEntryType::ErrorMessage,
+                "A tool call was denied permission".to_string(),
+            ),
+            _ => return None,
+        },
+        // Attachments and parts from other roles aren't shown
+        _ => return None,
+    };
+
+    Some(NormalizedEntry {
+        timestamp: None,
+        entry_type,
+        content,
+        metadata: Some(part.clone()),
+        duration_ms: None,
+        tool_duration_ms: None,
+    })
+}
+
+/// The printed response as output lines, for when the session can't be read. JSON is put on
+/// one line, so it normalizes line by line like the session's messages.
+fn response_lines(response: &str) -> Vec<String> {
+    match serde_json::from_str::<Value>(response.trim()) {
+        Ok(json) => vec![json.to_string()],
+        Err(_) => response
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(str::to_string)
+            .collect(),
+    }
+}
+
+/// Wait for opencode to print its response, which it does once the run is over, then store
+/// the run's session so tool calls and reasoning show up in the conversation
+async fn stream_session_to_db(
+    mut stdout: impl tokio::io::AsyncRead + Unpin,
+    pool: sqlx::SqlitePool,
+    config: Config,
+    attempt_id: Uuid,
+    execution_process_id: Uuid,
+) {
+    let mut response = String::new();
+    if let Err(e) = stdout.read_to_string(&mut response).await {
+        tracing::error!("Error reading stdout for attempt {}: {}", attempt_id, e);
+    }
+
+    let process = match ExecutionProcess::find_by_id(&pool, execution_process_id).await {
+        Ok(Some(process)) => process,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::error!(
+                "Failed to load execution process {}: {}",
+                execution_process_id,
+                e
+            );
+            return;
+        }
+    };
+
+    let worktree_path = process.working_directory;
+    let lines = match session::latest_session(&worktree_path, process.started_at.timestamp()).await
+    {
+        Ok(Some((session_id, lines))) if !lines.is_empty() => {
+            if let Err(e) =
+                ExecutorSession::update_session_id(&pool, execution_process_id, &session_id).await
+            {
+                tracing::error!(
+                    "Failed to update session ID for execution process {}: {}",
+                    execution_process_id,
+                    e
+                );
+            }
+            lines
+        }
+        Ok(_) => response_lines(&response),
+        Err(e) => {
+            tracing::warn!(
+                "Failed to read Charm Opencode session in {}, storing its response only: {}",
+                worktree_path,
+                e
+            );
+            response_lines(&response)
+        }
+    };
+    if lines.is_empty() {
+        return;
+    }
+
+    if let Some(patcher) = EntryPatcher::for_process(&pool, &config, execution_process_id).await {
+        for line in &lines {
+            patcher.push_line(line);
+        }
+        patcher.finish();
+    }
+    let output = lines.join("\n") + "\n";
+    if let Err(e) =
+        ExecutionProcess::append_output(&pool, execution_process_id, Some(&output), None).await
+    {
+        tracing::error!("Failed to store session for attempt {}: {}", attempt_id, e);
+    }
+    if let Err(e) =
+        ExecutionProcess::flush_output(&pool, execution_process_id, OutputStream::Stdout).await
+    {
+        tracing::error!("Failed to store session for attempt {}: {}", attempt_id, e);
+    }
+}
+
+impl ExtraArgs for CharmOpencodeExecutor {
+    fn extra_args_mut(&mut self) -> &mut Option<String> {
+        &mut self.extra_args
+    }
+}
+
+impl PromptTemplate for CharmOpencodeExecutor {
+    fn prompt_template_mut(&mut self) -> &mut Option<String> {
+        &mut self.prompt_template
+    }
+}
 
 #[async_trait]
 impl Executor for CharmOpencodeExecutor {
@@ -28,28 +237,18 @@ impl Executor for CharmOpencodeExecutor {
 
         use tokio::process::Command;
 
-        let prompt = if let Some(task_description) = task.description {
-            format!(
-                r#"project_id: {}
-            
-Task title: {}
-Task description: {}"#,
-                task.project_id, task.title, task_description
-            )
-        } else {
-            format!(
-                r#"project_id: {}
-            
-Task title: {}"#,
-                task.project_id, task.title
-            )
-        };
+        let prompt =
+            task_prompt(pool, &task, worktree_path, self.prompt_template.as_deref()).await?;
+        let prompt = self.scaffold_prompt(prompt);
 
         // Use shell command for cross-platform compatibility
         let (shell_cmd, shell_arg) = get_shell_command();
-        let opencode_command = format!(
-            "opencode -p \"{}\" --output-format=json",
-            prompt.replace('"', "\\\"")
+        let opencode_command = append_extra_args(
+            &format!(
+                "opencode -p \"{}\" --output-format=json",
+                prompt.replace('"', "\\\"")
+            ),
+            self.extra_args.as_deref(),
         );
 
         let mut command = Command::new(shell_cmd);
@@ -59,16 +258,15 @@ Task title: {}"#,
             .stderr(Stdio::piped())
             .current_dir(worktree_path)
             .arg(shell_arg)
-            .arg(opencode_command);
+            .arg(&opencode_command);
 
-        let child = command
-            .group_spawn() // Create new process group so we can kill entire tree
-            .map_err(|e| {
-                crate::executor::SpawnContext::from_command(&command, "CharmOpenCode")
-                    .with_task(task_id, Some(task.title.clone()))
-                    .with_context("CharmOpenCode CLI execution for new task")
-                    .spawn_error(e)
-            })?;
+        // Create new process group so we can kill entire tree
+        let child = crate::utils::process_env::group_spawn(&mut command).map_err(|e| {
+            crate::executor::SpawnContext::from_command(&command, "CharmOpenCode")
+                .with_task(task_id, Some(task.title.clone()))
+                .with_context("CharmOpenCode CLI execution for new task")
+                .spawn_error(e)
+        })?;
 
         Ok(child)
     }
@@ -87,10 +285,14 @@ Task title: {}"#,
 
         // CharmOpencode doesn't support session-based followup, so we ignore session_id
         // and just run with the new prompt
+        let prompt = self.scaffold_prompt(prompt.to_string());
         let (shell_cmd, shell_arg) = get_shell_command();
-        let opencode_command = format!(
-            "opencode -p \"{}\" --output-format=json",
-            prompt.replace('"', "\\\"")
+        let opencode_command = append_extra_args(
+            &format!(
+                "opencode -p \"{}\" --output-format=json",
+                prompt.replace('"', "\\\"")
+            ),
+            self.extra_args.as_deref(),
         );
 
         let mut command = Command::new(shell_cmd);
@@ -102,7 +304,7 @@ Task title: {}"#,
             .arg(shell_arg)
             .arg(&opencode_command);
 
-        let child = command.group_spawn().map_err(|e| {
+        let child = crate::utils::process_env::group_spawn(&mut command).map_err(|e| {
             crate::executor::SpawnContext::from_command(&command, "CharmOpenCode")
                 .with_context("CharmOpenCode CLI followup execution")
                 .spawn_error(e)
@@ -110,4 +312,170 @@ Task title: {}"#,
 
         Ok(child)
     }
+
+    /// The stored conversation comes from the saved session once the run is over
+    fn stores_stdout_lines(&self) -> bool {
+        false
+    }
+
+    fn plan_mode_support(&self) -> PlanModeSupport {
+        PlanModeSupport::Scaffolded
+    }
+
+    fn setup_streaming(
+        &self,
+        child: &mut AsyncGroupChild,
+        pool: &sqlx::SqlitePool,
+        config: &Config,
+        attempt_id: Uuid,
+        execution_process_id: Uuid,
+    ) -> Result<(), ExecutorError> {
+        let stdout = child
+            .inner()
+            .stdout
+            .take()
+            .expect("Failed to take stdout from child process");
+        let stderr = child
+            .inner()
+            .stderr
+            .take()
+            .expect("Failed to take stderr from child process");
+
+        tokio::spawn(stream_session_to_db(
+            stdout,
+            pool.clone(),
+            config.clone(),
+            attempt_id,
+            execution_process_id,
+        ));
+        tokio::spawn(stream_output_to_db(
+            stderr,
+            pool.clone(),
+            config.clone(),
+            attempt_id,
+            execution_process_id,
+            false,
+        ));
+        Ok(())
+    }
+
+    fn normalize_lines(
+        &self,
+        lines: TimedLines<'_>,
+        worktree_path: &str,
+    ) -> Result<NormalizedConversation, String> {
+        let session_id = lines.iter().find_map(|(line, _)| {
+            serde_json::from_str::<Value>(line)
+                .ok()?
+                .get("session_id")?
+                .as_str()
+                .map(str::to_string)
+        });
+
+        let mut entries = lines
+            .iter()
+            .flat_map(|(line, timestamp)| {
+                self.normalize_line(line, worktree_path)
+                    .into_iter()
+                    .map(move |entry| NormalizedEntry {
+                        timestamp: timestamp.clone(),
+                        ..entry
+                    })
+            })
+            .collect::<Vec<_>>();
+        link_tool_results(&mut entries);
+
+        Ok(NormalizedConversation {
+            entries,
+            session_id,
+            executor_type: "charm-opencode".to_string(),
+            prompt: None,
+            summary: None,
+        })
+    }
+
+    fn normalize_line(&self, line: &str, worktree_path: &str) -> Vec<NormalizedEntry> {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            return vec![];
+        }
+
+        let Ok(json) = serde_json::from_str::<Value>(trimmed) else {
+            // Responses printed with `--output-format=text`
+            return vec![NormalizedEntry {
+                timestamp: None,
+                entry_type: NormalizedEntryType::AssistantMessage,
+                content: trimmed.to_string(),
+                metadata: None,
+                duration_ms: None,
+                tool_duration_ms: None,
+            }];
+        };
+
+        if let (Some(role), Some(parts)) = (
+            json.get("role").and_then(|r| r.as_str()),
+            json.get("parts").and_then(|p| p.as_array()),
+        ) {
+            return parts
+                .iter()
+                .filter_map(|part| normalize_part(role, part, worktree_path))
+                .collect();
+        }
+
+        let entry = match json.get("response").and_then(|r| r.as_str()) {
+            Some(response) => NormalizedEntry {
+                timestamp: None,
+                entry_type: NormalizedEntryType::AssistantMessage,
+                content: response.to_string(),
+                metadata: None,
+                duration_ms: None,
+                tool_duration_ms: None,
+            },
+            None => NormalizedEntry {
+                timestamp: None,
+                entry_type: NormalizedEntryType::SystemMessage,
+                content: format!("Unrecognized JSON: {}", trimmed),
+                metadata: Some(json),
+                duration_ms: None,
+                tool_duration_ms: None,
+            },
+        };
+        vec![entry]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response_is_stored_when_session_is_missing() {
+        let lines = response_lines("{\n  \"response\": \"<plan>\\n1. Add a flag\\n</plan>\"\n}\n");
+        assert_eq!(
+            lines,
+            vec![r#"{"response":"<plan>\n1. Add a flag\n</plan>"}"#]
+        );
+
+        let executor = CharmOpencodeExecutor::default();
+        let logs = lines.join("\n");
+        let conversation = executor.normalize_logs(&logs, "/tmp/worktree").unwrap();
+        assert_eq!(conversation.entries.len(), 1);
+        assert!(matches!(
+            conversation.entries[0].entry_type,
+            NormalizedEntryType::AssistantMessage
+        ));
+        assert_eq!(conversation.session_id, None);
+        assert_eq!(
+            executor.extract_plan(&logs, "/tmp/worktree").as_deref(),
+            Some("1. Add a flag")
+        );
+    }
+
+    #[test]
+    fn test_text_responses_are_kept_line_by_line() {
+        assert_eq!(
+            response_lines("Added the flag.\n\nTests pass.\n"),
+            vec!["Added the flag.", "Tests pass."]
+        );
+    }
 }
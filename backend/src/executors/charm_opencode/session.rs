@@ -0,0 +1,103 @@
+//! Reads a run's session from the database Charm Opencode keeps in the project
+//!
+//! With `-p`, the CLI only prints its final response. Everything else it did, including its
+//! reasoning and tool calls, is saved as message parts in `<data directory>/opencode.db`.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+use sqlx::{sqlite::SqliteConnectOptions, Connection, Row, SqliteConnection};
+
+const DEFAULT_DATA_DIRECTORY: &str = ".opencode";
+const CONFIG_FILE: &str = ".opencode.json";
+
+/// The data directory from the project's or the user's config, relative to the project
+fn data_directory(worktree_path: &Path) -> PathBuf {
+    let configured = [
+        Some(worktree_path.join(CONFIG_FILE)),
+        dirs::home_dir().map(|home| home.join(CONFIG_FILE)),
+    ]
+    .into_iter()
+    .flatten()
+    .find_map(|path| {
+        let config: Value = serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()?;
+        config
+            .pointer("/data/directory")
+            .and_then(|dir| dir.as_str())
+            .map(PathBuf::from)
+    });
+
+    worktree_path.join(configured.unwrap_or_else(|| PathBuf::from(DEFAULT_DATA_DIRECTORY)))
+}
+
+/// The ID and messages of the most recent top-level session created since `since` (a Unix
+/// timestamp), one JSON line per message. `None` if there's no session to read.
+pub async fn latest_session(
+    worktree_path: &str,
+    since: i64,
+) -> Result<Option<(String, Vec<String>)>, sqlx::Error> {
+    let database = data_directory(Path::new(worktree_path)).join("opencode.db");
+    if !database.exists() {
+        return Ok(None);
+    }
+
+    let options = SqliteConnectOptions::new()
+        .filename(&database)
+        .read_only(true);
+    let mut connection = SqliteConnection::connect_with(&options).await?;
+
+    // Sessions with a parent belong to sub-agents, whose work shows up as the agent tool call
+    let Some(session_id) = sqlx::query_scalar::<_, String>(
+        "SELECT id FROM sessions WHERE parent_session_id IS NULL AND created_at >= ?
+         ORDER BY created_at DESC LIMIT 1",
+    )
+    .bind(since)
+    .fetch_optional(&mut connection)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    let rows = sqlx::query(
+        "SELECT role, parts, model FROM messages WHERE session_id = ? ORDER BY created_at, rowid",
+    )
+    .bind(&session_id)
+    .fetch_all(&mut connection)
+    .await?;
+    connection.close().await?;
+
+    let lines = rows
+        .into_iter()
+        .map(|row| {
+            let parts = row.get::<String, _>("parts");
+            serde_json::json!({
+                "session_id": session_id,
+                "role": row.get::<String, _>("role"),
+                "parts": serde_json::from_str::<Value>(&parts).unwrap_or(Value::Null),
+                "model": row.get::<Option<String>, _>("model"),
+            })
+            .to_string()
+        })
+        .collect();
+    Ok(Some((session_id, lines)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_config_moves_data_directory() {
+        let worktree = tempfile::tempdir().unwrap();
+        // The user's config may move it elsewhere, so only check a project-level override
+        std::fs::write(
+            worktree.path().join(CONFIG_FILE),
+            r#"{"data": {"directory": "build/opencode"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            data_directory(worktree.path()),
+            worktree.path().join("build/opencode")
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use serde_json::Value;
+
+use crate::{
+    executor::{format_todo_list, ActionType, FileEditHunk},
+    utils::path::make_path_relative,
+};
+
+fn input_str<'a>(input: &'a Value, key: &str) -> Option<&'a str> {
+    input.get(key).and_then(|v| v.as_str())
+}
+
+/// The first file a patch touches, from its `*** Add/Update/Delete File: <path>` headers
+fn patch_path(patch: &str) -> Option<&str> {
+    patch.lines().find_map(|line| {
+        ["*** Add File: ", "*** Update File: ", "*** Delete File: "]
+            .iter()
+            .find_map(|header| line.strip_prefix(header))
+            .map(str::trim)
+    })
+}
+
+/// Helper function to determine action type for tool usage
+pub fn determine_action_type(tool_name: &str, input: &Value, worktree_path: &str) -> ActionType {
+    match tool_name {
+        "view" => match input_str(input, "file_path") {
+            Some(path) => ActionType::FileRead {
+                path: make_path_relative(path, worktree_path),
+            },
+            None => ActionType::Other {
+                description: "File read operation".to_string(),
+            },
+        },
+        "write" => match input_str(input, "file_path") {
+            Some(path) => ActionType::FileWrite {
+                path: make_path_relative(path, worktree_path),
+            },
+            None => ActionType::Other {
+                description: "File write operation".to_string(),
+            },
+        },
+        "edit" => match input_str(input, "file_path") {
+            Some(path) => {
+                let path = make_path_relative(path, worktree_path);
+                match FileEditHunk::from_input(input, "old_string", "new_string") {
+                    Some(edit) => ActionType::FileEdit {
+                        path,
+                        edits: vec![edit],
+                    },
+                    None => ActionType::FileWrite { path },
+                }
+            }
+            None => ActionType::Other {
+                description: "File write operation".to_string(),
+            },
+        },
+        "patch" => match input_str(input, "patch_text").and_then(patch_path) {
+            Some(path) => ActionType::FileWrite {
+                path: make_path_relative(path, worktree_path),
+            },
+            None => ActionType::Other {
+                description: "File write operation".to_string(),
+            },
+        },
+        "bash" => match input_str(input, "command") {
+            Some(command) => ActionType::command_run(command),
+            None => ActionType::Other {
+                description: "Command execution".to_string(),
+            },
+        },
+        "grep" | "glob" => match input_str(input, "pattern") {
+            Some(pattern) => ActionType::Search {
+                query: pattern.to_string(),
+            },
+            None => ActionType::Other {
+                description: "Search operation".to_string(),
+            },
+        },
+        "sourcegraph" => match input_str(input, "query") {
+            Some(query) => ActionType::Search {
+                query: query.to_string(),
+            },
+            None => ActionType::Other {
+                description: "Search operation".to_string(),
+            },
+        },
+        "fetch" => match input_str(input, "url") {
+            Some(url) => ActionType::WebFetch {
+                url: url.to_string(),
+            },
+            None => ActionType::Other {
+                description: "Web fetch operation".to_string(),
+            },
+        },
+        "agent" => match input_str(input, "prompt") {
+            Some(prompt) => ActionType::TaskCreate {
+                description: prompt.to_string(),
+            },
+            None => ActionType::Other {
+                description: "Task creation".to_string(),
+            },
+        },
+        "ls" => ActionType::Other {
+            description: "List directory".to_string(),
+        },
+        "diagnostics" => ActionType::Other {
+            description: "Diagnostics".to_string(),
+        },
+        _ => ActionType::unknown_tool(tool_name),
+    }
+}
+
+/// Helper function to generate concise content for tool usage
+pub fn generate_tool_content(
+    tool_name: &str,
+    input: &Value,
+    action_type: &ActionType,
+    worktree_path: &str,
+) -> String {
+    match action_type {
+        ActionType::FileRead { path }
+        | ActionType::FileWrite { path }
+        | ActionType::FileEdit { path, .. }
+        | ActionType::Search { query: path }
+        | ActionType::CommandRun { command: path, .. }
+        | ActionType::WebFetch { url: path } => format!("`{}`", path),
+        ActionType::TaskCreate { description } => description.clone(),
+        ActionType::PlanPresentation { plan } => plan.clone(),
+        ActionType::TodoUpdate { todos } => format_todo_list(todos),
+        ActionType::Other { .. } => match (tool_name, input_str(input, "path")) {
+            ("ls", Some(path)) => {
+                let path = make_path_relative(path, worktree_path);
+                if path.is_empty() {
+                    "List directory".to_string()
+                } else {
+                    format!("List directory: `{}`", path)
+                }
+            }
+            ("ls", None) => "List directory".to_string(),
+            ("diagnostics", _) => match input_str(input, "file_path") {
+                Some(path) => format!("Diagnostics: `{}`", make_path_relative(path, worktree_path)),
+                None => "Diagnostics".to_string(),
+            },
+            _ => format!("`{}`", tool_name),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_tools_map_to_action_types() {
+        let worktree = "/tmp/worktree";
+        assert_eq!(
+            determine_action_type(
+                "view",
+                &json!({"file_path": "/tmp/worktree/src/main.go"}),
+                worktree
+            ),
+            ActionType::FileRead {
+                path: "src/main.go".to_string()
+            }
+        );
+        assert_eq!(
+            determine_action_type(
+                "patch",
+                &json!({"patch_text": "*** Begin Patch\n*** Update File: /tmp/worktree/go.mod\n@@\n*** End Patch"}),
+                worktree
+            ),
+            ActionType::FileWrite {
+                path: "go.mod".to_string()
+            }
+        );
+        assert_eq!(
+            determine_action_type("glob", &json!({"pattern": "**/*.go"}), worktree),
+            ActionType::Search {
+                query: "**/*.go".to_string()
+            }
+        );
+        assert_eq!(
+            determine_action_type("mcp_github_search", &json!({}), worktree),
+            ActionType::unknown_tool("mcp_github_search")
+        );
+    }
+
+    #[test]
+    fn test_list_directory_content_is_relative() {
+        let input = json!({"path": "/tmp/worktree/internal"});
+        let action_type = determine_action_type("ls", &input, "/tmp/worktree");
+        assert_eq!(
+            generate_tool_content("ls", &input, &action_type, "/tmp/worktree"),
+            "List directory: `internal`"
+        );
+    }
+}
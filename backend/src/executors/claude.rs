@@ -1,19 +1,32 @@
-use std::path::Path;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use async_trait::async_trait;
-use command_group::{AsyncCommandGroup, AsyncGroupChild};
+use command_group::AsyncGroupChild;
 use tokio::process::Command;
 use uuid::Uuid;
 
 use crate::{
     executor::{
-        ActionType, Executor, ExecutorError, NormalizedConversation, NormalizedEntry,
-        NormalizedEntryType,
+        format_todo_list, link_tool_results, spawn_output_streaming, ActionType, Executor,
+        ExecutorError, ExtraArgs, FileEditHunk, NormalizedConversation, NormalizedEntry,
+        NormalizedEntryType, PlanModeSupport, PromptTemplate, TimedLines, TodoItem, TokenUsage,
+    },
+    executors::{input::ExecutionInput, prompt::task_prompt},
+    models::{
+        config::{Config, ToolApprovalConfig},
+        task::Task,
     },
-    models::task::Task,
-    utils::shell::get_shell_command,
+    utils::shell::{append_extra_args, get_shell_command, shell_quote},
 };
 
+const SKIP_PERMISSIONS_FLAG: &str = "--dangerously-skip-permissions";
+
+/// Name of the MCP server answering permission prompts, as seen by Claude
+const APPROVAL_SERVER_NAME: &str = "vibe_kanban_approvals";
+
 fn create_watchkill_script(command: &str) -> String {
     let claude_plan_stop_indicator = "Exit plan mode?";
     format!(
@@ -42,6 +55,11 @@ exit "$exit_code"
 pub struct ClaudeExecutor {
     executor_type: String,
     command: String,
+    extra_args: Option<String>,
+    prompt_template: Option<String>,
+    tool_approvals: ToolApprovalConfig,
+    /// Whether the CLI reads stream-json messages from stdin while it works
+    streaming_input: bool,
 }
 
 impl Default for ClaudeExecutor {
@@ -55,16 +73,24 @@ impl ClaudeExecutor {
     pub fn new() -> Self {
         Self {
             executor_type: "Claude".to_string(),
-            command: "npx -y @anthropic-ai/claude-code@latest -p --dangerously-skip-permissions --verbose --output-format=stream-json".to_string(),
+            command: "npx -y @anthropic-ai/claude-code@latest -p --dangerously-skip-permissions --verbose --output-format=stream-json --input-format=stream-json".to_string(),
+            extra_args: None,
+            prompt_template: None,
+            tool_approvals: ToolApprovalConfig::default(),
+            streaming_input: true,
         }
     }
 
+    /// Plan mode runs Claude with `--permission-mode=plan`, wrapped in a script that
+    /// stops the process once the plan is presented
     pub fn new_plan_mode() -> Self {
-        let command = "npx -y @anthropic-ai/claude-code@latest -p --permission-mode=plan --verbose --output-format=stream-json";
-        let script = create_watchkill_script(command);
         Self {
             executor_type: "ClaudePlan".to_string(),
-            command: script,
+            command: "npx -y @anthropic-ai/claude-code@latest -p --permission-mode=plan --verbose --output-format=stream-json".to_string(),
+            extra_args: None,
+            prompt_template: None,
+            tool_approvals: ToolApprovalConfig::default(),
+            streaming_input: false,
         }
     }
 
@@ -73,7 +99,148 @@ impl ClaudeExecutor {
         Self {
             executor_type,
             command,
+            extra_args: None,
+            prompt_template: None,
+            tool_approvals: ToolApprovalConfig::default(),
+            streaming_input: false,
+        }
+    }
+
+    /// Apply tool approval settings from the user config to the executions this starts
+    pub fn with_tool_approvals(mut self, tool_approvals: ToolApprovalConfig) -> Self {
+        self.tool_approvals = tool_approvals;
+        self
+    }
+
+    /// Build the full shell command, optionally resuming an existing session
+    fn build_command(&self, resume_session_id: Option<&str>) -> String {
+        let mut command = append_extra_args(&self.command, self.extra_args.as_deref());
+        if let Some(session_id) = resume_session_id {
+            command = format!("{} --resume={}", command, session_id);
+        }
+
+        if self.executor_type == "ClaudePlan" {
+            create_watchkill_script(&command)
+        } else {
+            command
+        }
+    }
+
+    /// Send the prompt to stdin. With streaming input the prompt is the first stream-json
+    /// message and stdin stays open for later ones; otherwise stdin is closed after it.
+    async fn write_prompt(&self, child: &mut AsyncGroupChild, prompt: &str) -> std::io::Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        if self.streaming_input {
+            if let Some(stdin) = child.inner().stdin.as_mut() {
+                stdin
+                    .write_all(user_message_line(prompt).as_bytes())
+                    .await?;
+                stdin.write_all(b"\n").await?;
+                stdin.flush().await?;
+            }
+        } else if let Some(mut stdin) = child.inner().stdin.take() {
+            stdin.write_all(prompt.as_bytes()).await?;
+            stdin.shutdown().await?;
         }
+        Ok(())
+    }
+}
+
+/// A stream-json user message, as read from stdin and echoed in the transcript
+fn user_message_line(text: &str) -> String {
+    serde_json::json!({
+        "type": "user",
+        "message": {
+            "role": "user",
+            "content": [{"type": "text", "text": text}],
+        },
+    })
+    .to_string()
+}
+
+/// With tool approvals enabled, swap `--dangerously-skip-permissions` for a permission
+/// prompt tool served by vibe-kanban, so each tool use waits for a decision
+fn apply_tool_approvals(
+    command: &mut Command,
+    claude_command: String,
+    worktree_path: &str,
+    approvals: &ToolApprovalConfig,
+) -> std::io::Result<String> {
+    if !approvals.enabled || !claude_command.contains(SKIP_PERMISSIONS_FLAG) {
+        return Ok(claude_command);
+    }
+
+    let mcp_binary = find_mcp_binary().ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "the vibe-kanban MCP server binary must be installed next to vibe-kanban to use tool approvals",
+        )
+    })?;
+    let timeout_seconds = approvals.timeout_minutes as u64 * 60;
+
+    // Claude gives up on MCP tool calls long before a human may answer
+    command.env(
+        "MCP_TOOL_TIMEOUT",
+        ((timeout_seconds + 60) * 1000).to_string(),
+    );
+
+    Ok(claude_command.replace(
+        SKIP_PERMISSIONS_FLAG,
+        &permission_prompt_args(&mcp_binary, worktree_path, timeout_seconds),
+    ))
+}
+
+fn permission_prompt_args(mcp_binary: &Path, worktree_path: &str, timeout_seconds: u64) -> String {
+    let mcp_config = serde_json::json!({
+        "mcpServers": {
+            APPROVAL_SERVER_NAME: {
+                "command": mcp_binary.to_string_lossy(),
+                "args": [
+                    "--permission-prompt",
+                    "--worktree",
+                    worktree_path,
+                    "--timeout-seconds",
+                    timeout_seconds.to_string(),
+                ],
+            },
+        },
+    });
+
+    format!(
+        "--permission-prompt-tool mcp__{}__approve_tool_use --mcp-config {}",
+        APPROVAL_SERVER_NAME,
+        shell_quote(&mcp_config.to_string())
+    )
+}
+
+/// The MCP server ships next to the main binary: `vibe-kanban-mcp` in releases,
+/// `mcp_task_server` in cargo builds
+fn find_mcp_binary() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let dir = exe.parent()?;
+    ["vibe-kanban-mcp", "mcp_task_server"]
+        .iter()
+        .map(|name| dir.join(format!("{}{}", name, std::env::consts::EXE_SUFFIX)))
+        .find(|path| path.exists())
+}
+
+/// The `result` message ends Claude's turn
+fn is_result_line(line: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(line.trim())
+        .ok()
+        .is_some_and(|json| json.get("type").and_then(|t| t.as_str()) == Some("result"))
+}
+
+impl ExtraArgs for ClaudeExecutor {
+    fn extra_args_mut(&mut self) -> &mut Option<String> {
+        &mut self.extra_args
+    }
+}
+
+impl PromptTemplate for ClaudeExecutor {
+    fn prompt_template_mut(&mut self) -> &mut Option<String> {
+        &mut self.prompt_template
     }
 }
 
@@ -90,27 +257,11 @@ impl Executor for ClaudeExecutor {
             .await?
             .ok_or(ExecutorError::TaskNotFound)?;
 
-        let prompt = if let Some(task_description) = task.description {
-            format!(
-                r#"project_id: {}
-            
-Task title: {}
-Task description: {}"#,
-                task.project_id, task.title, task_description
-            )
-        } else {
-            format!(
-                r#"project_id: {}
-            
-Task title: {}"#,
-                task.project_id, task.title
-            )
-        };
+        let prompt =
+            task_prompt(pool, &task, worktree_path, self.prompt_template.as_deref()).await?;
 
         // Use shell command for cross-platform compatibility
         let (shell_cmd, shell_arg) = get_shell_command();
-        // Pass prompt via stdin instead of command line to avoid shell escaping issues
-        let claude_command = &self.command;
 
         let mut command = Command::new(shell_cmd);
         command
@@ -119,45 +270,47 @@ Task title: {}"#,
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
             .current_dir(worktree_path)
-            .arg(shell_arg)
-            .arg(claude_command)
             .env("NODE_NO_WARNINGS", "1");
 
-        let mut child = command
-            .group_spawn() // Create new process group so we can kill entire tree
-            .map_err(|e| {
-                crate::executor::SpawnContext::from_command(&command, &self.executor_type)
-                    .with_task(task_id, Some(task.title.clone()))
-                    .with_context(format!("{} CLI execution for new task", self.executor_type))
-                    .spawn_error(e)
-            })?;
+        // Pass prompt via stdin instead of command line to avoid shell escaping issues
+        let claude_command = apply_tool_approvals(
+            &mut command,
+            self.build_command(None),
+            worktree_path,
+            &self.tool_approvals,
+        )
+        .map_err(|e| {
+            crate::executor::SpawnContext::from_command(&command, &self.executor_type)
+                .with_task(task_id, Some(task.title.clone()))
+                .with_context("Failed to set up tool approvals")
+                .spawn_error(e)
+        })?;
+        command.arg(shell_arg).arg(&claude_command);
+
+        // Create new process group so we can kill entire tree
+        let mut child = crate::utils::process_env::group_spawn(&mut command).map_err(|e| {
+            crate::executor::SpawnContext::from_command(&command, &self.executor_type)
+                .with_task(task_id, Some(task.title.clone()))
+                .with_context(format!("{} CLI execution for new task", self.executor_type))
+                .spawn_error(e)
+        })?;
 
         // Write prompt to stdin safely
-        if let Some(mut stdin) = child.inner().stdin.take() {
-            use tokio::io::AsyncWriteExt;
-            tracing::debug!(
-                "Writing prompt to Claude stdin for task {}: {:?}",
-                task_id,
-                prompt
-            );
-            stdin.write_all(prompt.as_bytes()).await.map_err(|e| {
-                let context =
-                    crate::executor::SpawnContext::from_command(&command, &self.executor_type)
-                        .with_task(task_id, Some(task.title.clone()))
-                        .with_context(format!(
-                            "Failed to write prompt to {} CLI stdin",
-                            self.executor_type
-                        ));
-                ExecutorError::spawn_failed(e, context)
-            })?;
-            stdin.shutdown().await.map_err(|e| {
-                let context =
-                    crate::executor::SpawnContext::from_command(&command, &self.executor_type)
-                        .with_task(task_id, Some(task.title.clone()))
-                        .with_context(format!("Failed to close {} CLI stdin", self.executor_type));
-                ExecutorError::spawn_failed(e, context)
-            })?;
-        }
+        tracing::debug!(
+            "Writing prompt to Claude stdin for task {}: {:?}",
+            task_id,
+            prompt
+        );
+        self.write_prompt(&mut child, &prompt).await.map_err(|e| {
+            let context =
+                crate::executor::SpawnContext::from_command(&command, &self.executor_type)
+                    .with_task(task_id, Some(task.title.clone()))
+                    .with_context(format!(
+                        "Failed to write prompt to {} CLI stdin",
+                        self.executor_type
+                    ));
+            ExecutorError::spawn_failed(e, context)
+        })?;
 
         Ok(child)
     }
@@ -173,17 +326,6 @@ Task title: {}"#,
         // Use shell command for cross-platform compatibility
         let (shell_cmd, shell_arg) = get_shell_command();
 
-        // Determine the command based on whether this is plan mode or not
-        let claude_command = if self.executor_type == "ClaudePlan" {
-            let command = format!(
-                "npx -y @anthropic-ai/claude-code@latest -p --permission-mode=plan --verbose --output-format=stream-json --resume={}",
-                session_id
-            );
-            create_watchkill_script(&command)
-        } else {
-            format!("{} --resume={}", self.command, session_id)
-        };
-
         let mut command = Command::new(shell_cmd);
         command
             .kill_on_drop(true)
@@ -191,11 +333,22 @@ Task title: {}"#,
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
             .current_dir(worktree_path)
-            .arg(shell_arg)
-            .arg(&claude_command)
             .env("NODE_NO_WARNINGS", "1");
 
-        let mut child = command.group_spawn().map_err(|e| {
+        let claude_command = apply_tool_approvals(
+            &mut command,
+            self.build_command(Some(session_id)),
+            worktree_path,
+            &self.tool_approvals,
+        )
+        .map_err(|e| {
+            crate::executor::SpawnContext::from_command(&command, &self.executor_type)
+                .with_context("Failed to set up tool approvals")
+                .spawn_error(e)
+        })?;
+        command.arg(shell_arg).arg(&claude_command);
+
+        let mut child = crate::utils::process_env::group_spawn(&mut command).map_err(|e| {
             crate::executor::SpawnContext::from_command(&command, &self.executor_type)
                 .with_context(format!(
                     "{} CLI followup execution for session {}",
@@ -205,40 +358,57 @@ Task title: {}"#,
         })?;
 
         // Write prompt to stdin safely
-        if let Some(mut stdin) = child.inner().stdin.take() {
-            use tokio::io::AsyncWriteExt;
-            tracing::debug!(
-                "Writing prompt to {} stdin for session {}: {:?}",
-                self.executor_type,
-                session_id,
-                prompt
-            );
-            stdin.write_all(prompt.as_bytes()).await.map_err(|e| {
-                let context =
-                    crate::executor::SpawnContext::from_command(&command, &self.executor_type)
-                        .with_context(format!(
-                            "Failed to write prompt to {} CLI stdin for session {}",
-                            self.executor_type, session_id
-                        ));
-                ExecutorError::spawn_failed(e, context)
-            })?;
-            stdin.shutdown().await.map_err(|e| {
-                let context =
-                    crate::executor::SpawnContext::from_command(&command, &self.executor_type)
-                        .with_context(format!(
-                            "Failed to close {} CLI stdin for session {}",
-                            self.executor_type, session_id
-                        ));
-                ExecutorError::spawn_failed(e, context)
-            })?;
-        }
+        tracing::debug!(
+            "Writing prompt to {} stdin for session {}: {:?}",
+            self.executor_type,
+            session_id,
+            prompt
+        );
+        self.write_prompt(&mut child, prompt).await.map_err(|e| {
+            let context =
+                crate::executor::SpawnContext::from_command(&command, &self.executor_type)
+                    .with_context(format!(
+                        "Failed to write prompt to {} CLI stdin for session {}",
+                        self.executor_type, session_id
+                    ));
+            ExecutorError::spawn_failed(e, context)
+        })?;
 
         Ok(child)
     }
 
-    fn normalize_logs(
+    fn plan_mode_support(&self) -> PlanModeSupport {
+        // Other CLIs reusing this executor (e.g. Claude Code Router) aren't run in plan mode
+        match self.executor_type.as_str() {
+            "Claude" | "ClaudePlan" => PlanModeSupport::Native,
+            _ => PlanModeSupport::Unsupported,
+        }
+    }
+
+    fn format_input_message(&self, message: &str) -> Option<String> {
+        self.streaming_input.then(|| user_message_line(message))
+    }
+
+    fn setup_streaming(
+        &self,
+        child: &mut AsyncGroupChild,
+        pool: &sqlx::SqlitePool,
+        config: &Config,
+        attempt_id: Uuid,
+        execution_process_id: Uuid,
+    ) -> Result<(), ExecutorError> {
+        if self.streaming_input {
+            if let Some(stdin) = child.inner().stdin.take() {
+                ExecutionInput::register(execution_process_id, stdin, is_result_line);
+            }
+        }
+        spawn_output_streaming(child, pool, config, attempt_id, execution_process_id);
+        Ok(())
+    }
+
+    fn normalize_lines(
         &self,
-        logs: &str,
+        lines: TimedLines<'_>,
         worktree_path: &str,
     ) -> Result<NormalizedConversation, String> {
         use serde_json::Value;
@@ -246,7 +416,7 @@ Task title: {}"#,
         let mut entries = Vec::new();
         let mut session_id = None;
 
-        for line in logs.lines() {
+        for (line, timestamp) in lines.iter() {
             let trimmed = line.trim();
             if trimmed.is_empty() {
                 continue;
@@ -258,10 +428,12 @@ Task title: {}"#,
                 Err(_) => {
                     // If line isn't valid JSON, add it as raw text
                     entries.push(NormalizedEntry {
-                        timestamp: None,
+                        timestamp: timestamp.clone(),
                         entry_type: NormalizedEntryType::SystemMessage,
                         content: format!("Raw output: {}", trimmed),
                         metadata: None,
+                        duration_ms: None,
+                        tool_duration_ms: None,
                     });
                     continue;
                 }
@@ -292,11 +464,13 @@ Task title: {}"#,
                                                     .and_then(|t| t.as_str())
                                                 {
                                                     entries.push(NormalizedEntry {
-                                                        timestamp: None,
+                                                        timestamp: timestamp.clone(),
                                                         entry_type:
                                                             NormalizedEntryType::AssistantMessage,
                                                         content: text.to_string(),
                                                         metadata: Some(content_item.clone()),
+                                                        duration_ms: None,
+                                                        tool_duration_ms: None,
                                                     });
                                                 }
                                             }
@@ -321,13 +495,19 @@ Task title: {}"#,
                                                     );
 
                                                     entries.push(NormalizedEntry {
-                                                        timestamp: None,
+                                                        timestamp: timestamp.clone(),
                                                         entry_type: NormalizedEntryType::ToolUse {
                                                             tool_name: tool_name.to_string(),
                                                             action_type,
+                                                            tool_use_id: content_item
+                                                                .get("id")
+                                                                .and_then(|id| id.as_str())
+                                                                .map(str::to_string),
                                                         },
                                                         content,
                                                         metadata: Some(content_item.clone()),
+                                                        duration_ms: None,
+                                                        tool_duration_ms: None,
                                                     });
                                                 }
                                             }
@@ -344,21 +524,29 @@ Task title: {}"#,
                             if let Some(content) = message.get("content").and_then(|c| c.as_array())
                             {
                                 for content_item in content {
-                                    if let Some(content_type) =
-                                        content_item.get("type").and_then(|t| t.as_str())
-                                    {
-                                        if content_type == "text" {
+                                    match content_item.get("type").and_then(|t| t.as_str()) {
+                                        Some("text") => {
                                             if let Some(text) =
                                                 content_item.get("text").and_then(|t| t.as_str())
                                             {
                                                 entries.push(NormalizedEntry {
-                                                    timestamp: None,
+                                                    timestamp: timestamp.clone(),
                                                     entry_type: NormalizedEntryType::UserMessage,
                                                     content: text.to_string(),
                                                     metadata: Some(content_item.clone()),
+                                                    duration_ms: None,
+                                                    tool_duration_ms: None,
                                                 });
                                             }
                                         }
+                                        Some("tool_result") => {
+                                            if let Some(entry) =
+                                                tool_result_entry(content_item, timestamp.clone())
+                                            {
+                                                entries.push(entry);
+                                            }
+                                        }
+                                        _ => {}
                                     }
                                 }
                             }
@@ -369,7 +557,7 @@ Task title: {}"#,
                         if let Some(subtype) = json.get("subtype").and_then(|s| s.as_str()) {
                             if subtype == "init" {
                                 entries.push(NormalizedEntry {
-                                    timestamp: None,
+                                    timestamp: timestamp.clone(),
                                     entry_type: NormalizedEntryType::SystemMessage,
                                     content: format!(
                                         "System initialized with model: {}",
@@ -378,6 +566,8 @@ Task title: {}"#,
                                             .unwrap_or("unknown")
                                     ),
                                     metadata: Some(json.clone()),
+                                    duration_ms: None,
+                                    tool_duration_ms: None,
                                 });
                             }
                         }
@@ -399,14 +589,18 @@ Task title: {}"#,
                     }
                 }
                 entries.push(NormalizedEntry {
-                    timestamp: None,
+                    timestamp: timestamp.clone(),
                     entry_type: NormalizedEntryType::SystemMessage,
                     content: format!("Unrecognized JSON: {}", trimmed),
                     metadata: Some(json),
+                    duration_ms: None,
+                    tool_duration_ms: None,
                 });
             }
         }
 
+        link_tool_results(&mut entries);
+
         Ok(NormalizedConversation {
             entries,
             session_id,
@@ -415,6 +609,79 @@ Task title: {}"#,
             summary: None,
         })
     }
+
+    fn extract_usage(&self, logs: &str) -> Option<TokenUsage> {
+        use serde_json::Value;
+
+        let mut model = None;
+        let mut result_usage: Option<TokenUsage> = None;
+        // Assistant lines repeat the message usage for every content block, so keep one per message
+        let mut message_usage: HashMap<String, TokenUsage> = HashMap::new();
+
+        for line in logs.lines() {
+            let Ok(json) = serde_json::from_str::<Value>(line.trim()) else {
+                continue;
+            };
+
+            match json.get("type").and_then(|t| t.as_str()) {
+                Some("system") => {
+                    if model.is_none() {
+                        model = json
+                            .get("model")
+                            .and_then(|m| m.as_str())
+                            .map(|m| m.to_string());
+                    }
+                }
+                Some("assistant") => {
+                    let Some(message) = json.get("message") else {
+                        continue;
+                    };
+                    let (Some(id), Some(usage)) = (
+                        message.get("id").and_then(|id| id.as_str()),
+                        message.get("usage"),
+                    ) else {
+                        continue;
+                    };
+                    let mut usage = TokenUsage::from_usage_json(usage);
+                    usage.model = message
+                        .get("model")
+                        .and_then(|m| m.as_str())
+                        .map(|m| m.to_string());
+                    message_usage.insert(id.to_string(), usage);
+                }
+                Some("result") => {
+                    // The result line carries the totals for the whole run
+                    let mut usage = json
+                        .get("usage")
+                        .map(TokenUsage::from_usage_json)
+                        .unwrap_or_default();
+                    usage.cost_usd = json
+                        .get("total_cost_usd")
+                        .or_else(|| json.get("cost_usd"))
+                        .and_then(|c| c.as_f64());
+                    result_usage
+                        .get_or_insert_with(TokenUsage::default)
+                        .add(&usage);
+                }
+                _ => {}
+            }
+        }
+
+        // Runs that were stopped before finishing have no result line
+        let mut usage = result_usage.or_else(|| {
+            message_usage
+                .values()
+                .fold(None, |total: Option<TokenUsage>, usage| {
+                    let mut total = total.unwrap_or_default();
+                    total.add(usage);
+                    Some(total)
+                })
+        })?;
+        if usage.model.is_none() {
+            usage.model = model.or_else(|| message_usage.values().find_map(|u| u.model.clone()));
+        }
+        Some(usage)
+    }
 }
 
 impl ClaudeExecutor {
@@ -496,50 +763,17 @@ impl ClaudeExecutor {
         match action_type {
             ActionType::FileRead { path } => format!("`{}`", path),
             ActionType::FileWrite { path } => format!("`{}`", path),
-            ActionType::CommandRun { command } => format!("`{}`", command),
+            ActionType::FileEdit { path, .. } => format!("`{}`", path),
+            ActionType::CommandRun { command, .. } => format!("`{}`", command),
             ActionType::Search { query } => format!("`{}`", query),
             ActionType::WebFetch { url } => format!("`{}`", url),
             ActionType::TaskCreate { description } => description.clone(),
             ActionType::PlanPresentation { plan } => plan.clone(),
+            ActionType::TodoUpdate { todos } => format_todo_list(todos),
             ActionType::Other { description: _ } => {
                 // For other tools, try to extract key information or fall back to tool name
                 match tool_name.to_lowercase().as_str() {
-                    "todoread" | "todowrite" => {
-                        // Extract todo list from input to show actual todos
-                        if let Some(todos) = input.get("todos").and_then(|t| t.as_array()) {
-                            let mut todo_items = Vec::new();
-                            for todo in todos {
-                                if let Some(content) = todo.get("content").and_then(|c| c.as_str())
-                                {
-                                    let status = todo
-                                        .get("status")
-                                        .and_then(|s| s.as_str())
-                                        .unwrap_or("pending");
-                                    let status_emoji = match status {
-                                        "completed" => "✅",
-                                        "in_progress" => "🔄",
-                                        "pending" | "todo" => "⏳",
-                                        _ => "📝",
-                                    };
-                                    let priority = todo
-                                        .get("priority")
-                                        .and_then(|p| p.as_str())
-                                        .unwrap_or("medium");
-                                    todo_items.push(format!(
-                                        "{} {} ({})",
-                                        status_emoji, content, priority
-                                    ));
-                                }
-                            }
-                            if !todo_items.is_empty() {
-                                format!("TODO List:\n{}", todo_items.join("\n"))
-                            } else {
-                                "Managing TODO list".to_string()
-                            }
-                        } else {
-                            "Managing TODO list".to_string()
-                        }
-                    }
+                    "todoread" | "todowrite" => format_todo_list(&TodoItem::from_input(input)),
                     "ls" => {
                         if let Some(path) = input.get("path").and_then(|p| p.as_str()) {
                             let relative_path = self.make_path_relative(path, worktree_path);
@@ -598,25 +832,41 @@ impl ClaudeExecutor {
                 }
             }
             "edit" | "write" | "multiedit" => {
-                if let Some(file_path) = input.get("file_path").and_then(|p| p.as_str()) {
-                    ActionType::FileWrite {
-                        path: self.make_path_relative(file_path, worktree_path),
-                    }
-                } else if let Some(path) = input.get("path").and_then(|p| p.as_str()) {
-                    ActionType::FileWrite {
-                        path: self.make_path_relative(path, worktree_path),
-                    }
-                } else {
-                    ActionType::Other {
+                let Some(path) = input
+                    .get("file_path")
+                    .or_else(|| input.get("path"))
+                    .and_then(|p| p.as_str())
+                else {
+                    return ActionType::Other {
                         description: "File write operation".to_string(),
-                    }
+                    };
+                };
+                let path = self.make_path_relative(path, worktree_path);
+
+                let edits = match tool_name.to_lowercase().as_str() {
+                    "edit" => FileEditHunk::from_input(input, "old_string", "new_string")
+                        .into_iter()
+                        .collect(),
+                    "multiedit" => input
+                        .get("edits")
+                        .and_then(|edits| edits.as_array())
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|edit| {
+                            FileEditHunk::from_input(edit, "old_string", "new_string")
+                        })
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                if edits.is_empty() {
+                    ActionType::FileWrite { path }
+                } else {
+                    ActionType::FileEdit { path, edits }
                 }
             }
             "bash" => {
                 if let Some(command) = input.get("command").and_then(|c| c.as_str()) {
-                    ActionType::CommandRun {
-                        command: command.to_string(),
-                    }
+                    ActionType::command_run(command)
                 } else {
                     ActionType::Other {
                         description: "Command execution".to_string(),
@@ -682,13 +932,47 @@ impl ClaudeExecutor {
                     }
                 }
             }
-            _ => ActionType::Other {
-                description: format!("Tool: {}", tool_name),
+            "todowrite" => ActionType::TodoUpdate {
+                todos: TodoItem::from_input(input),
             },
+            _ => ActionType::unknown_tool(tool_name),
         }
     }
 }
 
+/// A `tool_result` item from a user message. Its content is either text or a list of blocks.
+fn tool_result_entry(
+    content_item: &serde_json::Value,
+    timestamp: Option<String>,
+) -> Option<NormalizedEntry> {
+    let tool_use_id = content_item.get("tool_use_id")?.as_str()?.to_string();
+    let content = match content_item.get("content") {
+        Some(serde_json::Value::String(text)) => text.clone(),
+        Some(serde_json::Value::Array(blocks)) => blocks
+            .iter()
+            .filter_map(|block| block.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    };
+
+    Some(NormalizedEntry {
+        timestamp,
+        entry_type: NormalizedEntryType::ToolResult {
+            tool_use_id,
+            is_error: content_item
+                .get("is_error")
+                .and_then(|e| e.as_bool())
+                .unwrap_or(false),
+            exit_code: None,
+        },
+        content,
+        metadata: Some(content_item.clone()),
+        duration_ms: None,
+        tool_duration_ms: None,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -719,6 +1003,61 @@ mod tests {
             .any(|e| e.content.contains("Unrecognized JSON")));
     }
 
+    #[test]
+    fn test_input_message_is_recorded_as_user_message() {
+        let executor = ClaudeExecutor::new();
+        let line = executor
+            .format_input_message("Use the existing helper instead")
+            .unwrap();
+
+        let entries = executor.normalize_line(&line, "/tmp/test-worktree");
+        assert_eq!(entries.len(), 1);
+        assert!(matches!(
+            entries[0].entry_type,
+            NormalizedEntryType::UserMessage
+        ));
+        assert_eq!(entries[0].content, "Use the existing helper instead");
+
+        assert!(!is_result_line(&line));
+        assert!(is_result_line(r#"{"type":"result","subtype":"success"}"#));
+
+        // Plan mode closes stdin after the prompt, so it can't be steered
+        assert!(ClaudeExecutor::new_plan_mode()
+            .format_input_message("hello")
+            .is_none());
+    }
+
+    #[test]
+    fn test_extract_usage_prefers_result_totals() {
+        let executor = ClaudeExecutor::new();
+        let logs = r#"{"type":"system","subtype":"init","session_id":"abc","model":"claude-sonnet-4-20250514"}
+{"type":"assistant","message":{"id":"msg_1","model":"claude-sonnet-4-20250514","content":[{"type":"text","text":"Hi"}],"usage":{"input_tokens":4,"cache_creation_input_tokens":100,"cache_read_input_tokens":0,"output_tokens":1}},"session_id":"abc"}
+{"type":"result","subtype":"success","total_cost_usd":0.0421,"usage":{"input_tokens":10,"cache_creation_input_tokens":200,"cache_read_input_tokens":300,"output_tokens":50}}"#;
+
+        let usage = executor.extract_usage(logs).unwrap();
+        assert_eq!(usage.model.as_deref(), Some("claude-sonnet-4-20250514"));
+        assert_eq!(usage.input_tokens, 10);
+        assert_eq!(usage.output_tokens, 50);
+        assert_eq!(usage.cache_creation_input_tokens, 200);
+        assert_eq!(usage.cache_read_input_tokens, 300);
+        assert_eq!(usage.cost_usd, Some(0.0421));
+    }
+
+    #[test]
+    fn test_extract_usage_without_result_dedupes_messages() {
+        let executor = ClaudeExecutor::new();
+        let logs = r#"{"type":"assistant","message":{"id":"msg_1","model":"claude-sonnet-4-20250514","content":[{"type":"text","text":"Hi"}],"usage":{"input_tokens":4,"output_tokens":1}}}
+{"type":"assistant","message":{"id":"msg_1","model":"claude-sonnet-4-20250514","content":[{"type":"tool_use","id":"t1","name":"LS","input":{}}],"usage":{"input_tokens":4,"output_tokens":1}}}
+{"type":"assistant","message":{"id":"msg_2","model":"claude-sonnet-4-20250514","content":[{"type":"text","text":"Done"}],"usage":{"input_tokens":6,"output_tokens":20}}}"#;
+
+        let usage = executor.extract_usage(logs).unwrap();
+        assert_eq!(usage.input_tokens, 10);
+        assert_eq!(usage.output_tokens, 21);
+        assert_eq!(usage.cost_usd, None);
+
+        assert!(executor.extract_usage("plain text output").is_none());
+    }
+
     #[test]
     fn test_make_path_relative() {
         let executor = ClaudeExecutor::new();
@@ -884,4 +1223,116 @@ mod tests {
 
         assert_eq!(result, "List directory: `components`");
     }
+
+    #[test]
+    fn test_build_command_appends_extra_args_before_resume() {
+        let executor = ClaudeExecutor::with_command("Claude".to_string(), "claude -p".to_string())
+            .with_extra_args(Some("--model opus --max-turns 20".to_string()));
+
+        assert_eq!(
+            executor.build_command(None),
+            "claude -p --model opus --max-turns 20"
+        );
+        assert_eq!(
+            executor.build_command(Some("abc")),
+            "claude -p --model opus --max-turns 20 --resume=abc"
+        );
+    }
+
+    #[test]
+    fn test_permission_prompt_args_point_claude_at_approval_server() {
+        let args = permission_prompt_args(
+            Path::new("/opt/vibe-kanban/vibe-kanban-mcp"),
+            "/tmp/worktree",
+            600,
+        );
+
+        assert!(args.starts_with(
+            "--permission-prompt-tool mcp__vibe_kanban_approvals__approve_tool_use --mcp-config "
+        ));
+        let quoted_config = args.split_once("--mcp-config ").unwrap().1;
+        let config: serde_json::Value =
+            serde_json::from_str(quoted_config.trim_matches('\'')).unwrap();
+        let server = &config["mcpServers"]["vibe_kanban_approvals"];
+        assert_eq!(server["command"], "/opt/vibe-kanban/vibe-kanban-mcp");
+        assert_eq!(
+            server["args"],
+            serde_json::json!([
+                "--permission-prompt",
+                "--worktree",
+                "/tmp/worktree",
+                "--timeout-seconds",
+                "600"
+            ])
+        );
+    }
+
+    #[test]
+    fn test_build_command_wraps_plan_mode() {
+        let executor =
+            ClaudeExecutor::new_plan_mode().with_extra_args(Some("--model opus".to_string()));
+        let script = executor.build_command(Some("abc"));
+
+        assert!(script.contains("Exit plan mode?"));
+        assert!(script.contains("--permission-mode=plan --verbose --output-format=stream-json --model opus --resume=abc"));
+    }
+
+    #[test]
+    fn test_tool_result_is_linked_to_its_tool_use() {
+        let executor = ClaudeExecutor::new();
+        let logs = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"toolu_1","name":"Bash","input":{"command":"cargo test"}}]},"session_id":"abc"}
+{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"toolu_1","content":[{"type":"text","text":"test result: FAILED"}],"is_error":true}]},"session_id":"abc"}"#;
+
+        let conversation = executor.normalize_logs(logs, "/tmp/test-worktree").unwrap();
+
+        assert_eq!(conversation.entries.len(), 2);
+        match &conversation.entries[0].entry_type {
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::CommandRun { output, .. },
+                tool_use_id,
+                ..
+            } => {
+                assert_eq!(output.as_deref(), Some("test result: FAILED"));
+                assert_eq!(tool_use_id.as_deref(), Some("toolu_1"));
+            }
+            other => panic!("Expected a command, got {:?}", other),
+        }
+        assert!(matches!(
+            &conversation.entries[1].entry_type,
+            NormalizedEntryType::ToolResult {
+                tool_use_id,
+                is_error: true,
+                exit_code: None,
+            } if tool_use_id == "toolu_1"
+        ));
+    }
+
+    #[test]
+    fn test_multiedit_keeps_each_hunk() {
+        let executor = ClaudeExecutor::new();
+        let input = serde_json::json!({
+            "file_path": "/tmp/test-worktree/src/lib.rs",
+            "edits": [
+                {"old_string": "foo", "new_string": "bar"},
+                {"old_string": "baz", "new_string": "qux", "replace_all": true}
+            ]
+        });
+
+        assert_eq!(
+            executor.extract_action_type("MultiEdit", &input, "/tmp/test-worktree"),
+            ActionType::FileEdit {
+                path: "src/lib.rs".to_string(),
+                edits: vec![
+                    FileEditHunk {
+                        old: "foo".to_string(),
+                        new: "bar".to_string(),
+                    },
+                    FileEditHunk {
+                        old: "baz".to_string(),
+                        new: "qux".to_string(),
+                    },
+                ],
+            }
+        );
+    }
 }
@@ -0,0 +1,732 @@
+//! Config-defined custom executors
+//!
+//! Custom executors let users wire arbitrary coding CLIs into vibe-kanban from
+//! `config.json` instead of adding a new `ExecutorConfig` variant. Each definition
+//! describes how to build the command line, how the prompt reaches the process,
+//! how to resume a session and which existing log normalizer understands its output.
+
+use std::{collections::HashSet, io::Write, process::Stdio, sync::Mutex};
+
+use async_trait::async_trait;
+use command_group::AsyncGroupChild;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tempfile::NamedTempFile;
+use tokio::{io::AsyncWriteExt, process::Command};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    executor::{
+        Executor, ExecutorConfig, ExecutorError, ExtraArgs, NormalizedConversation,
+        NormalizedEntry, NormalizedEntryType, PromptTemplate, SpawnContext, TimedLines, TokenUsage,
+    },
+    executors::{prompt::task_prompt, AmpExecutor, ClaudeExecutor, GeminiExecutor},
+    models::{config::Config, task::Task},
+    utils::shell::{append_extra_args, get_shell_command, shell_quote},
+};
+
+/// Prefix used when a custom executor is referenced by string (e.g. `custom:my-agent`)
+pub const CUSTOM_EXECUTOR_PREFIX: &str = "custom:";
+
+/// A coding agent CLI declared in the user config
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CustomExecutorConfig {
+    /// Unique identifier, referenced as `custom:<name>`
+    pub name: String,
+    /// Optional human readable label shown in the UI
+    pub label: Option<String>,
+    /// Shell command template. `{prompt}` and `{prompt_file}` are substituted
+    /// depending on `prompt_delivery`.
+    pub command: String,
+    pub prompt_delivery: PromptDelivery,
+    /// Shell command template used for follow-ups. Must contain `{session_id}`, which is
+    /// substituted shell-quoted.
+    pub follow_up_command: Option<String>,
+    pub session_id: Option<SessionIdExtractor>,
+    pub normalizer: CustomLogNormalizer,
+}
+
+/// How the prompt is handed to a custom executor process
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum PromptDelivery {
+    /// Written to stdin, which is then closed
+    Stdin,
+    /// Shell-quoted and substituted for `{prompt}` (or appended to the command)
+    Argument,
+    /// Written to a temporary file whose path replaces `{prompt_file}` (or is appended)
+    File,
+}
+
+/// How the external session ID is extracted from stdout lines
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(export)]
+pub enum SessionIdExtractor {
+    /// The first capture group (or whole match) of the pattern
+    Regex { pattern: String },
+    /// A dotted path into a JSON line, e.g. `$.session.id` or `threads[0].id`
+    JsonPath { path: String },
+}
+
+/// Which existing normalizer understands the executor's stdout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum CustomLogNormalizer {
+    Claude,
+    Amp,
+    Gemini,
+    PlainText,
+}
+
+impl CustomExecutorConfig {
+    /// Label shown to users, falling back to the name
+    pub fn display_label(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Check the definition for mistakes that would only surface at spawn time
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Custom executor name cannot be empty".to_string());
+        }
+        if self.command.trim().is_empty() {
+            return Err(format!(
+                "Custom executor '{}' has an empty command",
+                self.name
+            ));
+        }
+        if let Some(follow_up) = &self.follow_up_command {
+            if !follow_up.contains("{session_id}") {
+                return Err(format!(
+                    "Follow-up command for custom executor '{}' must contain {{session_id}}",
+                    self.name
+                ));
+            }
+        }
+        self.session_id_parser()?;
+        Ok(())
+    }
+
+    /// Compile the session ID extractor, if any, to run against every stdout line
+    pub fn session_id_parser(&self) -> Result<Option<SessionIdParser>, String> {
+        let parser = match &self.session_id {
+            None => return Ok(None),
+            Some(SessionIdExtractor::Regex { pattern }) => {
+                SessionIdParser::Regex(regex::Regex::new(pattern).map_err(|e| {
+                    format!(
+                        "Invalid session ID regex for custom executor '{}': {}",
+                        self.name, e
+                    )
+                })?)
+            }
+            Some(SessionIdExtractor::JsonPath { path }) => SessionIdParser::JsonPath(path.clone()),
+        };
+        Ok(Some(parser))
+    }
+
+    /// Build the shell command line, plus the prompt file when using file delivery. The
+    /// file is deleted when it's dropped.
+    fn render_command(
+        &self,
+        template: &str,
+        prompt: &str,
+        session_id: Option<&str>,
+    ) -> Result<(String, Option<NamedTempFile>), std::io::Error> {
+        let mut rendered = match session_id {
+            Some(session_id) => template.replace("{session_id}", &shell_quote(session_id)),
+            None => template.to_string(),
+        };
+
+        let prompt_file = match self.prompt_delivery {
+            PromptDelivery::Stdin => None,
+            PromptDelivery::Argument => {
+                rendered = substitute_or_append(&rendered, "{prompt}", &shell_quote(prompt));
+                None
+            }
+            PromptDelivery::File => {
+                let mut file = tempfile::Builder::new()
+                    .prefix("vibe-kanban-prompt-")
+                    .suffix(".md")
+                    .tempfile()?;
+                file.write_all(prompt.as_bytes())?;
+                file.flush()?;
+                rendered = substitute_or_append(
+                    &rendered,
+                    "{prompt_file}",
+                    &shell_quote(&file.path().to_string_lossy()),
+                );
+                Some(file)
+            }
+        };
+
+        Ok((rendered, prompt_file))
+    }
+}
+
+/// A `SessionIdExtractor` with its regex compiled
+pub enum SessionIdParser {
+    Regex(regex::Regex),
+    JsonPath(String),
+}
+
+impl SessionIdParser {
+    /// Extract a session ID from a single stdout line
+    pub fn extract(&self, line: &str) -> Option<String> {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            return None;
+        }
+
+        match self {
+            SessionIdParser::Regex(re) => {
+                let captures = re.captures(trimmed)?;
+                captures
+                    .get(1)
+                    .or_else(|| captures.get(0))
+                    .map(|m| m.as_str().to_string())
+            }
+            SessionIdParser::JsonPath(path) => {
+                let json: Value = serde_json::from_str(trimmed).ok()?;
+                match lookup_json_path(&json, path)? {
+                    Value::String(s) => Some(s.clone()),
+                    Value::Number(n) => Some(n.to_string()),
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
+/// Validate the custom executors of a config as a whole: each must be valid on its own, and
+/// names must be unique and not taken by a built-in executor
+pub fn validate_custom_executors(definitions: &[CustomExecutorConfig]) -> Result<(), String> {
+    let mut names = HashSet::new();
+    for definition in definitions {
+        definition.validate()?;
+        if definition.name.parse::<ExecutorConfig>().is_ok() {
+            return Err(format!(
+                "Custom executor '{}' has the name of a built-in executor",
+                definition.name
+            ));
+        }
+        if !names.insert(definition.name.as_str()) {
+            return Err(format!(
+                "There is more than one custom executor named '{}'",
+                definition.name
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The valid custom executor definitions of a config, sorted by name
+pub fn usable_custom_executors(definitions: &[CustomExecutorConfig]) -> Vec<&CustomExecutorConfig> {
+    let mut definitions = definitions
+        .iter()
+        .filter(|definition| definition.validate().is_ok())
+        .collect::<Vec<_>>();
+    definitions.sort_by(|a, b| a.name.cmp(&b.name));
+    definitions
+}
+
+/// Look up a valid custom executor definition of a config by name
+pub fn find_custom_executor<'a>(
+    definitions: &'a [CustomExecutorConfig],
+    name: &str,
+) -> Option<&'a CustomExecutorConfig> {
+    definitions
+        .iter()
+        .find(|definition| definition.name == name && definition.validate().is_ok())
+}
+
+fn substitute_or_append(template: &str, placeholder: &str, value: &str) -> String {
+    if template.contains(placeholder) {
+        template.replace(placeholder, value)
+    } else {
+        format!("{} {}", template, value)
+    }
+}
+
+/// Resolve a dotted JSON path such as `$.result.session_id` or `items[0].id`
+fn lookup_json_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    let path = path.trim().trim_start_matches('$').trim_start_matches('.');
+    let mut current = value;
+
+    for segment in path.split('.').filter(|s| !s.is_empty()) {
+        let (key, indexes) = match segment.find('[') {
+            Some(pos) => (&segment[..pos], &segment[pos..]),
+            None => (segment, ""),
+        };
+
+        if !key.is_empty() {
+            current = match current {
+                Value::Array(items) => items.get(key.parse::<usize>().ok()?)?,
+                _ => current.get(key)?,
+            };
+        }
+
+        for index in indexes.split(['[', ']']).filter(|s| !s.is_empty()) {
+            current = current.get(index.parse::<usize>().ok()?)?;
+        }
+    }
+
+    Some(current)
+}
+
+/// An executor backed by a `CustomExecutorConfig` from the user config
+pub struct CustomExecutor {
+    name: String,
+    /// `None` once the definition was removed from config
+    definition: Option<CustomExecutorConfig>,
+    extra_args: Option<String>,
+    prompt_template: Option<String>,
+    /// Prompt file of the last spawned process, handed to its output streaming in
+    /// `setup_streaming` so it is deleted once the process exits
+    prompt_file: Mutex<Option<NamedTempFile>>,
+}
+
+impl CustomExecutor {
+    pub fn new(name: String, definition: Option<CustomExecutorConfig>) -> Self {
+        Self {
+            name,
+            definition,
+            extra_args: None,
+            prompt_template: None,
+            prompt_file: Mutex::new(None),
+        }
+    }
+
+    fn executor_type(&self) -> String {
+        format!("{}{}", CUSTOM_EXECUTOR_PREFIX, self.name)
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn definition(&self) -> Result<CustomExecutorConfig, ExecutorError> {
+        self.definition.clone().ok_or_else(|| {
+            ExecutorError::ContextCollectionFailed(format!(
+                "Custom executor '{}' is not defined in config",
+                self.name
+            ))
+        })
+    }
+
+    async fn spawn_with_prompt(
+        &self,
+        definition: &CustomExecutorConfig,
+        template: &str,
+        prompt: &str,
+        session_id: Option<&str>,
+        worktree_path: &str,
+        task: Option<&Task>,
+    ) -> Result<AsyncGroupChild, ExecutorError> {
+        let executor_type = self.executor_type();
+        let (shell_cmd, shell_arg) = get_shell_command();
+
+        let (command_line, prompt_file) = definition
+            .render_command(template, prompt, session_id)
+            .map_err(|e| {
+            ExecutorError::ContextCollectionFailed(format!(
+                "Failed to write prompt file for {}: {}",
+                executor_type, e
+            ))
+        })?;
+
+        let command_line = append_extra_args(&command_line, self.extra_args.as_deref());
+
+        let mut command = Command::new(shell_cmd);
+        command
+            .kill_on_drop(true)
+            .stdin(if definition.prompt_delivery == PromptDelivery::Stdin {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .current_dir(worktree_path)
+            .arg(shell_arg)
+            .arg(&command_line)
+            .env("NODE_NO_WARNINGS", "1");
+        if let Some(file) = &prompt_file {
+            command.env("VIBE_KANBAN_PROMPT_FILE", file.path());
+        }
+
+        let mut context = SpawnContext::from_command(&command, &executor_type);
+        if let Some(task) = task {
+            context = context.with_task(task.id, Some(task.title.clone()));
+        }
+
+        let mut child = crate::utils::process_env::group_spawn(&mut command).map_err(|e| {
+            context
+                .clone()
+                .with_context(format!("{} execution", definition.display_label()))
+                .spawn_error(e)
+        })?;
+
+        if definition.prompt_delivery == PromptDelivery::Stdin {
+            if let Some(mut stdin) = child.inner().stdin.take() {
+                stdin.write_all(prompt.as_bytes()).await.map_err(|e| {
+                    ExecutorError::spawn_failed(
+                        e,
+                        context.clone().with_context(format!(
+                            "Failed to write prompt to {} stdin",
+                            executor_type
+                        )),
+                    )
+                })?;
+                stdin.shutdown().await.map_err(|e| {
+                    ExecutorError::spawn_failed(
+                        e,
+                        context
+                            .clone()
+                            .with_context(format!("Failed to close {} stdin", executor_type)),
+                    )
+                })?;
+            }
+        }
+
+        *self.prompt_file.lock().unwrap() = prompt_file;
+        Ok(child)
+    }
+
+    fn normalize_plain_text(
+        definition: Option<&CustomExecutorConfig>,
+        lines: TimedLines<'_>,
+    ) -> NormalizedConversation {
+        let parser = definition.and_then(|d| d.session_id_parser().ok().flatten());
+        let mut session_id = None;
+        let entries = lines
+            .iter()
+            .filter(|(line, _)| !line.trim().is_empty())
+            .map(|(line, timestamp)| {
+                if session_id.is_none() {
+                    session_id = parser.as_ref().and_then(|p| p.extract(line));
+                }
+                NormalizedEntry {
+                    timestamp,
+                    entry_type: NormalizedEntryType::AssistantMessage,
+                    content: line.trim_end().to_string(),
+                    metadata: None,
+                    duration_ms: None,
+                    tool_duration_ms: None,
+                }
+            })
+            .collect();
+
+        NormalizedConversation {
+            entries,
+            session_id,
+            executor_type: String::new(),
+            prompt: None,
+            summary: None,
+        }
+    }
+}
+
+impl ExtraArgs for CustomExecutor {
+    fn extra_args_mut(&mut self) -> &mut Option<String> {
+        &mut self.extra_args
+    }
+}
+
+impl PromptTemplate for CustomExecutor {
+    fn prompt_template_mut(&mut self) -> &mut Option<String> {
+        &mut self.prompt_template
+    }
+}
+
+#[async_trait]
+impl Executor for CustomExecutor {
+    async fn spawn(
+        &self,
+        pool: &sqlx::SqlitePool,
+        task_id: Uuid,
+        worktree_path: &str,
+    ) -> Result<AsyncGroupChild, ExecutorError> {
+        let definition = self.definition()?;
+        let task = Task::find_by_id(pool, task_id)
+            .await?
+            .ok_or(ExecutorError::TaskNotFound)?;
+
+        let prompt =
+            task_prompt(pool, &task, worktree_path, self.prompt_template.as_deref()).await?;
+
+        self.spawn_with_prompt(
+            &definition,
+            &definition.command,
+            &prompt,
+            None,
+            worktree_path,
+            Some(&task),
+        )
+        .await
+    }
+
+    async fn spawn_followup(
+        &self,
+        _pool: &sqlx::SqlitePool,
+        _task_id: Uuid,
+        session_id: &str,
+        prompt: &str,
+        worktree_path: &str,
+    ) -> Result<AsyncGroupChild, ExecutorError> {
+        let definition = self.definition()?;
+        let template = definition
+            .follow_up_command
+            .clone()
+            .ok_or(ExecutorError::FollowUpNotSupported)?;
+
+        self.spawn_with_prompt(
+            &definition,
+            &template,
+            prompt,
+            Some(session_id),
+            worktree_path,
+            None,
+        )
+        .await
+    }
+
+    fn normalize_lines(
+        &self,
+        lines: TimedLines<'_>,
+        worktree_path: &str,
+    ) -> Result<NormalizedConversation, String> {
+        // Fall back to plain text when the definition was removed from config,
+        // so historical logs remain readable
+        let definition = self.definition.as_ref();
+        let normalizer = definition
+            .map(|d| d.normalizer)
+            .unwrap_or(CustomLogNormalizer::PlainText);
+
+        let mut conversation = match normalizer {
+            CustomLogNormalizer::Claude => {
+                ClaudeExecutor::new().normalize_lines(lines, worktree_path)?
+            }
+            CustomLogNormalizer::Amp => {
+                AmpExecutor::default().normalize_lines(lines, worktree_path)?
+            }
+            CustomLogNormalizer::Gemini => {
+                GeminiExecutor::default().normalize_lines(lines, worktree_path)?
+            }
+            CustomLogNormalizer::PlainText => Self::normalize_plain_text(definition, lines),
+        };
+        conversation.executor_type = self.executor_type();
+        Ok(conversation)
+    }
+
+    fn extract_usage(&self, logs: &str) -> Option<TokenUsage> {
+        match self.definition.as_ref()?.normalizer {
+            CustomLogNormalizer::Claude => ClaudeExecutor::new().extract_usage(logs),
+            CustomLogNormalizer::Amp => AmpExecutor::default().extract_usage(logs),
+            CustomLogNormalizer::Gemini | CustomLogNormalizer::PlainText => None,
+        }
+    }
+
+    fn stores_stdout_lines(&self) -> bool {
+        // Gemini-style output is stored as formatted messages
+        self.definition()
+            .is_ok_and(|definition| definition.normalizer != CustomLogNormalizer::Gemini)
+    }
+
+    fn setup_streaming(
+        &self,
+        child: &mut AsyncGroupChild,
+        pool: &sqlx::SqlitePool,
+        config: &Config,
+        attempt_id: Uuid,
+        execution_process_id: Uuid,
+    ) -> Result<(), ExecutorError> {
+        let definition = self.definition()?;
+        let stdout = child
+            .inner()
+            .stdout
+            .take()
+            .expect("Failed to take stdout from child process");
+        let stderr = child
+            .inner()
+            .stderr
+            .take()
+            .expect("Failed to take stderr from child process");
+
+        if definition.normalizer == CustomLogNormalizer::Gemini {
+            // Gemini-style output is unstructured text, reuse its chunked streaming
+            tokio::spawn(GeminiExecutor::stream_gemini_chunked(
+                stdout,
+                pool.clone(),
+                attempt_id,
+                execution_process_id,
+            ));
+        } else if let Some(parser) = definition
+            .session_id_parser()
+            .map_err(ExecutorError::ContextCollectionFailed)?
+        {
+            tokio::spawn(crate::executor::stream_stdout_to_db(
+                stdout,
+                pool.clone(),
+                config.clone(),
+                attempt_id,
+                execution_process_id,
+                move |line: &str| parser.extract(line),
+            ));
+        } else {
+            tokio::spawn(crate::executor::stream_output_to_db(
+                stdout,
+                pool.clone(),
+                config.clone(),
+                attempt_id,
+                execution_process_id,
+                true,
+            ));
+        }
+        let prompt_file = self.prompt_file.lock().unwrap().take();
+        let pool = pool.clone();
+        let config = config.clone();
+        tokio::spawn(async move {
+            crate::executor::stream_output_to_db(
+                stderr,
+                pool,
+                config,
+                attempt_id,
+                execution_process_id,
+                false,
+            )
+            .await;
+            // Output closes when the process exits, after which it no longer needs the prompt
+            drop(prompt_file);
+        });
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definition(
+        prompt_delivery: PromptDelivery,
+        session_id: Option<SessionIdExtractor>,
+    ) -> CustomExecutorConfig {
+        CustomExecutorConfig {
+            name: "wrapper".to_string(),
+            label: None,
+            command: "agent run {prompt}".to_string(),
+            prompt_delivery,
+            follow_up_command: Some("agent resume {session_id}".to_string()),
+            session_id,
+            normalizer: CustomLogNormalizer::PlainText,
+        }
+    }
+
+    #[test]
+    fn test_extract_session_id_with_regex() {
+        let def = definition(
+            PromptDelivery::Stdin,
+            Some(SessionIdExtractor::Regex {
+                pattern: r"session: ([a-z0-9-]+)".to_string(),
+            }),
+        );
+        let parser = def.session_id_parser().unwrap().unwrap();
+        assert_eq!(
+            parser.extract("started session: abc-123\n"),
+            Some("abc-123".to_string())
+        );
+        assert_eq!(parser.extract("no session here"), None);
+    }
+
+    #[test]
+    fn test_extract_session_id_with_json_path() {
+        let def = definition(
+            PromptDelivery::Stdin,
+            Some(SessionIdExtractor::JsonPath {
+                path: "$.result.threads[1].id".to_string(),
+            }),
+        );
+        let parser = def.session_id_parser().unwrap().unwrap();
+        assert_eq!(
+            parser.extract(r#"{"result":{"threads":[{"id":"a"},{"id":"b"}]}}"#),
+            Some("b".to_string())
+        );
+        assert_eq!(parser.extract("not json"), None);
+    }
+
+    #[test]
+    fn test_validate_custom_executors_rejects_taken_names() {
+        let wrapper = definition(PromptDelivery::Stdin, None);
+        let mut other = wrapper.clone();
+        other.name = "other".to_string();
+        assert!(validate_custom_executors(&[wrapper.clone(), other]).is_ok());
+
+        assert!(
+            validate_custom_executors(&[wrapper.clone(), wrapper.clone()])
+                .unwrap_err()
+                .contains("more than one")
+        );
+
+        let mut claude = wrapper;
+        claude.name = "claude".to_string();
+        assert!(validate_custom_executors(&[claude])
+            .unwrap_err()
+            .contains("built-in"));
+    }
+
+    #[test]
+    fn test_render_command_argument_delivery_quotes_prompt() {
+        let def = definition(PromptDelivery::Argument, None);
+        let (rendered, prompt_file) = def
+            .render_command(&def.command, "fix the user's bug", None)
+            .unwrap();
+        assert_eq!(rendered, r#"agent run 'fix the user'\''s bug'"#);
+        assert!(prompt_file.is_none());
+    }
+
+    #[test]
+    fn test_render_command_file_delivery_removes_file_on_drop() {
+        let mut def = definition(PromptDelivery::File, None);
+        def.command = "agent run --prompt-file {prompt_file}".to_string();
+        let (rendered, prompt_file) = def.render_command(&def.command, "fix it", None).unwrap();
+        let prompt_file = prompt_file.unwrap();
+        let path = prompt_file.path().to_path_buf();
+        assert!(rendered.contains(&*path.to_string_lossy()));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "fix it");
+
+        drop(prompt_file);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_render_follow_up_substitutes_session_id() {
+        let def = definition(PromptDelivery::Stdin, None);
+        let (rendered, _) = def
+            .render_command(
+                def.follow_up_command.as_ref().unwrap(),
+                "ignored",
+                Some("s-1"),
+            )
+            .unwrap();
+        assert_eq!(rendered, "agent resume 's-1'");
+
+        // Session IDs come from the agent's output and must not reach the shell unquoted
+        let (rendered, _) = def
+            .render_command(
+                def.follow_up_command.as_ref().unwrap(),
+                "ignored",
+                Some("x; rm -rf ~"),
+            )
+            .unwrap();
+        assert_eq!(rendered, "agent resume 'x; rm -rf ~'");
+    }
+
+    #[test]
+    fn test_validate_rejects_follow_up_without_session_placeholder() {
+        let mut def = definition(PromptDelivery::Stdin, None);
+        def.follow_up_command = Some("agent resume".to_string());
+        assert!(def.validate().is_err());
+    }
+}
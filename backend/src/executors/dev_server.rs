@@ -1,5 +1,5 @@
 use async_trait::async_trait;
-use command_group::{AsyncCommandGroup, AsyncGroupChild};
+use command_group::AsyncGroupChild;
 use tokio::process::Command;
 use uuid::Uuid;
 
@@ -41,7 +41,7 @@ impl Executor for DevServerExecutor {
             .arg(&self.script)
             .current_dir(worktree_path);
 
-        let child = command.group_spawn().map_err(|e| {
+        let child = crate::utils::process_env::group_spawn(&mut command).map_err(|e| {
             crate::executor::SpawnContext::from_command(&command, "DevServer")
                 .with_task(task_id, Some(task.title.clone()))
                 .with_context("Development server execution")
@@ -1,5 +1,5 @@
 use async_trait::async_trait;
-use command_group::{AsyncCommandGroup, AsyncGroupChild};
+use command_group::AsyncGroupChild;
 use tokio::process::Command;
 use uuid::Uuid;
 
@@ -65,14 +65,13 @@ echo "Task completed: {}""#,
             .arg(shell_arg)
             .arg(&script);
 
-        let child = command
-            .group_spawn() // Create new process group so we can kill entire tree
-            .map_err(|e| {
-                crate::executor::SpawnContext::from_command(&command, "Echo")
-                    .with_task(task_id, Some(task.title.clone()))
-                    .with_context("Shell script execution for echo demo")
-                    .spawn_error(e)
-            })?;
+        // Create new process group so we can kill entire tree
+        let child = crate::utils::process_env::group_spawn(&mut command).map_err(|e| {
+            crate::executor::SpawnContext::from_command(&command, "Echo")
+                .with_task(task_id, Some(task.title.clone()))
+                .with_context("Shell script execution for echo demo")
+                .spawn_error(e)
+        })?;
 
         Ok(child)
     }
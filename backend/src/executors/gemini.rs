@@ -8,27 +8,45 @@ mod streaming;
 use std::{process::Stdio, time::Instant};
 
 use async_trait::async_trait;
-use command_group::{AsyncCommandGroup, AsyncGroupChild};
+use command_group::AsyncGroupChild;
 use config::{
     max_chunk_size, max_display_size, max_latency_ms, max_message_size, GeminiStreamConfig,
 };
-// Re-export for external use
 use serde_json::Value;
-pub use streaming::GeminiPatchBatch;
 use streaming::GeminiStreaming;
 use tokio::{io::AsyncWriteExt, process::Command};
 use uuid::Uuid;
 
 use crate::{
     executor::{
-        Executor, ExecutorError, NormalizedConversation, NormalizedEntry, NormalizedEntryType,
+        plan_mode_prompt, Executor, ExecutorError, ExtraArgs, NormalizedConversation,
+        NormalizedEntry, NormalizedEntryType, PlanModeSupport, PromptTemplate, TimedLines,
     },
-    models::task::Task,
-    utils::shell::get_shell_command,
+    executors::{prompt::task_prompt, streaming::LogStreaming},
+    models::{config::Config, task::Task},
+    utils::shell::{append_extra_args, get_shell_command},
 };
 
 /// An executor that uses Gemini CLI to process tasks
-pub struct GeminiExecutor;
+#[derive(Default)]
+pub struct GeminiExecutor {
+    extra_args: Option<String>,
+    prompt_template: Option<String>,
+    /// Ask for a plan through prompt scaffolding instead of making changes
+    plan_mode: bool,
+}
+
+impl ExtraArgs for GeminiExecutor {
+    fn extra_args_mut(&mut self) -> &mut Option<String> {
+        &mut self.extra_args
+    }
+}
+
+impl PromptTemplate for GeminiExecutor {
+    fn prompt_template_mut(&mut self) -> &mut Option<String> {
+        &mut self.prompt_template
+    }
+}
 
 #[async_trait]
 impl Executor for GeminiExecutor {
@@ -43,33 +61,19 @@ impl Executor for GeminiExecutor {
             .await?
             .ok_or(ExecutorError::TaskNotFound)?;
 
-        let prompt = if let Some(task_description) = task.description {
-            format!(
-                r#"project_id: {}
-            
-Task title: {}
-Task description: {}"#,
-                task.project_id, task.title, task_description
-            )
-        } else {
-            format!(
-                r#"project_id: {}
-            
-Task title: {}"#,
-                task.project_id, task.title
-            )
-        };
-
-        let mut command = Self::create_gemini_command(worktree_path);
-
-        let mut child = command
-            .group_spawn() // Create new process group so we can kill entire tree
-            .map_err(|e| {
-                crate::executor::SpawnContext::from_command(&command, "Gemini")
-                    .with_task(task_id, Some(task.title.clone()))
-                    .with_context("Gemini CLI execution for new task")
-                    .spawn_error(e)
-            })?;
+        let prompt =
+            task_prompt(pool, &task, worktree_path, self.prompt_template.as_deref()).await?;
+        let prompt = self.scaffold_prompt(prompt);
+
+        let mut command = Self::create_gemini_command(worktree_path, self.extra_args.as_deref());
+
+        // Create new process group so we can kill entire tree
+        let mut child = crate::utils::process_env::group_spawn(&mut command).map_err(|e| {
+            crate::executor::SpawnContext::from_command(&command, "Gemini")
+                .with_task(task_id, Some(task.title.clone()))
+                .with_context("Gemini CLI execution for new task")
+                .spawn_error(e)
+        })?;
 
         // Write prompt to stdin
         if let Some(mut stdin) = child.inner().stdin.take() {
@@ -102,6 +106,7 @@ Task title: {}"#,
     async fn execute_streaming(
         &self,
         pool: &sqlx::SqlitePool,
+        config: &Config,
         task_id: Uuid,
         attempt_id: Uuid,
         execution_process_id: Uuid,
@@ -123,7 +128,7 @@ Task title: {}"#,
             child.inner().id()
         );
 
-        Self::setup_streaming(pool, &mut child, attempt_id, execution_process_id);
+        Self::setup_streaming(pool, config, &mut child, attempt_id, execution_process_id);
 
         Ok(child)
     }
@@ -142,7 +147,14 @@ Task title: {}"#,
 
         let task = self.load_task(pool, task_id).await?;
         let resume_context = self.collect_resume_context(pool, &task, attempt_id).await?;
-        let comprehensive_prompt = self.build_comprehensive_prompt(&task, &resume_context, prompt);
+        // Gemini starts a new session for every follow-up, so the task's prompt is sent again
+        let task_prompt =
+            task_prompt(pool, &task, worktree_path, self.prompt_template.as_deref()).await?;
+        let comprehensive_prompt = self.scaffold_prompt(self.build_comprehensive_prompt(
+            &task_prompt,
+            &resume_context,
+            prompt,
+        ));
         self.spawn_process(worktree_path, &comprehensive_prompt, attempt_id)
             .await
     }
@@ -150,6 +162,7 @@ Task title: {}"#,
     async fn execute_followup_streaming(
         &self,
         pool: &sqlx::SqlitePool,
+        config: &Config,
         task_id: Uuid,
         attempt_id: Uuid,
         execution_process_id: Uuid,
@@ -176,20 +189,29 @@ Task title: {}"#,
             child.inner().id()
         );
 
-        Self::setup_streaming(pool, &mut child, attempt_id, execution_process_id);
+        Self::setup_streaming(pool, config, &mut child, attempt_id, execution_process_id);
 
         Ok(child)
     }
 
-    fn normalize_logs(
+    /// Output is stored as formatted messages rather than the lines printed
+    fn stores_stdout_lines(&self) -> bool {
+        false
+    }
+
+    fn plan_mode_support(&self) -> PlanModeSupport {
+        PlanModeSupport::Scaffolded
+    }
+
+    fn normalize_lines(
         &self,
-        logs: &str,
+        lines: TimedLines<'_>,
         _worktree_path: &str,
     ) -> Result<NormalizedConversation, String> {
         let mut entries: Vec<NormalizedEntry> = Vec::new();
         let mut parse_errors = Vec::new();
 
-        for (line_num, line) in logs.lines().enumerate() {
+        for (line_num, (line, timestamp)) in lines.iter().enumerate() {
             let trimmed = line.trim();
             if trimmed.is_empty() {
                 continue;
@@ -198,7 +220,9 @@ Task title: {}"#,
             // Try to parse as JSON first (for NormalizedEntry format)
             if trimmed.starts_with('{') {
                 match serde_json::from_str::<NormalizedEntry>(trimmed) {
-                    Ok(entry) => {
+                    Ok(mut entry) => {
+                        // Stored entries are stamped when streamed
+                        entry.timestamp = entry.timestamp.or(timestamp);
                         entries.push(entry);
                     }
                     Err(e) => {
@@ -212,10 +236,12 @@ Task title: {}"#,
 
                         // Create a fallback entry for unrecognized JSON
                         let fallback_entry = NormalizedEntry {
-                            timestamp: Some(chrono::Utc::now().to_rfc3339()),
+                            timestamp,
                             entry_type: NormalizedEntryType::SystemMessage,
                             content: format!("Raw output: {}", trimmed),
                             metadata: None,
+                            duration_ms: None,
+                            tool_duration_ms: None,
                         };
                         entries.push(fallback_entry);
                     }
@@ -223,10 +249,12 @@ Task title: {}"#,
             } else {
                 // For non-JSON lines, treat as plain text content
                 let text_entry = NormalizedEntry {
-                    timestamp: Some(chrono::Utc::now().to_rfc3339()),
+                    timestamp,
                     entry_type: NormalizedEntryType::AssistantMessage,
                     content: trimmed.to_string(),
                     metadata: None,
+                    duration_ms: None,
+                    tool_duration_ms: None,
                 };
                 entries.push(text_entry);
             }
@@ -242,7 +270,7 @@ Task title: {}"#,
 
         tracing::debug!(
             "Gemini normalize_logs processed {} lines, created {} entries",
-            logs.lines().count(),
+            lines.iter().count(),
             entries.len()
         );
 
@@ -260,10 +288,24 @@ Task title: {}"#,
 }
 
 impl GeminiExecutor {
+    /// Present a plan instead of making changes
+    pub fn with_plan_mode(mut self) -> Self {
+        self.plan_mode = true;
+        self
+    }
+
+    fn scaffold_prompt(&self, prompt: String) -> String {
+        if self.plan_mode {
+            plan_mode_prompt(&prompt)
+        } else {
+            prompt
+        }
+    }
+
     /// Create a standardized Gemini CLI command
-    fn create_gemini_command(worktree_path: &str) -> Command {
+    fn create_gemini_command(worktree_path: &str, extra_args: Option<&str>) -> Command {
         let (shell_cmd, shell_arg) = get_shell_command();
-        let gemini_command = "npx @google/gemini-cli@latest --yolo";
+        let gemini_command = append_extra_args("npx @google/gemini-cli@latest --yolo", extra_args);
 
         let mut command = Command::new(shell_cmd);
         command
@@ -273,7 +315,7 @@ impl GeminiExecutor {
             .stderr(Stdio::piped())
             .current_dir(worktree_path)
             .arg(shell_arg)
-            .arg(gemini_command)
+            .arg(&gemini_command)
             .env("NODE_NO_WARNINGS", "1");
         command
     }
@@ -308,6 +350,7 @@ impl GeminiExecutor {
     /// Setup streaming for both stdout and stderr
     fn setup_streaming(
         pool: &sqlx::SqlitePool,
+        config: &Config,
         child: &mut AsyncGroupChild,
         attempt_id: Uuid,
         execution_process_id: Uuid,
@@ -338,26 +381,19 @@ impl GeminiExecutor {
         tokio::spawn(crate::executor::stream_output_to_db(
             stderr,
             pool_clone2,
+            config.clone(),
             attempt_id,
             execution_process_id,
             false,
         ));
     }
 
-    /// Push patches to the Gemini WAL system
+    /// Push patches to the shared WAL
     pub fn push_patch(execution_process_id: Uuid, patches: Vec<Value>, content_length: usize) {
-        GeminiStreaming::push_patch(execution_process_id, patches, content_length);
-    }
-
-    /// Get WAL batches for an execution process, optionally filtering by cursor
-    pub fn get_wal_batches(
-        execution_process_id: Uuid,
-        after_batch_id: Option<u64>,
-    ) -> Option<Vec<GeminiPatchBatch>> {
-        GeminiStreaming::get_wal_batches(execution_process_id, after_batch_id)
+        LogStreaming::push_patch(execution_process_id, patches, content_length);
     }
 
-    /// Clean up WAL when execution process finishes
+    /// Flush remaining content and release the WAL when execution process finishes
     pub async fn finalize_execution(
         pool: &sqlx::SqlitePool,
         execution_process_id: Uuid,
@@ -472,17 +508,14 @@ impl GeminiExecutor {
 
     fn build_comprehensive_prompt(
         &self,
-        task: &Task,
+        task_prompt: &str,
         resume_context: &crate::models::task_attempt::AttemptResumeContext,
         prompt: &str,
     ) -> String {
         format!(
             r#"RESUME CONTEXT FOR CONTINUING TASK
 === TASK INFORMATION ===
-Project ID: {}
-Task ID: {}
-Task Title: {}
-Task Description: {}
+{}
 === EXECUTION HISTORY ===
 The following is the execution history from this task attempt:
 {}
@@ -496,12 +529,7 @@ The following git diff shows changes made from the base branch to the current st
 === INSTRUCTIONS ===
 You are continuing work on the above task. The execution history shows what has been done previously, and the git diff shows the current state of all changes. Please continue from where the previous execution left off, taking into account all the context provided above.
 "#,
-            task.project_id,
-            task.id,
-            task.title,
-            task.description
-                .as_deref()
-                .unwrap_or("No description provided"),
+            task_prompt,
             if resume_context.execution_history.trim().is_empty() {
                 "(No previous execution history)"
             } else {
@@ -528,9 +556,10 @@ You are continuing work on the above task. The execution history shows what has
             comprehensive_prompt.len()
         );
 
-        let mut command = GeminiExecutor::create_gemini_command(worktree_path);
+        let mut command =
+            GeminiExecutor::create_gemini_command(worktree_path, self.extra_args.as_deref());
 
-        let mut child = command.group_spawn().map_err(|e| {
+        let mut child = crate::utils::process_env::group_spawn(&mut command).map_err(|e| {
             crate::executor::SpawnContext::from_command(&command, "Gemini")
                 .with_context(format!(
                     "Gemini CLI followup execution with context for attempt {}",
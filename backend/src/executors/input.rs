@@ -0,0 +1,139 @@
+//! Stdin of running executions that accept messages while the agent works
+//!
+//! Executors whose CLI reads streaming input (e.g. Claude's `--input-format stream-json`)
+//! keep stdin open and register it here, so messages can be sent without waiting for the
+//! process to exit. Stdin is closed once the agent finishes its turn, letting the CLI exit.
+
+use std::{collections::HashMap, sync::Arc};
+
+use tokio::{io::AsyncWriteExt, process::ChildStdin, sync::Mutex as TokioMutex};
+use uuid::Uuid;
+
+type SharedStdin = Arc<TokioMutex<Option<ChildStdin>>>;
+
+struct OpenInput {
+    stdin: SharedStdin,
+    ends_turn: fn(&str) -> bool,
+}
+
+lazy_static::lazy_static! {
+    /// Maps execution_process_id → stdin accepting further messages
+    static ref INPUT_MAP: std::sync::Mutex<HashMap<Uuid, OpenInput>> =
+        std::sync::Mutex::new(HashMap::new());
+}
+
+#[derive(Debug)]
+pub enum ExecutionInputError {
+    /// The executor's CLI doesn't read input while running
+    NotSupported,
+    /// The process already finished its turn or exited
+    Closed,
+    Io(std::io::Error),
+    Database(sqlx::Error),
+}
+
+impl std::fmt::Display for ExecutionInputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExecutionInputError::NotSupported => {
+                write!(f, "This executor does not accept input while running")
+            }
+            ExecutionInputError::Closed => {
+                write!(f, "The execution is no longer accepting input")
+            }
+            ExecutionInputError::Io(e) => write!(f, "Failed to write to stdin: {}", e),
+            ExecutionInputError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ExecutionInputError {}
+
+impl From<std::io::Error> for ExecutionInputError {
+    fn from(err: std::io::Error) -> Self {
+        ExecutionInputError::Io(err)
+    }
+}
+
+impl From<sqlx::Error> for ExecutionInputError {
+    fn from(err: sqlx::Error) -> Self {
+        ExecutionInputError::Database(err)
+    }
+}
+
+/// Registry of open stdin handles for running executions
+pub struct ExecutionInput;
+
+impl ExecutionInput {
+    /// Keep a process's stdin open for messages until `ends_turn` matches an output line
+    pub fn register(execution_process_id: Uuid, stdin: ChildStdin, ends_turn: fn(&str) -> bool) {
+        if let Ok(mut input_map) = INPUT_MAP.lock() {
+            input_map.insert(
+                execution_process_id,
+                OpenInput {
+                    stdin: Arc::new(TokioMutex::new(Some(stdin))),
+                    ends_turn,
+                },
+            );
+        }
+    }
+
+    /// Write a single line to the process's stdin
+    pub async fn write_line(
+        execution_process_id: Uuid,
+        line: &str,
+    ) -> Result<(), ExecutionInputError> {
+        let stdin = Self::shared_stdin(execution_process_id).ok_or(ExecutionInputError::Closed)?;
+        let mut guard = stdin.lock().await;
+        let stdin = guard.as_mut().ok_or(ExecutionInputError::Closed)?;
+
+        stdin.write_all(line.as_bytes()).await?;
+        stdin.write_all(b"\n").await?;
+        stdin.flush().await?;
+        Ok(())
+    }
+
+    /// Inspect a line of process output, closing stdin when it ends the agent's turn
+    pub async fn observe_output(execution_process_id: Uuid, line: &str) {
+        let ends_turn = INPUT_MAP
+            .lock()
+            .ok()
+            .and_then(|input_map| {
+                input_map
+                    .get(&execution_process_id)
+                    .map(|input| (input.ends_turn)(line))
+            })
+            .unwrap_or(false);
+
+        if ends_turn {
+            tracing::debug!(
+                "Turn finished for execution process {}, closing stdin",
+                execution_process_id
+            );
+            Self::close(execution_process_id).await;
+        }
+    }
+
+    /// Close stdin and stop accepting messages
+    pub async fn close(execution_process_id: Uuid) {
+        let removed = INPUT_MAP
+            .lock()
+            .ok()
+            .and_then(|mut input_map| input_map.remove(&execution_process_id));
+
+        if let Some(input) = removed {
+            // Wait for any in-flight write before dropping the handle
+            if let Some(mut stdin) = input.stdin.lock().await.take() {
+                stdin.shutdown().await.ok();
+            }
+        }
+    }
+
+    fn shared_stdin(execution_process_id: Uuid) -> Option<SharedStdin> {
+        INPUT_MAP.lock().ok().and_then(|input_map| {
+            input_map
+                .get(&execution_process_id)
+                .map(|input| input.stdin.clone())
+        })
+    }
+}
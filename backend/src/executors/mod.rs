@@ -2,18 +2,29 @@ pub mod amp;
 pub mod ccr;
 pub mod charm_opencode;
 pub mod claude;
+pub mod custom;
 pub mod dev_server;
 pub mod echo;
 pub mod gemini;
+pub mod input;
+#[cfg(test)]
+mod normalizer_conformance;
+pub mod prompt;
+pub mod replay;
 pub mod setup_script;
 pub mod sst_opencode;
+pub mod streaming;
+pub mod test_script;
 
 pub use amp::AmpExecutor;
 pub use ccr::CCRExecutor;
 pub use charm_opencode::CharmOpencodeExecutor;
 pub use claude::ClaudeExecutor;
+pub use custom::{CustomExecutor, CustomExecutorConfig};
 pub use dev_server::DevServerExecutor;
 pub use echo::EchoExecutor;
 pub use gemini::GeminiExecutor;
+pub use replay::ReplayExecutor;
 pub use setup_script::SetupScriptExecutor;
 pub use sst_opencode::SstOpencodeExecutor;
+pub use test_script::TestScriptExecutor;
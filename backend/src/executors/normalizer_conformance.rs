@@ -0,0 +1,215 @@
+//! Golden-fixture conformance tests for the log normalizers
+//!
+//! `fixtures/normalizers/<executor>/<cli version>/<name>.log` is the output of a real run, as the
+//! CLI wrote it, recorded in [`WORKTREE_PATH`]. `<name>.expected.json` next to it is the
+//! conversation the executor normalizes it into, along with the tools that fell back to
+//! [`ActionType::Other`] because the normalizer doesn't recognize them. Run the tests with
+//! `UPDATE_NORMALIZER_SNAPSHOTS=1` to rewrite the snapshots after an intended change.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    executor::{ActionType, ExecutorConfig, NormalizedConversation, NormalizedEntryType},
+    executors::sst_opencode,
+    models::config::Config,
+};
+
+const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/normalizers");
+
+/// The worktree every transcript was recorded in
+const WORKTREE_PATH: &str = "/tmp/vibe-kanban/worktrees/vk-fixture";
+
+const UPDATE_ENV_VAR: &str = "UPDATE_NORMALIZER_SNAPSHOTS";
+
+/// Built-in executors without a normalizer, which need no fixtures
+const UNNORMALIZED_EXECUTORS: &[ExecutorConfig] = &[ExecutorConfig::Echo];
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Snapshot {
+    /// Tools that fell back to `ActionType::Other`
+    unknown_tools: Vec<String>,
+    conversation: NormalizedConversation,
+}
+
+struct Fixture {
+    executor: String,
+    cli_version: String,
+    transcript: PathBuf,
+}
+
+impl Fixture {
+    fn name(&self) -> String {
+        format!(
+            "{}/{}/{}",
+            self.executor,
+            self.cli_version,
+            self.transcript.file_name().unwrap().to_string_lossy()
+        )
+    }
+
+    fn snapshot_path(&self) -> PathBuf {
+        self.transcript.with_extension("expected.json")
+    }
+}
+
+fn subdirectories(dir: &Path) -> Vec<PathBuf> {
+    let mut dirs = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_dir())
+        .collect::<Vec<_>>();
+    dirs.sort();
+    dirs
+}
+
+fn fixtures() -> Vec<Fixture> {
+    let mut fixtures = Vec::new();
+    for executor_dir in subdirectories(Path::new(FIXTURES_DIR)) {
+        for version_dir in subdirectories(&executor_dir) {
+            let mut transcripts = std::fs::read_dir(&version_dir)
+                .unwrap()
+                .map(|entry| entry.unwrap().path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "log"))
+                .collect::<Vec<_>>();
+            transcripts.sort();
+            fixtures.extend(transcripts.into_iter().map(|transcript| Fixture {
+                executor: executor_dir.file_name().unwrap().to_string_lossy().into(),
+                cli_version: version_dir.file_name().unwrap().to_string_lossy().into(),
+                transcript,
+            }));
+        }
+    }
+    fixtures
+}
+
+/// Normalize a transcript the way its stored output would be
+fn normalize(executor: &ExecutorConfig, transcript: &str) -> Result<Snapshot, String> {
+    // SST Opencode stores the normalized entries it parses from stderr while streaming
+    let stored = match executor {
+        ExecutorConfig::SstOpencode => sst_opencode::stored_output(transcript, WORKTREE_PATH),
+        _ => transcript.to_string(),
+    };
+    let mut conversation = executor
+        .create_executor(&Config::default())
+        .normalize_logs(&stored, WORKTREE_PATH)?;
+
+    // Entries stamped with the time they were streamed or normalized would never match
+    if matches!(
+        executor,
+        ExecutorConfig::Gemini | ExecutorConfig::SstOpencode
+    ) {
+        for entry in &mut conversation.entries {
+            entry.timestamp = None;
+        }
+    }
+
+    let mut unknown_tools = Vec::new();
+    for entry in &conversation.entries {
+        if let NormalizedEntryType::ToolUse {
+            tool_name,
+            action_type,
+            ..
+        } = &entry.entry_type
+        {
+            if *action_type == ActionType::unknown_tool(tool_name)
+                && !unknown_tools.contains(tool_name)
+            {
+                unknown_tools.push(tool_name.clone());
+            }
+        }
+    }
+    unknown_tools.sort();
+
+    Ok(Snapshot {
+        unknown_tools,
+        conversation,
+    })
+}
+
+fn check_fixture(fixture: &Fixture, update: bool) -> Result<(), String> {
+    let executor = fixture.executor.parse::<ExecutorConfig>()?;
+    let transcript = std::fs::read_to_string(&fixture.transcript).map_err(|e| e.to_string())?;
+    let actual = serde_json::to_value(normalize(&executor, &transcript)?).unwrap();
+
+    let snapshot_path = fixture.snapshot_path();
+    if update {
+        let contents = serde_json::to_string_pretty(&actual).unwrap() + "\n";
+        return std::fs::write(&snapshot_path, contents).map_err(|e| e.to_string());
+    }
+
+    let expected = std::fs::read_to_string(&snapshot_path).map_err(|e| {
+        format!(
+            "No snapshot, run with {}=1 to create it: {}",
+            UPDATE_ENV_VAR, e
+        )
+    })?;
+    let expected: serde_json::Value =
+        serde_json::from_str(&expected).map_err(|e| format!("Invalid snapshot: {}", e))?;
+    if actual == expected {
+        return Ok(());
+    }
+
+    let mut report = String::new();
+    if actual["unknown_tools"] != expected["unknown_tools"] {
+        report.push_str(&format!(
+            "tools falling back to ActionType::Other changed from {} to {}\n",
+            expected["unknown_tools"], actual["unknown_tools"]
+        ));
+    }
+    report.push_str(&format!(
+        "normalized conversation differs from the snapshot, actual:\n{}",
+        serde_json::to_string_pretty(&actual).unwrap()
+    ));
+    Err(report)
+}
+
+#[test]
+fn test_normalizers_match_golden_fixtures() {
+    let update = std::env::var_os(UPDATE_ENV_VAR).is_some();
+    let fixtures = fixtures();
+    assert!(!fixtures.is_empty(), "No fixtures in {}", FIXTURES_DIR);
+
+    let failures = fixtures
+        .iter()
+        .filter_map(|fixture| {
+            check_fixture(fixture, update)
+                .err()
+                .map(|e| format!("{}: {}", fixture.name(), e))
+        })
+        .collect::<Vec<_>>();
+    assert!(
+        failures.is_empty(),
+        "{} of {} normalizer fixtures failed (run with {}=1 to accept intended changes):\n\n{}",
+        failures.len(),
+        fixtures.len(),
+        UPDATE_ENV_VAR,
+        failures.join("\n\n")
+    );
+}
+
+#[test]
+fn test_every_normalizer_has_fixtures() {
+    let covered = fixtures()
+        .into_iter()
+        .map(|fixture| fixture.executor)
+        .collect::<Vec<_>>();
+
+    for executor in ExecutorConfig::builtin() {
+        let name = executor.to_string();
+        if UNNORMALIZED_EXECUTORS
+            .iter()
+            .any(|unnormalized| unnormalized.to_string() == name)
+        {
+            continue;
+        }
+        assert!(
+            covered.contains(&name),
+            "No fixtures for {} in {}/{}",
+            name,
+            FIXTURES_DIR,
+            name
+        );
+    }
+}
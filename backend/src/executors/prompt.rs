@@ -0,0 +1,300 @@
+//! The prompt a coding agent starts a task with, rendered from a template
+//!
+//! Templates substitute `{{variable}}` with its value, and keep the text between
+//! `{{#variable}}` and `{{/variable}}` only when the variable isn't empty. A project's own
+//! template wins over the one in the user config, which wins over [`DEFAULT_PROMPT_TEMPLATE`].
+
+use std::path::Path;
+
+use uuid::Uuid;
+
+use crate::{
+    executor::ExecutorError,
+    models::{project::Project, task::Task, task_attempt::TaskAttempt},
+};
+
+/// Used unless the project or the user config sets a template
+pub const DEFAULT_PROMPT_TEMPLATE: &str = "project_id: {{project_id}}
+
+Task title: {{task_title}}
+{{#task_description}}
+Task description: {{task_description}}
+{{/task_description}}
+{{#linked_files}}
+
+Files referenced by the task:
+{{linked_files}}
+{{/linked_files}}
+{{#project_instructions}}
+
+{{project_instructions}}
+{{/project_instructions}}
+";
+
+/// Variables a template can use
+pub const PROMPT_TEMPLATE_VARIABLES: &[&str] = &[
+    "project_id",
+    "task_id",
+    "task_title",
+    "task_description",
+    "parent_task",
+    "branch",
+    "base_branch",
+    "project_instructions",
+    "linked_files",
+];
+
+/// A template's tags, in order: `(prefix, name)` where the prefix is `#`, `/` or empty
+fn tags(template: &str) -> impl Iterator<Item = Result<(&str, &str), String>> {
+    template.split("{{").skip(1).map(|rest| {
+        let (tag, _) = rest
+            .split_once("}}")
+            .ok_or_else(|| "A `{{` is never closed with `}}`".to_string())?;
+        let tag = tag.trim();
+        let (prefix, name) = match tag.strip_prefix('#') {
+            Some(name) => ("#", name),
+            None => match tag.strip_prefix('/') {
+                Some(name) => ("/", name),
+                None => ("", tag),
+            },
+        };
+        Ok((prefix, name.trim()))
+    })
+}
+
+/// Check that a template only uses known variables and closes every section it opens
+pub fn validate_prompt_template(template: &str) -> Result<(), String> {
+    let mut open_sections = Vec::new();
+    for tag in tags(template) {
+        let (prefix, name) = tag?;
+        if !PROMPT_TEMPLATE_VARIABLES.contains(&name) {
+            return Err(format!(
+                "Unknown prompt template variable `{}`, expected one of: {}",
+                name,
+                PROMPT_TEMPLATE_VARIABLES.join(", ")
+            ));
+        }
+        match prefix {
+            "#" => open_sections.push(name),
+            "/" if open_sections.pop() != Some(name) => {
+                return Err(format!("`{{{{/{}}}}}` doesn't close an open section", name));
+            }
+            _ => {}
+        }
+    }
+    match open_sections.pop() {
+        Some(name) => Err(format!("Section `{{{{#{}}}}}` is never closed", name)),
+        None => Ok(()),
+    }
+}
+
+/// Render a template with the given variable values. Unknown variables render as nothing.
+pub fn render_prompt_template(template: &str, variables: &[(&str, String)]) -> String {
+    let value = |name: &str| {
+        variables
+            .iter()
+            .find(|(variable, _)| *variable == name)
+            .map(|(_, value)| value.trim())
+            .unwrap_or_default()
+    };
+
+    let mut rendered = String::new();
+    // Sections whose variable is empty, and everything in them, are skipped
+    let mut skipped_sections = 0usize;
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(length) = rest[start..].find("}}") else {
+            break;
+        };
+        if skipped_sections == 0 {
+            rendered.push_str(&rest[..start]);
+        }
+        let tag = rest[start + 2..start + length].trim();
+        rest = &rest[start + length + 2..];
+
+        if let Some(name) = tag.strip_prefix('#') {
+            if skipped_sections > 0 || value(name.trim()).is_empty() {
+                skipped_sections += 1;
+            }
+        } else if tag.starts_with('/') {
+            skipped_sections = skipped_sections.saturating_sub(1);
+        } else if skipped_sections == 0 {
+            rendered.push_str(value(tag));
+            continue;
+        }
+        // Section tags on a line of their own don't leave an empty line behind
+        if rendered.is_empty() || rendered.ends_with('\n') {
+            rest = rest.strip_prefix('\n').unwrap_or(rest);
+        }
+    }
+    if skipped_sections == 0 {
+        rendered.push_str(rest);
+    }
+    rendered.trim_end().to_string()
+}
+
+/// Paths mentioned in a task description that are files in the worktree, as inserted by the
+/// `@` file picker
+fn linked_files(description: &str, worktree_path: &str) -> Vec<String> {
+    let mut files: Vec<String> = Vec::new();
+    for word in description.split_whitespace() {
+        let path = word
+            .trim_start_matches(['`', '"', '\'', '(', '['])
+            .trim_end_matches(['`', '"', '\'', ')', ']', ',', '.', ':', ';', '!', '?']);
+        let candidate = Path::new(path);
+        if path.is_empty()
+            || candidate.is_absolute()
+            || candidate
+                .components()
+                .any(|component| matches!(component, std::path::Component::ParentDir))
+        {
+            continue;
+        }
+        if Path::new(worktree_path).join(candidate).is_file() && !files.iter().any(|f| f == path) {
+            files.push(path.to_string());
+        }
+    }
+    files
+}
+
+/// The prompt to start a task's coding agent with in `worktree_path`. `config_template` is
+/// the prompt template from the user config.
+pub async fn task_prompt(
+    pool: &sqlx::SqlitePool,
+    task: &Task,
+    worktree_path: &str,
+    config_template: Option<&str>,
+) -> Result<String, ExecutorError> {
+    let project = Project::find_by_id(pool, task.project_id).await?;
+    let attempt = TaskAttempt::find_by_task_id(pool, task.id)
+        .await?
+        .into_iter()
+        .find(|attempt| attempt.worktree_path == worktree_path);
+    let parent_task = match task.parent_task_attempt {
+        Some(parent_attempt_id) => parent_task_title(pool, parent_attempt_id).await?,
+        None => None,
+    };
+
+    let description = task.description.clone().unwrap_or_default();
+    let linked_files = linked_files(&description, worktree_path)
+        .into_iter()
+        .map(|path| format!("- {}", path))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let variables = [
+        ("project_id", task.project_id.to_string()),
+        ("task_id", task.id.to_string()),
+        ("task_title", task.title.clone()),
+        ("task_description", description),
+        ("parent_task", parent_task.unwrap_or_default()),
+        (
+            "branch",
+            attempt
+                .as_ref()
+                .map(|a| a.branch.clone())
+                .unwrap_or_default(),
+        ),
+        (
+            "base_branch",
+            attempt
+                .as_ref()
+                .map(|a| a.base_branch.clone())
+                .unwrap_or_default(),
+        ),
+        (
+            "project_instructions",
+            project
+                .as_ref()
+                .and_then(|p| p.instructions.clone())
+                .unwrap_or_default(),
+        ),
+        ("linked_files", linked_files),
+    ];
+
+    let template = project
+        .and_then(|p| p.prompt_template)
+        .or_else(|| config_template.map(str::to_string))
+        .filter(|template| !template.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_PROMPT_TEMPLATE.to_string());
+    Ok(render_prompt_template(&template, &variables))
+}
+
+async fn parent_task_title(
+    pool: &sqlx::SqlitePool,
+    parent_attempt_id: Uuid,
+) -> Result<Option<String>, sqlx::Error> {
+    let Some(parent_attempt) = TaskAttempt::find_by_id(pool, parent_attempt_id).await? else {
+        return Ok(None);
+    };
+    Ok(Task::find_by_id(pool, parent_attempt.task_id)
+        .await?
+        .map(|parent| parent.title))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables(description: &str) -> Vec<(&'static str, String)> {
+        vec![
+            ("project_id", "p1".to_string()),
+            ("task_title", "Fix the build".to_string()),
+            ("task_description", description.to_string()),
+        ]
+    }
+
+    #[test]
+    fn test_default_template_matches_previous_prompt() {
+        assert_eq!(
+            render_prompt_template(DEFAULT_PROMPT_TEMPLATE, &variables("It fails on CI")),
+            "project_id: p1\n\nTask title: Fix the build\nTask description: It fails on CI"
+        );
+        assert_eq!(
+            render_prompt_template(DEFAULT_PROMPT_TEMPLATE, &variables("")),
+            "project_id: p1\n\nTask title: Fix the build"
+        );
+    }
+
+    #[test]
+    fn test_sections_render_only_when_their_variable_is_set() {
+        let template = "{{task_title}}\n{{#project_instructions}}\nRules:\n{{project_instructions}}\n{{/project_instructions}}\nGo.";
+        let mut vars = variables("");
+        assert_eq!(
+            render_prompt_template(template, &vars),
+            "Fix the build\nGo."
+        );
+
+        vars.push(("project_instructions", "Run cargo test".to_string()));
+        assert_eq!(
+            render_prompt_template(template, &vars),
+            "Fix the build\nRules:\nRun cargo test\nGo."
+        );
+    }
+
+    #[test]
+    fn test_validate_prompt_template() {
+        assert!(validate_prompt_template(DEFAULT_PROMPT_TEMPLATE).is_ok());
+        assert!(validate_prompt_template("{{task_titel}}")
+            .unwrap_err()
+            .contains("task_titel"));
+        assert!(validate_prompt_template("{{#branch}}on {{branch}}").is_err());
+        assert!(validate_prompt_template("{{#branch}}{{/base_branch}}").is_err());
+        assert!(validate_prompt_template("{{task_title").is_err());
+    }
+
+    #[test]
+    fn test_linked_files_exist_in_worktree() {
+        let worktree = tempfile::tempdir().unwrap();
+        std::fs::create_dir(worktree.path().join("src")).unwrap();
+        std::fs::write(worktree.path().join("src/main.rs"), "").unwrap();
+
+        let worktree_path = worktree.path().to_str().unwrap();
+        assert_eq!(
+            linked_files(
+                "Rename the flag in `src/main.rs`. See src/main.rs, src/missing.rs and ../secret",
+                worktree_path
+            ),
+            vec!["src/main.rs".to_string()]
+        );
+    }
+}
@@ -0,0 +1,413 @@
+//! Plays back a recorded run of another executor, for deterministic end-to-end tests
+//!
+//! A fixture holds the output lines of the recorded run with the time each was written, its
+//! exit code and optionally the changes it made as a patch. The recorded executor's streaming,
+//! normalization and usage extraction run on the replayed output, so the attempt lifecycle can
+//! be exercised without calling the real CLI. Executors that stream from their own
+//! `execute_streaming` (Gemini, SST Opencode) replay through the default line streaming.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use command_group::AsyncGroupChild;
+use serde::{Deserialize, Serialize};
+use tokio::{io::AsyncWriteExt, process::Command};
+use uuid::Uuid;
+
+use crate::{
+    executor::{
+        Executor, ExecutorConfig, ExecutorError, NormalizedConversation, NormalizedEntry,
+        TimedLines, TokenUsage,
+    },
+    executors::EchoExecutor,
+    models::config::Config,
+    utils::shell::get_shell_command,
+};
+
+/// Prefix of replay executors, e.g. `replay:fixtures/replay/claude-write-file.json`
+pub const REPLAY_EXECUTOR_PREFIX: &str = "replay:";
+
+/// Appended to a replay executor to speed up playback, e.g. `replay:run.json?speed=10`.
+/// A speed of 0 plays the whole fixture back without waiting.
+pub const REPLAY_SPEED_SEPARATOR: &str = "?speed=";
+
+/// Reads `O`/`E` prefixed lines from stdin and writes them to stdout or stderr
+const PLAYBACK_SCRIPT: &str = r#"while IFS= read -r line || [ -n "$line" ]; do
+  case "$line" in
+    E*) printf '%s\n' "${line#?}" >&2 ;;
+    *) printf '%s\n' "${line#?}" ;;
+  esac
+done
+exit "$0""#;
+
+/// A recorded executor run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayFixture {
+    /// Executor whose output was recorded, e.g. `claude`
+    pub executor: String,
+    pub events: Vec<ReplayEvent>,
+    #[serde(default)]
+    pub exit_code: i32,
+    /// Unified diff applied to the worktree before playback, standing in for the agent's edits
+    #[serde(default)]
+    pub patch: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayEvent {
+    /// Milliseconds since the recorded run started
+    pub at_ms: u64,
+    #[serde(default)]
+    pub stream: ReplayStream,
+    /// One line of output, without its newline
+    pub line: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReplayStream {
+    #[default]
+    Stdout,
+    Stderr,
+}
+
+impl ReplayFixture {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read replay fixture {}: {}", path, e))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| format!("Invalid replay fixture {}: {}", path, e))
+    }
+
+    /// The executor that produced the recorded output
+    pub fn recorded_executor(&self) -> Result<ExecutorConfig, String> {
+        match self.executor.parse::<ExecutorConfig>()? {
+            ExecutorConfig::Replay { .. } => {
+                Err("A replay fixture can't record another replay".to_string())
+            }
+            executor => Ok(executor),
+        }
+    }
+
+    /// Lines for the playback script, in the order they were written
+    fn playback_lines(&self) -> Vec<(u64, String)> {
+        self.events
+            .iter()
+            .flat_map(|event| {
+                let prefix = match event.stream {
+                    ReplayStream::Stdout => 'O',
+                    ReplayStream::Stderr => 'E',
+                };
+                event
+                    .line
+                    .split('\n')
+                    .map(move |line| (event.at_ms, format!("{}{}\n", prefix, line)))
+            })
+            .collect()
+    }
+}
+
+/// Split a replay executor into its fixture path and playback speed
+pub fn parse_replay_executor(spec: &str) -> Result<(String, Option<u32>), String> {
+    let (fixture, speed) = match spec.rsplit_once(REPLAY_SPEED_SEPARATOR) {
+        Some((fixture, speed)) => {
+            let speed = speed
+                .parse::<u32>()
+                .map_err(|_| format!("Invalid replay speed: {}", speed))?;
+            (fixture, Some(speed))
+        }
+        None => (spec, None),
+    };
+    if fixture.is_empty() {
+        return Err("Replay executor needs a fixture path".to_string());
+    }
+    Ok((fixture.to_string(), speed))
+}
+
+/// How long to wait before writing an event recorded `at_ms`, after one recorded `previous_ms`
+fn playback_delay(previous_ms: u64, at_ms: u64, speed: Option<u32>) -> Duration {
+    let gap = at_ms.saturating_sub(previous_ms);
+    match speed {
+        Some(0) => Duration::ZERO,
+        Some(speed) => Duration::from_millis(gap / u64::from(speed)),
+        None => Duration::from_millis(gap),
+    }
+}
+
+fn apply_patch(worktree_path: &str, patch: &str) -> Result<(), git2::Error> {
+    let repo = git2::Repository::open(worktree_path)?;
+    let diff = git2::Diff::from_buffer(patch.as_bytes())?;
+    repo.apply(&diff, git2::ApplyLocation::WorkDir, None)
+}
+
+/// An executor that replays a recorded fixture instead of running a coding agent.
+/// Follow-ups replay the same fixture again.
+pub struct ReplayExecutor {
+    fixture_path: String,
+    speed: Option<u32>,
+    fixture: Result<ReplayFixture, String>,
+    recorded: Box<dyn Executor>,
+}
+
+impl ReplayExecutor {
+    pub fn new(fixture_path: String, speed: Option<u32>, config: &Config) -> Self {
+        let fixture = ReplayFixture::load(&fixture_path);
+        // Without a valid fixture nothing is spawned, so there's no output to normalize
+        let recorded = fixture
+            .as_ref()
+            .ok()
+            .and_then(|fixture| fixture.recorded_executor().ok())
+            .map(|executor| executor.create_executor(config))
+            .unwrap_or_else(|| Box::new(EchoExecutor));
+        Self {
+            fixture_path,
+            speed,
+            fixture,
+            recorded,
+        }
+    }
+
+    async fn spawn_playback(
+        &self,
+        task_id: Uuid,
+        worktree_path: &str,
+    ) -> Result<AsyncGroupChild, ExecutorError> {
+        let fixture = self
+            .fixture
+            .clone()
+            .map_err(ExecutorError::ContextCollectionFailed)?;
+        if let Err(e) = fixture.recorded_executor() {
+            return Err(ExecutorError::ContextCollectionFailed(e));
+        }
+
+        let (shell_cmd, shell_arg) = get_shell_command();
+        if shell_cmd == "cmd" {
+            return Err(ExecutorError::ContextCollectionFailed(
+                "Replay needs a POSIX shell".to_string(),
+            ));
+        }
+
+        if let Some(patch) = &fixture.patch {
+            apply_patch(worktree_path, patch).map_err(|e| {
+                ExecutorError::GitError(format!(
+                    "Failed to apply patch from {}: {}",
+                    self.fixture_path, e
+                ))
+            })?;
+        }
+
+        let mut command = Command::new(shell_cmd);
+        command
+            .kill_on_drop(true)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .current_dir(worktree_path)
+            .arg(shell_arg)
+            .arg(PLAYBACK_SCRIPT)
+            .arg(fixture.exit_code.clamp(0, 255).to_string());
+
+        let mut child = crate::utils::process_env::group_spawn(&mut command).map_err(|e| {
+            crate::executor::SpawnContext::from_command(&command, "Replay")
+                .with_task(task_id, None)
+                .with_context(format!("Replaying {}", self.fixture_path))
+                .spawn_error(e)
+        })?;
+
+        let mut stdin = child
+            .inner()
+            .stdin
+            .take()
+            .expect("Failed to take stdin from child process");
+        let speed = self.speed;
+        tokio::spawn(async move {
+            let mut previous_ms = 0;
+            for (at_ms, line) in fixture.playback_lines() {
+                tokio::time::sleep(playback_delay(previous_ms, at_ms, speed)).await;
+                previous_ms = at_ms;
+                // The process was killed
+                if stdin.write_all(line.as_bytes()).await.is_err() {
+                    return;
+                }
+            }
+            // Dropping stdin ends the playback with the recorded exit code
+        });
+
+        Ok(child)
+    }
+}
+
+#[async_trait]
+impl Executor for ReplayExecutor {
+    async fn spawn(
+        &self,
+        _pool: &sqlx::SqlitePool,
+        task_id: Uuid,
+        worktree_path: &str,
+    ) -> Result<AsyncGroupChild, ExecutorError> {
+        self.spawn_playback(task_id, worktree_path).await
+    }
+
+    async fn spawn_followup(
+        &self,
+        _pool: &sqlx::SqlitePool,
+        task_id: Uuid,
+        _session_id: &str,
+        _prompt: &str,
+        worktree_path: &str,
+    ) -> Result<AsyncGroupChild, ExecutorError> {
+        self.spawn_playback(task_id, worktree_path).await
+    }
+
+    fn normalize_lines(
+        &self,
+        lines: TimedLines<'_>,
+        worktree_path: &str,
+    ) -> Result<NormalizedConversation, String> {
+        self.recorded.normalize_lines(lines, worktree_path)
+    }
+
+    fn normalize_line(&self, line: &str, worktree_path: &str) -> Vec<NormalizedEntry> {
+        self.recorded.normalize_line(line, worktree_path)
+    }
+
+    fn extract_usage(&self, logs: &str) -> Option<TokenUsage> {
+        self.recorded.extract_usage(logs)
+    }
+
+    fn stores_stdout_lines(&self) -> bool {
+        self.recorded.stores_stdout_lines()
+    }
+
+    fn setup_streaming(
+        &self,
+        child: &mut AsyncGroupChild,
+        pool: &sqlx::SqlitePool,
+        config: &Config,
+        attempt_id: Uuid,
+        execution_process_id: Uuid,
+    ) -> Result<(), ExecutorError> {
+        self.recorded
+            .setup_streaming(child, pool, config, attempt_id, execution_process_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::NormalizedEntryType;
+
+    fn sample_fixture_path() -> String {
+        format!(
+            "{}/fixtures/replay/claude-write-file.json",
+            env!("CARGO_MANIFEST_DIR")
+        )
+    }
+
+    #[test]
+    fn test_parse_replay_executor() {
+        assert_eq!(
+            parse_replay_executor("runs/claude.json"),
+            Ok(("runs/claude.json".to_string(), None))
+        );
+        assert_eq!(
+            parse_replay_executor("runs/claude.json?speed=10"),
+            Ok(("runs/claude.json".to_string(), Some(10)))
+        );
+        assert!(parse_replay_executor("runs/claude.json?speed=fast").is_err());
+        assert!(parse_replay_executor("").is_err());
+
+        let config: ExecutorConfig = "replay:runs/claude.json?speed=0".parse().unwrap();
+        assert_eq!(config.to_string(), "replay:runs/claude.json?speed=0");
+    }
+
+    #[test]
+    fn test_playback_delay_scales_gaps() {
+        assert_eq!(playback_delay(100, 600, None), Duration::from_millis(500));
+        assert_eq!(
+            playback_delay(100, 600, Some(10)),
+            Duration::from_millis(50)
+        );
+        assert_eq!(playback_delay(100, 600, Some(0)), Duration::ZERO);
+        assert_eq!(playback_delay(600, 100, None), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_playback_lines_prefix_streams() {
+        let fixture = ReplayFixture {
+            executor: "claude".to_string(),
+            events: vec![
+                ReplayEvent {
+                    at_ms: 0,
+                    stream: ReplayStream::Stdout,
+                    line: "{\"type\":\"system\"}".to_string(),
+                },
+                ReplayEvent {
+                    at_ms: 5,
+                    stream: ReplayStream::Stderr,
+                    line: "warn\ncontinued".to_string(),
+                },
+            ],
+            exit_code: 0,
+            patch: None,
+        };
+        assert_eq!(
+            fixture.playback_lines(),
+            vec![
+                (0, "O{\"type\":\"system\"}\n".to_string()),
+                (5, "Ewarn\n".to_string()),
+                (5, "Econtinued\n".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sample_fixture_normalizes_with_recorded_executor() {
+        let executor = ReplayExecutor::new(sample_fixture_path(), None, &Config::default());
+        let fixture = executor.fixture.clone().unwrap();
+        assert!(matches!(
+            fixture.recorded_executor(),
+            Ok(ExecutorConfig::Claude)
+        ));
+        assert!(fixture.patch.is_some());
+
+        let stdout = fixture
+            .events
+            .iter()
+            .filter(|event| event.stream == ReplayStream::Stdout)
+            .map(|event| format!("{}\n", event.line))
+            .collect::<String>();
+        let conversation = executor.normalize_logs(&stdout, "/tmp").unwrap();
+        assert_eq!(conversation.session_id.as_deref(), Some("replay-session-1"));
+        assert!(conversation
+            .entries
+            .iter()
+            .any(|entry| matches!(entry.entry_type, NormalizedEntryType::ToolUse { .. })));
+    }
+
+    #[test]
+    fn test_sample_fixture_patch_applies_to_worktree() {
+        let fixture = ReplayFixture::load(&sample_fixture_path()).unwrap();
+        let worktree = tempfile::tempdir().unwrap();
+        git2::Repository::init(worktree.path()).unwrap();
+        let worktree_path = worktree.path().to_str().unwrap();
+
+        apply_patch(worktree_path, fixture.patch.as_deref().unwrap()).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(worktree.path().join("hello.txt")).unwrap(),
+            "hello\n"
+        );
+        // The file exists now, so the same patch can't be applied twice
+        assert!(apply_patch(worktree_path, fixture.patch.as_deref().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_missing_fixture_is_reported() {
+        let executor =
+            ReplayExecutor::new("/does/not/exist.json".to_string(), None, &Config::default());
+        assert!(executor
+            .fixture
+            .unwrap_err()
+            .contains("/does/not/exist.json"));
+    }
+}
@@ -1,10 +1,10 @@
 use async_trait::async_trait;
-use command_group::{AsyncCommandGroup, AsyncGroupChild};
+use command_group::AsyncGroupChild;
 use tokio::process::Command;
 use uuid::Uuid;
 
 use crate::{
-    executor::{Executor, ExecutorError},
+    executor::{Executor, ExecutorError, TimedLines},
     models::{project::Project, task::Task},
     utils::shell::get_shell_command,
 };
@@ -47,7 +47,7 @@ impl Executor for SetupScriptExecutor {
             .arg(&self.script)
             .current_dir(worktree_path);
 
-        let child = command.group_spawn().map_err(|e| {
+        let child = crate::utils::process_env::group_spawn(&mut command).map_err(|e| {
             crate::executor::SpawnContext::from_command(&command, "SetupScript")
                 .with_task(task_id, Some(task.title.clone()))
                 .with_context("Setup script execution")
@@ -58,27 +58,33 @@ impl Executor for SetupScriptExecutor {
     }
 
     /// Normalize setup script logs into a readable format
-    fn normalize_logs(
+    fn normalize_lines(
         &self,
-        logs: &str,
+        lines: TimedLines<'_>,
         _worktree_path: &str,
     ) -> Result<crate::executor::NormalizedConversation, String> {
         let mut entries = Vec::new();
 
-        // Add script command as first entry
+        // Add script command as first entry, from when the script started printing
         entries.push(crate::executor::NormalizedEntry {
-            timestamp: None,
+            timestamp: lines.iter().next().and_then(|(_, timestamp)| timestamp),
             entry_type: crate::executor::NormalizedEntryType::SystemMessage,
             content: format!("Executing setup script:\n{}", self.script),
             metadata: None,
+            duration_ms: None,
+            tool_duration_ms: None,
         });
 
         // Process the logs - split by lines and create entries
-        if !logs.trim().is_empty() {
-            let lines: Vec<&str> = logs.lines().collect();
+        if lines.iter().any(|(line, _)| !line.trim().is_empty()) {
             let mut current_chunk = String::new();
+            // Chunks are stamped with the capture time of their first line
+            let mut chunk_timestamp = None;
 
-            for line in lines {
+            for (line, timestamp) in lines.iter() {
+                if current_chunk.is_empty() {
+                    chunk_timestamp = timestamp;
+                }
                 current_chunk.push_str(line);
                 current_chunk.push('\n');
 
@@ -98,10 +104,12 @@ impl Executor for SetupScriptExecutor {
                     };
 
                     entries.push(crate::executor::NormalizedEntry {
-                        timestamp: Some(chrono::Utc::now().to_rfc3339()),
+                        timestamp: chunk_timestamp.take(),
                         entry_type,
                         content: current_chunk.trim().to_string(),
                         metadata: None,
+                        duration_ms: None,
+                        tool_duration_ms: None,
                     });
 
                     current_chunk.clear();
@@ -111,10 +119,12 @@ impl Executor for SetupScriptExecutor {
             // Add any remaining content
             if !current_chunk.trim().is_empty() {
                 entries.push(crate::executor::NormalizedEntry {
-                    timestamp: Some(chrono::Utc::now().to_rfc3339()),
+                    timestamp: chunk_timestamp,
                     entry_type: crate::executor::NormalizedEntryType::SystemMessage,
                     content: current_chunk.trim().to_string(),
                     metadata: None,
+                    duration_ms: None,
+                    tool_duration_ms: None,
                 });
             }
         }
@@ -1,5 +1,5 @@
 use async_trait::async_trait;
-use command_group::{AsyncCommandGroup, AsyncGroupChild};
+use command_group::AsyncGroupChild;
 use serde_json::{json, Value};
 use tokio::{
     io::{AsyncBufReadExt, BufReader},
@@ -8,9 +8,16 @@ use tokio::{
 use uuid::Uuid;
 
 use crate::{
-    executor::{Executor, ExecutorError, NormalizedConversation, NormalizedEntry},
-    models::{execution_process::ExecutionProcess, executor_session::ExecutorSession, task::Task},
-    utils::shell::get_shell_command,
+    executor::{
+        plan_mode_prompt, Executor, ExecutorError, ExtraArgs, NormalizedConversation,
+        NormalizedEntry, PlanModeSupport, PromptTemplate, TimedLines,
+    },
+    executors::{prompt::task_prompt, streaming::EntryPatcher},
+    models::{
+        config::Config, execution_output::OutputStream, execution_process::ExecutionProcess,
+        executor_session::ExecutorSession, task::Task,
+    },
+    utils::shell::{append_extra_args, get_shell_command},
 };
 
 // Sub-modules for utilities
@@ -59,6 +66,11 @@ async fn process_line_for_content(
         }
     }
 
+    content_for_line(line, worktree_path)
+}
+
+/// Separate a line of output that isn't a session line into clean content and noise
+fn content_for_line(line: &str, worktree_path: &str) -> Option<Content> {
     // Check if line is noise - if so, discard it
     if OpenCodeFilter::is_noise(line) {
         return None;
@@ -84,6 +96,7 @@ async fn process_line_for_content(
 pub async fn stream_opencode_stderr_to_db(
     output: impl tokio::io::AsyncRead + Unpin,
     pool: sqlx::SqlitePool,
+    config: Config,
     attempt_id: Uuid,
     execution_process_id: Uuid,
     worktree_path: String,
@@ -91,6 +104,7 @@ pub async fn stream_opencode_stderr_to_db(
     let mut reader = BufReader::new(output);
     let mut line = String::new();
     let mut session_extracted = false;
+    let patcher = EntryPatcher::for_process(&pool, &config, execution_process_id).await;
 
     loop {
         line.clear();
@@ -116,6 +130,11 @@ pub async fn stream_opencode_stderr_to_db(
                         stdout,
                         stderr,
                     );
+                    if let (Some(patcher), Some(stdout)) = (patcher.as_ref(), stdout.as_deref()) {
+                        for normalized_line in stdout.lines() {
+                            patcher.push_line(normalized_line);
+                        }
+                    }
                     if let Err(e) = ExecutionProcess::append_output(
                         &pool,
                         execution_process_id,
@@ -138,6 +157,40 @@ pub async fn stream_opencode_stderr_to_db(
             }
         }
     }
+
+    for stream in [OutputStream::Stdout, OutputStream::Stderr] {
+        if let Err(e) = ExecutionProcess::flush_output(&pool, execution_process_id, stream).await {
+            tracing::error!(
+                "Failed to flush OpenCode output for attempt {}: {}",
+                attempt_id,
+                e
+            );
+        }
+    }
+    if let Some(patcher) = patcher {
+        patcher.finish();
+    }
+}
+
+/// The normalized output stored for a run's stderr, as streaming would store it
+#[cfg(test)]
+pub(crate) fn stored_output(stderr: &str, worktree_path: &str) -> String {
+    let mut session_extracted = false;
+    let mut stored = String::new();
+    for line in stderr.lines() {
+        if !session_extracted && parse_session_id_from_line(line).is_some() {
+            session_extracted = true;
+            continue;
+        }
+        if let Some(Content {
+            stdout: Some(stdout),
+            ..
+        }) = content_for_line(line, worktree_path)
+        {
+            stored.push_str(&stdout);
+        }
+    }
+    stored
 }
 
 /// Format OpenCode clean content as normalized JSON entries for direct database storage
@@ -217,6 +270,10 @@ fn format_opencode_content_as_normalized_json(content: &str, worktree_path: &str
 pub struct SstOpencodeExecutor {
     executor_type: String,
     command: String,
+    extra_args: Option<String>,
+    prompt_template: Option<String>,
+    /// Ask for a plan through prompt scaffolding instead of making changes
+    plan_mode: bool,
 }
 
 impl Default for SstOpencodeExecutor {
@@ -231,8 +288,37 @@ impl SstOpencodeExecutor {
         Self {
             executor_type: "SST Opencode".to_string(),
             command: "npx -y opencode-ai@latest run --print-logs".to_string(),
+            extra_args: None,
+            prompt_template: None,
+            plan_mode: false,
         }
     }
+
+    /// Present a plan instead of making changes
+    pub fn with_plan_mode(mut self) -> Self {
+        self.plan_mode = true;
+        self
+    }
+
+    fn scaffold_prompt(&self, prompt: String) -> String {
+        if self.plan_mode {
+            plan_mode_prompt(&prompt)
+        } else {
+            prompt
+        }
+    }
+}
+
+impl ExtraArgs for SstOpencodeExecutor {
+    fn extra_args_mut(&mut self) -> &mut Option<String> {
+        &mut self.extra_args
+    }
+}
+
+impl PromptTemplate for SstOpencodeExecutor {
+    fn prompt_template_mut(&mut self) -> &mut Option<String> {
+        &mut self.prompt_template
+    }
 }
 
 /// An executor that resumes an SST Opencode session
@@ -250,26 +336,13 @@ impl Executor for SstOpencodeExecutor {
             .await?
             .ok_or(ExecutorError::TaskNotFound)?;
 
-        let prompt = if let Some(task_description) = task.description {
-            format!(
-                r#"project_id: {}
-            
-Task title: {}
-Task description: {}"#,
-                task.project_id, task.title, task_description
-            )
-        } else {
-            format!(
-                r#"project_id: {}
-            
-Task title: {}"#,
-                task.project_id, task.title
-            )
-        };
+        let prompt =
+            task_prompt(pool, &task, worktree_path, self.prompt_template.as_deref()).await?;
+        let prompt = self.scaffold_prompt(prompt);
 
         // Use shell command for cross-platform compatibility
         let (shell_cmd, shell_arg) = get_shell_command();
-        let opencode_command = &self.command;
+        let opencode_command = append_extra_args(&self.command, self.extra_args.as_deref());
 
         let mut command = Command::new(shell_cmd);
         command
@@ -279,17 +352,16 @@ Task title: {}"#,
             .stderr(std::process::Stdio::piped())
             .current_dir(worktree_path)
             .arg(shell_arg)
-            .arg(opencode_command)
+            .arg(&opencode_command)
             .env("NODE_NO_WARNINGS", "1");
 
-        let mut child = command
-            .group_spawn() // Create new process group so we can kill entire tree
-            .map_err(|e| {
-                crate::executor::SpawnContext::from_command(&command, &self.executor_type)
-                    .with_task(task_id, Some(task.title.clone()))
-                    .with_context(format!("{} CLI execution for new task", self.executor_type))
-                    .spawn_error(e)
-            })?;
+        // Create new process group so we can kill entire tree
+        let mut child = crate::utils::process_env::group_spawn(&mut command).map_err(|e| {
+            crate::executor::SpawnContext::from_command(&command, &self.executor_type)
+                .with_task(task_id, Some(task.title.clone()))
+                .with_context(format!("{} CLI execution for new task", self.executor_type))
+                .spawn_error(e)
+        })?;
 
         // Write prompt to stdin safely
         if let Some(mut stdin) = child.inner().stdin.take() {
@@ -325,6 +397,7 @@ Task title: {}"#,
     async fn execute_streaming(
         &self,
         pool: &sqlx::SqlitePool,
+        config: &Config,
         task_id: Uuid,
         attempt_id: Uuid,
         execution_process_id: Uuid,
@@ -345,6 +418,7 @@ Task title: {}"#,
         tokio::spawn(stream_opencode_stderr_to_db(
             stderr,
             pool_clone,
+            config.clone(),
             attempt_id,
             execution_process_id,
             worktree_path_clone,
@@ -353,21 +427,32 @@ Task title: {}"#,
         Ok(child)
     }
 
-    fn normalize_logs(
+    /// The conversation is read from stderr, which isn't stored line for line
+    fn stores_stdout_lines(&self) -> bool {
+        false
+    }
+
+    fn plan_mode_support(&self) -> PlanModeSupport {
+        PlanModeSupport::Scaffolded
+    }
+
+    fn normalize_lines(
         &self,
-        logs: &str,
+        lines: TimedLines<'_>,
         _worktree_path: &str,
     ) -> Result<NormalizedConversation, String> {
         let mut entries = Vec::new();
 
-        for line in logs.lines() {
+        for (line, timestamp) in lines.iter() {
             let trimmed = line.trim();
             if trimmed.is_empty() {
                 continue;
             }
 
             // Simple passthrough: directly deserialize normalized JSON entries
-            if let Ok(entry) = serde_json::from_str::<NormalizedEntry>(trimmed) {
+            if let Ok(mut entry) = serde_json::from_str::<NormalizedEntry>(trimmed) {
+                // Stored entries are stamped when streamed
+                entry.timestamp = entry.timestamp.or(timestamp);
                 entries.push(entry);
             }
         }
@@ -385,6 +470,7 @@ Task title: {}"#,
     async fn execute_followup_streaming(
         &self,
         pool: &sqlx::SqlitePool,
+        config: &Config,
         task_id: Uuid,
         attempt_id: Uuid,
         execution_process_id: Uuid,
@@ -409,6 +495,7 @@ Task title: {}"#,
         tokio::spawn(stream_opencode_stderr_to_db(
             stderr,
             pool_clone,
+            config.clone(),
             attempt_id,
             execution_process_id,
             worktree_path_clone,
@@ -429,9 +516,15 @@ Task title: {}"#,
 
         use tokio::io::AsyncWriteExt;
 
+        let prompt = self.scaffold_prompt(prompt.to_string());
+
         // Use shell command for cross-platform compatibility
         let (shell_cmd, shell_arg) = get_shell_command();
-        let opencode_command = format!("{} --session {}", self.command, session_id);
+        let opencode_command = format!(
+            "{} --session {}",
+            append_extra_args(&self.command, self.extra_args.as_deref()),
+            session_id
+        );
 
         let mut command = Command::new(shell_cmd);
         command
@@ -444,7 +537,7 @@ Task title: {}"#,
             .arg(&opencode_command)
             .env("NODE_NO_WARNINGS", "1");
 
-        let mut child = command.group_spawn().map_err(|e| {
+        let mut child = crate::utils::process_env::group_spawn(&mut command).map_err(|e| {
             crate::executor::SpawnContext::from_command(&command, &self.executor_type)
                 .with_context(format!(
                     "{} CLI followup execution for session {}",
@@ -518,6 +611,7 @@ mod tests {
         if let crate::executor::NormalizedEntryType::ToolUse {
             tool_name,
             action_type,
+            ..
         } = &result.entries[0].entry_type
         {
             assert_eq!(tool_name, "Read");
@@ -541,6 +635,7 @@ mod tests {
         if let crate::executor::NormalizedEntryType::ToolUse {
             tool_name,
             action_type,
+            ..
         } = &result.entries[2].entry_type
         {
             assert_eq!(tool_name, "bash");
@@ -659,7 +754,11 @@ The file listing shows several items."#;
 
         assert_eq!(json["entry_type"]["type"], "tool_use");
         assert_eq!(json["entry_type"]["tool_name"], "todowrite"); // Normalized from "TodoWrite"
-        assert_eq!(json["entry_type"]["action_type"]["action"], "other"); // Changed from task_create to other
+        assert_eq!(json["entry_type"]["action_type"]["action"], "todo_update");
+        assert_eq!(
+            json["entry_type"]["action_type"]["todos"][1]["status"],
+            "in_progress"
+        );
 
         // Should contain formatted todo list
         let content_str = json["content"].as_str().unwrap();
@@ -678,7 +777,7 @@ The file listing shows several items."#;
 
         assert_eq!(json["entry_type"]["type"], "tool_use");
         assert_eq!(json["entry_type"]["tool_name"], "todowrite"); // Normalized from "Todo"
-        assert_eq!(json["entry_type"]["action_type"]["action"], "other"); // Changed from task_create to other
+        assert_eq!(json["entry_type"]["action_type"]["action"], "todo_update");
 
         // Should contain formatted todo list with proper emojis
         let content_str = json["content"].as_str().unwrap();
@@ -40,7 +40,8 @@ impl OpenCodeFilter {
         }
 
         // Spinner glyphs
-        if cleaned_trim.len() == 1 && "⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏".contains(cleaned_trim) {
+        if cleaned_trim.chars().count() == 1 && "⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏".contains(cleaned_trim)
+        {
             return true;
         }
 
@@ -162,6 +163,15 @@ mod tests {
         assert_eq!(parse_session_id_from_line(""), None);
     }
 
+    #[test]
+    fn test_spinner_glyphs_are_noise() {
+        use crate::executors::sst_opencode::filter::OpenCodeFilter;
+
+        assert!(OpenCodeFilter::is_noise("⠋"));
+        assert!(OpenCodeFilter::is_noise("\x1b[90m⠼\x1b[0m"));
+        assert!(!OpenCodeFilter::is_noise("a"));
+    }
+
     #[test]
     fn test_ansi_code_stripping() {
         use crate::executors::sst_opencode::filter::OpenCodeFilter;
@@ -1,6 +1,9 @@
 use serde_json::{json, Value};
 
-use crate::utils::path::make_path_relative;
+use crate::{
+    executor::{format_todo_list, ActionType, FileEditHunk, TodoItem},
+    utils::path::make_path_relative,
+};
 
 /// Normalize tool names to match frontend expectations for purple box styling
 pub fn normalize_tool_name(tool_name: &str) -> String {
@@ -27,10 +30,14 @@ pub fn determine_action_type(tool_name: &str, input: &Value, worktree_path: &str
         }
         "write" | "edit" => {
             if let Some(file_path) = input.get("filePath").and_then(|p| p.as_str()) {
-                json!({
-                    "action": "file_write",
-                    "path": make_path_relative(file_path, worktree_path)
-                })
+                let path = make_path_relative(file_path, worktree_path);
+                match FileEditHunk::from_input(input, "oldString", "newString") {
+                    Some(edit) => json!(ActionType::FileEdit {
+                        path,
+                        edits: vec![edit],
+                    }),
+                    None => json!({"action": "file_write", "path": path}),
+                }
             } else {
                 json!({"action": "other", "description": "File write operation"})
             }
@@ -49,10 +56,13 @@ pub fn determine_action_type(tool_name: &str, input: &Value, worktree_path: &str
                 json!({"action": "other", "description": "Search operation"})
             }
         }
-        "todowrite" | "todoread" => {
+        "todowrite" => json!(ActionType::TodoUpdate {
+            todos: TodoItem::from_input(input),
+        }),
+        "todoread" => {
             json!({"action": "other", "description": "TODO list management"})
         }
-        _ => json!({"action": "other", "description": format!("Tool: {}", tool_name)}),
+        _ => json!(ActionType::unknown_tool(tool_name)),
     }
 }
 
@@ -80,45 +90,11 @@ pub fn generate_tool_content(tool_name: &str, input: &Value, worktree_path: &str
                 "Execute command".to_string()
             }
         }
-        "todowrite" | "todoread" => generate_todo_content(input),
+        "todowrite" | "todoread" => format_todo_list(&TodoItem::from_input(input)),
         _ => format!("`{}`", tool_name),
     }
 }
 
-/// Generate formatted content for TODO tools
-fn generate_todo_content(input: &Value) -> String {
-    // Extract todo list from input to show actual todos
-    if let Some(todos) = input.get("todos").and_then(|t| t.as_array()) {
-        let mut todo_items = Vec::new();
-        for todo in todos {
-            if let Some(content) = todo.get("content").and_then(|c| c.as_str()) {
-                let status = todo
-                    .get("status")
-                    .and_then(|s| s.as_str())
-                    .unwrap_or("pending");
-                let status_emoji = match status {
-                    "completed" => "✅",
-                    "in_progress" => "🔄",
-                    "pending" | "todo" => "⏳",
-                    _ => "📝",
-                };
-                let priority = todo
-                    .get("priority")
-                    .and_then(|p| p.as_str())
-                    .unwrap_or("medium");
-                todo_items.push(format!("{} {} ({})", status_emoji, content, priority));
-            }
-        }
-        if !todo_items.is_empty() {
-            format!("TODO List:\n{}", todo_items.join("\n"))
-        } else {
-            "Managing TODO list".to_string()
-        }
-    } else {
-        "Managing TODO list".to_string()
-    }
-}
-
 #[cfg(test)]
 mod tests {
     #[test]
@@ -0,0 +1,618 @@
+//! Shared streaming functionality with WAL for normalized conversation patches
+//!
+//! Executors push JSON Patch batches against a `NormalizedConversation` as their output
+//! arrives. Batches are kept in an in-memory Write-Ahead Log (WAL) per execution process
+//! so SSE clients can resume streaming from a batch cursor.
+
+use std::{collections::HashMap, sync::Mutex, time::Instant};
+
+use json_patch::{patch, Patch, PatchOperation};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{
+    executor::{
+        format_timestamp, link_tool_result, ActionType, Executor, ExecutorConfig, NormalizedEntry,
+        NormalizedEntryType,
+    },
+    models::{
+        config::Config,
+        execution_process::{ExecutionProcess, ExecutionProcessType},
+    },
+    utils::process_env,
+};
+
+lazy_static::lazy_static! {
+    /// Write-Ahead Log: Maps execution_process_id → WAL state
+    static ref WAL_MAP: Mutex<HashMap<Uuid, WalState>> = Mutex::new(HashMap::new());
+}
+
+/// How long a finished process keeps its WAL, so SSE clients can drain the last batches
+/// before the execution monitor marks the process as completed
+const WAL_RETENTION_AFTER_EOF: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// A batch of JSON patches for conversation streaming
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatchBatch {
+    /// Monotonic batch identifier for cursor-based streaming
+    pub batch_id: u64,
+    /// Array of JSON Patch operations (RFC 6902 format)
+    pub patches: Vec<Value>,
+    /// ISO 8601 timestamp when this batch was created
+    pub timestamp: String,
+    /// Content length reported by the executor when this batch was pushed
+    pub content_length: usize,
+}
+
+/// WAL state for a single execution process
+#[derive(Debug)]
+pub struct WalState {
+    pub batches: Vec<PatchBatch>,
+    /// Content length of the batches pushed since the last compaction; the snapshot a
+    /// compaction leaves behind doesn't count
+    pub total_content_length: usize,
+    pub next_batch_id: u64,
+    pub last_compaction: Instant,
+    pub last_access: Instant,
+    /// Number of entries pushed with `push_entries`, i.e. the index the next one lands at
+    pub entry_count: usize,
+    /// Streamed commands still waiting for their result: tool use ID → (index, entry)
+    pub pending_commands: HashMap<String, (usize, NormalizedEntry)>,
+}
+
+impl Default for WalState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WalState {
+    pub fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            batches: Vec::new(),
+            total_content_length: 0,
+            next_batch_id: 1,
+            last_compaction: now,
+            last_access: now,
+            entry_count: 0,
+            pending_commands: HashMap::new(),
+        }
+    }
+}
+
+/// Configuration for WAL compaction and size limits
+#[derive(Debug, Clone)]
+pub struct WalConfig {
+    pub wal_compaction_threshold: usize,
+    pub wal_compaction_size: usize,
+    pub wal_compaction_interval_ms: u64,
+    pub max_wal_batches: usize,
+    pub max_wal_total_size: usize,
+}
+
+impl Default for WalConfig {
+    fn default() -> Self {
+        Self {
+            wal_compaction_threshold: 40,
+            wal_compaction_size: 16000,
+            wal_compaction_interval_ms: 30000,
+            max_wal_batches: 100,
+            max_wal_total_size: 1024 * 1024, // 1MB per process
+        }
+    }
+}
+
+/// WAL streaming utilities shared by all executors
+pub struct LogStreaming;
+
+impl LogStreaming {
+    /// Create an empty WAL for an execution process so SSE clients are served from it
+    /// even before the first patch arrives
+    pub fn register(execution_process_id: Uuid) {
+        if let Ok(mut wal_map) = WAL_MAP.lock() {
+            wal_map.entry(execution_process_id).or_default();
+        }
+    }
+
+    /// Whether an execution process currently has a WAL
+    pub fn has_wal(execution_process_id: Uuid) -> bool {
+        WAL_MAP
+            .lock()
+            .map(|wal_map| wal_map.contains_key(&execution_process_id))
+            .unwrap_or(false)
+    }
+
+    /// Push patches to the WAL
+    pub fn push_patch(execution_process_id: Uuid, patches: Vec<Value>, content_length: usize) {
+        let mut wal_map = WAL_MAP.lock().unwrap();
+        let wal_state = wal_map.entry(execution_process_id).or_default();
+        Self::push_batch(execution_process_id, wal_state, patches, content_length);
+    }
+
+    /// Append normalized entries to the conversation in the WAL.
+    ///
+    /// A tool result also replaces the streamed command it belongs to with one carrying its
+    /// output and exit code, the way `link_tool_results` does for a whole log.
+    pub fn push_entries(execution_process_id: Uuid, entries: Vec<NormalizedEntry>) {
+        let mut wal_map = WAL_MAP.lock().unwrap();
+        let wal_state = wal_map.entry(execution_process_id).or_default();
+
+        let mut patches = Vec::new();
+        let mut content_length = 0;
+        for entry in entries {
+            match &entry.entry_type {
+                NormalizedEntryType::ToolUse {
+                    action_type: ActionType::CommandRun { .. },
+                    tool_use_id: Some(id),
+                    ..
+                } => {
+                    wal_state
+                        .pending_commands
+                        .insert(id.clone(), (wal_state.entry_count, entry.clone()));
+                }
+                NormalizedEntryType::ToolResult { tool_use_id, .. } => {
+                    if let Some((index, mut command)) =
+                        wal_state.pending_commands.remove(tool_use_id)
+                    {
+                        if link_tool_result(&mut command, &entry) {
+                            content_length += entry.content.len();
+                            patches.push(serde_json::json!({
+                                "op": "replace",
+                                "path": format!("/entries/{}", index),
+                                "value": command,
+                            }));
+                        }
+                    }
+                }
+                _ => {}
+            }
+
+            content_length += entry.content.len();
+            patches.push(serde_json::json!({
+                "op": "add",
+                "path": "/entries/-",
+                "value": entry,
+            }));
+            wal_state.entry_count += 1;
+        }
+
+        Self::push_batch(execution_process_id, wal_state, patches, content_length);
+    }
+
+    fn push_batch(
+        execution_process_id: Uuid,
+        wal_state: &mut WalState,
+        mut patches: Vec<Value>,
+        content_length: usize,
+    ) {
+        for patch in &mut patches {
+            process_env::redact_process_value(execution_process_id, patch);
+        }
+        let config = WalConfig::default();
+
+        // Update access time for orphan cleanup
+        wal_state.last_access = Instant::now();
+
+        // Enforce size limits by compacting everything into a snapshot, so clients resuming
+        // from any cursor still see every entry
+        if wal_state.batches.len() >= config.max_wal_batches
+            || wal_state.total_content_length >= config.max_wal_total_size
+        {
+            tracing::warn!(
+                "WAL size limits exceeded for process {} (batches: {}, size: {}), forcing compaction",
+                execution_process_id,
+                wal_state.batches.len(),
+                wal_state.total_content_length
+            );
+            Self::compact_wal(wal_state, 0);
+        }
+
+        let batch = PatchBatch {
+            batch_id: wal_state.next_batch_id,
+            patches,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            content_length,
+        };
+
+        wal_state.next_batch_id += 1;
+        wal_state.batches.push(batch);
+        wal_state.total_content_length += content_length;
+
+        // Check if compaction is needed
+        if Self::should_compact(wal_state, &config) {
+            // Keep the most recent batches for smooth incremental updates
+            Self::compact_wal(wal_state, 3);
+        }
+    }
+
+    /// Get WAL batches for an execution process, optionally filtering by cursor
+    pub fn get_wal_batches(
+        execution_process_id: Uuid,
+        after_batch_id: Option<u64>,
+    ) -> Option<Vec<PatchBatch>> {
+        WAL_MAP.lock().ok().and_then(|mut wal_map| {
+            wal_map.get_mut(&execution_process_id).map(|wal_state| {
+                // Update access time when WAL is retrieved
+                wal_state.last_access = Instant::now();
+
+                match after_batch_id {
+                    Some(cursor) => {
+                        // Return only batches with batch_id > cursor
+                        wal_state
+                            .batches
+                            .iter()
+                            .filter(|batch| batch.batch_id > cursor)
+                            .cloned()
+                            .collect()
+                    }
+                    None => {
+                        // Return all batches
+                        wal_state.batches.clone()
+                    }
+                }
+            })
+        })
+    }
+
+    /// Schedule WAL cleanup once an execution process has finished producing output
+    pub fn finish(execution_process_id: Uuid) {
+        tokio::spawn(async move {
+            tokio::time::sleep(WAL_RETENTION_AFTER_EOF).await;
+            Self::purge_wal(execution_process_id);
+        });
+    }
+
+    /// Remove WAL entry for a specific execution process
+    pub fn purge_wal(execution_process_id: Uuid) {
+        if let Ok(mut wal_map) = WAL_MAP.lock() {
+            wal_map.remove(&execution_process_id);
+            tracing::debug!(
+                "Cleaned up WAL for execution process {}",
+                execution_process_id
+            );
+        }
+    }
+
+    /// Check if WAL compaction is needed based on configured thresholds
+    fn should_compact(wal_state: &WalState, config: &WalConfig) -> bool {
+        wal_state.batches.len() >= config.wal_compaction_threshold
+            || wal_state.total_content_length >= config.wal_compaction_size
+            || wal_state.last_compaction.elapsed().as_millis() as u64
+                >= config.wal_compaction_interval_ms
+    }
+
+    /// Compact WAL by losslessly merging all but the `recent_count` newest batches into a
+    /// snapshot
+    fn compact_wal(wal_state: &mut WalState, recent_count: usize) {
+        let compact_count = wal_state.batches.len().saturating_sub(recent_count);
+        if compact_count <= 1 {
+            return; // Not enough to compact
+        }
+
+        // Start with an empty conversation and apply all patches sequentially
+        let mut conversation_value = serde_json::json!({
+            "entries": [],
+            "session_id": null,
+            "executor_type": null,
+            "prompt": null,
+            "summary": null
+        });
+
+        let mut total_content_length = 0;
+        let newest_compacted_batch_id = wal_state.batches[compact_count - 1].batch_id;
+        let compact_timestamp = chrono::Utc::now().to_rfc3339();
+
+        // Apply patches from oldest to newest (excluding recent ones) using json-patch crate
+        for batch in &wal_state.batches[..compact_count] {
+            // Convert Vec<Value> to json_patch::Patch
+            let patch_operations: Result<Vec<PatchOperation>, _> = batch
+                .patches
+                .iter()
+                .map(|p| serde_json::from_value(p.clone()))
+                .collect();
+
+            match patch_operations {
+                Ok(ops) => {
+                    let patch_obj = Patch(ops);
+                    if let Err(e) = patch(&mut conversation_value, &patch_obj) {
+                        tracing::warn!("Failed to apply patch during compaction: {}, skipping", e);
+                        continue;
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to deserialize patch operations: {}, skipping", e);
+                    continue;
+                }
+            }
+            total_content_length += batch.content_length;
+        }
+
+        // Extract the final entries array for the snapshot
+        let final_entries = conversation_value
+            .get("entries")
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default();
+
+        // Create a single snapshot patch that replaces the entire entries array.
+        // It takes the id of the newest batch it covers: clients whose cursor is past
+        // that id already hold this state, everyone else needs the snapshot.
+        let snapshot_patch = PatchBatch {
+            batch_id: newest_compacted_batch_id,
+            patches: vec![serde_json::json!({
+                "op": "replace",
+                "path": "/entries",
+                "value": final_entries
+            })],
+            timestamp: compact_timestamp,
+            content_length: total_content_length,
+        };
+
+        // Replace old batches with snapshot + keep recent batches
+        let mut new_batches = vec![snapshot_patch];
+        new_batches.extend_from_slice(&wal_state.batches[compact_count..]);
+        wal_state.batches = new_batches;
+        wal_state.total_content_length = wal_state.batches[1..]
+            .iter()
+            .map(|batch| batch.content_length)
+            .sum();
+
+        wal_state.last_compaction = Instant::now();
+
+        tracing::info!(
+            "Losslessly compacted WAL: {} batches → {} (1 snapshot + {} recent), preserving all content",
+            compact_count + wal_state.batches.len() - 1,
+            wal_state.batches.len(),
+            wal_state.batches.len() - 1
+        );
+    }
+}
+
+/// Turns a coding agent's output lines into `add` patches on the process WAL
+pub struct EntryPatcher {
+    executor: Box<dyn Executor>,
+    worktree_path: String,
+    execution_process_id: Uuid,
+}
+
+impl EntryPatcher {
+    /// Build a patcher for a coding agent execution process.
+    ///
+    /// Returns `None` for other process types and unknown executors, which are not
+    /// rendered as a conversation.
+    pub async fn for_process(
+        pool: &sqlx::SqlitePool,
+        config: &Config,
+        execution_process_id: Uuid,
+    ) -> Option<Self> {
+        let process = match ExecutionProcess::find_by_id(pool, execution_process_id).await {
+            Ok(Some(process)) => process,
+            Ok(None) => return None,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to load execution process {} for streaming: {}",
+                    execution_process_id,
+                    e
+                );
+                return None;
+            }
+        };
+
+        if process.process_type != ExecutionProcessType::CodingAgent {
+            return None;
+        }
+
+        let executor = process
+            .executor_type
+            .as_deref()?
+            .parse::<ExecutorConfig>()
+            .ok()?
+            .create_executor(config);
+
+        Some(Self::new(
+            executor,
+            process.working_directory,
+            execution_process_id,
+        ))
+    }
+
+    pub fn new(
+        executor: Box<dyn Executor>,
+        worktree_path: String,
+        execution_process_id: Uuid,
+    ) -> Self {
+        Self {
+            executor,
+            worktree_path,
+            execution_process_id,
+        }
+    }
+
+    /// Normalize one line of output and push any resulting entries to the WAL.
+    ///
+    /// Entries are appended with `/entries/-`, so lines recorded from outside the output
+    /// stream (e.g. messages sent to stdin) interleave with them. The WAL counts the entries
+    /// to find the command a tool result belongs to.
+    pub fn push_line(&self, line: &str) {
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.trim().is_empty() {
+            return;
+        }
+
+        let mut entries = self.executor.normalize_line(line, &self.worktree_path);
+        if entries.is_empty() {
+            return;
+        }
+        // The line was just received, so that's when its entries happened. Durations are
+        // left to whole-log normalization, which sees the entries before and after.
+        let received_at = format_timestamp(chrono::Utc::now());
+        for entry in entries.iter_mut().filter(|entry| entry.timestamp.is_none()) {
+            entry.timestamp = Some(received_at.clone());
+        }
+
+        LogStreaming::push_entries(self.execution_process_id, entries);
+    }
+
+    /// Mark the process output as complete
+    pub fn finish(self) {
+        LogStreaming::finish(self.execution_process_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add_entry_patch(index: usize) -> Vec<Value> {
+        vec![serde_json::json!({
+            "op": "add",
+            "path": format!("/entries/{}", index),
+            "value": {"content": format!("entry {}", index)},
+        })]
+    }
+
+    fn replay(entries: Vec<Value>, batches: &[PatchBatch], after_batch_id: u64) -> Vec<Value> {
+        let mut conversation = serde_json::json!({ "entries": entries });
+        for batch in batches.iter().filter(|b| b.batch_id > after_batch_id) {
+            let ops: Vec<PatchOperation> = batch
+                .patches
+                .iter()
+                .map(|p| serde_json::from_value(p.clone()).unwrap())
+                .collect();
+            patch(&mut conversation, &Patch(ops)).unwrap();
+        }
+        conversation["entries"].as_array().cloned().unwrap()
+    }
+
+    #[test]
+    fn test_streamed_entries_match_renormalized_logs() {
+        let worktree_path = "/tmp/vibe-kanban/worktrees/vk-fixture";
+        let logs = std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/normalizers/claude/1.0.56/fix-typo.log"
+        ))
+        .unwrap();
+        let executor = ExecutorConfig::Claude.create_executor(&Config::default());
+        let expected = executor
+            .normalize_logs(&logs, worktree_path)
+            .unwrap()
+            .entries;
+
+        let id = Uuid::new_v4();
+        let patcher = EntryPatcher::new(executor, worktree_path.to_string(), id);
+        for line in logs.lines() {
+            patcher.push_line(line);
+        }
+        let streamed = replay(
+            Vec::new(),
+            &LogStreaming::get_wal_batches(id, None).unwrap(),
+            0,
+        );
+
+        // Streamed entries are stamped with the time they arrived
+        let without_timestamps = |entries: Vec<Value>| {
+            entries
+                .into_iter()
+                .map(|mut entry| {
+                    entry["timestamp"] = Value::Null;
+                    entry
+                })
+                .collect::<Vec<_>>()
+        };
+        let expected = expected
+            .iter()
+            .map(|entry| serde_json::to_value(entry).unwrap())
+            .collect();
+        assert_eq!(without_timestamps(streamed), without_timestamps(expected));
+
+        LogStreaming::purge_wal(id);
+    }
+
+    #[test]
+    fn test_get_wal_batches_respects_cursor() {
+        let id = Uuid::new_v4();
+        assert!(LogStreaming::get_wal_batches(id, None).is_none());
+
+        LogStreaming::register(id);
+        assert!(LogStreaming::has_wal(id));
+        assert!(LogStreaming::get_wal_batches(id, None).unwrap().is_empty());
+
+        for i in 0..3 {
+            LogStreaming::push_patch(id, add_entry_patch(i), 1);
+        }
+
+        let all = LogStreaming::get_wal_batches(id, None).unwrap();
+        assert_eq!(
+            all.iter().map(|b| b.batch_id).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        let after = LogStreaming::get_wal_batches(id, Some(2)).unwrap();
+        assert_eq!(after.len(), 1);
+        assert_eq!(after[0].batch_id, 3);
+
+        LogStreaming::purge_wal(id);
+        assert!(!LogStreaming::has_wal(id));
+    }
+
+    #[test]
+    fn test_compaction_is_lossless_for_new_and_resuming_clients() {
+        let id = Uuid::new_v4();
+        let total = WalConfig::default().wal_compaction_threshold + 5;
+        let mut seen_before_compaction = Vec::new();
+
+        for i in 0..total {
+            LogStreaming::push_patch(id, add_entry_patch(i), 1);
+            if i == 9 {
+                seen_before_compaction = LogStreaming::get_wal_batches(id, None).unwrap();
+            }
+        }
+
+        let batches = LogStreaming::get_wal_batches(id, None).unwrap();
+        assert!(batches.len() < total);
+
+        // A fresh client rebuilds the whole conversation
+        assert_eq!(replay(Vec::new(), &batches, 0).len(), total);
+
+        // A client that saw the first ten batches catches up without gaps
+        let cursor = seen_before_compaction.last().unwrap().batch_id;
+        let seen = replay(Vec::new(), &seen_before_compaction, 0);
+        let resumed = replay(seen, &batches, cursor);
+        assert_eq!(resumed.len(), total);
+
+        LogStreaming::purge_wal(id);
+    }
+
+    #[test]
+    fn test_size_limit_compacts_without_dropping_entries() {
+        let id = Uuid::new_v4();
+        let config = WalConfig::default();
+        // Few enough batches to stay under the compaction threshold, but large enough that
+        // together they cross the size limit
+        let count = config.wal_compaction_threshold / 2;
+        let content_length = config.max_wal_total_size / (count - 2);
+
+        let mut first_seen = Vec::new();
+        for i in 0..count {
+            LogStreaming::push_patch(id, add_entry_patch(i), content_length);
+            if i == 0 {
+                first_seen = LogStreaming::get_wal_batches(id, None).unwrap();
+            }
+        }
+
+        let batches = LogStreaming::get_wal_batches(id, None).unwrap();
+        assert!(batches.len() < count);
+        assert_eq!(replay(Vec::new(), &batches, 0).len(), count);
+
+        // A client that only saw the first batch still catches up on everything
+        let seen = replay(Vec::new(), &first_seen, 0);
+        assert_eq!(replay(seen, &batches, 1).len(), count);
+
+        let wal_map = WAL_MAP.lock().unwrap();
+        assert!(wal_map[&id].total_content_length < config.max_wal_total_size);
+        drop(wal_map);
+
+        LogStreaming::purge_wal(id);
+    }
+}
@@ -20,6 +20,9 @@ mod app_state;
 mod execution_monitor;
 mod executor;
 mod executors;
+// The MCP server binaries run these through the library. Declared here as well, so the
+// model functions only they call aren't dead code in this binary.
+#[allow(dead_code)]
 mod mcp;
 mod middleware;
 mod models;
@@ -35,7 +38,8 @@ use middleware::{
 };
 use models::{ApiResponse, Config};
 use routes::{
-    auth, config, filesystem, health, projects, stream, task_attempts, task_templates, tasks,
+    auth, config, events, filesystem, health, projects, search, stream, task_attempts,
+    task_templates, tasks,
 };
 use services::PrMonitorService;
 
@@ -165,6 +169,10 @@ fn main() -> anyhow::Result<()> {
             // Load configuration
             let config_path = utils::config_path();
             let config = Config::load(&config_path)?;
+            if let Err(e) = executors::custom::validate_custom_executors(&config.custom_executors)
+            {
+                tracing::warn!("Invalid custom executors in config: {}", e);
+            }
             let config_arc = Arc::new(RwLock::new(config));
 
             // Create app state
@@ -181,7 +189,7 @@ fn main() -> anyhow::Result<()> {
             });
 
             // Start PR monitoring service
-            let pr_monitor = PrMonitorService::new(pool.clone());
+            let pr_monitor = PrMonitorService::new(app_state.clone());
             let config_for_monitor = config_arc.clone();
 
             tokio::spawn(async move {
@@ -199,10 +207,12 @@ fn main() -> anyhow::Result<()> {
                 .merge(filesystem::filesystem_router())
                 .merge(config::config_router())
                 .merge(auth::auth_router())
+                .merge(search::search_router())
                 .route("/sounds/:filename", get(serve_sound_file))
                 .merge(
                     Router::new()
                         .route("/execution-processes/:process_id", get(task_attempts::get_execution_process))
+                        .route("/execution-processes/:process_id/output", get(task_attempts::get_execution_process_output))
                         .route_layer(from_fn_with_state(app_state.clone(), load_execution_process_simple_middleware))
                 );
 
@@ -229,6 +239,8 @@ fn main() -> anyhow::Result<()> {
             let project_routes = Router::new()
                 .merge(projects::projects_base_router())
                 .merge(projects::projects_with_id_router()
+                    .layer(from_fn_with_state(app_state.clone(), load_project_middleware)))
+                .merge(events::events_router()
                     .layer(from_fn_with_state(app_state.clone(), load_project_middleware)));
 
             // Task routes with appropriate middleware
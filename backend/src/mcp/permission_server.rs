@@ -0,0 +1,221 @@
+use std::{
+    future::Future,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use rmcp::{
+    handler::server::tool::{Parameters, ToolRouter},
+    model::{
+        CallToolResult, Content, Implementation, ProtocolVersion, ServerCapabilities, ServerInfo,
+    },
+    schemars, tool, tool_handler, tool_router, Error as RmcpError, ServerHandler,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use sqlx::SqlitePool;
+
+use crate::models::{
+    execution_process::ExecutionProcess,
+    task::Task,
+    task_attempt::TaskAttempt,
+    tool_allowlist::ToolAllowlist,
+    tool_approval::{ToolApproval, ToolApprovalStatus},
+};
+
+/// How often a pending request is checked for a decision
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ApproveToolUseRequest {
+    #[schemars(description = "Name of the tool the agent wants to use")]
+    pub tool_name: String,
+    #[schemars(description = "Input the agent wants to pass to the tool")]
+    pub input: Value,
+    #[schemars(description = "Identifier of the tool use, if available")]
+    pub tool_use_id: Option<String>,
+}
+
+/// Outcome reported back to Claude, in the shape `--permission-prompt-tool` expects
+enum Decision {
+    Allow,
+    Deny(String),
+}
+
+impl Decision {
+    fn to_response(&self, input: Value) -> Value {
+        match self {
+            Decision::Allow => serde_json::json!({
+                "behavior": "allow",
+                "updatedInput": input,
+            }),
+            Decision::Deny(message) => serde_json::json!({
+                "behavior": "deny",
+                "message": message,
+            }),
+        }
+    }
+}
+
+/// Answers Claude's tool permission prompts for the execution running in one worktree.
+/// Requests not covered by the project's allowlist wait for a human decision.
+#[derive(Debug, Clone)]
+pub struct PermissionServer {
+    pub pool: SqlitePool,
+    worktree_path: PathBuf,
+    timeout: Duration,
+    tool_router: ToolRouter<PermissionServer>,
+}
+
+impl PermissionServer {
+    pub fn new(pool: SqlitePool, worktree_path: PathBuf, timeout: Duration) -> Self {
+        Self {
+            pool,
+            worktree_path,
+            timeout,
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    async fn decide(&self, tool_name: &str, input: &Value) -> Result<Decision, sqlx::Error> {
+        let worktree = self.worktree_path.to_string_lossy();
+        let Some(process) =
+            ExecutionProcess::find_running_coding_agent_by_working_directory(&self.pool, &worktree)
+                .await?
+        else {
+            return Ok(Decision::Deny(
+                "No running vibe-kanban execution was found for this worktree".to_string(),
+            ));
+        };
+
+        let allowlist = match TaskAttempt::find_by_id(&self.pool, process.task_attempt_id).await? {
+            Some(attempt) => match Task::find_by_id(&self.pool, attempt.task_id).await? {
+                Some(task) => ToolAllowlist::find_for_project(&self.pool, task.project_id).await?,
+                None => ToolAllowlist::default(),
+            },
+            None => ToolAllowlist::default(),
+        };
+        let tool_input = input.to_string();
+
+        if allowlist.allows(tool_name, input, &self.worktree_path) {
+            ToolApproval::create(
+                &self.pool,
+                process.id,
+                tool_name,
+                &tool_input,
+                ToolApprovalStatus::Approved,
+                Some("Matched the project allowlist"),
+            )
+            .await?;
+            return Ok(Decision::Allow);
+        }
+
+        let approval = ToolApproval::create(
+            &self.pool,
+            process.id,
+            tool_name,
+            &tool_input,
+            ToolApprovalStatus::Pending,
+            None,
+        )
+        .await?;
+        tracing::info!(
+            "Waiting for approval {} of {} for execution process {}",
+            approval.id,
+            tool_name,
+            process.id
+        );
+
+        let started = Instant::now();
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+
+            let Some(current) = ToolApproval::find_by_id(&self.pool, approval.id).await? else {
+                return Ok(Decision::Deny(
+                    "The approval request was removed".to_string(),
+                ));
+            };
+
+            match current.status {
+                ToolApprovalStatus::Approved => return Ok(Decision::Allow),
+                ToolApprovalStatus::Denied => {
+                    return Ok(Decision::Deny(
+                        current
+                            .reason
+                            .unwrap_or_else(|| "The user denied this tool use".to_string()),
+                    ))
+                }
+                ToolApprovalStatus::TimedOut => {
+                    return Ok(Decision::Deny(
+                        "Nobody answered the approval request in time".to_string(),
+                    ))
+                }
+                ToolApprovalStatus::Pending if started.elapsed() >= self.timeout => {
+                    let reason = format!("No decision within {} seconds", self.timeout.as_secs());
+                    // A decision may have landed since we read the row; re-check it if so
+                    if ToolApproval::decide(
+                        &self.pool,
+                        approval.id,
+                        ToolApprovalStatus::TimedOut,
+                        Some(&reason),
+                    )
+                    .await?
+                    {
+                        return Ok(Decision::Deny(reason));
+                    }
+                }
+                ToolApprovalStatus::Pending => {}
+            }
+        }
+    }
+}
+
+#[tool_router]
+impl PermissionServer {
+    #[tool(
+        description = "Ask the vibe-kanban user whether a tool use is allowed. Blocks until they decide or the request times out."
+    )]
+    async fn approve_tool_use(
+        &self,
+        Parameters(ApproveToolUseRequest {
+            tool_name,
+            input,
+            tool_use_id,
+        }): Parameters<ApproveToolUseRequest>,
+    ) -> Result<CallToolResult, RmcpError> {
+        tracing::debug!(
+            "Permission requested for {} (tool use {:?})",
+            tool_name,
+            tool_use_id
+        );
+        let decision = match self.decide(&tool_name, &input).await {
+            Ok(decision) => decision,
+            Err(e) => {
+                tracing::error!("Failed to process approval for {}: {}", tool_name, e);
+                Decision::Deny(format!("Failed to record the approval request: {}", e))
+            }
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(
+            decision.to_response(input).to_string(),
+        )]))
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for PermissionServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            protocol_version: ProtocolVersion::V_2025_03_26,
+            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            server_info: Implementation {
+                name: "vibe-kanban-approvals".to_string(),
+                version: "1.0.0".to_string(),
+            },
+            instructions: Some(
+                "Permission prompt tool for coding agents run by vibe-kanban. TOOLS: 'approve_tool_use'."
+                    .to_string(),
+            ),
+        }
+    }
+}
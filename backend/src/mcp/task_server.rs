@@ -32,6 +32,10 @@ pub struct CreateTaskResponse {
     pub success: bool,
     pub task_id: String,
     pub message: String,
+    #[schemars(
+        description = "Executor that attempts on this task will use by default (the project's executor, or null for the global default)"
+    )]
+    pub executor: Option<String>,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -46,6 +50,16 @@ pub struct ProjectSummary {
     pub setup_script: Option<String>,
     #[schemars(description = "Optional development script for the project")]
     pub dev_script: Option<String>,
+    #[schemars(description = "Default executor for this project (null uses the global default)")]
+    pub executor: Option<String>,
+    #[schemars(description = "Extra CLI arguments passed to the executor for this project")]
+    pub executor_args: Option<String>,
+    #[schemars(
+        description = "Whether coding agents and setup scripts are confined to their worktree"
+    )]
+    pub sandbox: bool,
+    #[schemars(description = "Whether sandboxed processes may use the network")]
+    pub sandbox_network: bool,
     #[schemars(description = "Current git branch (if available)")]
     pub current_branch: Option<String>,
     #[schemars(description = "When the project was created")]
@@ -198,7 +212,6 @@ pub struct TaskServer {
 }
 
 impl TaskServer {
-    #[allow(dead_code)]
     pub fn new(pool: SqlitePool) -> Self {
         Self {
             pool,
@@ -237,8 +250,8 @@ impl TaskServer {
         };
 
         // Check if project exists
-        match Project::exists(&self.pool, project_uuid).await {
-            Ok(false) => {
+        let project = match Project::find_by_id(&self.pool, project_uuid).await {
+            Ok(None) => {
                 let error_response = serde_json::json!({
                     "success": false,
                     "error": "Project not found",
@@ -261,8 +274,8 @@ impl TaskServer {
                         .unwrap_or_else(|_| "Database error".to_string()),
                 )]));
             }
-            Ok(true) => {}
-        }
+            Ok(Some(project)) => project,
+        };
 
         let task_id = Uuid::new_v4();
         let create_task_data = CreateTask {
@@ -278,6 +291,7 @@ impl TaskServer {
                     success: true,
                     task_id: task_id.to_string(),
                     message: "Task created successfully".to_string(),
+                    executor: project.executor,
                 };
                 Ok(CallToolResult::success(vec![Content::text(
                     serde_json::to_string_pretty(&success_response)
@@ -315,6 +329,10 @@ impl TaskServer {
                             git_repo_path: project_with_branch.git_repo_path,
                             setup_script: project_with_branch.setup_script,
                             dev_script: project_with_branch.dev_script,
+                            executor: project_with_branch.executor,
+                            executor_args: project_with_branch.executor_args,
+                            sandbox: project_with_branch.sandbox,
+                            sandbox_network: project_with_branch.sandbox_network,
                             current_branch: project_with_branch.current_branch,
                             created_at: project_with_branch.created_at.to_rfc3339(),
                             updated_at: project_with_branch.updated_at.to_rfc3339(),
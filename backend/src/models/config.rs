@@ -1,9 +1,13 @@
 use std::path::PathBuf;
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
-use crate::executor::ExecutorConfig;
+use crate::{
+    executor::ExecutorConfig, executors::CustomExecutorConfig,
+    models::execution_process::ExecutionProcessType,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
@@ -20,6 +24,22 @@ pub struct Config {
     pub editor: EditorConfig,
     pub github: GitHubConfig,
     pub analytics_enabled: Option<bool>,
+    #[serde(default)]
+    pub custom_executors: Vec<CustomExecutorConfig>,
+    #[serde(default)]
+    pub execution_timeouts: ExecutionTimeouts,
+    #[serde(default)]
+    pub tool_approvals: ToolApprovalConfig,
+    #[serde(default)]
+    pub sandbox: SandboxConfig,
+    /// Prompt template for projects without their own, `None` uses the built-in one
+    #[serde(default)]
+    pub prompt_template: Option<String>,
+    /// Most attempts running at once across all projects, from their setup script through the
+    /// coding agent and tests; further attempts and follow-ups wait in the execution queue.
+    /// `None` for no limit
+    #[serde(default)]
+    pub max_concurrent_agents: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -78,6 +98,60 @@ pub enum SoundFile {
     Rooster,
 }
 
+/// Limits enforced by the execution monitor, per process type. All are disabled unless
+/// configured.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct ExecutionTimeouts {
+    pub setup_script: ProcessTimeouts,
+    pub coding_agent: ProcessTimeouts,
+    pub dev_server: ProcessTimeouts,
+    pub test_script: ProcessTimeouts,
+}
+
+/// `None` disables the corresponding limit
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ProcessTimeouts {
+    /// Kill the process once it has been running for this long
+    pub max_runtime_minutes: Option<u32>,
+    /// Kill the process if it writes no output and receives no input for this long. Time
+    /// spent waiting for a tool approval doesn't count.
+    pub idle_minutes: Option<u32>,
+}
+
+/// Route Claude's tool permission requests to a human instead of skipping them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct ToolApprovalConfig {
+    pub enabled: bool,
+    /// Requests nobody answers within this time are denied
+    pub timeout_minutes: u32,
+}
+
+/// Machine-wide settings for projects that sandbox their processes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct SandboxConfig {
+    /// Directories outside the worktree that sandboxed processes may write to, such as
+    /// package caches and agent state. `~` expands to the home directory.
+    ///
+    /// Files can be listed too, but they can only be rewritten in place. Allowing a file to
+    /// be created next to them would allow it anywhere beneath their directory, so tools
+    /// that save by renaming a new copy over the file can't save it. Claude saves
+    /// `~/.claude.json` that way, so changes it makes there while sandboxed are lost.
+    pub cache_dirs: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimeoutReason {
+    MaxRuntime { minutes: u32 },
+    Idle { minutes: u32 },
+}
+
 // Constants for frontend
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
@@ -168,6 +242,43 @@ impl Default for Config {
             editor: EditorConfig::default(),
             github: GitHubConfig::default(),
             analytics_enabled: None,
+            custom_executors: Vec::new(),
+            execution_timeouts: ExecutionTimeouts::default(),
+            tool_approvals: ToolApprovalConfig::default(),
+            sandbox: SandboxConfig::default(),
+            prompt_template: None,
+            max_concurrent_agents: None,
+        }
+    }
+}
+
+impl Default for ToolApprovalConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            timeout_minutes: 10,
+        }
+    }
+}
+
+impl Default for SandboxConfig {
+    fn default() -> Self {
+        Self {
+            cache_dirs: [
+                "~/.cache",
+                "~/.npm",
+                "~/.cargo/registry",
+                "~/.claude",
+                "~/.claude.json",
+                "~/.gemini",
+                "~/.config/amp",
+                "~/.local/share/amp",
+                "~/.config/opencode",
+                "~/.local/share/opencode",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect(),
         }
     }
 }
@@ -212,6 +323,56 @@ impl EditorConfig {
     }
 }
 
+impl ExecutionTimeouts {
+    pub fn for_process_type(&self, process_type: &ExecutionProcessType) -> &ProcessTimeouts {
+        match process_type {
+            ExecutionProcessType::SetupScript => &self.setup_script,
+            ExecutionProcessType::CodingAgent => &self.coding_agent,
+            ExecutionProcessType::DevServer => &self.dev_server,
+            ExecutionProcessType::TestScript => &self.test_script,
+        }
+    }
+}
+
+impl ProcessTimeouts {
+    /// Check whether a process started at `started_at` has exceeded either limit.
+    /// Processes without any activity are idle since they started.
+    pub fn check(
+        &self,
+        started_at: DateTime<Utc>,
+        last_activity_at: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> Option<TimeoutReason> {
+        if let Some(minutes) = self.max_runtime_minutes {
+            if now - started_at >= chrono::Duration::minutes(minutes as i64) {
+                return Some(TimeoutReason::MaxRuntime { minutes });
+            }
+        }
+
+        if let Some(minutes) = self.idle_minutes {
+            let last_activity = last_activity_at.unwrap_or(started_at).max(started_at);
+            if now - last_activity >= chrono::Duration::minutes(minutes as i64) {
+                return Some(TimeoutReason::Idle { minutes });
+            }
+        }
+
+        None
+    }
+}
+
+impl std::fmt::Display for TimeoutReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeoutReason::MaxRuntime { minutes } => {
+                write!(f, "exceeded the maximum runtime of {} minutes", minutes)
+            }
+            TimeoutReason::Idle { minutes } => {
+                write!(f, "was idle for {} minutes", minutes)
+            }
+        }
+    }
+}
+
 impl SoundFile {
     pub fn to_filename(&self) -> &'static str {
         match self {
@@ -372,3 +533,77 @@ impl Config {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_execution_timeouts_default_to_disabled() {
+        let timeouts: ExecutionTimeouts = serde_json::from_str("{}").unwrap();
+        for process_type in [
+            ExecutionProcessType::SetupScript,
+            ExecutionProcessType::CodingAgent,
+            ExecutionProcessType::DevServer,
+            ExecutionProcessType::TestScript,
+        ] {
+            assert_eq!(
+                timeouts.for_process_type(&process_type),
+                &ProcessTimeouts::default()
+            );
+        }
+    }
+
+    #[test]
+    fn test_process_timeouts_check() {
+        let started_at = Utc::now() - chrono::Duration::minutes(90);
+        let now = Utc::now();
+
+        let runtime = ProcessTimeouts {
+            max_runtime_minutes: Some(60),
+            idle_minutes: None,
+        };
+        assert_eq!(
+            runtime.check(started_at, Some(now), now),
+            Some(TimeoutReason::MaxRuntime { minutes: 60 })
+        );
+
+        let idle = ProcessTimeouts {
+            max_runtime_minutes: None,
+            idle_minutes: Some(30),
+        };
+        assert_eq!(idle.check(started_at, Some(now), now), None);
+        assert_eq!(
+            idle.check(started_at, Some(now - chrono::Duration::minutes(45)), now),
+            Some(TimeoutReason::Idle { minutes: 30 })
+        );
+        // No activity at all counts as idle since start
+        assert_eq!(
+            idle.check(started_at, None, now),
+            Some(TimeoutReason::Idle { minutes: 30 })
+        );
+
+        assert_eq!(
+            ProcessTimeouts::default().check(started_at, None, now),
+            None
+        );
+
+        assert_eq!(
+            TimeoutReason::Idle { minutes: 30 }.to_string(),
+            "was idle for 30 minutes"
+        );
+    }
+
+    #[test]
+    fn test_execution_timeouts_fill_missing_fields() {
+        let timeouts: ExecutionTimeouts =
+            serde_json::from_str(r#"{"coding_agent": {"max_runtime_minutes": 120}}"#).unwrap();
+
+        assert_eq!(timeouts.coding_agent.max_runtime_minutes, Some(120));
+        assert_eq!(timeouts.coding_agent.idle_minutes, None);
+        assert_eq!(
+            timeouts.setup_script,
+            ExecutionTimeouts::default().setup_script
+        );
+    }
+}
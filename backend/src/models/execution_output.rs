@@ -0,0 +1,356 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+lazy_static::lazy_static! {
+    /// Output read so far of running processes whose logs are being polled, so each poll
+    /// only reads the chunks appended since the last one
+    static ref POLLED_OUTPUT: Mutex<HashMap<(Uuid, OutputStream), OutputReader>> =
+        Mutex::new(HashMap::new());
+}
+
+/// Marker the stderr streamer puts between the chunks it flushes
+pub const STDERR_CHUNK_BOUNDARY: &str = "---STDERR_CHUNK_BOUNDARY---";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "output_stream", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+impl OutputStream {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OutputStream::Stdout => "stdout",
+            OutputStream::Stderr => "stderr",
+        }
+    }
+}
+
+/// A piece of a process's output, as appended
+#[derive(Debug, Clone, FromRow)]
+pub struct OutputChunk {
+    /// Where the chunk starts within its stream, in bytes
+    pub byte_offset: i64,
+    pub content: String,
+    /// When the chunk was captured
+    pub created_at: DateTime<Utc>,
+}
+
+/// A stream's output with the time each of its lines was captured
+#[derive(Debug, Clone, Default)]
+pub struct TimedOutput {
+    pub content: String,
+    /// Capture time of each line of `content`, in order
+    pub line_times: Vec<DateTime<Utc>>,
+}
+
+impl TimedOutput {
+    /// Append captured text, tagging the lines it starts with `captured_at`
+    fn push(&mut self, text: &str, captured_at: DateTime<Utc>) {
+        let mut at_line_start = self.content.is_empty() || self.content.ends_with('\n');
+        for c in text.chars() {
+            if at_line_start {
+                self.line_times.push(captured_at);
+            }
+            at_line_start = c == '\n';
+        }
+        self.content.push_str(text);
+    }
+
+    /// Capture time of the line containing byte `position` of the content
+    pub fn time_at(&self, position: usize) -> Option<DateTime<Utc>> {
+        let line = self.content.as_bytes()[..position.min(self.content.len())]
+            .iter()
+            .filter(|&&b| b == b'\n')
+            .count();
+        self.line_times
+            .get(line)
+            .or_else(|| self.line_times.last())
+            .copied()
+    }
+}
+
+/// Output of one stream from `offset` on, as served to clients reading it piece by piece
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct OutputRange {
+    pub content: String,
+    pub offset: usize,
+    /// Where the next read continues
+    pub next_offset: usize,
+}
+
+/// Append-only storage of execution process output
+pub struct ExecutionOutput;
+
+impl ExecutionOutput {
+    /// Append a chunk to the end of a stream
+    pub async fn append(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        stream: OutputStream,
+        content: &str,
+    ) -> Result<(), sqlx::Error> {
+        if content.is_empty() {
+            return Ok(());
+        }
+        let now = Utc::now();
+        // The offset is computed in the insert itself, so concurrent appends can't share one
+        sqlx::query!(
+            r#"INSERT INTO execution_output_chunks (
+                execution_process_id, stream, byte_offset, content, created_at
+               )
+               VALUES ($1, $2, COALESCE((
+                SELECT byte_offset + length(CAST(content AS BLOB))
+                  FROM execution_output_chunks
+                 WHERE execution_process_id = $1 AND stream = $2
+                 ORDER BY byte_offset DESC
+                 LIMIT 1
+               ), 0), $3, $4)"#,
+            execution_process_id,
+            stream,
+            content,
+            now
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Chunks of a stream that end after `from_offset`, in order
+    pub async fn find_chunks(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        stream: OutputStream,
+        from_offset: usize,
+    ) -> Result<Vec<OutputChunk>, sqlx::Error> {
+        let from_offset = from_offset as i64;
+        sqlx::query_as!(
+            OutputChunk,
+            r#"SELECT
+                byte_offset as "byte_offset!: i64",
+                content,
+                created_at as "created_at!: DateTime<Utc>"
+               FROM execution_output_chunks
+               WHERE execution_process_id = $1
+                 AND stream = $2
+                 AND byte_offset + length(CAST(content AS BLOB)) > $3
+               ORDER BY byte_offset ASC"#,
+            execution_process_id,
+            stream,
+            from_offset
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// A stream's output from `offset` on. Boundary markers are left out of stderr.
+    pub async fn read(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        stream: OutputStream,
+        offset: usize,
+    ) -> Result<OutputRange, sqlx::Error> {
+        let chunks = Self::find_chunks(pool, execution_process_id, stream, offset).await?;
+        let (mut content, next_offset) = join_chunks(&chunks, offset);
+        if stream == OutputStream::Stderr {
+            content = content.replace(STDERR_CHUNK_BOUNDARY, "");
+        }
+        Ok(OutputRange {
+            content,
+            offset,
+            next_offset,
+        })
+    }
+
+    /// All of a stream's output
+    pub async fn read_to_string(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        stream: OutputStream,
+    ) -> Result<String, sqlx::Error> {
+        let chunks = Self::find_chunks(pool, execution_process_id, stream, 0).await?;
+        Ok(join_chunks(&chunks, 0).0)
+    }
+
+    /// All of a stream's output with the time of each line
+    pub async fn read_timed(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        stream: OutputStream,
+    ) -> Result<TimedOutput, sqlx::Error> {
+        let mut reader = OutputReader::new(execution_process_id, stream);
+        reader.read_new(pool).await?;
+        Ok(reader.output)
+    }
+
+    /// All of a stream's output, for logs that are polled. While the process runs, the
+    /// output read so far is kept so the next poll only reads what was appended since.
+    pub async fn read_polled(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        stream: OutputStream,
+        running: bool,
+    ) -> Result<TimedOutput, sqlx::Error> {
+        let key = (execution_process_id, stream);
+        let cached = POLLED_OUTPUT.lock().unwrap().remove(&key);
+        let mut reader = cached.unwrap_or_else(|| OutputReader::new(execution_process_id, stream));
+        reader.read_new(pool).await?;
+
+        let output = reader.output().clone();
+        if running {
+            POLLED_OUTPUT.lock().unwrap().insert(key, reader);
+        }
+        Ok(output)
+    }
+
+    /// Drop the output kept for polling a process that finished
+    pub fn forget_polled(execution_process_id: Uuid) {
+        POLLED_OUTPUT
+            .lock()
+            .unwrap()
+            .retain(|(id, _), _| *id != execution_process_id);
+    }
+
+    /// Number of complete lines in a stream
+    pub async fn count_lines(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        stream: OutputStream,
+    ) -> Result<usize, sqlx::Error> {
+        let lines = sqlx::query_scalar!(
+            r#"SELECT COALESCE(SUM(length(content) - length(replace(content, char(10), ''))), 0)
+                as "lines!: i64"
+               FROM execution_output_chunks
+               WHERE execution_process_id = $1 AND stream = $2"#,
+            execution_process_id,
+            stream
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(lines as usize)
+    }
+}
+
+/// Reads a stream of a process incrementally, keeping what it read so far
+#[derive(Debug)]
+pub struct OutputReader {
+    execution_process_id: Uuid,
+    stream: OutputStream,
+    output: TimedOutput,
+    next_offset: usize,
+}
+
+impl OutputReader {
+    pub fn new(execution_process_id: Uuid, stream: OutputStream) -> Self {
+        Self {
+            execution_process_id,
+            stream,
+            output: TimedOutput::default(),
+            next_offset: 0,
+        }
+    }
+
+    /// Read the chunks appended since the last read, returning whether there were any
+    pub async fn read_new(&mut self, pool: &SqlitePool) -> Result<bool, sqlx::Error> {
+        let chunks = ExecutionOutput::find_chunks(
+            pool,
+            self.execution_process_id,
+            self.stream,
+            self.next_offset,
+        )
+        .await?;
+        let mut read_any = false;
+        for chunk in &chunks {
+            let content = chunk_from(chunk, self.next_offset);
+            self.output.push(content, chunk.created_at);
+            read_any |= !content.is_empty();
+            self.next_offset = chunk.byte_offset as usize + chunk.content.len();
+        }
+        Ok(read_any)
+    }
+
+    pub fn output(&self) -> &TimedOutput {
+        &self.output
+    }
+}
+
+/// Concatenate chunks that end after `from_offset`, cutting what comes before it off the first.
+/// Returns the content and the offset just past it.
+fn join_chunks(chunks: &[OutputChunk], from_offset: usize) -> (String, usize) {
+    let mut content = String::new();
+    let mut next_offset = from_offset;
+    for chunk in chunks {
+        content.push_str(chunk_from(chunk, from_offset));
+        next_offset = chunk.byte_offset as usize + chunk.content.len();
+    }
+    (content, next_offset)
+}
+
+/// The part of a chunk from `from_offset` on, moved forward to a character boundary
+fn chunk_from(chunk: &OutputChunk, from_offset: usize) -> &str {
+    let start = from_offset.saturating_sub(chunk.byte_offset as usize);
+    let mut start = start.min(chunk.content.len());
+    while !chunk.content.is_char_boundary(start) {
+        start += 1;
+    }
+    &chunk.content[start..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(byte_offset: i64, content: &str) -> OutputChunk {
+        OutputChunk {
+            byte_offset,
+            content: content.to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_join_chunks_reads_from_an_offset() {
+        let chunks = vec![chunk(0, "first\n"), chunk(6, "second\n")];
+        assert_eq!(join_chunks(&chunks, 0), ("first\nsecond\n".to_string(), 13));
+        assert_eq!(join_chunks(&chunks[1..], 9), ("ond\n".to_string(), 13));
+        assert_eq!(join_chunks(&[], 13), (String::new(), 13));
+
+        // An offset inside a character moves on to the next one
+        let chunks = vec![chunk(0, "héllo")];
+        assert_eq!(join_chunks(&chunks, 2), ("llo".to_string(), 6));
+    }
+
+    #[test]
+    fn test_timed_output_tags_lines_with_their_capture_time() {
+        let first = Utc::now();
+        let second = first + chrono::Duration::seconds(5);
+        let mut output = TimedOutput::default();
+        output.push("one\ntw", first);
+        // The rest of a line keeps the time it started
+        output.push("o\nthree\n", second);
+        output.push("four", second + chrono::Duration::seconds(1));
+
+        assert_eq!(output.content.lines().count(), output.line_times.len());
+        assert_eq!(
+            output.line_times,
+            vec![first, first, second, second + chrono::Duration::seconds(1)]
+        );
+        assert_eq!(output.time_at(0), Some(first));
+        assert_eq!(output.time_at(8), Some(second));
+        assert_eq!(
+            output.time_at(100),
+            Some(second + chrono::Duration::seconds(1))
+        );
+    }
+}
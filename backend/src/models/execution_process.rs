@@ -1,26 +1,14 @@
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize, Serializer};
+use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, SqlitePool, Type};
 use ts_rs::TS;
 use uuid::Uuid;
 
-use crate::app_state::ExecutionType;
-
-/// Filter out stderr boundary markers from output
-fn filter_stderr_boundary_markers(stderr: &Option<String>) -> Option<String> {
-    stderr
-        .as_ref()
-        .map(|s| s.replace("---STDERR_CHUNK_BOUNDARY---", ""))
-}
-
-/// Custom serializer for stderr field that filters out boundary markers
-fn serialize_filtered_stderr<S>(stderr: &Option<String>, serializer: S) -> Result<S::Ok, S::Error>
-where
-    S: Serializer,
-{
-    let filtered = filter_stderr_boundary_markers(stderr);
-    filtered.serialize(serializer)
-}
+use super::{
+    execution_output::{ExecutionOutput, OutputStream},
+    tool_approval::ToolApproval,
+};
+use crate::{app_state::ExecutionType, utils::process_env};
 
 #[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS)]
 #[sqlx(type_name = "execution_process_status", rename_all = "lowercase")]
@@ -31,6 +19,7 @@ pub enum ExecutionProcessStatus {
     Completed,
     Failed,
     Killed,
+    TimedOut,
 }
 
 #[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS)]
@@ -41,6 +30,7 @@ pub enum ExecutionProcessType {
     SetupScript,
     CodingAgent,
     DevServer,
+    TestScript,
 }
 
 impl From<ExecutionType> for ExecutionProcessType {
@@ -49,6 +39,7 @@ impl From<ExecutionType> for ExecutionProcessType {
             ExecutionType::SetupScript => ExecutionProcessType::SetupScript,
             ExecutionType::CodingAgent => ExecutionProcessType::CodingAgent,
             ExecutionType::DevServer => ExecutionProcessType::DevServer,
+            ExecutionType::TestScript => ExecutionProcessType::TestScript,
         }
     }
 }
@@ -59,6 +50,7 @@ impl From<ExecutionProcessType> for ExecutionType {
             ExecutionProcessType::SetupScript => ExecutionType::SetupScript,
             ExecutionProcessType::CodingAgent => ExecutionType::CodingAgent,
             ExecutionProcessType::DevServer => ExecutionType::DevServer,
+            ExecutionProcessType::TestScript => ExecutionType::TestScript,
         }
     }
 }
@@ -74,16 +66,45 @@ pub struct ExecutionProcess {
     pub command: String,
     pub args: Option<String>, // JSON array of arguments
     pub working_directory: String,
-    pub stdout: Option<String>,
-    #[serde(serialize_with = "serialize_filtered_stderr")]
-    pub stderr: Option<String>,
     pub exit_code: Option<i64>,
+    pub sandbox_policy: Option<String>, // JSON SandboxPolicy, None when not sandboxed
+    /// OS process id of the spawned command, which leads its own process group
+    pub pid: Option<i64>,
+    pub pgid: Option<i64>,
+    /// Start time and boot of the process, which tell it apart from a later process that
+    /// reuses its pid; `None` where they can't be read (off Linux)
+    pub process_start_time: Option<i64>,
+    pub boot_id: Option<String>,
     pub started_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// Timing information the execution monitor uses to enforce timeouts
+#[derive(Debug, Clone, FromRow)]
+pub struct ExecutionProcessActivity {
+    pub id: Uuid,
+    pub process_type: ExecutionProcessType,
+    pub started_at: DateTime<Utc>,
+    /// Last output on either stream, which includes input sent to the process
+    pub last_activity_at: Option<DateTime<Utc>>,
+    /// When a tool approval was last decided, after which the agent resumes
+    pub last_approval_decided_at: Option<DateTime<Utc>>,
+    /// Blocked on a human deciding a tool approval
+    pub awaiting_approval: bool,
+}
+
+impl ExecutionProcessActivity {
+    /// Latest activity for idle timeouts, where waiting for an approval counts as active
+    pub fn last_activity(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if self.awaiting_approval {
+            return Some(now);
+        }
+        self.last_activity_at.max(self.last_approval_decided_at)
+    }
+}
+
 #[derive(Debug, Deserialize, TS)]
 #[ts(export)]
 pub struct CreateExecutionProcess {
@@ -93,6 +114,14 @@ pub struct CreateExecutionProcess {
     pub command: String,
     pub args: Option<String>,
     pub working_directory: String,
+    pub sandbox_policy: Option<String>,
+}
+
+/// A message for the stdin of a running execution process
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct SendExecutionInput {
+    pub message: String,
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -136,9 +165,12 @@ impl ExecutionProcess {
                 command, 
                 args, 
                 working_directory, 
-                stdout, 
-                stderr, 
                 exit_code,
+                sandbox_policy,
+                pid,
+                pgid,
+                process_start_time,
+                boot_id,
                 started_at as "started_at!: DateTime<Utc>",
                 completed_at as "completed_at?: DateTime<Utc>",
                 created_at as "created_at!: DateTime<Utc>", 
@@ -167,9 +199,12 @@ impl ExecutionProcess {
                 command, 
                 args, 
                 working_directory, 
-                stdout, 
-                stderr, 
                 exit_code,
+                sandbox_policy,
+                pid,
+                pgid,
+                process_start_time,
+                boot_id,
                 started_at as "started_at!: DateTime<Utc>",
                 completed_at as "completed_at?: DateTime<Utc>",
                 created_at as "created_at!: DateTime<Utc>", 
@@ -226,9 +261,12 @@ impl ExecutionProcess {
                 command, 
                 args, 
                 working_directory, 
-                stdout, 
-                stderr, 
                 exit_code,
+                sandbox_policy,
+                pid,
+                pgid,
+                process_start_time,
+                boot_id,
                 started_at as "started_at!: DateTime<Utc>",
                 completed_at as "completed_at?: DateTime<Utc>",
                 created_at as "created_at!: DateTime<Utc>", 
@@ -241,6 +279,31 @@ impl ExecutionProcess {
         .await
     }
 
+    /// Find start and last activity times for running execution processes
+    pub async fn find_running_activity(
+        pool: &SqlitePool,
+    ) -> Result<Vec<ExecutionProcessActivity>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcessActivity,
+            r#"SELECT 
+                ep.id as "id!: Uuid", 
+                ep.process_type as "process_type!: ExecutionProcessType",
+                ep.started_at as "started_at!: DateTime<Utc>",
+                ep.last_activity_at as "last_activity_at?: DateTime<Utc>",
+                (SELECT MAX(decided_at) FROM tool_approvals WHERE execution_process_id = ep.id)
+                    as "last_approval_decided_at?: DateTime<Utc>",
+                EXISTS (
+                    SELECT 1 FROM tool_approvals
+                    WHERE execution_process_id = ep.id AND status = 'pending'
+                ) as "awaiting_approval!: bool"
+               FROM execution_processes ep
+               WHERE ep.status = 'running' 
+               ORDER BY ep.created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     /// Find running dev servers for a specific project
     pub async fn find_running_dev_servers_by_project(
         pool: &SqlitePool,
@@ -257,9 +320,12 @@ impl ExecutionProcess {
                 ep.command, 
                 ep.args, 
                 ep.working_directory, 
-                ep.stdout, 
-                ep.stderr, 
                 ep.exit_code,
+                ep.sandbox_policy,
+                ep.pid,
+                ep.pgid,
+                ep.process_start_time,
+                ep.boot_id,
                 ep.started_at as "started_at!: DateTime<Utc>",
                 ep.completed_at as "completed_at?: DateTime<Utc>",
                 ep.created_at as "created_at!: DateTime<Utc>", 
@@ -277,6 +343,44 @@ impl ExecutionProcess {
         .await
     }
 
+    /// Find the running coding agent working in a worktree
+    pub async fn find_running_coding_agent_by_working_directory(
+        pool: &SqlitePool,
+        working_directory: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcess,
+            r#"SELECT 
+                id as "id!: Uuid", 
+                task_attempt_id as "task_attempt_id!: Uuid", 
+                process_type as "process_type!: ExecutionProcessType",
+                executor_type,
+                status as "status!: ExecutionProcessStatus",
+                command, 
+                args, 
+                working_directory, 
+                exit_code,
+                sandbox_policy,
+                pid,
+                pgid,
+                process_start_time,
+                boot_id,
+                started_at as "started_at!: DateTime<Utc>",
+                completed_at as "completed_at?: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>", 
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM execution_processes 
+               WHERE status = 'running' 
+               AND process_type = 'codingagent'
+               AND working_directory = $1
+               ORDER BY created_at DESC
+               LIMIT 1"#,
+            working_directory
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
     /// Create a new execution process
     pub async fn create(
         pool: &SqlitePool,
@@ -289,10 +393,10 @@ impl ExecutionProcess {
             ExecutionProcess,
             r#"INSERT INTO execution_processes (
                 id, task_attempt_id, process_type, executor_type, status, command, args, 
-                working_directory, stdout, stderr, exit_code, started_at, 
-                completed_at, created_at, updated_at
+                working_directory, exit_code, sandbox_policy, started_at, completed_at, 
+                created_at, updated_at
                ) 
-               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15) 
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14) 
                RETURNING 
                 id as "id!: Uuid", 
                 task_attempt_id as "task_attempt_id!: Uuid", 
@@ -302,9 +406,12 @@ impl ExecutionProcess {
                 command, 
                 args, 
                 working_directory, 
-                stdout, 
-                stderr, 
                 exit_code,
+                sandbox_policy,
+                pid,
+                pgid,
+                process_start_time,
+                boot_id,
                 started_at as "started_at!: DateTime<Utc>",
                 completed_at as "completed_at?: DateTime<Utc>",
                 created_at as "created_at!: DateTime<Utc>", 
@@ -317,9 +424,8 @@ impl ExecutionProcess {
             data.command,
             data.args,
             data.working_directory,
-            None::<String>,        // stdout
-            None::<String>,        // stderr
-            None::<i64>,           // exit_code
+            None::<i64>, // exit_code
+            data.sandbox_policy,
             now,                   // started_at
             None::<DateTime<Utc>>, // completed_at
             now,                   // created_at
@@ -354,6 +460,45 @@ impl ExecutionProcess {
         .execute(pool)
         .await?;
 
+        // A finished process can no longer act on its pending tool approvals
+        if completed_at.is_some() {
+            ToolApproval::expire_pending_for_execution_process(pool, id).await?;
+            process_env::release_secrets(id);
+            ExecutionOutput::forget_polled(id);
+        }
+
+        Ok(())
+    }
+
+    /// Identity of the process the execution was spawned as, if it was recorded
+    #[cfg(unix)]
+    pub fn process_identity(&self) -> Option<crate::utils::process_group::ProcessIdentity> {
+        Some(crate::utils::process_group::ProcessIdentity {
+            start_time: self.process_start_time?,
+            boot_id: self.boot_id.clone()?,
+        })
+    }
+
+    /// Record the OS process and process group an execution was spawned as
+    pub async fn set_process_ids(
+        pool: &SqlitePool,
+        id: Uuid,
+        pid: i64,
+        pgid: i64,
+        process_start_time: Option<i64>,
+        boot_id: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE execution_processes SET pid = $1, pgid = $2, process_start_time = $3, boot_id = $4 WHERE id = $5",
+            pid,
+            pgid,
+            process_start_time,
+            boot_id,
+            id
+        )
+        .execute(pool)
+        .await?;
+
         Ok(())
     }
 
@@ -363,9 +508,11 @@ impl ExecutionProcess {
         id: Uuid,
         stdout_append: &str,
     ) -> Result<(), sqlx::Error> {
+        let stdout_append =
+            process_env::redact_process_output(id, OutputStream::Stdout, stdout_append);
+        ExecutionOutput::append(pool, id, OutputStream::Stdout, &stdout_append).await?;
         sqlx::query!(
-            "UPDATE execution_processes SET stdout = COALESCE(stdout, '') || $1, updated_at = datetime('now') WHERE id = $2",
-            stdout_append,
+            "UPDATE execution_processes SET last_activity_at = datetime('now'), updated_at = datetime('now') WHERE id = $1",
             id
         )
         .execute(pool)
@@ -380,9 +527,11 @@ impl ExecutionProcess {
         id: Uuid,
         stderr_append: &str,
     ) -> Result<(), sqlx::Error> {
+        let stderr_append =
+            process_env::redact_process_output(id, OutputStream::Stderr, stderr_append);
+        ExecutionOutput::append(pool, id, OutputStream::Stderr, &stderr_append).await?;
         sqlx::query!(
-            "UPDATE execution_processes SET stderr = COALESCE(stderr, '') || $1, updated_at = datetime('now') WHERE id = $2",
-            stderr_append,
+            "UPDATE execution_processes SET last_activity_at = datetime('now'), updated_at = datetime('now') WHERE id = $1",
             id
         )
         .execute(pool)
@@ -391,6 +540,19 @@ impl ExecutionProcess {
         Ok(())
     }
 
+    /// Store the output of a finished stream that redaction held back, in case it ended
+    /// with what looked like the start of a secret
+    pub async fn flush_output(
+        pool: &SqlitePool,
+        id: Uuid,
+        stream: OutputStream,
+    ) -> Result<(), sqlx::Error> {
+        match process_env::flush_process_output(id, stream) {
+            Some(held) => ExecutionOutput::append(pool, id, stream, &held).await,
+            None => Ok(()),
+        }
+    }
+
     /// Append to both stdout and stderr for this execution process
     pub async fn append_output(
         pool: &SqlitePool,
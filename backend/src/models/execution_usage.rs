@@ -0,0 +1,159 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::executor::TokenUsage;
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ExecutionUsage {
+    pub id: Uuid,
+    pub execution_process_id: Uuid,
+    pub model: Option<String>,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_creation_input_tokens: i64,
+    pub cache_read_input_tokens: i64,
+    pub cost_usd: Option<f64>, // Only set when the executor reports cost
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Aggregated usage across a set of execution processes
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct UsageTotals {
+    pub execution_count: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_creation_input_tokens: i64,
+    pub cache_read_input_tokens: i64,
+    pub cost_usd: Option<f64>, // Sum of reported costs, None if no execution reported one
+}
+
+impl ExecutionUsage {
+    /// Record usage for an execution process, replacing any earlier report
+    pub async fn upsert(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        usage: &TokenUsage,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let now = Utc::now();
+
+        sqlx::query_as!(
+            ExecutionUsage,
+            r#"INSERT INTO execution_usage (
+                id, execution_process_id, model, input_tokens, output_tokens,
+                cache_creation_input_tokens, cache_read_input_tokens, cost_usd,
+                created_at, updated_at
+               )
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+               ON CONFLICT(execution_process_id) DO UPDATE SET
+                model = excluded.model,
+                input_tokens = excluded.input_tokens,
+                output_tokens = excluded.output_tokens,
+                cache_creation_input_tokens = excluded.cache_creation_input_tokens,
+                cache_read_input_tokens = excluded.cache_read_input_tokens,
+                cost_usd = excluded.cost_usd,
+                updated_at = excluded.updated_at
+               RETURNING
+                id as "id!: Uuid",
+                execution_process_id as "execution_process_id!: Uuid",
+                model,
+                input_tokens as "input_tokens!: i64",
+                output_tokens as "output_tokens!: i64",
+                cache_creation_input_tokens as "cache_creation_input_tokens!: i64",
+                cache_read_input_tokens as "cache_read_input_tokens!: i64",
+                cost_usd as "cost_usd?: f64",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            execution_process_id,
+            usage.model,
+            usage.input_tokens,
+            usage.output_tokens,
+            usage.cache_creation_input_tokens,
+            usage.cache_read_input_tokens,
+            usage.cost_usd,
+            now,
+            now
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Total usage across all execution processes of a task attempt
+    pub async fn totals_for_task_attempt(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<UsageTotals, sqlx::Error> {
+        sqlx::query_as!(
+            UsageTotals,
+            r#"SELECT
+                COUNT(eu.id) as "execution_count!: i64",
+                COALESCE(SUM(eu.input_tokens), 0) as "input_tokens!: i64",
+                COALESCE(SUM(eu.output_tokens), 0) as "output_tokens!: i64",
+                COALESCE(SUM(eu.cache_creation_input_tokens), 0) as "cache_creation_input_tokens!: i64",
+                COALESCE(SUM(eu.cache_read_input_tokens), 0) as "cache_read_input_tokens!: i64",
+                SUM(eu.cost_usd) as "cost_usd?: f64"
+               FROM execution_usage eu
+               JOIN execution_processes ep ON eu.execution_process_id = ep.id
+               WHERE ep.task_attempt_id = $1"#,
+            task_attempt_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Total usage across all attempts of a task
+    pub async fn totals_for_task(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<UsageTotals, sqlx::Error> {
+        sqlx::query_as!(
+            UsageTotals,
+            r#"SELECT
+                COUNT(eu.id) as "execution_count!: i64",
+                COALESCE(SUM(eu.input_tokens), 0) as "input_tokens!: i64",
+                COALESCE(SUM(eu.output_tokens), 0) as "output_tokens!: i64",
+                COALESCE(SUM(eu.cache_creation_input_tokens), 0) as "cache_creation_input_tokens!: i64",
+                COALESCE(SUM(eu.cache_read_input_tokens), 0) as "cache_read_input_tokens!: i64",
+                SUM(eu.cost_usd) as "cost_usd?: f64"
+               FROM execution_usage eu
+               JOIN execution_processes ep ON eu.execution_process_id = ep.id
+               JOIN task_attempts ta ON ep.task_attempt_id = ta.id
+               WHERE ta.task_id = $1"#,
+            task_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Total usage across all tasks of a project
+    pub async fn totals_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<UsageTotals, sqlx::Error> {
+        sqlx::query_as!(
+            UsageTotals,
+            r#"SELECT
+                COUNT(eu.id) as "execution_count!: i64",
+                COALESCE(SUM(eu.input_tokens), 0) as "input_tokens!: i64",
+                COALESCE(SUM(eu.output_tokens), 0) as "output_tokens!: i64",
+                COALESCE(SUM(eu.cache_creation_input_tokens), 0) as "cache_creation_input_tokens!: i64",
+                COALESCE(SUM(eu.cache_read_input_tokens), 0) as "cache_read_input_tokens!: i64",
+                SUM(eu.cost_usd) as "cost_usd?: f64"
+               FROM execution_usage eu
+               JOIN execution_processes ep ON eu.execution_process_id = ep.id
+               JOIN task_attempts ta ON ep.task_attempt_id = ta.id
+               JOIN tasks t ON ta.task_id = t.id
+               WHERE t.project_id = $1"#,
+            project_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
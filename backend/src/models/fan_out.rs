@@ -0,0 +1,350 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::{
+    execution_process::{
+        ExecutionProcess, ExecutionProcessStatus, ExecutionProcessSummary, ExecutionProcessType,
+    },
+    execution_usage::{ExecutionUsage, UsageTotals},
+    task_attempt::{DiffChunkType, TaskAttempt, TaskAttemptError, WorktreeDiff},
+};
+
+/// Most attempts a single fan-out may start
+pub const MAX_FAN_OUT_ATTEMPTS: usize = 8;
+
+/// Start several attempts on one task at once, each in its own worktree and branch
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct CreateFanOut {
+    /// One attempt per entry; repeat an executor to run it several times
+    pub executors: Vec<String>,
+    pub base_branch: Option<String>,
+    /// Queue priority shared by every attempt of the fan-out
+    pub priority: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, TS)]
+#[ts(export)]
+pub struct DiffStats {
+    pub files_changed: usize,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+}
+
+/// How one attempt of a fan-out did so far
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct AttemptComparison {
+    pub attempt: TaskAttempt,
+    /// Status of the latest coding agent run, `None` until one started
+    pub status: Option<ExecutionProcessStatus>,
+    pub exit_code: Option<i64>,
+    /// Time spent in coding agent runs, counting running ones up to now
+    pub duration_seconds: i64,
+    /// `None` once the worktree of an unmerged attempt was cleaned up
+    pub diff_stats: Option<DiffStats>,
+    /// Result of the latest test script run, `None` without a finished run
+    pub tests_passed: Option<bool>,
+    pub usage: UsageTotals,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct FanOutComparison {
+    pub fan_out_id: Uuid,
+    pub attempts: Vec<AttemptComparison>,
+}
+
+impl DiffStats {
+    pub fn from_diff(diff: &WorktreeDiff) -> Self {
+        let mut stats = DiffStats {
+            files_changed: diff.files.len(),
+            ..Default::default()
+        };
+        for chunk in diff.files.iter().flat_map(|file| &file.chunks) {
+            let lines = chunk.content.lines().count().max(1);
+            match chunk.chunk_type {
+                DiffChunkType::Insert => stats.lines_added += lines,
+                DiffChunkType::Delete => stats.lines_removed += lines,
+                DiffChunkType::Equal => {}
+            }
+        }
+        stats
+    }
+}
+
+impl AttemptComparison {
+    /// Summarize an attempt from its processes, oldest first
+    fn from_processes(
+        attempt: TaskAttempt,
+        processes: &[ExecutionProcessSummary],
+        diff_stats: Option<DiffStats>,
+        usage: UsageTotals,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let mut coding_agents = processes
+            .iter()
+            .filter(|p| p.process_type == ExecutionProcessType::CodingAgent);
+        let duration_seconds = coding_agents
+            .clone()
+            .map(|p| (p.completed_at.unwrap_or(now) - p.started_at).num_seconds())
+            .sum();
+        let latest_agent = coding_agents.next_back();
+
+        // A test run still going, or stopped before it finished, says nothing about the changes
+        let tests_passed = processes
+            .iter()
+            .rev()
+            .find(|p| p.process_type == ExecutionProcessType::TestScript)
+            .and_then(|p| match p.status {
+                ExecutionProcessStatus::Completed => Some(true),
+                ExecutionProcessStatus::Failed | ExecutionProcessStatus::TimedOut => Some(false),
+                ExecutionProcessStatus::Running | ExecutionProcessStatus::Killed => None,
+            });
+
+        AttemptComparison {
+            attempt,
+            status: latest_agent.map(|p| p.status.clone()),
+            exit_code: latest_agent.and_then(|p| p.exit_code),
+            duration_seconds,
+            diff_stats,
+            tests_passed,
+            usage,
+        }
+    }
+
+    pub async fn load(
+        pool: &SqlitePool,
+        attempt: TaskAttempt,
+        project_id: Uuid,
+    ) -> Result<Self, TaskAttemptError> {
+        let processes =
+            ExecutionProcess::find_summaries_by_task_attempt_id(pool, attempt.id).await?;
+        let usage = ExecutionUsage::totals_for_task_attempt(pool, attempt.id).await?;
+
+        // Computing the diff of an unmerged attempt would recreate a cleaned up worktree
+        let diff_stats = if attempt.worktree_deleted && attempt.merge_commit.is_none() {
+            None
+        } else {
+            TaskAttempt::get_diff(pool, attempt.id, attempt.task_id, project_id)
+                .await
+                .ok()
+                .map(|diff| DiffStats::from_diff(&diff))
+        };
+
+        Ok(Self::from_processes(
+            attempt,
+            &processes,
+            diff_stats,
+            usage,
+            Utc::now(),
+        ))
+    }
+}
+
+impl FanOutComparison {
+    pub async fn load(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        project_id: Uuid,
+        fan_out_id: Uuid,
+    ) -> Result<Self, TaskAttemptError> {
+        let mut attempts = Vec::new();
+        for attempt in TaskAttempt::find_by_fan_out_id(pool, task_id, fan_out_id).await? {
+            attempts.push(AttemptComparison::load(pool, attempt, project_id).await?);
+        }
+        Ok(FanOutComparison {
+            fan_out_id,
+            attempts,
+        })
+    }
+}
+
+/// Create a fan-out's attempts one executor at a time, starting each as soon as it exists.
+/// A failed create ends the fan-out there, and the attempts created before it still run
+/// rather than waiting for a start that never comes.
+pub async fn create_and_start_each<A, E, F>(
+    executors: &[String],
+    mut create: impl FnMut(&str) -> F,
+    mut start: impl FnMut(&A),
+) -> Result<Vec<A>, E>
+where
+    F: std::future::Future<Output = Result<A, E>>,
+{
+    let mut attempts = Vec::new();
+    for executor in executors {
+        let attempt = create(executor).await?;
+        start(&attempt);
+        attempts.push(attempt);
+    }
+    Ok(attempts)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use futures_util::FutureExt;
+
+    use super::*;
+    use crate::models::task_attempt::{DiffChunk, FileDiff};
+
+    fn attempt() -> TaskAttempt {
+        TaskAttempt {
+            id: Uuid::new_v4(),
+            task_id: Uuid::new_v4(),
+            worktree_path: "/tmp/worktree".to_string(),
+            branch: "vk-1234-task".to_string(),
+            base_branch: "main".to_string(),
+            merge_commit: None,
+            executor: Some("claude".to_string()),
+            pr_url: None,
+            pr_number: None,
+            pr_status: None,
+            pr_merged_at: None,
+            worktree_deleted: false,
+            setup_completed_at: None,
+            fan_out_id: Some(Uuid::new_v4()),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn process(
+        process_type: ExecutionProcessType,
+        status: ExecutionProcessStatus,
+        started_at: DateTime<Utc>,
+        completed_at: Option<DateTime<Utc>>,
+    ) -> ExecutionProcessSummary {
+        ExecutionProcessSummary {
+            id: Uuid::new_v4(),
+            task_attempt_id: Uuid::new_v4(),
+            process_type,
+            executor_type: None,
+            status,
+            command: "executor".to_string(),
+            args: None,
+            working_directory: "/tmp/worktree".to_string(),
+            exit_code: None,
+            started_at,
+            completed_at,
+            created_at: started_at,
+            updated_at: started_at,
+        }
+    }
+
+    fn no_usage() -> UsageTotals {
+        UsageTotals {
+            execution_count: 0,
+            input_tokens: 0,
+            output_tokens: 0,
+            cache_creation_input_tokens: 0,
+            cache_read_input_tokens: 0,
+            cost_usd: None,
+        }
+    }
+
+    #[test]
+    fn test_diff_stats_count_changed_lines() {
+        let chunk = |chunk_type, content: &str| DiffChunk {
+            chunk_type,
+            content: content.to_string(),
+        };
+        let diff = WorktreeDiff {
+            files: vec![
+                FileDiff {
+                    path: "src/main.rs".to_string(),
+                    chunks: vec![
+                        chunk(DiffChunkType::Equal, "fn main() {\n"),
+                        chunk(DiffChunkType::Delete, "    old();\n"),
+                        chunk(DiffChunkType::Insert, "    new();\n    more();\n"),
+                    ],
+                },
+                FileDiff {
+                    path: "logo.png".to_string(),
+                    chunks: vec![chunk(DiffChunkType::Insert, "Added file")],
+                },
+            ],
+        };
+
+        assert_eq!(
+            DiffStats::from_diff(&diff),
+            DiffStats {
+                files_changed: 2,
+                lines_added: 3,
+                lines_removed: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_comparison_uses_latest_runs_and_sums_agent_time() {
+        let now = Utc::now();
+        let start = now - Duration::minutes(10);
+        let processes = vec![
+            process(
+                ExecutionProcessType::CodingAgent,
+                ExecutionProcessStatus::Completed,
+                start,
+                Some(start + Duration::seconds(120)),
+            ),
+            process(
+                ExecutionProcessType::TestScript,
+                ExecutionProcessStatus::Failed,
+                start + Duration::seconds(130),
+                Some(start + Duration::seconds(200)),
+            ),
+            process(
+                ExecutionProcessType::CodingAgent,
+                ExecutionProcessStatus::Running,
+                now - Duration::seconds(30),
+                None,
+            ),
+        ];
+
+        let comparison =
+            AttemptComparison::from_processes(attempt(), &processes, None, no_usage(), now);
+        assert_eq!(comparison.status, Some(ExecutionProcessStatus::Running));
+        assert_eq!(comparison.duration_seconds, 150);
+        assert_eq!(comparison.tests_passed, Some(false));
+    }
+
+    #[test]
+    fn test_comparison_ignores_unfinished_test_runs() {
+        let now = Utc::now();
+        let processes = vec![process(
+            ExecutionProcessType::TestScript,
+            ExecutionProcessStatus::Running,
+            now,
+            None,
+        )];
+
+        let comparison =
+            AttemptComparison::from_processes(attempt(), &processes, None, no_usage(), now);
+        assert_eq!(comparison.status, None);
+        assert_eq!(comparison.tests_passed, None);
+    }
+
+    #[test]
+    fn test_create_and_start_each_starts_attempts_created_before_a_failure() {
+        let executors = ["claude", "amp", "gemini"].map(String::from);
+        let mut started = Vec::new();
+        let result = create_and_start_each(
+            &executors,
+            |executor| {
+                std::future::ready(match executor {
+                    "amp" => Err("no worktree"),
+                    executor => Ok(executor.to_string()),
+                })
+            },
+            |attempt: &String| started.push(attempt.clone()),
+        )
+        .now_or_never()
+        .unwrap();
+
+        assert_eq!(result, Err("no worktree"));
+        assert_eq!(started, ["claude"]);
+    }
+}
@@ -1,10 +1,20 @@
 pub mod api_response;
 pub mod config;
+pub mod execution_output;
 pub mod execution_process;
+pub mod execution_usage;
 pub mod executor_session;
+pub mod fan_out;
+pub mod plan_presentation;
 pub mod project;
+pub mod project_env_var;
+pub mod queued_attempt;
+pub mod search;
 pub mod task;
 pub mod task_attempt;
+pub mod tool_allowlist;
+pub mod tool_approval;
+pub mod verification_run;
 
 pub mod task_template;
 
@@ -0,0 +1,136 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "plan_author", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum PlanAuthor {
+    Agent,
+    User,
+}
+
+/// A version of the plan presented by a plan mode attempt
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PlanPresentation {
+    pub id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub execution_process_id: Option<Uuid>, // Process that presented the plan, None for edits
+    pub version: i64,
+    pub plan: String,
+    pub author: PlanAuthor,
+    pub approved_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct UpdatePlanPresentation {
+    pub plan: String,
+}
+
+impl PlanPresentation {
+    /// Store a plan as the attempt's next version
+    pub async fn create_version(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        execution_process_id: Option<Uuid>,
+        plan: &str,
+        author: PlanAuthor,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let now = Utc::now();
+
+        sqlx::query_as!(
+            PlanPresentation,
+            r#"INSERT INTO plan_presentations (
+                id, task_attempt_id, execution_process_id, version, plan, author, created_at
+               )
+               SELECT $1, $2, $3, COALESCE(MAX(version), 0) + 1, $4, $5, $6
+               FROM plan_presentations
+               WHERE task_attempt_id = $2
+               RETURNING
+                id as "id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                execution_process_id as "execution_process_id?: Uuid",
+                version as "version!: i64",
+                plan,
+                author as "author!: PlanAuthor",
+                approved_at as "approved_at?: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            task_attempt_id,
+            execution_process_id,
+            plan,
+            author,
+            now
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// All versions of an attempt's plan, oldest first
+    pub async fn find_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PlanPresentation,
+            r#"SELECT
+                id as "id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                execution_process_id as "execution_process_id?: Uuid",
+                version as "version!: i64",
+                plan,
+                author as "author!: PlanAuthor",
+                approved_at as "approved_at?: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>"
+               FROM plan_presentations
+               WHERE task_attempt_id = $1
+               ORDER BY version ASC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The plan presented by a specific execution process, if it was recorded
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PlanPresentation,
+            r#"SELECT
+                id as "id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                execution_process_id as "execution_process_id?: Uuid",
+                version as "version!: i64",
+                plan,
+                author as "author!: PlanAuthor",
+                approved_at as "approved_at?: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>"
+               FROM plan_presentations
+               WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn mark_approved(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            "UPDATE plan_presentations SET approved_at = $1 WHERE id = $2",
+            now,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -13,6 +13,27 @@ pub struct Project {
     pub git_repo_path: String,
     pub setup_script: Option<String>,
     pub dev_script: Option<String>,
+    /// Command run after each coding agent to check the attempt's changes (e.g. `cargo test`)
+    pub test_script: Option<String>,
+    /// Follow-ups sent to the coding agent with the output of a failing test script, 0 turns the
+    /// verify-and-fix loop off
+    pub max_fix_iterations: i64,
+    /// Most of the project's attempts running at once, from their setup script through the coding
+    /// agent and tests; further attempts and follow-ups wait in the execution queue. 0 leaves
+    /// only the global limit
+    pub max_concurrent_agents: i64,
+    /// Default executor for attempts in this project, overriding the global config
+    pub executor: Option<String>,
+    /// Extra CLI arguments appended to the coding agent command (e.g. `--model opus`)
+    pub executor_args: Option<String>,
+    /// Confine coding agents and setup scripts to their worktree (Linux only)
+    pub sandbox: bool,
+    /// Whether sandboxed processes may use the network
+    pub sandbox_network: bool,
+    /// Template for the prompt coding agents start tasks with, overriding the global config
+    pub prompt_template: Option<String>,
+    /// House rules for coding agents, rendered into prompts as `{{project_instructions}}`
+    pub instructions: Option<String>,
 
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
@@ -28,6 +49,19 @@ pub struct CreateProject {
     pub use_existing_repo: bool,
     pub setup_script: Option<String>,
     pub dev_script: Option<String>,
+    pub test_script: Option<String>,
+    /// `None` turns the verify-and-fix loop off
+    pub max_fix_iterations: Option<i64>,
+    /// `None` leaves only the global limit
+    pub max_concurrent_agents: Option<i64>,
+    pub executor: Option<String>,
+    pub executor_args: Option<String>,
+    /// `None` leaves the project unsandboxed
+    pub sandbox: Option<bool>,
+    /// `None` allows network access
+    pub sandbox_network: Option<bool>,
+    pub prompt_template: Option<String>,
+    pub instructions: Option<String>,
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -37,6 +71,24 @@ pub struct UpdateProject {
     pub git_repo_path: Option<String>,
     pub setup_script: Option<String>,
     pub dev_script: Option<String>,
+    /// `None` keeps the current value, an empty string clears it
+    pub test_script: Option<String>,
+    /// `None` keeps the current value
+    pub max_fix_iterations: Option<i64>,
+    /// `None` keeps the current value
+    pub max_concurrent_agents: Option<i64>,
+    /// `None` keeps the current value, an empty string clears it
+    pub executor: Option<String>,
+    /// `None` keeps the current value, an empty string clears it
+    pub executor_args: Option<String>,
+    /// `None` keeps the current value
+    pub sandbox: Option<bool>,
+    /// `None` keeps the current value
+    pub sandbox_network: Option<bool>,
+    /// `None` keeps the current value, an empty string clears it
+    pub prompt_template: Option<String>,
+    /// `None` keeps the current value, an empty string clears it
+    pub instructions: Option<String>,
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -47,6 +99,15 @@ pub struct ProjectWithBranch {
     pub git_repo_path: String,
     pub setup_script: Option<String>,
     pub dev_script: Option<String>,
+    pub test_script: Option<String>,
+    pub max_fix_iterations: i64,
+    pub max_concurrent_agents: i64,
+    pub executor: Option<String>,
+    pub executor_args: Option<String>,
+    pub sandbox: bool,
+    pub sandbox_network: bool,
+    pub prompt_template: Option<String>,
+    pub instructions: Option<String>,
     pub current_branch: Option<String>,
 
     #[ts(type = "Date")]
@@ -92,7 +153,7 @@ impl Project {
     pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as!(
             Project,
-            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects ORDER BY created_at DESC"#
+            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, executor, executor_args, sandbox as "sandbox!: bool", sandbox_network as "sandbox_network!: bool", prompt_template, instructions, test_script, max_fix_iterations as "max_fix_iterations!: i64", max_concurrent_agents as "max_concurrent_agents!: i64", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects ORDER BY created_at DESC"#
         )
         .fetch_all(pool)
         .await
@@ -101,7 +162,7 @@ impl Project {
     pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Project,
-            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE id = $1"#,
+            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, executor, executor_args, sandbox as "sandbox!: bool", sandbox_network as "sandbox_network!: bool", prompt_template, instructions, test_script, max_fix_iterations as "max_fix_iterations!: i64", max_concurrent_agents as "max_concurrent_agents!: i64", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE id = $1"#,
             id
         )
         .fetch_optional(pool)
@@ -114,7 +175,7 @@ impl Project {
     ) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Project,
-            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE git_repo_path = $1"#,
+            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, executor, executor_args, sandbox as "sandbox!: bool", sandbox_network as "sandbox_network!: bool", prompt_template, instructions, test_script, max_fix_iterations as "max_fix_iterations!: i64", max_concurrent_agents as "max_concurrent_agents!: i64", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE git_repo_path = $1"#,
             git_repo_path
         )
         .fetch_optional(pool)
@@ -128,7 +189,7 @@ impl Project {
     ) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Project,
-            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE git_repo_path = $1 AND id != $2"#,
+            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, executor, executor_args, sandbox as "sandbox!: bool", sandbox_network as "sandbox_network!: bool", prompt_template, instructions, test_script, max_fix_iterations as "max_fix_iterations!: i64", max_concurrent_agents as "max_concurrent_agents!: i64", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE git_repo_path = $1 AND id != $2"#,
             git_repo_path,
             exclude_id
         )
@@ -141,19 +202,33 @@ impl Project {
         data: &CreateProject,
         project_id: Uuid,
     ) -> Result<Self, sqlx::Error> {
+        let sandbox = data.sandbox.unwrap_or(false);
+        let sandbox_network = data.sandbox_network.unwrap_or(true);
+        let max_fix_iterations = data.max_fix_iterations.unwrap_or(0);
+        let max_concurrent_agents = data.max_concurrent_agents.unwrap_or(0);
         sqlx::query_as!(
             Project,
-            r#"INSERT INTO projects (id, name, git_repo_path, setup_script, dev_script) VALUES ($1, $2, $3, $4, $5) RETURNING id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            r#"INSERT INTO projects (id, name, git_repo_path, setup_script, dev_script, executor, executor_args, sandbox, sandbox_network, prompt_template, instructions, test_script, max_fix_iterations, max_concurrent_agents) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14) RETURNING id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, executor, executor_args, sandbox as "sandbox!: bool", sandbox_network as "sandbox_network!: bool", prompt_template, instructions, test_script, max_fix_iterations as "max_fix_iterations!: i64", max_concurrent_agents as "max_concurrent_agents!: i64", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             project_id,
             data.name,
             data.git_repo_path,
             data.setup_script,
-            data.dev_script
+            data.dev_script,
+            data.executor,
+            data.executor_args,
+            sandbox,
+            sandbox_network,
+            data.prompt_template,
+            data.instructions,
+            data.test_script,
+            max_fix_iterations,
+            max_concurrent_agents
         )
         .fetch_one(pool)
         .await
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn update(
         pool: &SqlitePool,
         id: Uuid,
@@ -161,15 +236,33 @@ impl Project {
         git_repo_path: String,
         setup_script: Option<String>,
         dev_script: Option<String>,
+        executor: Option<String>,
+        executor_args: Option<String>,
+        sandbox: bool,
+        sandbox_network: bool,
+        prompt_template: Option<String>,
+        instructions: Option<String>,
+        test_script: Option<String>,
+        max_fix_iterations: i64,
+        max_concurrent_agents: i64,
     ) -> Result<Self, sqlx::Error> {
         sqlx::query_as!(
             Project,
-            r#"UPDATE projects SET name = $2, git_repo_path = $3, setup_script = $4, dev_script = $5 WHERE id = $1 RETURNING id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            r#"UPDATE projects SET name = $2, git_repo_path = $3, setup_script = $4, dev_script = $5, executor = $6, executor_args = $7, sandbox = $8, sandbox_network = $9, prompt_template = $10, instructions = $11, test_script = $12, max_fix_iterations = $13, max_concurrent_agents = $14 WHERE id = $1 RETURNING id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, executor, executor_args, sandbox as "sandbox!: bool", sandbox_network as "sandbox_network!: bool", prompt_template, instructions, test_script, max_fix_iterations as "max_fix_iterations!: i64", max_concurrent_agents as "max_concurrent_agents!: i64", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             id,
             name,
             git_repo_path,
             setup_script,
-            dev_script
+            dev_script,
+            executor,
+            executor_args,
+            sandbox,
+            sandbox_network,
+            prompt_template,
+            instructions,
+            test_script,
+            max_fix_iterations,
+            max_concurrent_agents
         )
         .fetch_one(pool)
         .await
@@ -182,21 +275,6 @@ impl Project {
         Ok(result.rows_affected())
     }
 
-    pub async fn exists(pool: &SqlitePool, id: Uuid) -> Result<bool, sqlx::Error> {
-        let result = sqlx::query!(
-            r#"
-                SELECT COUNT(*) as "count!: i64"
-                FROM projects
-                WHERE id = $1
-            "#,
-            id
-        )
-        .fetch_one(pool)
-        .await?;
-
-        Ok(result.count > 0)
-    }
-
     pub fn get_current_branch(&self) -> Result<String, git2::Error> {
         let repo = Repository::open(&self.git_repo_path)?;
         let head = repo.head()?;
@@ -217,6 +295,15 @@ impl Project {
             git_repo_path: self.git_repo_path,
             setup_script: self.setup_script,
             dev_script: self.dev_script,
+            test_script: self.test_script,
+            max_fix_iterations: self.max_fix_iterations,
+            max_concurrent_agents: self.max_concurrent_agents,
+            executor: self.executor,
+            executor_args: self.executor_args,
+            sandbox: self.sandbox,
+            sandbox_network: self.sandbox_network,
+            prompt_template: self.prompt_template,
+            instructions: self.instructions,
             current_branch,
             created_at: self.created_at,
             updated_at: self.updated_at,
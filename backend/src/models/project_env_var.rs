@@ -0,0 +1,208 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::utils::process_env::ProcessEnvironment;
+
+/// An environment variable injected into the processes spawned for a project
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ProjectEnvVar {
+    pub key: String,
+    /// Never returned for secrets. When saving, leaving a secret's value empty keeps
+    /// the stored one.
+    pub value: Option<String>,
+    pub secret: bool,
+}
+
+#[derive(Debug)]
+pub enum ProjectEnvVarError {
+    Database(sqlx::Error),
+    Validation(String),
+}
+
+impl std::fmt::Display for ProjectEnvVarError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProjectEnvVarError::Database(e) => write!(f, "Database error: {}", e),
+            ProjectEnvVarError::Validation(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ProjectEnvVarError {}
+
+impl From<sqlx::Error> for ProjectEnvVarError {
+    fn from(err: sqlx::Error) -> Self {
+        ProjectEnvVarError::Database(err)
+    }
+}
+
+impl ProjectEnvVar {
+    /// All variables of a project, including secret values
+    pub async fn find_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT key, value, secret as "secret!: bool"
+               FROM project_env_vars
+               WHERE project_id = $1
+               ORDER BY key ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ProjectEnvVar {
+                key: row.key,
+                value: Some(row.value),
+                secret: row.secret,
+            })
+            .collect())
+    }
+
+    /// Replace a project's variables. Secrets submitted without a value keep their
+    /// stored value.
+    pub async fn replace_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        vars: &[ProjectEnvVar],
+    ) -> Result<Vec<Self>, ProjectEnvVarError> {
+        validate(vars)?;
+
+        let stored_secrets = Self::find_for_project(pool, project_id)
+            .await?
+            .into_iter()
+            .filter(|var| var.secret)
+            .filter_map(|var| var.value.map(|value| (var.key, value)))
+            .collect::<HashMap<_, _>>();
+
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!(
+            "DELETE FROM project_env_vars WHERE project_id = $1",
+            project_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for var in vars {
+            let key = var.key.trim();
+            let value = match var.value.as_deref().filter(|v| !v.is_empty()) {
+                Some(value) => value.to_string(),
+                None if var.secret => stored_secrets.get(key).cloned().ok_or_else(|| {
+                    ProjectEnvVarError::Validation(format!("Secret {} needs a value", key))
+                })?,
+                None => String::new(),
+            };
+            let id = Uuid::new_v4();
+            sqlx::query!(
+                r#"INSERT INTO project_env_vars (id, project_id, key, value, secret)
+                   VALUES ($1, $2, $3, $4, $5)"#,
+                id,
+                project_id,
+                key,
+                value,
+                var.secret
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(Self::find_for_project(pool, project_id).await?)
+    }
+
+    /// The environment to spawn a project's processes with
+    pub async fn environment_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<ProcessEnvironment, sqlx::Error> {
+        let mut environment = ProcessEnvironment::default();
+        for var in Self::find_for_project(pool, project_id).await? {
+            let value = var.value.unwrap_or_default();
+            if var.secret {
+                environment.secrets.push(value.clone());
+            }
+            environment.vars.push((var.key, value));
+        }
+        Ok(environment)
+    }
+
+    /// Hide the value of a secret before it leaves the server
+    pub fn redacted(self) -> Self {
+        if self.secret {
+            ProjectEnvVar {
+                value: None,
+                ..self
+            }
+        } else {
+            self
+        }
+    }
+}
+
+fn validate(vars: &[ProjectEnvVar]) -> Result<(), ProjectEnvVarError> {
+    let mut seen = HashSet::new();
+    for var in vars {
+        let key = var.key.trim();
+        let mut chars = key.chars();
+        let valid = chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            return Err(ProjectEnvVarError::Validation(format!(
+                "Invalid environment variable name '{}'",
+                var.key
+            )));
+        }
+        if !seen.insert(key) {
+            return Err(ProjectEnvVarError::Validation(format!(
+                "Environment variable {} is defined twice",
+                key
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn var(key: &str, value: Option<&str>, secret: bool) -> ProjectEnvVar {
+        ProjectEnvVar {
+            key: key.to_string(),
+            value: value.map(str::to_string),
+            secret,
+        }
+    }
+
+    #[test]
+    fn test_validate_keys() {
+        assert!(validate(&[var("API_KEY", Some("x"), true), var("_DEBUG", None, false)]).is_ok());
+        assert!(validate(&[var("1PASSWORD", Some("x"), false)]).is_err());
+        assert!(validate(&[var("MY-KEY", Some("x"), false)]).is_err());
+        assert!(validate(&[var("", Some("x"), false)]).is_err());
+        assert!(validate(&[var("KEY", Some("a"), false), var(" KEY", Some("b"), false)]).is_err());
+    }
+
+    #[test]
+    fn test_redacted_hides_only_secrets() {
+        assert_eq!(
+            var("API_KEY", Some("sk-1234"), true).redacted(),
+            var("API_KEY", None, true)
+        );
+        assert_eq!(
+            var("PORT", Some("3000"), false).redacted(),
+            var("PORT", Some("3000"), false)
+        );
+    }
+}
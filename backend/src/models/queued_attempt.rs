@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, SqlitePool};
+use uuid::Uuid;
+
+/// An attempt waiting in the execution queue for a free slot to start. Once admitted, it
+/// stays in the queue table holding its slot until it stops running.
+#[derive(Debug, Clone, FromRow)]
+pub struct QueuedAttempt {
+    pub task_attempt_id: Uuid,
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    /// Higher starts first; equal priorities start in the order they were queued
+    pub priority: i64,
+    pub queued_at: DateTime<Utc>,
+    /// Prompt to continue the attempt's session with, `None` to start its first run
+    pub follow_up_prompt: Option<String>,
+    /// The project's own limit on running attempts, 0 for none
+    pub project_max_concurrent_agents: i64,
+}
+
+impl QueuedAttempt {
+    /// Queue an attempt, keeping its place if it's already queued or running. Returns whether it
+    /// was queued now.
+    pub async fn enqueue(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        priority: i64,
+        follow_up_prompt: Option<&str>,
+    ) -> Result<bool, sqlx::Error> {
+        let now = Utc::now();
+        let result = sqlx::query!(
+            "INSERT INTO execution_queue (task_attempt_id, priority, queued_at, follow_up_prompt)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (task_attempt_id) DO NOTHING",
+            task_attempt_id,
+            priority,
+            now,
+            follow_up_prompt
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The attempts waiting to start, next first
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            QueuedAttempt,
+            r#"SELECT
+                eq.task_attempt_id as "task_attempt_id!: Uuid",
+                ta.task_id as "task_id!: Uuid",
+                t.project_id as "project_id!: Uuid",
+                eq.priority as "priority!: i64",
+                eq.queued_at as "queued_at!: DateTime<Utc>",
+                eq.follow_up_prompt,
+                p.max_concurrent_agents as "project_max_concurrent_agents!: i64"
+               FROM execution_queue eq
+               JOIN task_attempts ta ON ta.id = eq.task_attempt_id
+               JOIN tasks t ON t.id = ta.task_id
+               JOIN projects p ON p.id = t.project_id
+               WHERE eq.admitted_at IS NULL
+               ORDER BY eq.priority DESC, eq.queued_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Take a waiting attempt out of the queue, returning whether it was waiting
+    pub async fn remove(pool: &SqlitePool, task_attempt_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM execution_queue WHERE task_attempt_id = $1 AND admitted_at IS NULL",
+            task_attempt_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Give a waiting attempt its slot, returning whether it was waiting
+    pub async fn admit(pool: &SqlitePool, task_attempt_id: Uuid) -> Result<bool, sqlx::Error> {
+        let now = Utc::now();
+        let result = sqlx::query!(
+            "UPDATE execution_queue SET admitted_at = $1
+             WHERE task_attempt_id = $2 AND admitted_at IS NULL",
+            now,
+            task_attempt_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Whether an attempt holds a slot
+    pub async fn is_admitted(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let record = sqlx::query!(
+            r#"SELECT EXISTS (
+                SELECT 1 FROM execution_queue
+                WHERE task_attempt_id = $1 AND admitted_at IS NOT NULL
+               ) as "admitted!: bool""#,
+            task_attempt_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(record.admitted)
+    }
+
+    /// Free the slot of an admitted attempt
+    pub async fn release(pool: &SqlitePool, task_attempt_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "DELETE FROM execution_queue WHERE task_attempt_id = $1 AND admitted_at IS NOT NULL",
+            task_attempt_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Free the slots of admitted attempts with no setup script, coding agent or test script
+    /// running any more, returning how many were freed
+    pub async fn release_finished(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM execution_queue
+             WHERE admitted_at IS NOT NULL
+               AND NOT EXISTS (
+                 SELECT 1 FROM execution_processes ep
+                 WHERE ep.task_attempt_id = execution_queue.task_attempt_id
+                   AND ep.status = 'running'
+                   AND ep.process_type IN ('setupscript', 'codingagent', 'testscript')
+               )"
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Attempts holding a slot, per project
+    pub async fn admitted_attempts_by_project(
+        pool: &SqlitePool,
+    ) -> Result<HashMap<Uuid, usize>, sqlx::Error> {
+        let records = sqlx::query!(
+            r#"SELECT
+                t.project_id as "project_id!: Uuid",
+                COUNT(*) as "admitted!: i64"
+               FROM execution_queue eq
+               JOIN task_attempts ta ON ta.id = eq.task_attempt_id
+               JOIN tasks t ON t.id = ta.task_id
+               WHERE eq.admitted_at IS NOT NULL
+               GROUP BY t.project_id"#
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(records
+            .into_iter()
+            .map(|record| (record.project_id, record.admitted as usize))
+            .collect())
+    }
+}
+
+/// 1-based place of an attempt in the queue, `None` when it isn't queued
+pub fn queue_position(queue: &[QueuedAttempt], task_attempt_id: Uuid) -> Option<usize> {
+    queue
+        .iter()
+        .position(|queued| queued.task_attempt_id == task_attempt_id)
+        .map(|index| index + 1)
+}
+
+/// The queued attempts that may start now, next to the attempts already admitted, in queue
+/// order. An attempt held back by its project's limit doesn't hold back attempts of other
+/// projects behind it.
+pub fn startable_attempts<'a>(
+    queue: &'a [QueuedAttempt],
+    admitted_by_project: &HashMap<Uuid, usize>,
+    global_limit: Option<usize>,
+) -> Vec<&'a QueuedAttempt> {
+    let mut running_by_project = admitted_by_project.clone();
+    let mut running: usize = running_by_project.values().sum();
+    let mut startable = Vec::new();
+
+    for queued in queue {
+        if global_limit.is_some_and(|limit| running >= limit) {
+            break;
+        }
+        let project_running = running_by_project.entry(queued.project_id).or_default();
+        let project_limit = queued.project_max_concurrent_agents;
+        if project_limit > 0 && *project_running >= project_limit as usize {
+            continue;
+        }
+        *project_running += 1;
+        running += 1;
+        startable.push(queued);
+    }
+    startable
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queued(project_id: Uuid, project_limit: i64) -> QueuedAttempt {
+        QueuedAttempt {
+            task_attempt_id: Uuid::new_v4(),
+            task_id: Uuid::new_v4(),
+            project_id,
+            priority: 0,
+            queued_at: Utc::now(),
+            follow_up_prompt: None,
+            project_max_concurrent_agents: project_limit,
+        }
+    }
+
+    fn ids(attempts: Vec<&QueuedAttempt>) -> Vec<Uuid> {
+        attempts.iter().map(|a| a.task_attempt_id).collect()
+    }
+
+    #[test]
+    fn test_global_limit_counts_admitted_attempts() {
+        let project = Uuid::new_v4();
+        let queue = vec![queued(project, 0), queued(project, 0), queued(project, 0)];
+        let running = HashMap::from([(project, 1)]);
+
+        assert_eq!(
+            ids(startable_attempts(&queue, &running, Some(3))),
+            vec![queue[0].task_attempt_id, queue[1].task_attempt_id]
+        );
+        assert_eq!(startable_attempts(&queue, &HashMap::new(), None).len(), 3);
+        assert_eq!(queue_position(&queue, queue[2].task_attempt_id), Some(3));
+        assert_eq!(queue_position(&queue, Uuid::new_v4()), None);
+    }
+
+    #[test]
+    fn test_project_limit_lets_other_projects_pass() {
+        let busy = Uuid::new_v4();
+        let idle = Uuid::new_v4();
+        let queue = vec![queued(busy, 1), queued(busy, 1), queued(idle, 1)];
+        let running = HashMap::from([(busy, 0)]);
+
+        assert_eq!(
+            ids(startable_attempts(&queue, &running, Some(4))),
+            vec![queue[0].task_attempt_id, queue[2].task_attempt_id]
+        );
+    }
+}
@@ -0,0 +1,242 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    executor::{ExecutorConfig, NormalizedEntry},
+    models::{
+        config::Config,
+        execution_output::{ExecutionOutput, OutputStream},
+        task::TaskStatus,
+    },
+};
+
+/// Hits returned when the query doesn't ask for a number, and the most it may ask for
+const DEFAULT_LIMIT: u32 = 50;
+const MAX_LIMIT: u32 = 200;
+
+/// Finished conversations indexed per pass, so a large backlog doesn't hold up the monitor
+const CONVERSATIONS_PER_PASS: i64 = 20;
+
+/// What a search hit matched
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum SearchHitKind {
+    /// A task's title or description
+    Task,
+    /// The prompt a coding agent was started with
+    Prompt,
+    /// A coding agent's final message
+    Summary,
+    /// A coding agent's normalized conversation
+    Conversation,
+}
+
+/// Filters for a search, all optional but the query
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    pub q: String,
+    pub project_id: Option<Uuid>,
+    /// Executor of the attempt; task hits match when any of their attempts used it
+    pub executor: Option<String>,
+    pub status: Option<TaskStatus>,
+    /// Only hits created (tasks) or started (processes) in this range
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct SearchHit {
+    pub kind: SearchHitKind,
+    pub project_id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub task_status: TaskStatus,
+    /// Set for every hit but tasks
+    pub attempt_id: Option<Uuid>,
+    pub process_id: Option<Uuid>,
+    pub executor: Option<String>,
+    /// Matching excerpt, with matches between `<mark>` and `</mark>`. The rest is not
+    /// escaped, so render it as text.
+    pub snippet: String,
+    /// Lower is better
+    pub score: f64,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl SearchHit {
+    /// Hits for a query, best first
+    pub async fn search(pool: &SqlitePool, query: &SearchQuery) -> Result<Vec<Self>, sqlx::Error> {
+        let Some(fts_query) = fts_query(&query.q) else {
+            return Ok(Vec::new());
+        };
+        let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+        sqlx::query_as!(
+            SearchHit,
+            r#"SELECT
+                si.kind as "kind!: SearchHitKind",
+                t.project_id as "project_id!: Uuid",
+                t.id as "task_id!: Uuid",
+                t.title as "task_title!",
+                t.status as "task_status!: TaskStatus",
+                ta.id as "attempt_id?: Uuid",
+                ep.id as "process_id?: Uuid",
+                ta.executor as "executor?",
+                snippet(search_index, -1, '<mark>', '</mark>', '…', 16) as "snippet!: String",
+                bm25(search_index, 0.0, 0.0, 10.0, 1.0) as "score!: f64",
+                COALESCE(ep.started_at, t.created_at) as "created_at!: DateTime<Utc>"
+               FROM search_index si
+               LEFT JOIN execution_processes ep ON si.kind != 'task' AND ep.id = si.source_id
+               LEFT JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+               JOIN tasks t ON t.id = CASE WHEN si.kind = 'task' THEN si.source_id ELSE ta.task_id END
+               WHERE search_index MATCH $1
+                 AND ($2 IS NULL OR t.project_id = $2)
+                 AND ($3 IS NULL OR t.status = $3)
+                 AND ($4 IS NULL OR CASE
+                     WHEN si.kind = 'task' THEN EXISTS (
+                         SELECT 1 FROM task_attempts a WHERE a.task_id = t.id AND a.executor = $4
+                     )
+                     ELSE ta.executor = $4
+                 END)
+                 AND ($5 IS NULL OR datetime(COALESCE(ep.started_at, t.created_at)) >= datetime($5))
+                 AND ($6 IS NULL OR datetime(COALESCE(ep.started_at, t.created_at)) <= datetime($6))
+               ORDER BY bm25(search_index, 0.0, 0.0, 10.0, 1.0)
+               LIMIT $7"#,
+            fts_query,
+            query.project_id,
+            query.status,
+            query.executor,
+            query.from,
+            query.to,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
+
+/// Keeps the search index up to date with what triggers can't: conversations, which are
+/// normalized from a coding agent's output
+pub struct SearchIndex;
+
+impl SearchIndex {
+    /// Index the conversations of coding agents that finished since the last pass, returning
+    /// how many were indexed
+    pub async fn index_conversations(
+        pool: &SqlitePool,
+        config: &Config,
+    ) -> Result<usize, sqlx::Error> {
+        let pending = sqlx::query!(
+            r#"SELECT
+                id as "id!: Uuid",
+                executor_type,
+                working_directory
+               FROM execution_processes
+               WHERE process_type = 'codingagent'
+                 AND status != 'running'
+                 AND conversation_indexed_at IS NULL
+               ORDER BY started_at DESC
+               LIMIT $1"#,
+            CONVERSATIONS_PER_PASS
+        )
+        .fetch_all(pool)
+        .await?;
+
+        for process in &pending {
+            let stdout =
+                ExecutionOutput::read_to_string(pool, process.id, OutputStream::Stdout).await?;
+            let entries = process
+                .executor_type
+                .as_deref()
+                .and_then(|executor_type| executor_type.parse::<ExecutorConfig>().ok())
+                .and_then(|executor| {
+                    executor
+                        .create_executor(config)
+                        .normalize_logs(&stdout, &process.working_directory)
+                        .ok()
+                })
+                .map(|conversation| conversation.entries)
+                .unwrap_or_default();
+            let body = conversation_text(&entries);
+
+            let now = Utc::now();
+            let mut tx = pool.begin().await?;
+            sqlx::query!(
+                "DELETE FROM search_index WHERE kind = 'conversation' AND source_id = $1",
+                process.id
+            )
+            .execute(&mut *tx)
+            .await?;
+            if !body.is_empty() {
+                sqlx::query!(
+                    "INSERT INTO search_index (kind, source_id, body) VALUES ('conversation', $1, $2)",
+                    process.id,
+                    body
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+            sqlx::query!(
+                "UPDATE execution_processes SET conversation_indexed_at = $1 WHERE id = $2",
+                now,
+                process.id
+            )
+            .execute(&mut *tx)
+            .await?;
+            tx.commit().await?;
+        }
+
+        Ok(pending.len())
+    }
+}
+
+/// The searchable text of a conversation, one entry per line
+fn conversation_text(entries: &[NormalizedEntry]) -> String {
+    entries
+        .iter()
+        .map(|entry| entry.content.trim())
+        .filter(|content| !content.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// An FTS5 query matching every word of what the user typed, the last one as a prefix so
+/// results show up while typing. Words are quoted, so punctuation and FTS5 operators in the
+/// input are matched literally instead of failing the query.
+fn fts_query(input: &str) -> Option<String> {
+    let words: Vec<String> = input
+        .split_whitespace()
+        .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+        .collect();
+    if words.is_empty() {
+        return None;
+    }
+    Some(format!("{}*", words.join(" ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fts_query_quotes_words_and_prefixes_the_last() {
+        assert_eq!(fts_query("  "), None);
+        assert_eq!(
+            fts_query("migration code"),
+            Some("\"migration\" \"code\"*".to_string())
+        );
+        // Operators, column filters and quotes are just text
+        assert_eq!(
+            fts_query("title:foo OR \"bar"),
+            Some("\"title:foo\" \"OR\" \"\"\"bar\"*".to_string())
+        );
+    }
+}
@@ -41,6 +41,8 @@ pub struct TaskWithAttemptStatus {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub has_in_progress_attempt: bool,
+    /// An attempt is waiting in the execution queue
+    pub has_queued_attempt: bool,
     pub has_merged_attempt: bool,
     pub last_attempt_failed: bool,
     pub latest_attempt_executor: Option<String>,
@@ -63,6 +65,8 @@ pub struct CreateTaskAndStart {
     pub description: Option<String>,
     pub parent_task_attempt: Option<Uuid>,
     pub executor: Option<crate::executor::ExecutorConfig>,
+    /// Queue priority of the started attempt, higher starts first
+    pub priority: Option<i64>,
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -101,6 +105,15 @@ impl Task {
      LIMIT 1
   ) THEN 1 ELSE 0 END            AS "has_in_progress_attempt!: i64",
 
+  CASE WHEN EXISTS (
+    SELECT 1
+      FROM task_attempts ta
+      JOIN execution_queue eq
+        ON eq.task_attempt_id = ta.id
+     WHERE ta.task_id       = t.id
+     LIMIT 1
+  ) THEN 1 ELSE 0 END            AS "has_queued_attempt!: i64",
+
   CASE WHEN EXISTS (
     SELECT 1
       FROM task_attempts ta
@@ -118,7 +131,7 @@ impl Task {
        AND ep.process_type IN ('setupscript','codingagent')
      ORDER BY ep.created_at DESC
      LIMIT 1
-  ) IN ('failed','killed') THEN 1 ELSE 0 END
+  ) IN ('failed','killed','timedout') THEN 1 ELSE 0 END
                                  AS "last_attempt_failed!: i64",
 
   ( SELECT ta.executor
@@ -148,6 +161,7 @@ ORDER BY t.created_at DESC"#,
                 created_at: rec.created_at,
                 updated_at: rec.updated_at,
                 has_in_progress_attempt: rec.has_in_progress_attempt != 0,
+                has_queued_attempt: rec.has_queued_attempt != 0,
                 has_merged_attempt: rec.has_merged_attempt != 0,
                 last_attempt_failed: rec.last_attempt_failed != 0,
                 latest_attempt_executor: rec.latest_attempt_executor,
@@ -8,9 +8,13 @@ use tracing::info;
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::{project::Project, task::Task};
+use super::{
+    project::Project,
+    queued_attempt::{queue_position, QueuedAttempt},
+    task::Task,
+};
 use crate::services::{
-    CreatePrRequest, GitHubRepoInfo, GitHubService, GitHubServiceError, GitService,
+    CreatePrRequest, ExecutionQueue, GitHubRepoInfo, GitHubService, GitHubServiceError, GitService,
     GitServiceError, ProcessService,
 };
 
@@ -76,6 +80,7 @@ impl From<GitHubServiceError> for TaskAttemptError {
 #[serde(rename_all = "lowercase")]
 #[ts(export)]
 pub enum TaskAttemptStatus {
+    Queued,
     SetupRunning,
     SetupComplete,
     SetupFailed,
@@ -100,6 +105,7 @@ pub struct TaskAttempt {
     pub pr_merged_at: Option<DateTime<Utc>>, // When PR was merged
     pub worktree_deleted: bool,    // Flag indicating if worktree has been cleaned up
     pub setup_completed_at: Option<DateTime<Utc>>, // When setup script was last completed
+    pub fan_out_id: Option<Uuid>,  // Shared by attempts started together to race on the task
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -109,6 +115,8 @@ pub struct TaskAttempt {
 pub struct CreateTaskAttempt {
     pub executor: Option<String>, // Optional executor name (defaults to "echo")
     pub base_branch: Option<String>, // Optional base branch to checkout (defaults to current HEAD)
+    /// Queued attempts with a higher priority start first when agents are at their limit
+    pub priority: Option<i64>,
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -178,14 +186,18 @@ pub struct BranchStatus {
 #[ts(export)]
 pub enum ExecutionState {
     NotStarted,
+    /// Waiting in the execution queue for a free coding agent slot
+    Queued,
     SetupRunning,
     SetupComplete,
     SetupFailed,
     SetupStopped,
+    SetupTimedOut,
     CodingAgentRunning,
     CodingAgentComplete,
     CodingAgentFailed,
     CodingAgentStopped,
+    CodingAgentTimedOut,
     Complete,
 }
 
@@ -197,6 +209,8 @@ pub struct TaskAttemptState {
     pub has_setup_script: bool,
     pub setup_process_id: Option<String>,
     pub coding_agent_process_id: Option<String>,
+    /// 1-based place in the execution queue while the attempt is queued
+    pub queue_position: Option<usize>,
 }
 
 /// Context data for resume operations (simplified)
@@ -237,6 +251,7 @@ impl TaskAttempt {
                        ta.pr_merged_at      AS "pr_merged_at: DateTime<Utc>",
                        ta.worktree_deleted  AS "worktree_deleted!: bool",
                        ta.setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                       ta.fan_out_id        AS "fan_out_id: Uuid",
                        ta.created_at        AS "created_at!: DateTime<Utc>",
                        ta.updated_at        AS "updated_at!: DateTime<Utc>"
                FROM    task_attempts ta
@@ -317,6 +332,7 @@ impl TaskAttempt {
                        pr_merged_at      AS "pr_merged_at: DateTime<Utc>",
                        worktree_deleted  AS "worktree_deleted!: bool",
                        setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                       fan_out_id        AS "fan_out_id: Uuid",
                        created_at        AS "created_at!: DateTime<Utc>",
                        updated_at        AS "updated_at!: DateTime<Utc>"
                FROM    task_attempts
@@ -346,6 +362,7 @@ impl TaskAttempt {
                        pr_merged_at      AS "pr_merged_at: DateTime<Utc>",
                        worktree_deleted  AS "worktree_deleted!: bool",
                        setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                       fan_out_id        AS "fan_out_id: Uuid",
                        created_at        AS "created_at!: DateTime<Utc>",
                        updated_at        AS "updated_at!: DateTime<Utc>"
                FROM    task_attempts
@@ -357,6 +374,40 @@ impl TaskAttempt {
         .await
     }
 
+    /// Attempts started together by a fan-out, oldest first
+    pub async fn find_by_fan_out_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        fan_out_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskAttempt,
+            r#"SELECT  id                AS "id!: Uuid",
+                       task_id           AS "task_id!: Uuid",
+                       worktree_path,
+                       branch,
+                       base_branch,
+                       merge_commit,
+                       executor,
+                       pr_url,
+                       pr_number,
+                       pr_status,
+                       pr_merged_at      AS "pr_merged_at: DateTime<Utc>",
+                       worktree_deleted  AS "worktree_deleted!: bool",
+                       setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                       fan_out_id        AS "fan_out_id: Uuid",
+                       created_at        AS "created_at!: DateTime<Utc>",
+                       updated_at        AS "updated_at!: DateTime<Utc>"
+               FROM    task_attempts
+               WHERE   task_id = $1 AND fan_out_id = $2
+               ORDER BY created_at ASC"#,
+            task_id,
+            fan_out_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     /// Find task attempts by task_id with project git repo path for cleanup operations
     pub async fn find_by_task_id_with_project(
         pool: &SqlitePool,
@@ -434,6 +485,7 @@ impl TaskAttempt {
         pool: &SqlitePool,
         data: &CreateTaskAttempt,
         task_id: Uuid,
+        fan_out_id: Option<Uuid>,
     ) -> Result<Self, TaskAttemptError> {
         let attempt_id = Uuid::new_v4();
         // let prefixed_id = format!("vibe-kanban-{}", attempt_id);
@@ -481,9 +533,9 @@ impl TaskAttempt {
         // Insert the record into the database
         Ok(sqlx::query_as!(
             TaskAttempt,
-            r#"INSERT INTO task_attempts (id, task_id, worktree_path, branch, base_branch, merge_commit, executor, pr_url, pr_number, pr_status, pr_merged_at, worktree_deleted, setup_completed_at)
-               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
-               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", worktree_path, branch, base_branch, merge_commit, executor, pr_url, pr_number, pr_status, pr_merged_at as "pr_merged_at: DateTime<Utc>", worktree_deleted as "worktree_deleted!: bool", setup_completed_at as "setup_completed_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            r#"INSERT INTO task_attempts (id, task_id, worktree_path, branch, base_branch, merge_commit, executor, pr_url, pr_number, pr_status, pr_merged_at, worktree_deleted, setup_completed_at, fan_out_id)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", worktree_path, branch, base_branch, merge_commit, executor, pr_url, pr_number, pr_status, pr_merged_at as "pr_merged_at: DateTime<Utc>", worktree_deleted as "worktree_deleted!: bool", setup_completed_at as "setup_completed_at: DateTime<Utc>", fan_out_id as "fan_out_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             attempt_id,
             task_id,
             worktree_path_str,
@@ -496,7 +548,8 @@ impl TaskAttempt {
             Option::<String>::None, // pr_status is None during creation
             Option::<DateTime<Utc>>::None, // pr_merged_at is None during creation
             false, // worktree_deleted is false during creation
-            Option::<DateTime<Utc>>::None // setup_completed_at is None during creation
+            Option::<DateTime<Utc>>::None, // setup_completed_at is None during creation
+            fan_out_id
         )
         .fetch_one(pool)
         .await?)
@@ -586,15 +639,16 @@ impl TaskAttempt {
         Ok(merge_commit_id)
     }
 
-    /// Start the execution flow for a task attempt (setup script + executor)
+    /// Queue the execution flow for a task attempt (setup script + executor), which starts once
+    /// the concurrency limits leave room for it
     pub async fn start_execution(
-        pool: &SqlitePool,
         app_state: &crate::app_state::AppState,
         attempt_id: Uuid,
         task_id: Uuid,
         project_id: Uuid,
+        priority: i64,
     ) -> Result<(), TaskAttemptError> {
-        ProcessService::start_execution(pool, app_state, attempt_id, task_id, project_id).await
+        ExecutionQueue::enqueue(app_state, attempt_id, task_id, project_id, priority).await
     }
 
     /// Start a dev server for this task attempt
@@ -608,20 +662,19 @@ impl TaskAttempt {
         ProcessService::start_dev_server(pool, app_state, attempt_id, task_id, project_id).await
     }
 
-    /// Start a follow-up execution using the same executor type as the first process
+    /// Start a follow-up execution using the same executor type as the first process, once the
+    /// concurrency limits leave room for it
     /// Returns the attempt_id that was actually used (always the original attempt_id for session continuity)
     pub async fn start_followup_execution(
-        pool: &SqlitePool,
         app_state: &crate::app_state::AppState,
         attempt_id: Uuid,
         task_id: Uuid,
         project_id: Uuid,
         prompt: &str,
     ) -> Result<Uuid, TaskAttemptError> {
-        ProcessService::start_followup_execution(
-            pool, app_state, attempt_id, task_id, project_id, prompt,
-        )
-        .await
+        ExecutionQueue::enqueue_follow_up(app_state, attempt_id, task_id, project_id, prompt)
+            .await?;
+        Ok(attempt_id)
     }
 
     /// Ensure worktree exists, recreating from branch if needed (cold task support)
@@ -1027,6 +1080,9 @@ impl TaskAttempt {
                             crate::models::execution_process::ExecutionProcessStatus::Killed => {
                                 ExecutionState::CodingAgentStopped
                             }
+                            crate::models::execution_process::ExecutionProcessStatus::TimedOut => {
+                                ExecutionState::CodingAgentTimedOut
+                            }
                         }
                     } else {
                         ExecutionState::SetupComplete
@@ -1038,6 +1094,9 @@ impl TaskAttempt {
                 crate::models::execution_process::ExecutionProcessStatus::Killed => {
                     ExecutionState::SetupStopped
                 }
+                crate::models::execution_process::ExecutionProcessStatus::TimedOut => {
+                    ExecutionState::SetupTimedOut
+                }
             }
         } else if let Some(agent) = coding_agent_process {
             // No setup script, only coding agent
@@ -1054,12 +1113,23 @@ impl TaskAttempt {
                 crate::models::execution_process::ExecutionProcessStatus::Killed => {
                     ExecutionState::CodingAgentStopped
                 }
+                crate::models::execution_process::ExecutionProcessStatus::TimedOut => {
+                    ExecutionState::CodingAgentTimedOut
+                }
             }
         } else {
             // No processes started yet
             ExecutionState::NotStarted
         };
 
+        let queue = QueuedAttempt::find_all(pool).await?;
+        let queue_position = queue_position(&queue, attempt_id);
+        let execution_state = if queue_position.is_some() {
+            ExecutionState::Queued
+        } else {
+            execution_state
+        };
+
         // Check if there are any changes (quick diff check)
         let has_changes = match Self::get_diff(pool, attempt_id, task_id, project_id).await {
             Ok(diff) => !diff.files.is_empty(),
@@ -1072,6 +1142,7 @@ impl TaskAttempt {
             has_setup_script,
             setup_process_id: setup_process.map(|p| p.id.to_string()),
             coding_agent_process_id: coding_agent_process.map(|p| p.id.to_string()),
+            queue_position,
         })
     }
 
@@ -1127,11 +1198,15 @@ impl TaskAttempt {
 
         let mut history = String::new();
         for process in coding_processes {
-            if let Some(stdout) = process.stdout {
-                if !stdout.trim().is_empty() {
-                    history.push_str(&stdout);
-                    history.push('\n');
-                }
+            let stdout = super::execution_output::ExecutionOutput::read_to_string(
+                pool,
+                process.id,
+                super::execution_output::OutputStream::Stdout,
+            )
+            .await?;
+            if !stdout.trim().is_empty() {
+                history.push_str(&stdout);
+                history.push('\n');
             }
         }
 
@@ -0,0 +1,247 @@
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Tools whose only side effect is on the file named by their path parameter
+const FILE_TOOLS: &[&str] = &[
+    "Read",
+    "Edit",
+    "MultiEdit",
+    "Write",
+    "NotebookEdit",
+    "Glob",
+    "Grep",
+    "LS",
+];
+
+/// Characters that let a command do more than its allowlisted prefix suggests
+const SHELL_METACHARACTERS: &[char] = &[';', '&', '|', '`', '$', '(', ')', '<', '>', '\n', '\r'];
+
+/// Tool uses a project approves without asking
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ToolAllowlist {
+    /// Commands, or command prefixes followed by arguments, that Bash may run
+    pub commands: Vec<String>,
+    /// Paths relative to the worktree that file tools may read and write under
+    pub paths: Vec<String>,
+}
+
+impl ToolAllowlist {
+    pub async fn find_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT kind, pattern
+               FROM project_tool_allowlist
+               WHERE project_id = $1
+               ORDER BY created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let mut allowlist = Self::default();
+        for row in rows {
+            match row.kind.as_str() {
+                "command" => allowlist.commands.push(row.pattern),
+                "path" => allowlist.paths.push(row.pattern),
+                _ => {}
+            }
+        }
+        Ok(allowlist)
+    }
+
+    /// Replace a project's allowlist, dropping blank entries
+    pub async fn replace_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        allowlist: &ToolAllowlist,
+    ) -> Result<Self, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!(
+            "DELETE FROM project_tool_allowlist WHERE project_id = $1",
+            project_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let entries = allowlist
+            .commands
+            .iter()
+            .map(|pattern| ("command", pattern))
+            .chain(allowlist.paths.iter().map(|pattern| ("path", pattern)));
+        for (kind, pattern) in entries {
+            let pattern = pattern.trim();
+            if pattern.is_empty() {
+                continue;
+            }
+            let id = Uuid::new_v4();
+            sqlx::query!(
+                r#"INSERT INTO project_tool_allowlist (id, project_id, kind, pattern)
+                   VALUES ($1, $2, $3, $4)"#,
+                id,
+                project_id,
+                kind,
+                pattern
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Self::find_for_project(pool, project_id).await
+    }
+
+    /// Whether a tool use is covered by this allowlist. Paths are compared lexically,
+    /// so anything that escapes the worktree via `..` is never matched.
+    pub fn allows(&self, tool_name: &str, input: &Value, worktree_path: &Path) -> bool {
+        if tool_name == "Bash" {
+            return input
+                .get("command")
+                .and_then(|c| c.as_str())
+                .is_some_and(|command| self.allows_command(command));
+        }
+
+        if FILE_TOOLS.contains(&tool_name) {
+            let target = ["file_path", "notebook_path", "path"]
+                .iter()
+                .find_map(|key| input.get(*key).and_then(|p| p.as_str()))
+                // Search tools default to the working directory
+                .unwrap_or(".");
+            return self.allows_path(target, worktree_path);
+        }
+
+        false
+    }
+
+    fn allows_command(&self, command: &str) -> bool {
+        let command = command.trim();
+        if command.contains(SHELL_METACHARACTERS) {
+            return false;
+        }
+
+        self.commands.iter().map(|c| c.trim()).any(|allowed| {
+            !allowed.is_empty()
+                && (command == allowed
+                    || command
+                        .strip_prefix(allowed)
+                        .is_some_and(|rest| rest.starts_with(' ')))
+        })
+    }
+
+    fn allows_path(&self, target: &str, worktree_path: &Path) -> bool {
+        let Some(target) = normalize_path(&worktree_path.join(target)) else {
+            return false;
+        };
+
+        self.paths
+            .iter()
+            .map(|p| p.trim())
+            .filter(|p| !p.is_empty())
+            .filter_map(|allowed| normalize_path(&worktree_path.join(allowed)))
+            .any(|allowed| target.starts_with(allowed))
+    }
+}
+
+/// Resolve `.` and `..` without touching the filesystem
+fn normalize_path(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return None;
+                }
+            }
+            other => normalized.push(other),
+        }
+    }
+    Some(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn allowlist() -> ToolAllowlist {
+        ToolAllowlist {
+            commands: vec!["cargo test".to_string(), "ls".to_string()],
+            paths: vec!["src".to_string(), "README.md".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_allows_commands_by_prefix() {
+        let allowlist = allowlist();
+        let worktree = Path::new("/tmp/worktree");
+        let bash = |command: &str| json!({ "command": command });
+
+        assert!(allowlist.allows("Bash", &bash("cargo test"), worktree));
+        assert!(allowlist.allows("Bash", &bash("cargo test --workspace"), worktree));
+        assert!(allowlist.allows("Bash", &bash("ls -la"), worktree));
+        // Prefixes only match whole words
+        assert!(!allowlist.allows("Bash", &bash("lsof"), worktree));
+        assert!(!allowlist.allows("Bash", &bash("cargo testing"), worktree));
+        // Chaining or substitution escapes the allowlisted command
+        assert!(!allowlist.allows("Bash", &bash("cargo test && rm -rf /"), worktree));
+        assert!(!allowlist.allows("Bash", &bash("ls $(rm -rf /)"), worktree));
+        assert!(!allowlist.allows("Bash", &bash("ls > out.txt"), worktree));
+        assert!(!allowlist.allows("Bash", &bash("ls\nrm -rf /"), worktree));
+    }
+
+    #[test]
+    fn test_allows_paths_inside_worktree() {
+        let allowlist = allowlist();
+        let worktree = Path::new("/tmp/worktree");
+
+        assert!(allowlist.allows(
+            "Edit",
+            &json!({ "file_path": "/tmp/worktree/src/main.rs" }),
+            worktree
+        ));
+        assert!(allowlist.allows("Read", &json!({ "file_path": "README.md" }), worktree));
+        assert!(allowlist.allows("Grep", &json!({ "path": "src", "pattern": "x" }), worktree));
+        assert!(!allowlist.allows(
+            "Write",
+            &json!({ "file_path": "/tmp/worktree/Cargo.toml" }),
+            worktree
+        ));
+        // `..` can't climb out of an allowed directory
+        assert!(!allowlist.allows(
+            "Write",
+            &json!({ "file_path": "/tmp/worktree/src/../../secrets" }),
+            worktree
+        ));
+        // Searching the whole worktree isn't covered by a subdirectory
+        assert!(!allowlist.allows("Glob", &json!({ "pattern": "**/*" }), worktree));
+        // Sibling names sharing a prefix don't match
+        assert!(!allowlist.allows(
+            "Read",
+            &json!({ "file_path": "/tmp/worktree/src-old/lib.rs" }),
+            worktree
+        ));
+    }
+
+    #[test]
+    fn test_unknown_tools_are_never_allowed() {
+        let allowlist = allowlist();
+        let worktree = Path::new("/tmp/worktree");
+
+        assert!(!allowlist.allows(
+            "WebFetch",
+            &json!({ "url": "https://example.com" }),
+            worktree
+        ));
+        assert!(!allowlist.allows("Bash", &json!({}), worktree));
+    }
+}
@@ -0,0 +1,175 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "tool_approval_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum ToolApprovalStatus {
+    Pending,
+    Approved,
+    Denied,
+    TimedOut,
+}
+
+/// A tool permission request raised by a coding agent
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ToolApproval {
+    pub id: Uuid,
+    pub execution_process_id: Uuid,
+    pub tool_name: String,
+    pub tool_input: String, // JSON object passed to the tool
+    pub status: ToolApprovalStatus,
+    pub reason: Option<String>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct RespondToolApproval {
+    pub approved: bool,
+    pub reason: Option<String>,
+}
+
+impl ToolApproval {
+    /// Record a permission request. Requests matched by an allowlist are created
+    /// already approved so they still show up in the attempt's history.
+    pub async fn create(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        tool_name: &str,
+        tool_input: &str,
+        status: ToolApprovalStatus,
+        reason: Option<&str>,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let now = Utc::now();
+        let decided_at = (status != ToolApprovalStatus::Pending).then_some(now);
+
+        sqlx::query_as!(
+            ToolApproval,
+            r#"INSERT INTO tool_approvals (
+                id, execution_process_id, tool_name, tool_input, status, reason,
+                decided_at, created_at, updated_at
+               )
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+               RETURNING
+                id as "id!: Uuid",
+                execution_process_id as "execution_process_id!: Uuid",
+                tool_name,
+                tool_input,
+                status as "status!: ToolApprovalStatus",
+                reason,
+                decided_at as "decided_at?: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            execution_process_id,
+            tool_name,
+            tool_input,
+            status,
+            reason,
+            decided_at,
+            now,
+            now
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ToolApproval,
+            r#"SELECT
+                id as "id!: Uuid",
+                execution_process_id as "execution_process_id!: Uuid",
+                tool_name,
+                tool_input,
+                status as "status!: ToolApprovalStatus",
+                reason,
+                decided_at as "decided_at?: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM tool_approvals
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// All permission requests raised by the execution processes of a task attempt
+    pub async fn find_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ToolApproval,
+            r#"SELECT
+                tap.id as "id!: Uuid",
+                tap.execution_process_id as "execution_process_id!: Uuid",
+                tap.tool_name,
+                tap.tool_input,
+                tap.status as "status!: ToolApprovalStatus",
+                tap.reason,
+                tap.decided_at as "decided_at?: DateTime<Utc>",
+                tap.created_at as "created_at!: DateTime<Utc>",
+                tap.updated_at as "updated_at!: DateTime<Utc>"
+               FROM tool_approvals tap
+               JOIN execution_processes ep ON tap.execution_process_id = ep.id
+               WHERE ep.task_attempt_id = $1
+               ORDER BY tap.created_at ASC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Decide a pending request. Returns false if it was already decided.
+    pub async fn decide(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: ToolApprovalStatus,
+        reason: Option<&str>,
+    ) -> Result<bool, sqlx::Error> {
+        let now = Utc::now();
+        let result = sqlx::query!(
+            r#"UPDATE tool_approvals
+               SET status = $1, reason = $2, decided_at = $3, updated_at = $3
+               WHERE id = $4 AND status = 'pending'"#,
+            status,
+            reason,
+            now,
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Deny every request still pending for an execution process, e.g. once it exits
+    pub async fn expire_pending_for_execution_process(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let now = Utc::now();
+        let result = sqlx::query!(
+            r#"UPDATE tool_approvals
+               SET status = 'timedout', decided_at = $1, updated_at = $1
+               WHERE execution_process_id = $2 AND status = 'pending'"#,
+            now,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
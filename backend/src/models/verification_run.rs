@@ -0,0 +1,243 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::execution_process::{ExecutionProcessSummary, ExecutionProcessType};
+
+/// Most follow-ups a project may let the verify-and-fix loop send in a row
+pub const MAX_FIX_ITERATIONS: i64 = 10;
+
+/// Test output beyond this many characters is cut from the start of a fix prompt
+const MAX_FIX_OUTPUT_CHARS: usize = 12_000;
+
+/// A test script run of the verify-and-fix loop
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct VerificationRun {
+    pub id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub execution_process_id: Uuid,
+    /// 1 for the run after an agent the user started, counting up with each requested fix
+    pub iteration: i64,
+    pub passed: bool,
+    /// Whether the failing output was sent back to the coding agent
+    pub fix_requested: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl VerificationRun {
+    pub async fn create(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        execution_process_id: Uuid,
+        iteration: i64,
+        passed: bool,
+        fix_requested: bool,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let now = Utc::now();
+
+        sqlx::query_as!(
+            VerificationRun,
+            r#"INSERT INTO verification_runs (
+                id, task_attempt_id, execution_process_id, iteration, passed, fix_requested,
+                created_at
+               )
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               RETURNING
+                id as "id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                execution_process_id as "execution_process_id!: Uuid",
+                iteration as "iteration!: i64",
+                passed as "passed!: bool",
+                fix_requested as "fix_requested!: bool",
+                created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            task_attempt_id,
+            execution_process_id,
+            iteration,
+            passed,
+            fix_requested,
+            now
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// All verification runs of an attempt, oldest first
+    pub async fn find_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            VerificationRun,
+            r#"SELECT
+                id as "id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                execution_process_id as "execution_process_id!: Uuid",
+                iteration as "iteration!: i64",
+                passed as "passed!: bool",
+                fix_requested as "fix_requested!: bool",
+                created_at as "created_at!: DateTime<Utc>"
+               FROM verification_runs
+               WHERE task_attempt_id = $1
+               ORDER BY created_at ASC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Iteration of the test run `execution_process_id`, given the attempt's processes oldest
+    /// first. It continues the loop only when the previous run requested a fix and that fix is
+    /// the one agent run in between; anything else means the user took over.
+    pub fn next_iteration(
+        previous: Option<&VerificationRun>,
+        processes: &[ExecutionProcessSummary],
+        execution_process_id: Uuid,
+    ) -> i64 {
+        let Some(previous) = previous.filter(|run| run.fix_requested) else {
+            return 1;
+        };
+        let position = |id: Uuid| processes.iter().position(|p| p.id == id);
+        let (Some(start), Some(end)) = (
+            position(previous.execution_process_id),
+            position(execution_process_id),
+        ) else {
+            return 1;
+        };
+        let agent_runs = processes
+            .get(start + 1..end)
+            .unwrap_or_default()
+            .iter()
+            .filter(|p| p.process_type == ExecutionProcessType::CodingAgent)
+            .count();
+        if agent_runs == 1 {
+            previous.iteration + 1
+        } else {
+            1
+        }
+    }
+}
+
+/// Follow-up prompt asking the coding agent to fix what a failing test script reported
+pub fn fix_prompt(test_script: &str, exit_code: Option<i64>, output: &str) -> String {
+    let output = output.trim();
+    let mut start = output.len().saturating_sub(MAX_FIX_OUTPUT_CHARS);
+    while !output.is_char_boundary(start) {
+        start += 1;
+    }
+    let truncated = if start > 0 {
+        "(earlier output truncated)\n"
+    } else {
+        ""
+    };
+    let failure = match exit_code {
+        Some(code) => format!("failed with exit code {}", code),
+        None => "did not finish".to_string(),
+    };
+
+    format!(
+        "The test script `{}` {} after your changes. Fix the problems it reports.\n\nOutput:\n```\n{}{}\n```",
+        test_script.trim(),
+        failure,
+        truncated,
+        &output[start..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::execution_process::ExecutionProcessStatus;
+
+    fn process(process_type: ExecutionProcessType) -> ExecutionProcessSummary {
+        ExecutionProcessSummary {
+            id: Uuid::new_v4(),
+            task_attempt_id: Uuid::new_v4(),
+            process_type,
+            executor_type: None,
+            status: ExecutionProcessStatus::Completed,
+            command: "bash".to_string(),
+            args: None,
+            working_directory: "/tmp/worktree".to_string(),
+            exit_code: Some(0),
+            started_at: Utc::now(),
+            completed_at: Some(Utc::now()),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn run(execution_process_id: Uuid, iteration: i64, fix_requested: bool) -> VerificationRun {
+        VerificationRun {
+            id: Uuid::new_v4(),
+            task_attempt_id: Uuid::new_v4(),
+            execution_process_id,
+            iteration,
+            passed: false,
+            fix_requested,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_iteration_counts_up_after_a_requested_fix() {
+        let first_test = process(ExecutionProcessType::TestScript);
+        let processes = vec![
+            process(ExecutionProcessType::CodingAgent),
+            first_test.clone(),
+            process(ExecutionProcessType::CodingAgent),
+            process(ExecutionProcessType::TestScript),
+        ];
+        let current = processes[3].id;
+
+        assert_eq!(
+            VerificationRun::next_iteration(None, &processes, current),
+            1
+        );
+        let previous = run(first_test.id, 2, true);
+        assert_eq!(
+            VerificationRun::next_iteration(Some(&previous), &processes, current),
+            3
+        );
+        let exhausted = run(first_test.id, 2, false);
+        assert_eq!(
+            VerificationRun::next_iteration(Some(&exhausted), &processes, current),
+            1
+        );
+    }
+
+    #[test]
+    fn test_iteration_restarts_when_the_user_sent_a_follow_up() {
+        let first_test = process(ExecutionProcessType::TestScript);
+        // The requested fix was stopped and the user sent their own follow-up
+        let processes = vec![
+            first_test.clone(),
+            process(ExecutionProcessType::CodingAgent),
+            process(ExecutionProcessType::CodingAgent),
+            process(ExecutionProcessType::TestScript),
+        ];
+        let previous = run(first_test.id, 1, true);
+
+        assert_eq!(
+            VerificationRun::next_iteration(Some(&previous), &processes, processes[3].id),
+            1
+        );
+    }
+
+    #[test]
+    fn test_fix_prompt_keeps_the_end_of_long_output() {
+        let prompt = fix_prompt("cargo test\n", Some(101), "error: it broke");
+        assert!(prompt.starts_with("The test script `cargo test` failed with exit code 101"));
+        assert!(prompt.ends_with("```\nerror: it broke\n```"));
+
+        let output = format!("{}é{}", "a".repeat(MAX_FIX_OUTPUT_CHARS), "tail");
+        let prompt = fix_prompt("npm test", None, &output);
+        assert!(prompt.contains("did not finish"));
+        assert!(prompt.contains("(earlier output truncated)\n"));
+        assert!(prompt.ends_with("étail\n```"));
+    }
+}
@@ -13,7 +13,8 @@ use ts_rs::TS;
 
 use crate::{
     app_state::AppState,
-    executor::ExecutorConfig,
+    executor::{ExecutorConfig, ExecutorConstants, McpConfigFormat, McpConfigLocation},
+    executors,
     models::{
         config::{Config, EditorConstants, SoundConstants},
         ApiResponse,
@@ -41,6 +42,23 @@ async fn update_config(
 ) -> ResponseJson<ApiResponse<Config>> {
     let config_path = utils::config_path();
 
+    if let Err(e) = executors::custom::validate_custom_executors(&new_config.custom_executors) {
+        return ResponseJson(ApiResponse::error(&e));
+    }
+    if let Some(Err(e)) = new_config
+        .prompt_template
+        .as_deref()
+        .map(executors::prompt::validate_prompt_template)
+    {
+        return ResponseJson(ApiResponse::error(&e));
+    }
+
+    if new_config.max_concurrent_agents == Some(0) {
+        return ResponseJson(ApiResponse::error(
+            "Concurrent agents must be at least 1, or unset for no limit",
+        ));
+    }
+
     match new_config.save(&config_path) {
         Ok(_) => {
             let mut config = app_state.get_config().write().await;
@@ -62,12 +80,17 @@ async fn update_config(
 pub struct ConfigConstants {
     pub editor: EditorConstants,
     pub sound: SoundConstants,
+    pub executor: ExecutorConstants,
 }
 
-async fn get_config_constants() -> ResponseJson<ApiResponse<ConfigConstants>> {
+async fn get_config_constants(
+    State(app_state): State<AppState>,
+) -> ResponseJson<ApiResponse<ConfigConstants>> {
+    let config = app_state.get_config().read().await;
     let constants = ConfigConstants {
         editor: EditorConstants::new(),
         sound: SoundConstants::new(),
+        executor: ExecutorConstants::new(&config),
     };
 
     ResponseJson(ApiResponse::success(constants))
@@ -189,14 +212,14 @@ async fn update_mcp_servers_in_config(
         .unwrap_or_else(|_| "{}".to_string());
     let mut config: Value = serde_json::from_str(&file_content)?;
 
-    // Get the attribute path for MCP servers
-    let mcp_path = executor_config.mcp_attribute_path().unwrap();
+    // Get where the MCP servers live in the file
+    let mcp_config = executor_config.mcp_config().unwrap();
 
     // Get the current server count for comparison
-    let old_servers = get_mcp_servers_from_config_path(&config, &mcp_path).len();
+    let old_servers = get_mcp_servers_from_config_path(&config, &mcp_config).len();
 
     // Set the MCP servers using the correct attribute path
-    set_mcp_servers_in_config_path(&mut config, &mcp_path, &new_servers)?;
+    set_mcp_servers_in_config_path(&mut config, &mcp_config, &new_servers)?;
 
     // Write the updated config back to file
     let updated_content = serde_json::to_string_pretty(&config)?;
@@ -226,20 +249,24 @@ async fn read_mcp_servers_from_config(
         .unwrap_or_else(|_| "{}".to_string());
     let config: Value = serde_json::from_str(&file_content)?;
 
-    // Get the attribute path for MCP servers
-    let mcp_path = executor_config.mcp_attribute_path().unwrap();
+    // Get where the MCP servers live in the file
+    let mcp_config = executor_config.mcp_config().unwrap();
 
     // Get the servers using the correct attribute path
-    let servers = get_mcp_servers_from_config_path(&config, &mcp_path);
+    let servers = get_mcp_servers_from_config_path(&config, &mcp_config);
 
     Ok(servers)
 }
 
 /// Helper function to get MCP servers from config using a path
-fn get_mcp_servers_from_config_path(config: &Value, path: &[&str]) -> HashMap<String, Value> {
-    // Special handling for AMP - use flat key structure
-    if path.len() == 2 && path[0] == "amp" && path[1] == "mcpServers" {
-        let flat_key = format!("{}.{}", path[0], path[1]);
+fn get_mcp_servers_from_config_path(
+    config: &Value,
+    location: &McpConfigLocation,
+) -> HashMap<String, Value> {
+    let path = &location.attribute_path;
+    // Some executors (e.g. Amp) use a flat dotted key instead of nested objects
+    if location.format == McpConfigFormat::DottedKey {
+        let flat_key = path.join(".");
         let current = match config.get(&flat_key) {
             Some(val) => val,
             None => return HashMap::new(),
@@ -257,7 +284,7 @@ fn get_mcp_servers_from_config_path(config: &Value, path: &[&str]) -> HashMap<St
         let mut current = config;
 
         // Navigate to the target location
-        for &part in path {
+        for part in path {
             current = match current.get(part) {
                 Some(val) => val,
                 None => return HashMap::new(),
@@ -278,17 +305,18 @@ fn get_mcp_servers_from_config_path(config: &Value, path: &[&str]) -> HashMap<St
 /// Helper function to set MCP servers in config using a path
 fn set_mcp_servers_in_config_path(
     config: &mut Value,
-    path: &[&str],
+    location: &McpConfigLocation,
     servers: &HashMap<String, Value>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let path = &location.attribute_path;
     // Ensure config is an object
     if !config.is_object() {
         *config = serde_json::json!({});
     }
 
-    // Special handling for AMP - use flat key structure
-    if path.len() == 2 && path[0] == "amp" && path[1] == "mcpServers" {
-        let flat_key = format!("{}.{}", path[0], path[1]);
+    // Some executors (e.g. Amp) use a flat dotted key instead of nested objects
+    if location.format == McpConfigFormat::DottedKey {
+        let flat_key = path.join(".");
         config
             .as_object_mut()
             .unwrap()
@@ -299,12 +327,12 @@ fn set_mcp_servers_in_config_path(
     let mut current = config;
 
     // Navigate/create the nested structure (all parts except the last)
-    for &part in &path[..path.len() - 1] {
+    for part in &path[..path.len() - 1] {
         if current.get(part).is_none() {
             current
                 .as_object_mut()
                 .unwrap()
-                .insert(part.to_string(), serde_json::json!({}));
+                .insert(part.clone(), serde_json::json!({}));
         }
         current = current.get_mut(part).unwrap();
         if !current.is_object() {
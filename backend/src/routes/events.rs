@@ -0,0 +1,84 @@
+use axum::{
+    extract::{Query, State},
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
+    routing::get,
+    Extension, Router,
+};
+use futures_util::stream::Stream;
+use serde::Deserialize;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::{app_state::AppState, models::project::Project, services::event_bus::ProjectEvent};
+
+/// Header a reconnecting `EventSource` sends with the id of the last event it received
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+
+/// Query parameters for resuming the event stream
+#[derive(Debug, Deserialize)]
+pub struct EventsQuery {
+    /// Id of the last event the client saw
+    since: Option<u64>,
+}
+
+/// SSE stream of a project's board changes
+///
+/// GET /api/projects/:id/events?since=123
+///
+/// Every `board` event carries its id, which a reconnecting `EventSource` resumes after on
+/// its own. A `resync` event means events were missed and the client has to reload.
+pub async fn project_events(
+    Extension(project): Extension<Project>,
+    Query(query): Query<EventsQuery>,
+    headers: HeaderMap,
+    State(app_state): State<AppState>,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    let cursor = headers
+        .get(LAST_EVENT_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+        .or(query.since);
+    let (replay, mut receiver) = app_state.events.subscribe(cursor);
+    let project_id = project.id;
+
+    let stream = async_stream::stream! {
+        match replay {
+            Some(events) => {
+                for event in events.iter().filter(|event| event.project_id == project_id) {
+                    yield Ok(board_event(event));
+                }
+            }
+            None => yield Ok(resync_event()),
+        }
+
+        loop {
+            match receiver.recv().await {
+                Ok(event) if event.project_id == project_id => yield Ok(board_event(&event)),
+                Ok(_) => {}
+                Err(RecvError::Lagged(missed)) => {
+                    tracing::debug!("Event stream of project {} missed {} events", project_id, missed);
+                    yield Ok(resync_event());
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    };
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+fn board_event(event: &ProjectEvent) -> Event {
+    Event::default()
+        .id(event.id.to_string())
+        .event("board")
+        .data(serde_json::to_string(event).unwrap_or_default())
+}
+
+fn resync_event() -> Event {
+    Event::default().event("resync").data("{}")
+}
+
+/// Router exposing `/projects/:id/events`
+pub fn events_router() -> Router<AppState> {
+    Router::new().route("/projects/:id/events", get(project_events))
+}
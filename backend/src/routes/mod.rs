@@ -1,8 +1,10 @@
 pub mod auth;
 pub mod config;
+pub mod events;
 pub mod filesystem;
 pub mod health;
 pub mod projects;
+pub mod search;
 pub mod stream;
 pub mod task_attempts;
 pub mod task_templates;
@@ -11,11 +11,17 @@ use uuid::Uuid;
 
 use crate::{
     app_state::AppState,
+    executor::ExecutorConfig,
+    executors::prompt::validate_prompt_template,
     models::{
+        execution_usage::{ExecutionUsage, UsageTotals},
         project::{
             CreateBranch, CreateProject, GitBranch, Project, ProjectWithBranch, SearchMatchType,
             SearchResult, UpdateProject,
         },
+        project_env_var::{ProjectEnvVar, ProjectEnvVarError},
+        tool_allowlist::ToolAllowlist,
+        verification_run::MAX_FIX_ITERATIONS,
         ApiResponse,
     },
 };
@@ -95,12 +101,34 @@ pub async fn create_project_branch(
 
 pub async fn create_project(
     State(app_state): State<AppState>,
-    Json(payload): Json<CreateProject>,
+    Json(mut payload): Json<CreateProject>,
 ) -> Result<ResponseJson<ApiResponse<Project>>, StatusCode> {
     let id = Uuid::new_v4();
 
     tracing::debug!("Creating project '{}'", payload.name);
 
+    if let Err(e) = validate_executor(payload.executor.as_deref()) {
+        return Ok(ResponseJson(ApiResponse::error(&e)));
+    }
+    if let Err(e) = validate_max_fix_iterations(payload.max_fix_iterations) {
+        return Ok(ResponseJson(ApiResponse::error(&e)));
+    }
+    if let Err(e) = validate_max_concurrent_agents(payload.max_concurrent_agents) {
+        return Ok(ResponseJson(ApiResponse::error(&e)));
+    }
+    if let Some(Err(e)) = payload
+        .prompt_template
+        .as_deref()
+        .map(validate_prompt_template)
+    {
+        return Ok(ResponseJson(ApiResponse::error(&e)));
+    }
+    payload.executor = payload.executor.filter(|e| !e.trim().is_empty());
+    payload.executor_args = payload.executor_args.filter(|a| !a.trim().is_empty());
+    payload.prompt_template = payload.prompt_template.filter(|t| !t.trim().is_empty());
+    payload.instructions = payload.instructions.filter(|i| !i.trim().is_empty());
+    payload.test_script = payload.test_script.filter(|s| !s.trim().is_empty());
+
     // Check if git repo path is already used by another project
     match Project::find_by_git_repo_path(&app_state.db_pool, &payload.git_repo_path).await {
         Ok(Some(_)) => {
@@ -192,6 +220,9 @@ pub async fn create_project(
                         "use_existing_repo": payload.use_existing_repo,
                         "has_setup_script": payload.setup_script.is_some(),
                         "has_dev_script": payload.dev_script.is_some(),
+                        "has_test_script": payload.test_script.is_some(),
+                        "max_fix_iterations": project.max_fix_iterations,
+                        "max_concurrent_agents": project.max_concurrent_agents,
                     })),
                 )
                 .await;
@@ -244,10 +275,58 @@ pub async fn update_project(
         git_repo_path,
         setup_script,
         dev_script,
+        test_script,
+        max_fix_iterations,
+        max_concurrent_agents,
+        executor,
+        executor_args,
+        sandbox,
+        sandbox_network,
+        prompt_template,
+        instructions,
     } = payload;
 
+    if let Err(e) = validate_executor(executor.as_deref()) {
+        return Ok(ResponseJson(ApiResponse::error(&e)));
+    }
+    if let Err(e) = validate_max_fix_iterations(max_fix_iterations) {
+        return Ok(ResponseJson(ApiResponse::error(&e)));
+    }
+    if let Err(e) = validate_max_concurrent_agents(max_concurrent_agents) {
+        return Ok(ResponseJson(ApiResponse::error(&e)));
+    }
+    if let Some(Err(e)) = prompt_template.as_deref().map(validate_prompt_template) {
+        return Ok(ResponseJson(ApiResponse::error(&e)));
+    }
+
     let name = name.unwrap_or(existing_project.name);
     let git_repo_path = git_repo_path.unwrap_or(existing_project.git_repo_path);
+    // Older clients don't send the executor fields, so only an explicit empty string clears them
+    let executor = match executor {
+        Some(executor) => Some(executor).filter(|e| !e.trim().is_empty()),
+        None => existing_project.executor,
+    };
+    let executor_args = match executor_args {
+        Some(args) => Some(args).filter(|a| !a.trim().is_empty()),
+        None => existing_project.executor_args,
+    };
+    let sandbox = sandbox.unwrap_or(existing_project.sandbox);
+    let sandbox_network = sandbox_network.unwrap_or(existing_project.sandbox_network);
+    let prompt_template = match prompt_template {
+        Some(template) => Some(template).filter(|t| !t.trim().is_empty()),
+        None => existing_project.prompt_template,
+    };
+    let instructions = match instructions {
+        Some(instructions) => Some(instructions).filter(|i| !i.trim().is_empty()),
+        None => existing_project.instructions,
+    };
+    let test_script = match test_script {
+        Some(script) => Some(script).filter(|s| !s.trim().is_empty()),
+        None => existing_project.test_script,
+    };
+    let max_fix_iterations = max_fix_iterations.unwrap_or(existing_project.max_fix_iterations);
+    let max_concurrent_agents =
+        max_concurrent_agents.unwrap_or(existing_project.max_concurrent_agents);
 
     match Project::update(
         &app_state.db_pool,
@@ -256,6 +335,15 @@ pub async fn update_project(
         git_repo_path,
         setup_script,
         dev_script,
+        executor,
+        executor_args,
+        sandbox,
+        sandbox_network,
+        prompt_template,
+        instructions,
+        test_script,
+        max_fix_iterations,
+        max_concurrent_agents,
     )
     .await
     {
@@ -267,6 +355,31 @@ pub async fn update_project(
     }
 }
 
+/// Reject executor names that `ExecutorConfig` cannot parse; blank means "use the global default"
+fn validate_executor(executor: Option<&str>) -> Result<(), String> {
+    match executor.map(str::trim) {
+        Some(name) if !name.is_empty() => name.parse::<ExecutorConfig>().map(|_| ()),
+        _ => Ok(()),
+    }
+}
+
+fn validate_max_fix_iterations(max_fix_iterations: Option<i64>) -> Result<(), String> {
+    match max_fix_iterations {
+        Some(n) if !(0..=MAX_FIX_ITERATIONS).contains(&n) => Err(format!(
+            "Fix iterations must be between 0 and {}",
+            MAX_FIX_ITERATIONS
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn validate_max_concurrent_agents(max_concurrent_agents: Option<i64>) -> Result<(), String> {
+    match max_concurrent_agents {
+        Some(n) if n < 0 => Err("Concurrent agents can't be negative".to_string()),
+        _ => Ok(()),
+    }
+}
+
 pub async fn delete_project(
     Extension(project): Extension<Project>,
     State(app_state): State<AppState>,
@@ -476,6 +589,96 @@ async fn search_files_in_repo(
     Ok(results)
 }
 
+pub async fn get_project_usage(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<UsageTotals>>, StatusCode> {
+    match ExecutionUsage::totals_for_project(&app_state.db_pool, project.id).await {
+        Ok(totals) => Ok(ResponseJson(ApiResponse::success(totals))),
+        Err(e) => {
+            tracing::error!("Failed to fetch usage for project {}: {}", project.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn get_project_tool_allowlist(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<ToolAllowlist>>, StatusCode> {
+    match ToolAllowlist::find_for_project(&app_state.db_pool, project.id).await {
+        Ok(allowlist) => Ok(ResponseJson(ApiResponse::success(allowlist))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch tool allowlist for project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn update_project_tool_allowlist(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<ToolAllowlist>,
+) -> Result<ResponseJson<ApiResponse<ToolAllowlist>>, StatusCode> {
+    match ToolAllowlist::replace_for_project(&app_state.db_pool, project.id, &payload).await {
+        Ok(allowlist) => Ok(ResponseJson(ApiResponse::success(allowlist))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to update tool allowlist for project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn get_project_env_vars(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectEnvVar>>>, StatusCode> {
+    match ProjectEnvVar::find_for_project(&app_state.db_pool, project.id).await {
+        Ok(vars) => Ok(ResponseJson(ApiResponse::success(
+            vars.into_iter().map(ProjectEnvVar::redacted).collect(),
+        ))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch environment variables for project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn update_project_env_vars(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<Vec<ProjectEnvVar>>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectEnvVar>>>, StatusCode> {
+    match ProjectEnvVar::replace_for_project(&app_state.db_pool, project.id, &payload).await {
+        Ok(vars) => Ok(ResponseJson(ApiResponse::success(
+            vars.into_iter().map(ProjectEnvVar::redacted).collect(),
+        ))),
+        Err(ProjectEnvVarError::Validation(message)) => {
+            Ok(ResponseJson(ApiResponse::error(&message)))
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to update environment variables for project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
 pub fn projects_base_router() -> Router<AppState> {
     Router::new().route("/projects", get(get_projects).post(create_project))
 }
@@ -494,5 +697,14 @@ pub fn projects_with_id_router() -> Router<AppState> {
             get(get_project_branches).post(create_project_branch),
         )
         .route("/projects/:id/search", get(search_project_files))
+        .route("/projects/:id/usage", get(get_project_usage))
+        .route(
+            "/projects/:id/tool-allowlist",
+            get(get_project_tool_allowlist).put(update_project_tool_allowlist),
+        )
+        .route(
+            "/projects/:id/env",
+            get(get_project_env_vars).put(update_project_env_vars),
+        )
         .route("/projects/:id/open-editor", post(open_project_in_editor))
 }
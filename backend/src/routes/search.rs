@@ -0,0 +1,35 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+    routing::get,
+    Router,
+};
+
+use crate::{
+    app_state::AppState,
+    models::{
+        search::{SearchHit, SearchQuery},
+        ApiResponse,
+    },
+};
+
+/// Search tasks, agent prompts and summaries, and conversations
+///
+/// GET /api/search?q=migration&project_id=..&executor=claude&status=done&from=..&to=..
+pub async fn search(
+    Query(query): Query<SearchQuery>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<SearchHit>>>, StatusCode> {
+    match SearchHit::search(&app_state.db_pool, &query).await {
+        Ok(hits) => Ok(ResponseJson(ApiResponse::success(hits))),
+        Err(e) => {
+            tracing::error!("Failed to search for '{}': {}", query.q, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub fn search_router() -> Router<AppState> {
+    Router::new().route("/search", get(search))
+}
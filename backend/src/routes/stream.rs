@@ -9,15 +9,25 @@ use axum::{
 use futures_util::stream::Stream;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sqlx::SqlitePool;
+use tokio::sync::broadcast::{self, error::TryRecvError};
 use uuid::Uuid;
 
 use crate::{
     app_state::AppState,
-    executors::gemini::GeminiExecutor,
-    models::execution_process::{ExecutionProcess, ExecutionProcessStatus},
+    executors::streaming::LogStreaming,
+    models::{
+        config::Config,
+        execution_output::{OutputReader, OutputStream},
+        execution_process::{ExecutionProcess, ExecutionProcessStatus},
+    },
+    services::event_bus::{BoardEvent, ProjectEvent},
 };
 
-/// Interval for DB tail polling (ms) - now blazing fast for real-time updates
+/// Interval for WAL polling (ms)
+const WAL_INTERVAL_MS: u64 = 50;
+
+/// Interval for DB tail polling (ms), used when a process has no WAL
 const TAIL_INTERVAL_MS: u64 = 100;
 
 /// Structured batch data for SSE streaming
@@ -42,49 +52,48 @@ pub async fn normalized_logs_stream(
     Query(query): Query<StreamQuery>,
     State(app_state): State<AppState>,
 ) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
-    // Check if this is a Gemini executor (only executor with streaming support)
-    let is_gemini = match ExecutionProcess::find_by_id(&app_state.db_pool, process_id).await {
-        Ok(Some(process)) => process.executor_type.as_deref() == Some("gemini"),
-        _ => {
-            tracing::warn!(
-                "Failed to find execution process {} for SSE streaming",
-                process_id
-            );
-            false
-        }
+    // Coding agents started by this server push patches to the WAL as output arrives.
+    // Processes without one (e.g. started before a restart) fall back to DB polling.
+    let has_wal = LogStreaming::has_wal(process_id);
+    let poll_interval = if has_wal {
+        WAL_INTERVAL_MS
+    } else {
+        TAIL_INTERVAL_MS
     };
 
-    // Use blazing fast polling interval for Gemini (only streaming executor)
-    let poll_interval = if is_gemini { 50 } else { TAIL_INTERVAL_MS };
+    // Subscribed before the process is loaded, so its completion can't slip in between
+    let (_, mut events) = app_state.events.subscribe(None);
 
     // Stream that yields patches from WAL (fast-path) or DB tail (fallback)
     let stream = async_stream::stream! {
-        // Track previous stdout length and entry count for database polling fallback
-        let mut last_len: usize = 0;
-        let mut last_entry_count: usize = query.since_batch_id.unwrap_or(1) as usize;
         let mut interval = tokio::time::interval(Duration::from_millis(poll_interval));
         let mut last_seen_batch_id: u64 = query.since_batch_id.unwrap_or(0); // Cursor for WAL streaming
 
-        // Monotonic batch ID for fallback polling (always start at 1)
-        let since = query.since_batch_id.unwrap_or(1);
-        let mut fallback_batch_id: u64 = since + 1;
+        // Stdout read so far by the database polling fallback
+        let mut stdout = OutputReader::new(process_id, OutputStream::Stdout);
+
+        let process = match ExecutionProcess::find_by_id(&app_state.db_pool, process_id).await {
+            Ok(Some(proc)) => proc,
+            _ => {
+                tracing::warn!("Execution process {} not found during SSE streaming", process_id);
+                return;
+            }
+        };
+        let mut completed = process.status != ExecutionProcessStatus::Running;
+        let config = app_state.get_config().read().await.clone();
 
         loop {
             interval.tick().await;
 
-            // Check process status first
-            let process_status = match ExecutionProcess::find_by_id(&app_state.db_pool, process_id).await {
-                Ok(Some(proc)) => proc.status,
-                _ => {
-                    tracing::warn!("Execution process {} not found during SSE streaming", process_id);
-                    break;
-                }
-            };
+            // Checked before reading, so the last read has everything the process wrote
+            if !completed {
+                completed = process_completed(&app_state.db_pool, &mut events, process_id).await;
+            }
 
-            if is_gemini {
-                // Gemini streaming: Read from Gemini WAL using cursor
+            if has_wal {
+                // Read from the WAL using cursor
                 let cursor = if last_seen_batch_id == 0 { None } else { Some(last_seen_batch_id) };
-                if let Some(new_batches) = GeminiExecutor::get_wal_batches(process_id, cursor) {
+                if let Some(new_batches) = LogStreaming::get_wal_batches(process_id, cursor) {
                     // Send any new batches since last cursor
                     for batch in &new_batches {
                         // Send full batch including batch_id for cursor tracking
@@ -98,93 +107,13 @@ pub async fn normalized_logs_stream(
                         last_seen_batch_id = batch.batch_id.max(last_seen_batch_id);
                     }
                 }
-            } else {
-                // Fallback: Database polling for non-streaming executors
-                // 1. Load the process
-                    let proc = match ExecutionProcess::find_by_id(&app_state.db_pool, process_id)
-                    .await
-                    .ok()
-                    .flatten()
-                {
-                    Some(p) => p,
-                    None => {
-                        tracing::warn!("Execution process {} not found during SSE polling", process_id);
-                        continue;
-                    }
-                };
-
-                // 2. Grab the stdout and check if there's new content
-                let stdout = match proc.stdout {
-                    Some(ref s) if s.len() > last_len && !s[last_len..].trim().is_empty() => s.clone(),
-                    _ => continue, // no new output
-                };
-
-                // 3. Instantiate the right executor
-                let executor = match proc.executor_type
-                    .as_deref()
-                    .unwrap_or("unknown")
-                    .parse::<crate::executor::ExecutorConfig>()
-                    .ok()
-                    .map(|cfg| cfg.create_executor())
-                {
-                    Some(exec) => exec,
-                    None => {
-                        tracing::warn!(
-                            "Unknown executor '{}' for process {}",
-                            proc.executor_type.unwrap_or_default(),
-                            process_id
-                        );
-                        continue;
-                    }
-                };
-
-                // 4. Normalize logs
-                let normalized = match executor.normalize_logs(&stdout, &proc.working_directory) {
-                    Ok(norm) => norm,
-                    Err(err) => {
-                        tracing::error!(
-                            "Failed to normalize logs for process {}: {}",
-                            process_id,
-                            err
-                        );
-                        continue;
-                    }
-                };
-
-                if last_entry_count > normalized.entries.len() {
-                    continue;
-                }
-
-                // 5. Compute patches for any new entries
-                let new_entries = [&normalized.entries[last_entry_count - 1]];
-                if new_entries.is_empty() {
-                    continue;
-                }
-                let patches: Vec<Value> = new_entries
-                    .iter()
-                    .map(|entry| serde_json::json!({
-                        "op": "add",
-                        "path": "/entries/-",
-                        "value": entry
-                    }))
-                    .collect();
-
-                // 6. Emit the batch
-                let batch_data = BatchData {
-                    batch_id: fallback_batch_id - 1,
-                    patches,
-                };
+            } else if let Some(batch_data) = poll_db_snapshot(&app_state.db_pool, &config, &process, &mut stdout, &mut last_seen_batch_id).await {
                 let json = serde_json::to_string(&batch_data).unwrap_or_default();
                 yield Ok(Event::default().event("patch").data(json));
-
-                // 7. Update our cursors
-                fallback_batch_id += 1;
-                last_entry_count += 1;
-                last_len = stdout.len();
             }
 
             // Stop streaming when process completed
-            if process_status != ExecutionProcessStatus::Running {
+            if completed {
                 break;
             }
         }
@@ -193,6 +122,100 @@ pub async fn normalized_logs_stream(
     Sse::new(stream).keep_alive(axum::response::sse::KeepAlive::default())
 }
 
+/// Whether the board events published since the last check include the process completing.
+/// A subscriber that fell behind can't tell, so it asks the database instead.
+async fn process_completed(
+    pool: &SqlitePool,
+    events: &mut broadcast::Receiver<ProjectEvent>,
+    process_id: Uuid,
+) -> bool {
+    loop {
+        match events.try_recv() {
+            Ok(ProjectEvent {
+                event: BoardEvent::ProcessCompleted { process_id: id, .. },
+                ..
+            }) if id == process_id => return true,
+            Ok(_) => {}
+            Err(TryRecvError::Empty) => return false,
+            Err(TryRecvError::Lagged(_)) => {
+                return !matches!(
+                    ExecutionProcess::find_by_id(pool, process_id).await,
+                    Ok(Some(ExecutionProcess {
+                        status: ExecutionProcessStatus::Running,
+                        ..
+                    }))
+                );
+            }
+            Err(TryRecvError::Closed) => return true,
+        }
+    }
+}
+
+/// Fallback for processes without a WAL: re-normalize stdout when it grows and replace the
+/// whole entries array, which is safe to apply on top of whatever the client already has
+async fn poll_db_snapshot(
+    pool: &SqlitePool,
+    config: &Config,
+    process: &ExecutionProcess,
+    stdout: &mut OutputReader,
+    last_batch_id: &mut u64,
+) -> Option<BatchData> {
+    // 1. Read only the stdout appended since the last poll
+    match stdout.read_new(pool).await {
+        Ok(true) => {}
+        Ok(false) => return None, // no new output
+        Err(err) => {
+            tracing::error!("Failed to read stdout of process {}: {}", process.id, err);
+            return None;
+        }
+    }
+
+    // 2. Instantiate the right executor
+    let executor = match process
+        .executor_type
+        .as_deref()
+        .unwrap_or("unknown")
+        .parse::<crate::executor::ExecutorConfig>()
+        .ok()
+        .map(|executor| executor.create_executor(config))
+    {
+        Some(exec) => exec,
+        None => {
+            tracing::warn!(
+                "Unknown executor '{}' for process {}",
+                process.executor_type.clone().unwrap_or_default(),
+                process.id
+            );
+            return None;
+        }
+    };
+
+    // 3. Normalize logs
+    let normalized =
+        match executor.normalize_timed_logs(stdout.output(), &process.working_directory) {
+            Ok(norm) => norm,
+            Err(err) => {
+                tracing::error!(
+                    "Failed to normalize logs for process {}: {}",
+                    process.id,
+                    err
+                );
+                return None;
+            }
+        };
+
+    // 4. Emit a snapshot batch and update our cursor
+    *last_batch_id += 1;
+    Some(BatchData {
+        batch_id: *last_batch_id,
+        patches: vec![serde_json::json!({
+            "op": "replace",
+            "path": "/entries",
+            "value": normalized.entries,
+        })],
+    })
+}
+
 /// Router exposing `/normalized-logs/stream`
 pub fn stream_router() -> Router<AppState> {
     Router::new().route(
@@ -1,8 +1,9 @@
 use axum::{
-    extract::{Query, State},
-    http::StatusCode,
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
     middleware::from_fn_with_state,
-    response::Json as ResponseJson,
+    response::{Json as ResponseJson, Response},
     routing::get,
     Extension, Json, Router,
 };
@@ -13,23 +14,40 @@ use uuid::Uuid;
 
 use crate::{
     app_state::AppState,
+    execution_monitor::cleanup_attempt_worktree,
     executor::{
-        ActionType, ExecutorConfig, NormalizedConversation, NormalizedEntry, NormalizedEntryType,
+        fill_durations, format_timestamp, ExecutorConfig, NormalizedConversation, NormalizedEntry,
+        NormalizedEntryType, PlanModeSupport,
     },
+    executors::input::ExecutionInputError,
     middleware::{load_execution_process_with_context_middleware, load_task_attempt_middleware},
     models::{
         config::Config,
+        execution_output::{ExecutionOutput, OutputRange, OutputStream, STDERR_CHUNK_BOUNDARY},
         execution_process::{
-            ExecutionProcess, ExecutionProcessStatus, ExecutionProcessSummary, ExecutionProcessType,
+            ExecutionProcess, ExecutionProcessStatus, ExecutionProcessSummary,
+            ExecutionProcessType, SendExecutionInput,
         },
+        execution_usage::{ExecutionUsage, UsageTotals},
+        fan_out::{create_and_start_each, CreateFanOut, FanOutComparison, MAX_FAN_OUT_ATTEMPTS},
+        plan_presentation::{PlanAuthor, PlanPresentation, UpdatePlanPresentation},
         project::Project,
+        project_env_var::ProjectEnvVar,
+        queued_attempt::QueuedAttempt,
         task::{Task, TaskStatus},
         task_attempt::{
             BranchStatus, CreateFollowUpAttempt, CreatePrParams, CreateTaskAttempt, TaskAttempt,
             TaskAttemptState, WorktreeDiff,
         },
+        tool_approval::{RespondToolApproval, ToolApproval, ToolApprovalStatus},
+        verification_run::VerificationRun,
         ApiResponse,
     },
+    services::{
+        transcript::{Redactor, Transcript, TranscriptFormat, TranscriptProcess},
+        BoardEvent, ProcessService,
+    },
+    utils::text::{git_branch_id, short_uuid},
 };
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -62,10 +80,13 @@ pub struct ProcessLogsResponse {
     pub normalized_conversation: NormalizedConversation,
 }
 
-// Helper to normalize logs for a process (extracted from get_execution_process_normalized_logs)
+// Helper to normalize logs for a process (extracted from get_execution_process_normalized_logs).
+// `polled` keeps a running process's output read so far, for logs that are read repeatedly.
 async fn normalize_process_logs(
     db_pool: &SqlitePool,
+    config: &Config,
     process: &ExecutionProcess,
+    polled: bool,
 ) -> NormalizedConversation {
     use crate::models::{
         execution_process::ExecutionProcessType, executor_session::ExecutorSession,
@@ -75,16 +96,19 @@ async fn normalize_process_logs(
         .ok()
         .flatten();
 
-    let has_stdout = process
-        .stdout
-        .as_ref()
-        .map(|s| !s.trim().is_empty())
-        .unwrap_or(false);
-    let has_stderr = process
-        .stderr
-        .as_ref()
-        .map(|s| !s.trim().is_empty())
-        .unwrap_or(false);
+    // Logs are polled while the process runs, so only read the output appended since
+    let running = process.status == ExecutionProcessStatus::Running;
+    let read = |stream| async move {
+        if polled {
+            ExecutionOutput::read_polled(db_pool, process.id, stream, running).await
+        } else {
+            ExecutionOutput::read_timed(db_pool, process.id, stream).await
+        }
+    };
+    let stdout = read(OutputStream::Stdout).await.unwrap_or_default();
+    let stderr = read(OutputStream::Stderr).await.unwrap_or_default();
+    let has_stdout = !stdout.content.trim().is_empty();
+    let has_stderr = !stderr.content.trim().is_empty();
 
     if !has_stdout && !has_stderr {
         return NormalizedConversation {
@@ -99,61 +123,58 @@ async fn normalize_process_logs(
         };
     }
 
-    // Parse stdout as JSONL using executor normalization
+    // Parse stdout as JSONL using executor normalization, timing entries by their lines
     let mut stdout_entries = Vec::new();
-    if let Some(stdout) = &process.stdout {
-        if !stdout.trim().is_empty() {
-            let executor_type = process.executor_type.as_deref().unwrap_or("unknown");
-            let executor_config = if process.process_type == ExecutionProcessType::SetupScript {
-                ExecutorConfig::SetupScript {
-                    script: executor_session
-                        .as_ref()
-                        .and_then(|s| s.prompt.clone())
-                        .unwrap_or_else(|| "setup script".to_string()),
-                }
-            } else {
-                match executor_type.to_string().parse() {
-                    Ok(config) => config,
-                    Err(_) => {
-                        return NormalizedConversation {
-                            entries: vec![],
-                            session_id: None,
-                            executor_type: executor_type.to_string(),
-                            prompt: executor_session.as_ref().and_then(|s| s.prompt.clone()),
-                            summary: executor_session.as_ref().and_then(|s| s.summary.clone()),
-                        };
-                    }
+    if has_stdout {
+        let executor_type = process.executor_type.as_deref().unwrap_or("unknown");
+        let executor_config = if process.process_type == ExecutionProcessType::SetupScript {
+            ExecutorConfig::SetupScript {
+                script: executor_session
+                    .as_ref()
+                    .and_then(|s| s.prompt.clone())
+                    .unwrap_or_else(|| "setup script".to_string()),
+            }
+        } else {
+            match executor_type.to_string().parse() {
+                Ok(config) => config,
+                Err(_) => {
+                    return NormalizedConversation {
+                        entries: vec![],
+                        session_id: None,
+                        executor_type: executor_type.to_string(),
+                        prompt: executor_session.as_ref().and_then(|s| s.prompt.clone()),
+                        summary: executor_session.as_ref().and_then(|s| s.summary.clone()),
+                    };
                 }
-            };
-            let executor = executor_config.create_executor();
-            let working_dir_path = match std::fs::canonicalize(&process.working_directory) {
-                Ok(canonical_path) => canonical_path.to_string_lossy().to_string(),
-                Err(_) => process.working_directory.clone(),
-            };
-            if let Ok(normalized) = executor.normalize_logs(stdout, &working_dir_path) {
-                stdout_entries = normalized.entries;
             }
+        };
+        let executor = executor_config.create_executor(config);
+        let working_dir_path = match std::fs::canonicalize(&process.working_directory) {
+            Ok(canonical_path) => canonical_path.to_string_lossy().to_string(),
+            Err(_) => process.working_directory.clone(),
+        };
+        if let Ok(normalized) = executor.normalize_timed_logs(&stdout, &working_dir_path) {
+            stdout_entries = normalized.entries;
         }
     }
-    // Parse stderr chunks separated by boundary markers
+    // Parse stderr chunks separated by boundary markers, each timed by its first line
     let mut stderr_entries = Vec::new();
-    if let Some(stderr) = &process.stderr {
-        let trimmed = stderr.trim();
-        if !trimmed.is_empty() {
-            let chunks: Vec<&str> = trimmed.split("---STDERR_CHUNK_BOUNDARY---").collect();
-            for chunk in chunks {
-                let chunk_trimmed = chunk.trim();
-                if !chunk_trimmed.is_empty() {
-                    let filtered_content = chunk_trimmed.replace("---STDERR_CHUNK_BOUNDARY---", "");
-                    if !filtered_content.trim().is_empty() {
-                        stderr_entries.push(NormalizedEntry {
-                            timestamp: Some(chrono::Utc::now().to_rfc3339()),
-                            entry_type: NormalizedEntryType::ErrorMessage,
-                            content: filtered_content.trim().to_string(),
-                            metadata: None,
-                        });
-                    }
-                }
+    if has_stderr {
+        let mut position = 0;
+        for chunk in stderr.content.split(STDERR_CHUNK_BOUNDARY) {
+            let chunk_start = position;
+            position += chunk.len() + STDERR_CHUNK_BOUNDARY.len();
+            let chunk_trimmed = chunk.trim();
+            if !chunk_trimmed.is_empty() {
+                let leading = chunk.len() - chunk.trim_start().len();
+                stderr_entries.push(NormalizedEntry {
+                    timestamp: stderr.time_at(chunk_start + leading).map(format_timestamp),
+                    entry_type: NormalizedEntryType::ErrorMessage,
+                    content: chunk_trimmed.to_string(),
+                    metadata: None,
+                    duration_ms: None,
+                    tool_duration_ms: None,
+                });
             }
         }
     }
@@ -166,6 +187,8 @@ async fn normalize_process_logs(
         (None, Some(_)) => std::cmp::Ordering::Greater,
         (None, None) => std::cmp::Ordering::Equal,
     });
+    // Stderr interleaved with stdout changes the time since the previous entry
+    fill_durations(&mut all_entries);
     let executor_type = if process.process_type == ExecutionProcessType::SetupScript {
         "setup-script".to_string()
     } else {
@@ -201,9 +224,11 @@ pub async fn get_task_attempt_all_logs(
         Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
     };
     // For each process, normalize logs
+    let config = app_state.get_config().read().await.clone();
     let mut result = Vec::new();
     for process in processes {
-        let normalized_conversation = normalize_process_logs(&app_state.db_pool, &process).await;
+        let normalized_conversation =
+            normalize_process_logs(&app_state.db_pool, &config, &process, true).await;
         result.push(ProcessLogsResponse {
             id: process.id,
             process_type: process.process_type.clone(),
@@ -216,6 +241,106 @@ pub async fn get_task_attempt_all_logs(
     Ok(Json(ApiResponse::success(result)))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct TranscriptQuery {
+    #[serde(default)]
+    format: TranscriptFormat,
+    /// Replace the project's secrets, well-known tokens and the worktree path
+    #[serde(default)]
+    redact: bool,
+}
+
+/// Export everything an attempt did as a Markdown, HTML or JSON file
+pub async fn export_task_attempt_transcript(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    Query(query): Query<TranscriptQuery>,
+    State(app_state): State<AppState>,
+) -> Result<Response, StatusCode> {
+    let pool = &app_state.db_pool;
+    let processes = ExecutionProcess::find_by_task_attempt_id(pool, task_attempt.id)
+        .await
+        .map_err(|e| {
+            tracing::error!(
+                "Failed to fetch execution processes for attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let config = app_state.get_config().read().await.clone();
+    let mut transcript_processes = Vec::new();
+    for process in &processes {
+        if process.process_type == ExecutionProcessType::CodingAgent {
+            // Exported once, so not worth keeping in the polling cache
+            let conversation = normalize_process_logs(pool, &config, process, false).await;
+            transcript_processes.push(TranscriptProcess::coding_agent(process, conversation));
+            continue;
+        }
+        let mut output = String::new();
+        for stream in [OutputStream::Stdout, OutputStream::Stderr] {
+            match ExecutionOutput::read(pool, process.id, stream, 0).await {
+                Ok(range) => output.push_str(&range.content),
+                Err(e) => {
+                    tracing::error!("Failed to read output of process {}: {}", process.id, e);
+                    return Err(StatusCode::INTERNAL_SERVER_ERROR);
+                }
+            }
+        }
+        transcript_processes.push(TranscriptProcess::script(process, output));
+    }
+
+    // Attempts whose worktree is gone are still worth exporting without their diff
+    let diff = match TaskAttempt::get_diff(pool, task_attempt.id, task.id, project.id).await {
+        Ok(diff) => Some(diff),
+        Err(e) => {
+            tracing::warn!(
+                "Exporting attempt {} without its diff: {}",
+                task_attempt.id,
+                e
+            );
+            None
+        }
+    };
+
+    let mut transcript = Transcript::new(
+        &project,
+        &task,
+        &task_attempt,
+        transcript_processes,
+        diff.as_ref(),
+    );
+    if query.redact {
+        let environment = ProjectEnvVar::environment_for_project(pool, project.id)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to load secrets of project {}: {}", project.id, e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+        transcript.redact(&Redactor::new(
+            environment.secrets,
+            &task_attempt.worktree_path,
+        ));
+    }
+
+    let filename = format!(
+        "transcript-{}-{}.{}",
+        git_branch_id(&task.title),
+        short_uuid(&task_attempt.id),
+        query.format.extension()
+    );
+    Response::builder()
+        .header(header::CONTENT_TYPE, query.format.content_type())
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{filename}\""),
+        )
+        .body(Body::from(transcript.render(query.format)))
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
 pub async fn get_task_attempts(
     Extension(_project): Extension<Project>,
     Extension(task): Extension<Task>,
@@ -230,6 +355,16 @@ pub async fn get_task_attempts(
     }
 }
 
+/// Reject plan mode executors whose agent can't plan
+fn validate_plan_mode(executor: &ExecutorConfig, config: &Config) -> Result<(), String> {
+    if executor.is_plan_mode()
+        && executor.create_executor(config).plan_mode_support() == PlanModeSupport::Unsupported
+    {
+        return Err(format!("Executor {} doesn't support plan mode", executor));
+    }
+    Ok(())
+}
+
 pub async fn create_task_attempt(
     Extension(_project): Extension<Project>,
     Extension(task): Extension<Task>,
@@ -238,7 +373,17 @@ pub async fn create_task_attempt(
 ) -> Result<ResponseJson<ApiResponse<TaskAttempt>>, StatusCode> {
     let executor_string = payload.executor.as_ref().map(|exec| exec.to_string());
 
-    match TaskAttempt::create(&app_state.db_pool, &payload, task.id).await {
+    let config = app_state.get_config().read().await.clone();
+    if let Some(Err(e)) = payload
+        .executor
+        .as_deref()
+        .and_then(|exec| exec.parse::<ExecutorConfig>().ok())
+        .map(|exec| validate_plan_mode(&exec, &config))
+    {
+        return Ok(ResponseJson(ApiResponse::error(&e)));
+    }
+
+    match TaskAttempt::create(&app_state.db_pool, &payload, task.id, None).await {
         Ok(attempt) => {
             app_state
                 .track_analytics_event(
@@ -256,13 +401,14 @@ pub async fn create_task_attempt(
             let attempt_id = attempt.id;
             let task_id = task.id;
             let project_id = _project.id;
+            let priority = payload.priority.unwrap_or_default();
             tokio::spawn(async move {
                 if let Err(e) = TaskAttempt::start_execution(
-                    &app_state_clone.db_pool,
                     &app_state_clone,
                     attempt_id,
                     task_id,
                     project_id,
+                    priority,
                 )
                 .await
                 {
@@ -283,6 +429,219 @@ pub async fn create_task_attempt(
     }
 }
 
+/// Start one attempt per requested executor so they race on the task
+pub async fn create_fan_out(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<CreateFanOut>,
+) -> Result<ResponseJson<ApiResponse<FanOutComparison>>, StatusCode> {
+    if payload.executors.is_empty() || payload.executors.len() > MAX_FAN_OUT_ATTEMPTS {
+        return Ok(ResponseJson(ApiResponse::error(&format!(
+            "A fan-out starts between 1 and {} attempts",
+            MAX_FAN_OUT_ATTEMPTS
+        ))));
+    }
+    let config = app_state.get_config().read().await.clone();
+    for executor in &payload.executors {
+        // Falling back to the default executor would make the comparison misleading
+        let executor = match executor.parse::<ExecutorConfig>() {
+            Ok(executor) => executor,
+            Err(e) => return Ok(ResponseJson(ApiResponse::error(&e))),
+        };
+        if let Err(e) = validate_plan_mode(&executor, &config) {
+            return Ok(ResponseJson(ApiResponse::error(&e)));
+        }
+    }
+
+    let fan_out_id = Uuid::new_v4();
+    let priority = payload.priority.unwrap_or_default();
+    let created =
+        create_and_start_each(
+            &payload.executors,
+            |executor| {
+                let create_attempt = CreateTaskAttempt {
+                    executor: Some(executor.to_string()),
+                    base_branch: payload.base_branch.clone(),
+                    priority: payload.priority,
+                };
+                let pool = app_state.db_pool.clone();
+                let task_id = task.id;
+                async move {
+                    TaskAttempt::create(&pool, &create_attempt, task_id, Some(fan_out_id)).await
+                }
+            },
+            |attempt| {
+                // Start execution asynchronously (don't block the response)
+                let app_state = app_state.clone();
+                let attempt_id = attempt.id;
+                let executor = attempt.executor.clone();
+                let task_id = task.id;
+                let project_id = project.id;
+                tokio::spawn(async move {
+                    app_state
+                        .track_analytics_event(
+                            "task_attempt_started",
+                            Some(serde_json::json!({
+                                "task_id": task_id.to_string(),
+                                "executor_type": executor.as_deref().unwrap_or("default"),
+                                "attempt_id": attempt_id.to_string(),
+                                "fan_out_id": fan_out_id.to_string(),
+                            })),
+                        )
+                        .await;
+                    if let Err(e) = TaskAttempt::start_execution(
+                        &app_state, attempt_id, task_id, project_id, priority,
+                    )
+                    .await
+                    {
+                        tracing::error!(
+                            "Failed to start execution for task attempt {}: {}",
+                            attempt_id,
+                            e
+                        );
+                    }
+                });
+            },
+        )
+        .await;
+    if let Err(e) = created {
+        tracing::error!("Failed to create attempt for fan-out {}: {}", fan_out_id, e);
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    match FanOutComparison::load(&app_state.db_pool, task.id, project.id, fan_out_id).await {
+        Ok(comparison) => Ok(ResponseJson(ApiResponse::success(comparison))),
+        Err(e) => {
+            tracing::error!("Failed to compare fan-out {}: {}", fan_out_id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Compare the attempts of the fan-out this attempt belongs to
+pub async fn get_fan_out_comparison(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<FanOutComparison>>, StatusCode> {
+    let Some(fan_out_id) = task_attempt.fan_out_id else {
+        return Ok(ResponseJson(ApiResponse::error(
+            "This attempt wasn't started by a fan-out",
+        )));
+    };
+
+    match FanOutComparison::load(&app_state.db_pool, task.id, project.id, fan_out_id).await {
+        Ok(comparison) => Ok(ResponseJson(ApiResponse::success(comparison))),
+        Err(e) => {
+            tracing::error!("Failed to compare fan-out {}: {}", fan_out_id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Merge this attempt as the winner of its fan-out, then stop the others and clean up their
+/// worktrees. Their branches are kept.
+pub async fn pick_fan_out_winner(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<FanOutComparison>>, StatusCode> {
+    let pool = &app_state.db_pool;
+    let Some(fan_out_id) = task_attempt.fan_out_id else {
+        return Ok(ResponseJson(ApiResponse::error(
+            "This attempt wasn't started by a fan-out",
+        )));
+    };
+    let attempts = match TaskAttempt::find_by_fan_out_id(pool, task.id, fan_out_id).await {
+        Ok(attempts) => attempts,
+        Err(e) => {
+            tracing::error!("Failed to fetch attempts of fan-out {}: {}", fan_out_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    if attempts
+        .iter()
+        .any(|attempt| attempt.merge_commit.is_some())
+    {
+        return Ok(ResponseJson(ApiResponse::error(
+            "A winner was already merged for this fan-out",
+        )));
+    }
+
+    // Nothing may keep writing to the worktrees while the winner is merged
+    for attempt in &attempts {
+        match stop_attempt_processes(&app_state, attempt.id).await {
+            Ok((_, errors)) if errors.is_empty() => {}
+            Ok((_, errors)) => {
+                return Ok(ResponseJson(ApiResponse::error(&format!(
+                    "Failed to stop attempt {}: {}",
+                    attempt.id,
+                    errors.join(", ")
+                ))));
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Failed to fetch execution processes for attempt {}: {}",
+                    attempt.id,
+                    e
+                );
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        }
+    }
+
+    if let Err(e) = TaskAttempt::merge_changes(pool, task_attempt.id, task.id, project.id).await {
+        tracing::error!("Failed to merge task attempt {}: {}", task_attempt.id, e);
+        return Ok(ResponseJson(ApiResponse::error(&format!(
+            "Failed to merge: {}",
+            e
+        ))));
+    }
+    if let Err(e) = app_state
+        .set_task_status(task.id, project.id, TaskStatus::Done)
+        .await
+    {
+        tracing::error!("Failed to update task status to Done after merge: {}", e);
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    for attempt in &attempts {
+        if attempt.id != task_attempt.id && !attempt.worktree_deleted {
+            cleanup_attempt_worktree(
+                &app_state,
+                attempt.id,
+                &attempt.worktree_path,
+                &project.git_repo_path,
+            )
+            .await;
+        }
+    }
+
+    app_state
+        .track_analytics_event(
+            "task_attempt_merged",
+            Some(serde_json::json!({
+                "task_id": task.id.to_string(),
+                "project_id": project.id.to_string(),
+                "attempt_id": task_attempt.id.to_string(),
+                "fan_out_id": fan_out_id.to_string(),
+                "fan_out_attempts": attempts.len(),
+            })),
+        )
+        .await;
+
+    match FanOutComparison::load(pool, task.id, project.id, fan_out_id).await {
+        Ok(comparison) => Ok(ResponseJson(ApiResponse::success(comparison))),
+        Err(e) => {
+            tracing::error!("Failed to compare fan-out {}: {}", fan_out_id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
 pub async fn get_task_attempt_diff(
     Extension(project): Extension<Project>,
     Extension(task): Extension<Task>,
@@ -313,13 +672,9 @@ pub async fn merge_task_attempt(
     {
         Ok(_) => {
             // Update task status to Done
-            if let Err(e) = Task::update_status(
-                &app_state.db_pool,
-                task.id,
-                project.id,
-                crate::models::task::TaskStatus::Done,
-            )
-            .await
+            if let Err(e) = app_state
+                .set_task_status(task.id, project.id, TaskStatus::Done)
+                .await
             {
                 tracing::error!("Failed to update task status to Done after merge: {}", e);
                 return Err(StatusCode::INTERNAL_SERVER_ERROR);
@@ -604,34 +959,61 @@ pub async fn get_execution_process(
     Ok(ResponseJson(ApiResponse::success(execution_process)))
 }
 
-#[axum::debug_handler]
-pub async fn stop_all_execution_processes(
-    Extension(_project): Extension<Project>,
-    Extension(_task): Extension<Task>,
-    Extension(task_attempt): Extension<TaskAttempt>,
+#[derive(Debug, Deserialize)]
+pub struct ProcessOutputQuery {
+    stream: OutputStream,
+    /// Byte offset to read from, the `next_offset` of the previous read
+    #[serde(default)]
+    offset: usize,
+}
+
+/// Output of an execution process from an offset on, so clients polling a running process
+/// only fetch what was added since their last read
+pub async fn get_execution_process_output(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    Query(query): Query<ProcessOutputQuery>,
     State(app_state): State<AppState>,
-) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
-    // Get all execution processes for the task attempt
-    let processes = match ExecutionProcess::find_by_task_attempt_id(
+) -> Result<ResponseJson<ApiResponse<OutputRange>>, StatusCode> {
+    match ExecutionOutput::read(
         &app_state.db_pool,
-        task_attempt.id,
+        execution_process.id,
+        query.stream,
+        query.offset,
     )
     .await
     {
-        Ok(processes) => processes,
+        Ok(range) => Ok(ResponseJson(ApiResponse::success(range))),
         Err(e) => {
             tracing::error!(
-                "Failed to fetch execution processes for attempt {}: {}",
-                task_attempt.id,
+                "Failed to read output of execution process {}: {}",
+                execution_process.id,
                 e
             );
-            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
-    };
+    }
+}
 
+/// Stop all running processes of an attempt, or take it out of the execution queue if it
+/// hasn't started yet.
+///
+/// Returns how many processes were stopped and the errors hit on the way.
+async fn stop_attempt_processes(
+    app_state: &AppState,
+    attempt_id: Uuid,
+) -> Result<(usize, Vec<String>), sqlx::Error> {
     let mut stopped_count = 0;
     let mut errors = Vec::new();
 
+    if QueuedAttempt::remove(&app_state.db_pool, attempt_id).await? {
+        stopped_count += 1;
+    }
+
+    // Get all execution processes for the task attempt
+    let processes =
+        ExecutionProcess::find_by_task_attempt_id(&app_state.db_pool, attempt_id).await?;
+    let config = app_state.get_config().read().await.clone();
+
     // Stop all running processes
     for process in processes {
         match app_state.stop_running_execution_by_id(process.id).await {
@@ -649,8 +1031,21 @@ pub async fn stop_all_execution_processes(
                 {
                     tracing::error!("Failed to update execution process status: {}", e);
                     errors.push(format!("Failed to update process {} status", process.id));
-                } else {
-                    // Process stopped successfully
+                    continue;
+                }
+                app_state
+                    .events
+                    .publish_process_status(&app_state.db_pool, process.id)
+                    .await;
+                if let Err(e) =
+                    ProcessService::record_execution_usage(&app_state.db_pool, &config, process.id)
+                        .await
+                {
+                    tracing::error!(
+                        "Failed to record usage for execution process {}: {}",
+                        process.id,
+                        e
+                    );
                 }
             }
             Ok(false) => {
@@ -663,6 +1058,28 @@ pub async fn stop_all_execution_processes(
         }
     }
 
+    Ok((stopped_count, errors))
+}
+
+#[axum::debug_handler]
+pub async fn stop_all_execution_processes(
+    Extension(_project): Extension<Project>,
+    Extension(_task): Extension<Task>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    let (stopped_count, errors) = match stop_attempt_processes(&app_state, task_attempt.id).await {
+        Ok(result) => result,
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch execution processes for attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
     if !errors.is_empty() {
         return Ok(ResponseJson(ApiResponse::error(&format!(
             "Stopped {} processes, but encountered errors: {}",
@@ -671,10 +1088,6 @@ pub async fn stop_all_execution_processes(
         ))));
     }
 
-    if stopped_count == 0 {
-        return Ok(ResponseJson(ApiResponse::success(())));
-    }
-
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
@@ -718,12 +1131,70 @@ pub async fn stop_execution_process(
         tracing::error!("Failed to update execution process status: {}", e);
         return Err(StatusCode::INTERNAL_SERVER_ERROR);
     }
+    app_state
+        .events
+        .publish_process_status(&app_state.db_pool, execution_process.id)
+        .await;
 
-    // Process stopped successfully
+    let config = app_state.get_config().read().await.clone();
+    if let Err(e) =
+        ProcessService::record_execution_usage(&app_state.db_pool, &config, execution_process.id)
+            .await
+    {
+        tracing::error!(
+            "Failed to record usage for execution process {}: {}",
+            execution_process.id,
+            e
+        );
+    }
 
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+#[axum::debug_handler]
+pub async fn send_execution_process_input(
+    Extension(_project): Extension<Project>,
+    Extension(_task): Extension<Task>,
+    Extension(_task_attempt): Extension<TaskAttempt>,
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<SendExecutionInput>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    let message = payload.message.trim();
+    if message.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error("Message cannot be empty")));
+    }
+
+    if execution_process.status != ExecutionProcessStatus::Running {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Execution process is not running",
+        )));
+    }
+
+    let config = app_state.get_config().read().await.clone();
+    match ProcessService::send_execution_input(
+        &app_state.db_pool,
+        &config,
+        &execution_process,
+        message,
+    )
+    .await
+    {
+        Ok(()) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(e @ (ExecutionInputError::NotSupported | ExecutionInputError::Closed)) => {
+            Ok(ResponseJson(ApiResponse::error(&e.to_string())))
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to send input to execution process {}: {}",
+                execution_process.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
 #[derive(serde::Deserialize)]
 pub struct DeleteFileQuery {
     file_path: String,
@@ -766,9 +1237,41 @@ pub async fn create_followup_attempt(
     State(app_state): State<AppState>,
     Json(payload): Json<CreateFollowUpAttempt>,
 ) -> Result<ResponseJson<ApiResponse<FollowUpResponse>>, StatusCode> {
+    let execution_processes = match ExecutionProcess::find_by_task_attempt_id(
+        &app_state.db_pool,
+        task_attempt.id,
+    )
+    .await
+    {
+        Ok(processes) => processes,
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch execution processes for task attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    let config = app_state.get_config().read().await.clone();
+    if let Some(capabilities) = execution_processes
+        .iter()
+        .rev()
+        .find(|p| p.process_type == ExecutionProcessType::CodingAgent)
+        .and_then(|p| p.executor_type.as_deref())
+        .and_then(|executor_type| executor_type.parse::<ExecutorConfig>().ok())
+        .map(|executor| executor.capabilities(&config))
+    {
+        if !capabilities.follow_up {
+            return Ok(ResponseJson(ApiResponse::error(&format!(
+                "{} doesn't support follow-ups",
+                capabilities.label
+            ))));
+        }
+    }
+
     // Start follow-up execution synchronously to catch errors
     match TaskAttempt::start_followup_execution(
-        &app_state.db_pool,
         &app_state,
         task_attempt.id,
         task.id,
@@ -852,6 +1355,11 @@ pub async fn start_dev_server(
                     dev_server.id,
                     e
                 );
+            } else {
+                app_state
+                    .events
+                    .publish_process_status(&app_state.db_pool, dev_server.id)
+                    .await;
             }
         }
     }
@@ -900,80 +1408,100 @@ pub async fn get_task_attempt_execution_state(
     }
 }
 
-/// Find plan content with context by searching through multiple processes in the same attempt
-async fn find_plan_content_with_context(
-    pool: &SqlitePool,
+/// Stored plan versions of an attempt. Attempts whose plan wasn't recorded when the
+/// execution finished are backfilled from their most recent plan mode process.
+async fn load_plan_presentations(
+    app_state: &AppState,
     attempt_id: Uuid,
-) -> Result<String, StatusCode> {
-    // Get all execution processes for this attempt
-    let execution_processes =
-        match ExecutionProcess::find_by_task_attempt_id(pool, attempt_id).await {
-            Ok(processes) => processes,
-            Err(e) => {
-                tracing::error!(
-                    "Failed to fetch execution processes for attempt {}: {}",
-                    attempt_id,
-                    e
-                );
-                return Err(StatusCode::INTERNAL_SERVER_ERROR);
-            }
-        };
+) -> Result<Vec<PlanPresentation>, sqlx::Error> {
+    let pool = &app_state.db_pool;
+    let presentations = PlanPresentation::find_by_task_attempt_id(pool, attempt_id).await?;
+    if !presentations.is_empty() {
+        return Ok(presentations);
+    }
 
-    // Look for claudeplan processes (most recent first)
-    for claudeplan_process in execution_processes
-        .iter()
-        .rev()
-        .filter(|p| p.executor_type.as_deref() == Some("claude-plan"))
-    {
-        if let Some(stdout) = &claudeplan_process.stdout {
-            if !stdout.trim().is_empty() {
-                // Create executor and normalize logs
-                let executor_config = ExecutorConfig::ClaudePlan;
-                let executor = executor_config.create_executor();
-
-                // Use working directory for normalization
-                let working_dir_path =
-                    match std::fs::canonicalize(&claudeplan_process.working_directory) {
-                        Ok(canonical_path) => canonical_path.to_string_lossy().to_string(),
-                        Err(_) => claudeplan_process.working_directory.clone(),
-                    };
+    let execution_processes = ExecutionProcess::find_by_task_attempt_id(pool, attempt_id).await?;
+    let config = app_state.get_config().read().await.clone();
+    for process in execution_processes.iter().rev() {
+        if ProcessService::record_plan_presentation(pool, &config, process.id)
+            .await?
+            .is_some()
+        {
+            return PlanPresentation::find_by_task_attempt_id(pool, attempt_id).await;
+        }
+    }
 
-                // Normalize logs and extract plan content
-                match executor.normalize_logs(stdout, &working_dir_path) {
-                    Ok(normalized_conversation) => {
-                        // Search for plan content in the normalized conversation
-                        if let Some(plan_content) = normalized_conversation
-                            .entries
-                            .iter()
-                            .rev()
-                            .find_map(|entry| {
-                                if let NormalizedEntryType::ToolUse {
-                                    action_type: ActionType::PlanPresentation { plan },
-                                    ..
-                                } = &entry.entry_type
-                                {
-                                    Some(plan.clone())
-                                } else {
-                                    None
-                                }
-                            })
-                        {
-                            return Ok(plan_content);
-                        }
-                    }
-                    Err(_) => {
-                        continue;
-                    }
-                }
-            }
+    Ok(Vec::new())
+}
+
+pub async fn get_task_attempt_plans(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<PlanPresentation>>>, StatusCode> {
+    match load_plan_presentations(&app_state, task_attempt.id).await {
+        Ok(presentations) => Ok(ResponseJson(ApiResponse::success(presentations))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch plans for task attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
+}
 
-    tracing::error!(
-        "No claudeplan content found in any process in attempt {}",
-        attempt_id
-    );
-    Err(StatusCode::NOT_FOUND)
+/// Save an edited plan as the attempt's next version
+pub async fn update_task_attempt_plan(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<UpdatePlanPresentation>,
+) -> Result<ResponseJson<ApiResponse<PlanPresentation>>, StatusCode> {
+    if payload.plan.trim().is_empty() {
+        return Ok(ResponseJson(ApiResponse::error("Plan cannot be empty")));
+    }
+
+    let presentations = match load_plan_presentations(&app_state, task_attempt.id).await {
+        Ok(presentations) => presentations,
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch plans for task attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    if presentations.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "This attempt hasn't presented a plan yet",
+        )));
+    }
+    if presentations.iter().any(|p| p.approved_at.is_some()) {
+        return Ok(ResponseJson(ApiResponse::error(
+            "The plan has already been approved",
+        )));
+    }
+
+    match PlanPresentation::create_version(
+        &app_state.db_pool,
+        task_attempt.id,
+        None,
+        &payload.plan,
+        PlanAuthor::User,
+    )
+    .await
+    {
+        Ok(presentation) => Ok(ResponseJson(ApiResponse::success(presentation))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to save plan for task attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
 }
 
 pub async fn approve_plan(
@@ -984,8 +1512,27 @@ pub async fn approve_plan(
 ) -> Result<ResponseJson<ApiResponse<FollowUpResponse>>, StatusCode> {
     let current_task = &task;
 
-    // Find plan content with context across the task hierarchy
-    let plan_content = find_plan_content_with_context(&app_state.db_pool, task_attempt.id).await?;
+    let presentations = match load_plan_presentations(&app_state, task_attempt.id).await {
+        Ok(presentations) => presentations,
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch plans for task attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    if presentations.iter().any(|p| p.approved_at.is_some()) {
+        return Ok(ResponseJson(ApiResponse::error(
+            "The plan has already been approved",
+        )));
+    }
+    let Some(plan) = presentations.last() else {
+        tracing::error!("No plan found for task attempt {}", task_attempt.id);
+        return Err(StatusCode::NOT_FOUND);
+    };
+    let plan_content = plan.plan.clone();
 
     use crate::models::task::CreateTask;
     let new_task_id = Uuid::new_v4();
@@ -1003,10 +1550,22 @@ pub async fn approve_plan(
             return Err(StatusCode::INTERNAL_SERVER_ERROR);
         }
     };
+    app_state.events.publish(
+        project.id,
+        BoardEvent::TaskCreated {
+            task_id: new_task.id,
+        },
+    );
+
+    if let Err(e) = PlanPresentation::mark_approved(&app_state.db_pool, plan.id).await {
+        tracing::error!("Failed to mark plan {} as approved: {}", plan.id, e);
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
 
     // Mark original task as completed since it now has children
-    if let Err(e) =
-        Task::update_status(&app_state.db_pool, task.id, project.id, TaskStatus::Done).await
+    if let Err(e) = app_state
+        .set_task_status(task.id, project.id, TaskStatus::Done)
+        .await
     {
         tracing::error!("Failed to update original task status to Done: {}", e);
         return Err(StatusCode::INTERNAL_SERVER_ERROR);
@@ -1050,11 +1609,143 @@ pub async fn get_task_attempt_children(
     }
 }
 
+pub async fn get_task_attempt_usage(
+    Extension(_project): Extension<Project>,
+    Extension(_task): Extension<Task>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<UsageTotals>>, StatusCode> {
+    match ExecutionUsage::totals_for_task_attempt(&app_state.db_pool, task_attempt.id).await {
+        Ok(totals) => Ok(ResponseJson(ApiResponse::success(totals))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch usage for task attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Test script runs of the attempt's verify-and-fix loop, oldest first
+pub async fn get_task_attempt_verification_runs(
+    Extension(_project): Extension<Project>,
+    Extension(_task): Extension<Task>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<VerificationRun>>>, StatusCode> {
+    match VerificationRun::find_by_task_attempt_id(&app_state.db_pool, task_attempt.id).await {
+        Ok(runs) => Ok(ResponseJson(ApiResponse::success(runs))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch verification runs for task attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn get_task_attempt_approvals(
+    Extension(_project): Extension<Project>,
+    Extension(_task): Extension<Task>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<ToolApproval>>>, StatusCode> {
+    match ToolApproval::find_by_task_attempt_id(&app_state.db_pool, task_attempt.id).await {
+        Ok(approvals) => Ok(ResponseJson(ApiResponse::success(approvals))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch tool approvals for task attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn respond_to_tool_approval(
+    Extension(_project): Extension<Project>,
+    Extension(_task): Extension<Task>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    Path((_project_id, _task_id, _attempt_id, approval_id)): Path<(Uuid, Uuid, Uuid, Uuid)>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<RespondToolApproval>,
+) -> Result<ResponseJson<ApiResponse<ToolApproval>>, StatusCode> {
+    let pool = &app_state.db_pool;
+
+    let approval = match ToolApproval::find_by_id(pool, approval_id).await {
+        Ok(Some(approval)) => approval,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!("Failed to fetch tool approval {}: {}", approval_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    // The approval must belong to one of this attempt's execution processes
+    match ExecutionProcess::find_by_id(pool, approval.execution_process_id).await {
+        Ok(Some(process)) if process.task_attempt_id == task_attempt.id => {}
+        Ok(_) => return Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch execution process {}: {}",
+                approval.execution_process_id,
+                e
+            );
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+
+    let status = if payload.approved {
+        ToolApprovalStatus::Approved
+    } else {
+        ToolApprovalStatus::Denied
+    };
+    let reason = payload
+        .reason
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty());
+
+    match ToolApproval::decide(pool, approval_id, status, reason).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return Ok(ResponseJson(ApiResponse::error(
+                "This request has already been decided",
+            )))
+        }
+        Err(e) => {
+            tracing::error!("Failed to decide tool approval {}: {}", approval_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+
+    match ToolApproval::find_by_id(pool, approval_id).await {
+        Ok(Some(approval)) => Ok(ResponseJson(ApiResponse::success(approval))),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!("Failed to fetch tool approval {}: {}", approval_id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
 pub fn task_attempts_list_router(_state: AppState) -> Router<AppState> {
-    Router::new().route(
-        "/projects/:project_id/tasks/:task_id/attempts",
-        get(get_task_attempts).post(create_task_attempt),
-    )
+    use axum::routing::post;
+
+    Router::new()
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts",
+            get(get_task_attempts).post(create_task_attempt),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/fan-out",
+            post(create_fan_out),
+        )
 }
 
 pub fn task_attempts_with_id_router(_state: AppState) -> Router<AppState> {
@@ -1103,12 +1794,20 @@ pub fn task_attempts_with_id_router(_state: AppState) -> Router<AppState> {
                     "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/execution-processes/:process_id/stop",
                     post(stop_execution_process),
                 )
+                .route(
+                    "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/execution-processes/:process_id/input",
+                    post(send_execution_process_input),
+                )
                 .route_layer(from_fn_with_state(_state.clone(), load_execution_process_with_context_middleware))
         )
         .route(
             "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/logs",
             get(get_task_attempt_all_logs),
         )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/transcript",
+            get(export_task_attempt_transcript),
+        )
         .route(
             "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/follow-up",
             post(create_followup_attempt),
@@ -1125,10 +1824,38 @@ pub fn task_attempts_with_id_router(_state: AppState) -> Router<AppState> {
             "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/approve-plan",
             post(approve_plan),
         )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/plans",
+            get(get_task_attempt_plans).post(update_task_attempt_plan),
+        )
         .route(
             "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/children",
             get(get_task_attempt_children),
         )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/usage",
+            get(get_task_attempt_usage),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/verification-runs",
+            get(get_task_attempt_verification_runs),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/fan-out",
+            get(get_fan_out_comparison),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/pick-winner",
+            post(pick_fan_out_winner),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/approvals",
+            get(get_task_attempt_approvals),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/approvals/:approval_id/respond",
+            post(respond_to_tool_approval),
+        )
         .merge(
             Router::new()
                 .route(
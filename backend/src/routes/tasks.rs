@@ -8,11 +8,13 @@ use crate::{
     app_state::AppState,
     execution_monitor,
     models::{
+        execution_usage::{ExecutionUsage, UsageTotals},
         project::Project,
         task::{CreateTask, CreateTaskAndStart, Task, TaskWithAttemptStatus, UpdateTask},
         task_attempt::{CreateTaskAttempt, TaskAttempt},
         ApiResponse,
     },
+    services::BoardEvent,
 };
 
 pub async fn get_project_tasks(
@@ -52,6 +54,10 @@ pub async fn create_task(
 
     match Task::create(&app_state.db_pool, &payload, id).await {
         Ok(task) => {
+            app_state
+                .events
+                .publish(project.id, BoardEvent::TaskCreated { task_id: task.id });
+
             // Track task creation event
             app_state
                 .track_analytics_event(
@@ -103,15 +109,23 @@ pub async fn create_task_and_start(
             return Err(StatusCode::INTERNAL_SERVER_ERROR);
         }
     };
+    app_state
+        .events
+        .publish(project.id, BoardEvent::TaskCreated { task_id });
 
-    // Create task attempt
-    let executor_string = payload.executor.as_ref().map(|exec| exec.to_string());
+    // Create task attempt, defaulting to the project's executor when none was requested
+    let executor_string = payload
+        .executor
+        .as_ref()
+        .map(|exec| exec.to_string())
+        .or_else(|| project.executor.clone());
     let attempt_payload = CreateTaskAttempt {
         executor: executor_string.clone(),
         base_branch: None, // Not supported in task creation endpoint, only in task attempts
+        priority: payload.priority,
     };
 
-    match TaskAttempt::create(&app_state.db_pool, &attempt_payload, task_id).await {
+    match TaskAttempt::create(&app_state.db_pool, &attempt_payload, task_id, None).await {
         Ok(attempt) => {
             app_state
                 .track_analytics_event(
@@ -138,13 +152,14 @@ pub async fn create_task_and_start(
             // Start execution asynchronously (don't block the response)
             let app_state_clone = app_state.clone();
             let attempt_id = attempt.id;
+            let priority = attempt_payload.priority.unwrap_or_default();
             tokio::spawn(async move {
                 if let Err(e) = TaskAttempt::start_execution(
-                    &app_state_clone.db_pool,
                     &app_state_clone,
                     attempt_id,
                     task_id,
                     project.id,
+                    priority,
                 )
                 .await
                 {
@@ -190,7 +205,16 @@ pub async fn update_task(
     )
     .await
     {
-        Ok(task) => Ok(ResponseJson(ApiResponse::success(task))),
+        Ok(task) => {
+            app_state.events.publish(
+                project.id,
+                BoardEvent::TaskUpdated {
+                    task_id: task.id,
+                    status: task.status.clone(),
+                },
+            );
+            Ok(ResponseJson(ApiResponse::success(task)))
+        }
         Err(e) => {
             tracing::error!("Failed to update task: {}", e);
             Err(StatusCode::INTERNAL_SERVER_ERROR)
@@ -204,7 +228,7 @@ pub async fn delete_task(
     State(app_state): State<AppState>,
 ) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
     // Clean up all worktrees for this task before deletion
-    if let Err(e) = execution_monitor::cleanup_task_worktrees(&app_state.db_pool, task.id).await {
+    if let Err(e) = execution_monitor::cleanup_task_worktrees(&app_state, task.id).await {
         tracing::error!("Failed to cleanup worktrees for task {}: {}", task.id, e);
         // Continue with deletion even if cleanup fails
     }
@@ -245,6 +269,9 @@ pub async fn delete_task(
             if rows_affected == 0 {
                 Err(StatusCode::NOT_FOUND)
             } else {
+                app_state
+                    .events
+                    .publish(project.id, BoardEvent::TaskDeleted { task_id: task.id });
                 Ok(ResponseJson(ApiResponse::success(())))
             }
         }
@@ -255,6 +282,19 @@ pub async fn delete_task(
     }
 }
 
+pub async fn get_task_usage(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<UsageTotals>>, StatusCode> {
+    match ExecutionUsage::totals_for_task(&app_state.db_pool, task.id).await {
+        Ok(totals) => Ok(ResponseJson(ApiResponse::success(totals))),
+        Err(e) => {
+            tracing::error!("Failed to fetch usage for task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
 pub fn tasks_project_router() -> Router<AppState> {
     use axum::routing::post;
 
@@ -270,8 +310,13 @@ pub fn tasks_project_router() -> Router<AppState> {
 }
 
 pub fn tasks_with_id_router() -> Router<AppState> {
-    Router::new().route(
-        "/projects/:project_id/tasks/:task_id",
-        get(get_task).put(update_task).delete(delete_task),
-    )
+    Router::new()
+        .route(
+            "/projects/:project_id/tasks/:task_id",
+            get(get_task).put(update_task).delete(delete_task),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/usage",
+            get(get_task_usage),
+        )
 }
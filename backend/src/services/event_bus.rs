@@ -0,0 +1,296 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tokio::sync::broadcast;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessStatus, ExecutionProcessType},
+    task::{Task, TaskStatus},
+    task_attempt::{TaskAttempt, TaskAttemptStatus},
+};
+
+/// Events kept for clients resuming from a cursor
+const EVENT_HISTORY: usize = 1000;
+
+/// Events a subscriber can fall behind by before it has to reload
+const CHANNEL_CAPACITY: usize = 256;
+
+/// A change on a project's board
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(export)]
+pub enum BoardEvent {
+    TaskCreated {
+        task_id: Uuid,
+    },
+    TaskUpdated {
+        task_id: Uuid,
+        status: TaskStatus,
+    },
+    TaskDeleted {
+        task_id: Uuid,
+    },
+    AttemptStatusChanged {
+        task_id: Uuid,
+        attempt_id: Uuid,
+        status: TaskAttemptStatus,
+    },
+    ProcessStarted {
+        task_id: Uuid,
+        attempt_id: Uuid,
+        process_id: Uuid,
+        process_type: ExecutionProcessType,
+    },
+    ProcessCompleted {
+        task_id: Uuid,
+        attempt_id: Uuid,
+        process_id: Uuid,
+        process_type: ExecutionProcessType,
+        status: ExecutionProcessStatus,
+    },
+    PrStatusChanged {
+        task_id: Uuid,
+        attempt_id: Uuid,
+        pr_status: String,
+        merged: bool,
+    },
+    WorktreeCleanedUp {
+        task_id: Uuid,
+        attempt_id: Uuid,
+    },
+}
+
+impl BoardEvent {
+    /// The attempt status a process starting or completing moves its attempt to
+    fn attempt_status(process: &ExecutionProcess) -> Option<TaskAttemptStatus> {
+        let succeeded = process.status == ExecutionProcessStatus::Completed;
+        match (&process.process_type, &process.status) {
+            (ExecutionProcessType::SetupScript, ExecutionProcessStatus::Running) => {
+                Some(TaskAttemptStatus::SetupRunning)
+            }
+            (ExecutionProcessType::SetupScript, _) if succeeded => {
+                Some(TaskAttemptStatus::SetupComplete)
+            }
+            (ExecutionProcessType::SetupScript, _) => Some(TaskAttemptStatus::SetupFailed),
+            (ExecutionProcessType::CodingAgent, ExecutionProcessStatus::Running) => {
+                Some(TaskAttemptStatus::ExecutorRunning)
+            }
+            (ExecutionProcessType::CodingAgent, _) if succeeded => {
+                Some(TaskAttemptStatus::ExecutorComplete)
+            }
+            (ExecutionProcessType::CodingAgent, _) => Some(TaskAttemptStatus::ExecutorFailed),
+            _ => None,
+        }
+    }
+}
+
+/// A published board event
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ProjectEvent {
+    /// Cursor to resume the stream after this event
+    #[ts(type = "number")]
+    pub id: u64,
+    pub project_id: Uuid,
+    pub event: BoardEvent,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+struct EventHistory {
+    last_id: u64,
+    events: VecDeque<ProjectEvent>,
+}
+
+impl EventHistory {
+    /// Events after `cursor`, or `None` if some of them were already dropped or the cursor
+    /// is from another server run
+    fn after(&self, cursor: u64) -> Option<Vec<ProjectEvent>> {
+        let first_id = self
+            .events
+            .front()
+            .map_or(self.last_id + 1, |event| event.id);
+        if cursor > self.last_id || cursor + 1 < first_id {
+            return None;
+        }
+        Some(
+            self.events
+                .iter()
+                .filter(|event| event.id > cursor)
+                .cloned()
+                .collect(),
+        )
+    }
+}
+
+/// In-process broadcast of board changes, for clients watching projects live
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<ProjectEvent>,
+    history: Arc<Mutex<EventHistory>>,
+}
+
+impl std::fmt::Debug for EventBus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventBus").finish_non_exhaustive()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self {
+            sender,
+            history: Arc::new(Mutex::new(EventHistory {
+                // Ids continue from the boot time, so a cursor from before a restart is never
+                // taken for one of this run
+                last_id: Utc::now().timestamp_millis() as u64 * 1000,
+                events: VecDeque::new(),
+            })),
+        }
+    }
+
+    pub fn publish(&self, project_id: Uuid, event: BoardEvent) {
+        let mut history = self.history.lock().unwrap();
+        history.last_id += 1;
+        let event = ProjectEvent {
+            id: history.last_id,
+            project_id,
+            event,
+            created_at: Utc::now(),
+        };
+        history.events.push_back(event.clone());
+        if history.events.len() > EVENT_HISTORY {
+            history.events.pop_front();
+        }
+        // Sent under the lock, so a subscriber sees every event after its replay exactly once.
+        // Without subscribers there's no one to tell.
+        let _ = self.sender.send(event);
+    }
+
+    /// Subscribe to events, along with the ones published after `cursor`. The replay is
+    /// `None` when the cursor is too old to resume from, and the client has to reload.
+    pub fn subscribe(
+        &self,
+        cursor: Option<u64>,
+    ) -> (Option<Vec<ProjectEvent>>, broadcast::Receiver<ProjectEvent>) {
+        let history = self.history.lock().unwrap();
+        let receiver = self.sender.subscribe();
+        let replay = match cursor {
+            Some(cursor) => history.after(cursor),
+            None => Some(Vec::new()),
+        };
+        (replay, receiver)
+    }
+
+    /// Publish that a process started or completed, and the attempt status that follows
+    pub async fn publish_process_status(&self, pool: &SqlitePool, process_id: Uuid) {
+        let Ok(Some(process)) = ExecutionProcess::find_by_id(pool, process_id).await else {
+            return;
+        };
+        let Some(task) = Self::attempt_task(pool, process.task_attempt_id).await else {
+            return;
+        };
+
+        let event = if process.status == ExecutionProcessStatus::Running {
+            BoardEvent::ProcessStarted {
+                task_id: task.id,
+                attempt_id: process.task_attempt_id,
+                process_id,
+                process_type: process.process_type.clone(),
+            }
+        } else {
+            BoardEvent::ProcessCompleted {
+                task_id: task.id,
+                attempt_id: process.task_attempt_id,
+                process_id,
+                process_type: process.process_type.clone(),
+                status: process.status.clone(),
+            }
+        };
+        self.publish(task.project_id, event);
+
+        if let Some(status) = BoardEvent::attempt_status(&process) {
+            self.publish(
+                task.project_id,
+                BoardEvent::AttemptStatusChanged {
+                    task_id: task.id,
+                    attempt_id: process.task_attempt_id,
+                    status,
+                },
+            );
+        }
+    }
+
+    /// Publish that an attempt's worktree was removed
+    pub async fn publish_worktree_cleaned_up(&self, pool: &SqlitePool, attempt_id: Uuid) {
+        if let Some(task) = Self::attempt_task(pool, attempt_id).await {
+            self.publish(
+                task.project_id,
+                BoardEvent::WorktreeCleanedUp {
+                    task_id: task.id,
+                    attempt_id,
+                },
+            );
+        }
+    }
+
+    async fn attempt_task(pool: &SqlitePool, attempt_id: Uuid) -> Option<Task> {
+        let attempt = TaskAttempt::find_by_id(pool, attempt_id).await.ok()??;
+        Task::find_by_id(pool, attempt.task_id).await.ok()?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task_created(bus: &EventBus, project_id: Uuid) -> u64 {
+        bus.publish(
+            project_id,
+            BoardEvent::TaskCreated {
+                task_id: Uuid::new_v4(),
+            },
+        );
+        bus.history.lock().unwrap().last_id
+    }
+
+    #[test]
+    fn test_subscribers_resume_after_their_cursor() {
+        let bus = EventBus::new();
+        let project_id = Uuid::new_v4();
+        let first = task_created(&bus, project_id);
+        let second = task_created(&bus, project_id);
+
+        let (replay, mut receiver) = bus.subscribe(Some(first));
+        let replay = replay.unwrap();
+        assert_eq!(replay.len(), 1);
+        assert_eq!(replay[0].id, second);
+
+        // Up to date, and live from here
+        assert!(bus.subscribe(Some(second)).0.unwrap().is_empty());
+        let third = task_created(&bus, project_id);
+        assert_eq!(receiver.try_recv().unwrap().id, third);
+
+        // A cursor from another server run, or one the history no longer reaches, can't resume
+        assert!(bus.subscribe(Some(third + 1)).0.is_none());
+        for _ in 0..EVENT_HISTORY {
+            task_created(&bus, project_id);
+        }
+        assert!(bus.subscribe(Some(first)).0.is_none());
+        assert!(bus.subscribe(None).0.unwrap().is_empty());
+    }
+}
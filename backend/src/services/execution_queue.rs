@@ -0,0 +1,220 @@
+use chrono::Utc;
+use tokio::sync::Mutex;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    models::{
+        queued_attempt::{startable_attempts, QueuedAttempt},
+        task::TaskStatus,
+        task_attempt::{TaskAttemptError, TaskAttemptStatus},
+    },
+    services::{BoardEvent, ProcessService},
+};
+
+/// Held while admitting, starting and releasing attempts, so two dispatches can't both take the
+/// last free slot and a slot isn't freed between an attempt's admission and its first process
+static DISPATCH_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// Scheduler in front of `ProcessService` that holds attempts and follow-ups back while the
+/// global or per-project limit on running attempts is reached. An attempt counts from its
+/// admission until none of its setup script, coding agent or test script runs any more.
+pub struct ExecutionQueue;
+
+impl ExecutionQueue {
+    /// Queue an attempt for execution and start it right away if a slot is free
+    pub async fn enqueue(
+        app_state: &AppState,
+        attempt_id: Uuid,
+        task_id: Uuid,
+        project_id: Uuid,
+        priority: i64,
+    ) -> Result<(), TaskAttemptError> {
+        QueuedAttempt::enqueue(&app_state.db_pool, attempt_id, priority, None).await?;
+        Self::publish_queued(app_state, attempt_id, task_id, project_id).await?;
+
+        Self::dispatch(app_state).await
+    }
+
+    /// Continue an attempt's session with a follow-up. An attempt that still holds its slot, e.g.
+    /// one whose tests just failed, continues right away; others queue for a slot like a new
+    /// attempt.
+    pub async fn enqueue_follow_up(
+        app_state: &AppState,
+        attempt_id: Uuid,
+        task_id: Uuid,
+        project_id: Uuid,
+        prompt: &str,
+    ) -> Result<(), TaskAttemptError> {
+        let pool = &app_state.db_pool;
+        {
+            let _guard = DISPATCH_LOCK.lock().await;
+            if QueuedAttempt::is_admitted(pool, attempt_id).await? {
+                return ProcessService::start_followup_execution(
+                    pool, app_state, attempt_id, task_id, project_id, prompt,
+                )
+                .await
+                .map(|_| ());
+            }
+            if !QueuedAttempt::enqueue(pool, attempt_id, 0, Some(prompt)).await? {
+                return Err(TaskAttemptError::ValidationError(
+                    "Task attempt is already queued".to_string(),
+                ));
+            }
+        }
+        Self::publish_queued(app_state, attempt_id, task_id, project_id).await?;
+
+        Self::dispatch(app_state).await
+    }
+
+    /// A queued attempt is as good as started from the board's point of view
+    async fn publish_queued(
+        app_state: &AppState,
+        attempt_id: Uuid,
+        task_id: Uuid,
+        project_id: Uuid,
+    ) -> Result<(), TaskAttemptError> {
+        app_state
+            .set_task_status(task_id, project_id, TaskStatus::InProgress)
+            .await?;
+        app_state.events.publish(
+            project_id,
+            BoardEvent::AttemptStatusChanged {
+                task_id,
+                attempt_id,
+                status: TaskAttemptStatus::Queued,
+            },
+        );
+        Ok(())
+    }
+
+    /// Start every queued attempt the concurrency limits allow, in queue order
+    pub async fn dispatch(app_state: &AppState) -> Result<(), TaskAttemptError> {
+        let _guard = DISPATCH_LOCK.lock().await;
+        let pool = &app_state.db_pool;
+
+        let queue = QueuedAttempt::find_all(pool).await?;
+        if queue.is_empty() {
+            return Ok(());
+        }
+        let admitted = QueuedAttempt::admitted_attempts_by_project(pool).await?;
+        let global_limit = app_state
+            .get_config()
+            .read()
+            .await
+            .max_concurrent_agents
+            .map(|limit| limit as usize);
+
+        for queued in startable_attempts(&queue, &admitted, global_limit) {
+            // Already taken by a stop request
+            if !QueuedAttempt::admit(pool, queued.task_attempt_id).await? {
+                continue;
+            }
+            info!(
+                "Starting task attempt {} (priority {}) after {}s in the queue",
+                queued.task_attempt_id,
+                queued.priority,
+                (Utc::now() - queued.queued_at).num_seconds()
+            );
+            let started = match &queued.follow_up_prompt {
+                Some(prompt) => ProcessService::start_followup_execution(
+                    pool,
+                    app_state,
+                    queued.task_attempt_id,
+                    queued.task_id,
+                    queued.project_id,
+                    prompt,
+                )
+                .await
+                .map(|_| ()),
+                None => {
+                    ProcessService::start_execution(
+                        pool,
+                        app_state,
+                        queued.task_attempt_id,
+                        queued.task_id,
+                        queued.project_id,
+                    )
+                    .await
+                }
+            };
+            if let Err(e) = started {
+                error!(
+                    "Failed to start execution for task attempt {}: {}",
+                    queued.task_attempt_id, e
+                );
+                QueuedAttempt::release(pool, queued.task_attempt_id).await?;
+                Self::fail_start(app_state, queued).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Free the slots of attempts that stopped running. Only the execution monitor calls this,
+    /// once it's done handling completions: those start an attempt's next process only after
+    /// marking its previous one done, and the attempt must keep its slot in between.
+    pub async fn release_finished(app_state: &AppState) -> Result<(), TaskAttemptError> {
+        let _guard = DISPATCH_LOCK.lock().await;
+        let released = QueuedAttempt::release_finished(&app_state.db_pool).await?;
+        if released > 0 {
+            info!("Freed the execution slots of {} task attempts", released);
+        }
+        Ok(())
+    }
+
+    /// Give up on an attempt that left the queue but couldn't start, so its task doesn't stay
+    /// in progress with nothing running
+    async fn fail_start(app_state: &AppState, queued: &QueuedAttempt) {
+        if let Err(e) = app_state
+            .set_task_status(queued.task_id, queued.project_id, TaskStatus::InReview)
+            .await
+        {
+            error!(
+                "Failed to update task status to InReview for task attempt {}: {}",
+                queued.task_attempt_id, e
+            );
+        }
+        app_state
+            .events
+            .publish(queued.project_id, start_failed_event(queued));
+    }
+}
+
+/// What the board hears about an attempt that couldn't start, besides its task moving to
+/// review
+fn start_failed_event(queued: &QueuedAttempt) -> BoardEvent {
+    BoardEvent::AttemptStatusChanged {
+        task_id: queued.task_id,
+        attempt_id: queued.task_attempt_id,
+        status: TaskAttemptStatus::ExecutorFailed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_start_failed_event_fails_the_attempt() {
+        let queued = QueuedAttempt {
+            task_attempt_id: Uuid::new_v4(),
+            task_id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            priority: 0,
+            queued_at: Utc::now(),
+            follow_up_prompt: None,
+            project_max_concurrent_agents: 0,
+        };
+
+        assert_eq!(
+            start_failed_event(&queued),
+            BoardEvent::AttemptStatusChanged {
+                task_id: queued.task_id,
+                attempt_id: queued.task_attempt_id,
+                status: TaskAttemptStatus::ExecutorFailed,
+            }
+        );
+    }
+}
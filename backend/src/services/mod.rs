@@ -1,11 +1,16 @@
 pub mod analytics;
+pub mod event_bus;
+pub mod execution_queue;
 pub mod git_service;
 pub mod github_service;
 pub mod notification_service;
 pub mod pr_monitor;
 pub mod process_service;
+pub mod transcript;
 
 pub use analytics::{generate_user_id, AnalyticsConfig, AnalyticsService};
+pub use event_bus::{BoardEvent, EventBus};
+pub use execution_queue::ExecutionQueue;
 pub use git_service::{GitService, GitServiceError};
 pub use github_service::{CreatePrRequest, GitHubRepoInfo, GitHubService, GitHubServiceError};
 pub use notification_service::{NotificationConfig, NotificationService};
@@ -1,22 +1,18 @@
 use std::{sync::Arc, time::Duration};
 
-use sqlx::SqlitePool;
 use tokio::{sync::RwLock, time::interval};
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
 use crate::{
-    models::{
-        config::Config,
-        task::{Task, TaskStatus},
-        task_attempt::TaskAttempt,
-    },
-    services::{GitHubRepoInfo, GitHubService, GitService},
+    app_state::AppState,
+    models::{config::Config, task::TaskStatus, task_attempt::TaskAttempt},
+    services::{BoardEvent, GitHubRepoInfo, GitHubService, GitService},
 };
 
 /// Service to monitor GitHub PRs and update task status when they are merged
 pub struct PrMonitorService {
-    pool: SqlitePool,
+    app_state: AppState,
     poll_interval: Duration,
 }
 
@@ -32,9 +28,9 @@ pub struct PrInfo {
 }
 
 impl PrMonitorService {
-    pub fn new(pool: SqlitePool) -> Self {
+    pub fn new(app_state: AppState) -> Self {
         Self {
-            pool,
+            app_state,
             poll_interval: Duration::from_secs(60), // Check every minute
         }
     }
@@ -118,7 +114,7 @@ impl PrMonitorService {
                JOIN projects p ON t.project_id = p.id
                WHERE ta.pr_status = 'open' AND ta.pr_number IS NOT NULL"#
         )
-        .fetch_all(&self.pool)
+        .fetch_all(&self.app_state.db_pool)
         .await?;
 
         let mut pr_infos = Vec::new();
@@ -184,13 +180,22 @@ impl PrMonitorService {
             let merge_commit_sha = pr_status.merge_commit_sha.as_deref();
 
             TaskAttempt::update_pr_status(
-                &self.pool,
+                &self.app_state.db_pool,
                 pr_info.attempt_id,
                 &pr_status.status,
                 pr_status.merged_at,
                 merge_commit_sha,
             )
             .await?;
+            self.app_state.events.publish(
+                pr_info.project_id,
+                BoardEvent::PrStatusChanged {
+                    task_id: pr_info.task_id,
+                    attempt_id: pr_info.attempt_id,
+                    pr_status: pr_status.status.clone(),
+                    merged: pr_status.merged,
+                },
+            );
 
             // If the PR was merged, update the task status to done
             if pr_status.merged {
@@ -199,13 +204,9 @@ impl PrMonitorService {
                     pr_info.pr_number, pr_info.task_id
                 );
 
-                Task::update_status(
-                    &self.pool,
-                    pr_info.task_id,
-                    pr_info.project_id,
-                    TaskStatus::Done,
-                )
-                .await?;
+                self.app_state
+                    .set_task_status(pr_info.task_id, pr_info.project_id, TaskStatus::Done)
+                    .await?;
             }
         }
 
@@ -2,16 +2,32 @@ use sqlx::SqlitePool;
 use tracing::{debug, info};
 use uuid::Uuid;
 
+#[cfg(unix)]
+use crate::utils::process_group::ProcessIdentity;
 use crate::{
-    executor::Executor,
+    app_state::ExecutionChild,
+    executor::{stream_output_to_db, Executor},
+    executors::{
+        input::{ExecutionInput, ExecutionInputError},
+        streaming::{EntryPatcher, LogStreaming},
+    },
     models::{
+        config::Config,
+        execution_output::{ExecutionOutput, OutputStream},
         execution_process::{CreateExecutionProcess, ExecutionProcess, ExecutionProcessType},
+        execution_usage::ExecutionUsage,
         executor_session::{CreateExecutorSession, ExecutorSession},
+        plan_presentation::{PlanAuthor, PlanPresentation},
         project::Project,
+        project_env_var::ProjectEnvVar,
         task::Task,
         task_attempt::{TaskAttempt, TaskAttemptError},
     },
-    utils::shell::get_shell_command,
+    utils::{
+        process_env::{self, ProcessEnvironment},
+        sandbox::SandboxPolicy,
+        shell::get_shell_command,
+    },
 };
 
 /// Service responsible for managing process execution lifecycle
@@ -108,6 +124,16 @@ impl ProcessService {
         let setup_script = project.setup_script.as_ref().unwrap();
         let process_id = Uuid::new_v4();
 
+        let mut environment = Self::process_environment(
+            pool,
+            app_state,
+            project_id,
+            &ExecutionProcessType::SetupScript,
+            &task_attempt.worktree_path,
+        )
+        .await?;
+        environment.execution_process_id = Some(process_id);
+
         // Create execution process record with delegation context
         let _execution_process = Self::create_execution_process_record_with_delegation(
             pool,
@@ -116,6 +142,7 @@ impl ProcessService {
             setup_script,
             &task_attempt.worktree_path,
             delegation_context,
+            environment.sandbox.as_ref(),
         )
         .await?;
 
@@ -128,15 +155,26 @@ impl ProcessService {
         );
 
         // Execute the setup script
-        let child = Self::execute_setup_script_process(
-            setup_script,
-            pool,
-            task_id,
-            attempt_id,
-            process_id,
-            &task_attempt.worktree_path,
-        )
-        .await?;
+        process_env::register_secrets(process_id, environment.secrets.clone());
+        let config = app_state.get_config().read().await.clone();
+        let child = match environment
+            .scope(Self::execute_setup_script_process(
+                setup_script,
+                pool,
+                &config,
+                task_id,
+                attempt_id,
+                process_id,
+                &task_attempt.worktree_path,
+            ))
+            .await
+        {
+            Ok(child) => child,
+            Err(e) => {
+                process_env::release_secrets(process_id);
+                return Err(e);
+            }
+        };
 
         // Register for monitoring
         Self::register_for_monitoring(
@@ -164,14 +202,16 @@ impl ProcessService {
         task_id: Uuid,
         project_id: Uuid,
     ) -> Result<(), TaskAttemptError> {
-        use crate::models::task::{Task, TaskStatus};
+        use crate::models::task::TaskStatus;
 
         // Load required entities
         let (task_attempt, project) =
             Self::load_execution_context(pool, attempt_id, project_id).await?;
 
         // Update task status to indicate execution has started
-        Task::update_status(pool, task_id, project_id, TaskStatus::InProgress).await?;
+        app_state
+            .set_task_status(task_id, project_id, TaskStatus::InProgress)
+            .await?;
 
         // Determine execution sequence based on project configuration
         if Self::should_run_setup_script(&project) {
@@ -195,13 +235,17 @@ impl ProcessService {
         app_state: &crate::app_state::AppState,
         attempt_id: Uuid,
         task_id: Uuid,
-        _project_id: Uuid,
+        project_id: Uuid,
     ) -> Result<(), TaskAttemptError> {
-        let task_attempt = TaskAttempt::find_by_id(pool, attempt_id)
-            .await?
-            .ok_or(TaskAttemptError::TaskNotFound)?;
+        let (task_attempt, project) =
+            Self::load_execution_context(pool, attempt_id, project_id).await?;
 
-        let executor_config = Self::resolve_executor_config(&task_attempt.executor);
+        let global_executor = app_state.get_config().read().await.executor.clone();
+        let executor_config = Self::resolve_executor_config(
+            task_attempt.executor.as_deref(),
+            project.executor.as_deref(),
+            &global_executor,
+        );
 
         Self::start_process_execution(
             pool,
@@ -211,6 +255,7 @@ impl ProcessService {
             crate::executor::ExecutorType::CodingAgent {
                 config: executor_config,
                 follow_up: None,
+                extra_args: project.executor_args,
             },
             "Starting executor".to_string(),
             ExecutionProcessType::CodingAgent,
@@ -301,6 +346,39 @@ impl ProcessService {
         result
     }
 
+    /// Run the project's test script against the attempt's committed changes.
+    ///
+    /// Returns `false` without starting anything when the project has no test script.
+    pub async fn start_test_script(
+        pool: &SqlitePool,
+        app_state: &crate::app_state::AppState,
+        attempt_id: Uuid,
+        task_id: Uuid,
+        project_id: Uuid,
+    ) -> Result<bool, TaskAttemptError> {
+        let (task_attempt, project) =
+            Self::load_execution_context(pool, attempt_id, project_id).await?;
+        let Some(test_script) = project
+            .test_script
+            .filter(|script| !script.trim().is_empty())
+        else {
+            return Ok(false);
+        };
+
+        Self::start_process_execution(
+            pool,
+            app_state,
+            attempt_id,
+            task_id,
+            crate::executor::ExecutorType::TestScript(test_script),
+            "Starting test script".to_string(),
+            ExecutionProcessType::TestScript,
+            &task_attempt.worktree_path,
+        )
+        .await?;
+        Ok(true)
+    }
+
     /// Start a follow-up execution using the same executor type as the first process (with automatic setup)
     /// Returns the attempt_id that was actually used (always the original attempt_id for session continuity)
     pub async fn start_followup_execution(
@@ -311,7 +389,7 @@ impl ProcessService {
         project_id: Uuid,
         prompt: &str,
     ) -> Result<Uuid, TaskAttemptError> {
-        use crate::models::task::{Task, TaskStatus};
+        use crate::models::task::TaskStatus;
 
         // Get the current task attempt to check if worktree is deleted
         let current_attempt = TaskAttempt::find_by_id(pool, attempt_id)
@@ -333,7 +411,9 @@ impl ProcessService {
         }
 
         // Update task status to indicate follow-up execution has started
-        Task::update_status(pool, task_id, project_id, TaskStatus::InProgress).await?;
+        app_state
+            .set_task_status(task_id, project_id, TaskStatus::InProgress)
+            .await?;
 
         // Ensure worktree exists (recreate if needed for cold task support)
         // This will resurrect the worktree at the exact same path for session continuity
@@ -430,6 +510,11 @@ impl ProcessService {
             }
         };
 
+        // Project executor arguments apply to follow-ups too (e.g. keep the same model)
+        let extra_args = Project::find_by_id(pool, project_id)
+            .await?
+            .and_then(|project| project.executor_args);
+
         // Try to use follow-up with session ID, but fall back to new session if it fails
         let followup_executor = if let Some(session_id) = &executor_session.session_id {
             // First try with session ID for continuation
@@ -443,6 +528,7 @@ impl ProcessService {
                     session_id: session_id.clone(),
                     prompt: prompt.to_string(),
                 }),
+                extra_args: extra_args.clone(),
             }
         } else {
             // No session ID available, start new session
@@ -453,6 +539,7 @@ impl ProcessService {
             crate::executor::ExecutorType::CodingAgent {
                 config: executor_config.clone(),
                 follow_up: None,
+                extra_args: extra_args.clone(),
             }
         };
 
@@ -483,6 +570,7 @@ impl ProcessService {
             let new_session_executor = crate::executor::ExecutorType::CodingAgent {
                 config: executor_config,
                 follow_up: None,
+                extra_args,
             };
 
             Self::start_process_execution(
@@ -518,6 +606,19 @@ impl ProcessService {
     ) -> Result<(), TaskAttemptError> {
         let process_id = Uuid::new_v4();
 
+        let task = Task::find_by_id(pool, task_id)
+            .await?
+            .ok_or(TaskAttemptError::TaskNotFound)?;
+        let mut environment = Self::process_environment(
+            pool,
+            app_state,
+            task.project_id,
+            &process_type,
+            worktree_path,
+        )
+        .await?;
+        environment.execution_process_id = Some(process_id);
+
         // Create execution process record
         let _execution_process = Self::create_execution_process_record(
             pool,
@@ -526,6 +627,7 @@ impl ProcessService {
             &executor_type,
             process_type.clone(),
             worktree_path,
+            environment.sandbox.as_ref(),
         )
         .await?;
 
@@ -553,16 +655,35 @@ impl ProcessService {
 
         tracing::info!("Starting {} for task attempt {}", activity_note, attempt_id);
 
+        // Open the WAL up front so SSE clients connecting right away stream patches
+        if matches!(process_type, ExecutionProcessType::CodingAgent) {
+            LogStreaming::register(process_id);
+        }
+
+        // Spawn with the project's environment, redacting its secrets from the output
+        process_env::register_secrets(process_id, environment.secrets.clone());
+
         // Execute the process
-        let child = Self::execute_process(
-            &executor_type,
-            pool,
-            task_id,
-            attempt_id,
-            process_id,
-            worktree_path,
-        )
-        .await?;
+        let config = app_state.get_config().read().await.clone();
+        let child = match environment
+            .scope(Self::execute_process(
+                &executor_type,
+                pool,
+                &config,
+                task_id,
+                attempt_id,
+                process_id,
+                worktree_path,
+            ))
+            .await
+        {
+            Ok(child) => child,
+            Err(e) => {
+                LogStreaming::purge_wal(process_id);
+                process_env::release_secrets(process_id);
+                return Err(e);
+            }
+        };
 
         // Register for monitoring
         Self::register_for_monitoring(app_state, process_id, attempt_id, &process_type, child)
@@ -576,6 +697,34 @@ impl ProcessService {
         Ok(())
     }
 
+    /// The environment a process is spawned with: the project's variables and, for the coding
+    /// agents and setup scripts of a sandboxed project, its sandbox
+    async fn process_environment(
+        pool: &SqlitePool,
+        app_state: &crate::app_state::AppState,
+        project_id: Uuid,
+        process_type: &ExecutionProcessType,
+        worktree_path: &str,
+    ) -> Result<ProcessEnvironment, TaskAttemptError> {
+        let project = Project::find_by_id(pool, project_id)
+            .await?
+            .ok_or(TaskAttemptError::ProjectNotFound)?;
+        let mut environment = ProjectEnvVar::environment_for_project(pool, project.id).await?;
+
+        if project.sandbox && !matches!(process_type, ExecutionProcessType::DevServer) {
+            let config = app_state.get_config().read().await.sandbox.clone();
+            environment.sandbox =
+                SandboxPolicy::for_worktree(worktree_path, project.sandbox_network, &config);
+            if let Some(sandbox) = &environment.sandbox {
+                sandbox.verify().map_err(|e| {
+                    TaskAttemptError::ValidationError(format!("Cannot sandbox process: {}", e))
+                })?;
+            }
+        }
+
+        Ok(environment)
+    }
+
     /// Load the execution context (task attempt and project) with validation
     async fn load_execution_context(
         pool: &SqlitePool,
@@ -626,18 +775,163 @@ impl ProcessService {
         .await
     }
 
-    /// Resolve executor configuration from string name
-    fn resolve_executor_config(executor_name: &Option<String>) -> crate::executor::ExecutorConfig {
-        match executor_name.as_ref().map(|s| s.as_str()) {
-            Some("claude") => crate::executor::ExecutorConfig::Claude,
-            Some("claude-plan") => crate::executor::ExecutorConfig::ClaudePlan,
-            Some("claude-code-router") => crate::executor::ExecutorConfig::ClaudeCodeRouter,
-            Some("amp") => crate::executor::ExecutorConfig::Amp,
-            Some("gemini") => crate::executor::ExecutorConfig::Gemini,
-            Some("charm-opencode") => crate::executor::ExecutorConfig::CharmOpencode,
-            Some("sst-opencode") => crate::executor::ExecutorConfig::SstOpencode,
-            _ => crate::executor::ExecutorConfig::Echo, // Default for "echo" or None
+    /// Extract token usage from a finished coding agent's logs and store it.
+    ///
+    /// Does nothing for other process types or executors that don't report usage.
+    pub async fn record_execution_usage(
+        pool: &SqlitePool,
+        config: &Config,
+        execution_process_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        let Some(process) = ExecutionProcess::find_by_id(pool, execution_process_id).await? else {
+            return Ok(());
+        };
+        if process.process_type != ExecutionProcessType::CodingAgent {
+            return Ok(());
+        }
+        let Some(executor_type) = &process.executor_type else {
+            return Ok(());
+        };
+        let Ok(executor) = executor_type.parse::<crate::executor::ExecutorConfig>() else {
+            return Ok(());
+        };
+        let stdout =
+            ExecutionOutput::read_to_string(pool, execution_process_id, OutputStream::Stdout)
+                .await?;
+
+        if let Some(usage) = executor.create_executor(config).extract_usage(&stdout) {
+            ExecutionUsage::upsert(pool, execution_process_id, &usage).await?;
+            debug!(
+                "Recorded usage for execution process {}: {} input / {} output tokens",
+                execution_process_id, usage.input_tokens, usage.output_tokens
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Store the plan presented by a finished plan mode execution as the attempt's next
+    /// plan version. Does nothing if the executor didn't present a plan.
+    pub async fn record_plan_presentation(
+        pool: &SqlitePool,
+        config: &Config,
+        execution_process_id: Uuid,
+    ) -> Result<Option<PlanPresentation>, sqlx::Error> {
+        let Some(process) = ExecutionProcess::find_by_id(pool, execution_process_id).await? else {
+            return Ok(None);
+        };
+        if process.process_type != ExecutionProcessType::CodingAgent {
+            return Ok(None);
+        }
+        let Some(executor_type) = &process.executor_type else {
+            return Ok(None);
+        };
+        let Ok(executor) = executor_type.parse::<crate::executor::ExecutorConfig>() else {
+            return Ok(None);
+        };
+        if !executor.is_plan_mode() {
+            return Ok(None);
+        }
+        if let Some(existing) =
+            PlanPresentation::find_by_execution_process_id(pool, execution_process_id).await?
+        {
+            return Ok(Some(existing));
+        }
+
+        // Normalize against the canonical worktree path, as the agent reports it
+        let worktree_path = match std::fs::canonicalize(&process.working_directory) {
+            Ok(canonical_path) => canonical_path.to_string_lossy().to_string(),
+            Err(_) => process.working_directory.clone(),
+        };
+        let stdout =
+            ExecutionOutput::read_to_string(pool, execution_process_id, OutputStream::Stdout)
+                .await?;
+        let Some(plan) = executor
+            .create_executor(config)
+            .extract_plan(&stdout, &worktree_path)
+            .filter(|plan| !plan.trim().is_empty())
+        else {
+            return Ok(None);
+        };
+
+        let presentation = PlanPresentation::create_version(
+            pool,
+            process.task_attempt_id,
+            Some(execution_process_id),
+            &plan,
+            PlanAuthor::Agent,
+        )
+        .await?;
+        info!(
+            "Recorded plan version {} for task attempt {}",
+            presentation.version, process.task_attempt_id
+        );
+
+        Ok(Some(presentation))
+    }
+
+    /// Send a message to the stdin of a running coding agent and record it in the transcript.
+    ///
+    /// The message is written in the executor's input format and the same line is appended
+    /// to stdout, so it shows up as a `UserMessage` entry wherever the logs are normalized.
+    pub async fn send_execution_input(
+        pool: &SqlitePool,
+        config: &Config,
+        execution_process: &ExecutionProcess,
+        message: &str,
+    ) -> Result<(), ExecutionInputError> {
+        let executor = execution_process
+            .executor_type
+            .as_deref()
+            .and_then(|executor_type| {
+                executor_type
+                    .parse::<crate::executor::ExecutorConfig>()
+                    .ok()
+            })
+            .map(|executor| executor.create_executor(config))
+            .ok_or(ExecutionInputError::NotSupported)?;
+        let line = executor
+            .format_input_message(message)
+            .ok_or(ExecutionInputError::NotSupported)?;
+
+        ExecutionInput::write_line(execution_process.id, &line).await?;
+
+        ExecutionProcess::append_stdout(pool, execution_process.id, &format!("{}\n", line)).await?;
+        EntryPatcher::new(
+            executor,
+            execution_process.working_directory.clone(),
+            execution_process.id,
+        )
+        .push_line(&line);
+
+        info!(
+            "Sent input to execution process {} ({} bytes)",
+            execution_process.id,
+            message.len()
+        );
+        Ok(())
+    }
+
+    /// Resolve the executor for a coding agent run.
+    ///
+    /// The attempt's explicit choice wins, then the project default, then the global config.
+    /// Unparseable names are logged and skipped rather than silently falling back to Echo.
+    pub fn resolve_executor_config(
+        attempt_executor: Option<&str>,
+        project_executor: Option<&str>,
+        global_executor: &crate::executor::ExecutorConfig,
+    ) -> crate::executor::ExecutorConfig {
+        for (source, name) in [("attempt", attempt_executor), ("project", project_executor)] {
+            let Some(name) = name.map(str::trim).filter(|name| !name.is_empty()) else {
+                continue;
+            };
+            match name.parse::<crate::executor::ExecutorConfig>() {
+                Ok(config) => return config,
+                Err(e) => tracing::warn!("Ignoring {} executor '{}': {}", source, name, e),
+            }
         }
+
+        global_executor.clone()
     }
 
     /// Create execution process database record
@@ -648,6 +942,7 @@ impl ProcessService {
         executor_type: &crate::executor::ExecutorType,
         process_type: ExecutionProcessType,
         worktree_path: &str,
+        sandbox: Option<&SandboxPolicy>,
     ) -> Result<ExecutionProcess, TaskAttemptError> {
         let (shell_cmd, shell_arg) = get_shell_command();
         let (command, args, executor_type_string) = match executor_type {
@@ -661,13 +956,25 @@ impl ProcessService {
                 Some(serde_json::to_string(&[shell_arg, "dev_server"]).unwrap()),
                 None, // Dev servers don't have an executor type
             ),
-            crate::executor::ExecutorType::CodingAgent { config, follow_up } => {
+            crate::executor::ExecutorType::TestScript(_) => (
+                shell_cmd.to_string(),
+                Some(serde_json::to_string(&[shell_arg, "test-script"]).unwrap()),
+                None,
+            ),
+            crate::executor::ExecutorType::CodingAgent {
+                config,
+                follow_up,
+                extra_args,
+            } => {
                 let command = if follow_up.is_some() {
                     "followup_executor".to_string()
                 } else {
                     "executor".to_string()
                 };
-                (command, None, Some(format!("{}", config)))
+                let args = extra_args
+                    .as_ref()
+                    .map(|args| serde_json::to_string(&[args]).unwrap());
+                (command, args, Some(format!("{}", config)))
             }
         };
 
@@ -678,6 +985,7 @@ impl ProcessService {
             command,
             args,
             working_directory: worktree_path.to_string(),
+            sandbox_policy: sandbox.map(|policy| serde_json::to_string(policy).unwrap()),
         };
 
         ExecutionProcess::create(pool, &create_process, process_id)
@@ -720,12 +1028,13 @@ impl ProcessService {
     async fn execute_process(
         executor_type: &crate::executor::ExecutorType,
         pool: &SqlitePool,
+        config: &Config,
         task_id: Uuid,
         attempt_id: Uuid,
         process_id: Uuid,
         worktree_path: &str,
     ) -> Result<command_group::AsyncGroupChild, TaskAttemptError> {
-        use crate::executors::{DevServerExecutor, SetupScriptExecutor};
+        use crate::executors::{DevServerExecutor, SetupScriptExecutor, TestScriptExecutor};
 
         let result = match executor_type {
             crate::executor::ExecutorType::SetupScript(script) => {
@@ -733,7 +1042,7 @@ impl ProcessService {
                     script: script.clone(),
                 };
                 executor
-                    .execute_streaming(pool, task_id, attempt_id, process_id, worktree_path)
+                    .execute_streaming(pool, config, task_id, attempt_id, process_id, worktree_path)
                     .await
             }
             crate::executor::ExecutorType::DevServer(script) => {
@@ -741,16 +1050,30 @@ impl ProcessService {
                     script: script.clone(),
                 };
                 executor
-                    .execute_streaming(pool, task_id, attempt_id, process_id, worktree_path)
+                    .execute_streaming(pool, config, task_id, attempt_id, process_id, worktree_path)
+                    .await
+            }
+            crate::executor::ExecutorType::TestScript(script) => {
+                let executor = TestScriptExecutor {
+                    script: script.clone(),
+                };
+                executor
+                    .execute_streaming(pool, config, task_id, attempt_id, process_id, worktree_path)
                     .await
             }
-            crate::executor::ExecutorType::CodingAgent { config, follow_up } => {
-                let executor = config.create_executor();
+            crate::executor::ExecutorType::CodingAgent {
+                config: executor_config,
+                follow_up,
+                extra_args,
+            } => {
+                let executor =
+                    executor_config.create_executor_with_args(config, extra_args.clone());
 
                 if let Some(ref follow_up_info) = follow_up {
                     executor
                         .execute_followup_streaming(
                             pool,
+                            config,
                             task_id,
                             attempt_id,
                             process_id,
@@ -761,7 +1084,14 @@ impl ProcessService {
                         .await
                 } else {
                     executor
-                        .execute_streaming(pool, task_id, attempt_id, process_id, worktree_path)
+                        .execute_streaming(
+                            pool,
+                            config,
+                            task_id,
+                            attempt_id,
+                            process_id,
+                            worktree_path,
+                        )
                         .await
                 }
             }
@@ -782,18 +1112,157 @@ impl ProcessService {
             ExecutionProcessType::SetupScript => crate::app_state::ExecutionType::SetupScript,
             ExecutionProcessType::CodingAgent => crate::app_state::ExecutionType::CodingAgent,
             ExecutionProcessType::DevServer => crate::app_state::ExecutionType::DevServer,
+            ExecutionProcessType::TestScript => crate::app_state::ExecutionType::TestScript,
         };
 
+        // Leading its own group, the child's pid is also the group's id. Its identity tells it
+        // apart from a process reusing the pid by the time a restarted server looks for it.
+        if let Some(pid) = child.id() {
+            #[cfg(unix)]
+            let (start_time, boot_id) = ProcessIdentity::of(pid as i32)
+                .map_or((None, None), |identity| {
+                    (Some(identity.start_time), Some(identity.boot_id))
+                });
+            #[cfg(not(unix))]
+            let (start_time, boot_id) = (None, None);
+            if let Err(e) = ExecutionProcess::set_process_ids(
+                &app_state.db_pool,
+                process_id,
+                pid.into(),
+                pid.into(),
+                start_time,
+                boot_id.as_deref(),
+            )
+            .await
+            {
+                tracing::error!(
+                    "Failed to record process ids of execution {}: {}",
+                    process_id,
+                    e
+                );
+            }
+        }
+
         app_state
             .add_running_execution(
                 process_id,
                 crate::app_state::RunningExecution {
                     task_attempt_id: attempt_id,
                     _execution_type: execution_type,
-                    child,
+                    child: ExecutionChild::Spawned(child),
                 },
             )
             .await;
+        app_state
+            .events
+            .publish_process_status(&app_state.db_pool, process_id)
+            .await;
+    }
+
+    /// Pick up an execution still running from before a server restart: stream the rest of
+    /// its output logs and monitor its process group until it exits
+    #[cfg(unix)]
+    pub async fn reattach_execution(
+        app_state: &crate::app_state::AppState,
+        process: &ExecutionProcess,
+        project_id: Uuid,
+        pgid: i32,
+        identity: ProcessIdentity,
+    ) -> Result<(), TaskAttemptError> {
+        let config = app_state.get_config().read().await.clone();
+        Self::stream_remaining_output(
+            &app_state.db_pool,
+            &config,
+            process,
+            project_id,
+            Some((pgid, identity.clone())),
+        )
+        .await?;
+
+        app_state
+            .add_running_execution(
+                process.id,
+                crate::app_state::RunningExecution {
+                    task_attempt_id: process.task_attempt_id,
+                    _execution_type: process.process_type.clone().into(),
+                    child: ExecutionChild::Reattached { pgid, identity },
+                },
+            )
+            .await;
+        Ok(())
+    }
+
+    /// Store what a process printed to its logs but isn't stored yet, streaming on while its
+    /// process group runs; `None` for a process that's gone. Stdout continues after the lines
+    /// already stored; stderr is stored in chunks that can't be matched against the log, so it
+    /// continues from the log's end.
+    #[cfg(unix)]
+    pub async fn stream_remaining_output(
+        pool: &SqlitePool,
+        config: &Config,
+        process: &ExecutionProcess,
+        project_id: Uuid,
+        group: Option<(i32, ProcessIdentity)>,
+    ) -> Result<tokio::task::JoinHandle<()>, TaskAttemptError> {
+        use std::io::SeekFrom;
+
+        use crate::utils::{output_log, process_group};
+
+        let log_error = |e: std::io::Error| {
+            TaskAttemptError::ValidationError(format!(
+                "Cannot read output logs of execution {}: {}",
+                process.id, e
+            ))
+        };
+
+        // Secrets are only known in memory, so register them again for redaction
+        let environment = ProjectEnvVar::environment_for_project(pool, project_id).await?;
+        process_env::register_secrets(process.id, environment.secrets);
+        if matches!(process.process_type, ExecutionProcessType::CodingAgent) {
+            LogStreaming::register(process.id);
+        }
+
+        let stored_lines =
+            ExecutionOutput::count_lines(pool, process.id, OutputStream::Stdout).await?;
+        let stdout_offset =
+            std::fs::File::open(output_log::log_path(process.id, OutputStream::Stdout))
+                .and_then(|log| output_log::line_offset(log, stored_lines))
+                .map_err(log_error)?;
+        let exited = move || {
+            group
+                .as_ref()
+                .is_none_or(|(pgid, identity)| !process_group::is_running_as(*pgid, identity))
+        };
+        let stdout = output_log::tail(
+            process.id,
+            OutputStream::Stdout,
+            SeekFrom::Start(stdout_offset),
+            exited.clone(),
+        )
+        .map_err(log_error)?;
+        let stderr = output_log::tail(process.id, OutputStream::Stderr, SeekFrom::End(0), exited)
+            .map_err(log_error)?;
+        let stdout = tokio::process::ChildStdout::from_std(std::process::ChildStdout::from(stdout))
+            .map_err(log_error)?;
+        let stderr = tokio::process::ChildStderr::from_std(std::process::ChildStderr::from(stderr))
+            .map_err(log_error)?;
+
+        tokio::spawn(stream_output_to_db(
+            stderr,
+            pool.clone(),
+            config.clone(),
+            process.task_attempt_id,
+            process.id,
+            false,
+        ));
+        Ok(tokio::spawn(stream_output_to_db(
+            stdout,
+            pool.clone(),
+            config.clone(),
+            process.task_attempt_id,
+            process.id,
+            true,
+        )))
     }
 
     /// Create execution process database record with delegation context
@@ -804,6 +1273,7 @@ impl ProcessService {
         _setup_script: &str,
         worktree_path: &str,
         delegation_context: serde_json::Value,
+        sandbox: Option<&SandboxPolicy>,
     ) -> Result<ExecutionProcess, TaskAttemptError> {
         let (shell_cmd, shell_arg) = get_shell_command();
 
@@ -822,6 +1292,7 @@ impl ProcessService {
             command: shell_cmd.to_string(),
             args: Some(args_with_delegation.to_string()),
             working_directory: worktree_path.to_string(),
+            sandbox_policy: sandbox.map(|policy| serde_json::to_string(policy).unwrap()),
         };
 
         ExecutionProcess::create(pool, &create_process, process_id)
@@ -833,6 +1304,7 @@ impl ProcessService {
     async fn execute_setup_script_process(
         setup_script: &str,
         pool: &SqlitePool,
+        config: &Config,
         task_id: Uuid,
         attempt_id: Uuid,
         process_id: Uuid,
@@ -845,8 +1317,46 @@ impl ProcessService {
         };
 
         executor
-            .execute_streaming(pool, task_id, attempt_id, process_id, worktree_path)
+            .execute_streaming(pool, config, task_id, attempt_id, process_id, worktree_path)
             .await
             .map_err(|e| TaskAttemptError::Git(git2::Error::from_str(&e.to_string())))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::ExecutorConfig;
+
+    #[test]
+    fn test_resolve_executor_prefers_attempt_then_project_then_global() {
+        let global = ExecutorConfig::Claude;
+
+        assert!(matches!(
+            ProcessService::resolve_executor_config(Some("amp"), Some("gemini"), &global),
+            ExecutorConfig::Amp
+        ));
+        assert!(matches!(
+            ProcessService::resolve_executor_config(None, Some("gemini"), &global),
+            ExecutorConfig::Gemini
+        ));
+        assert!(matches!(
+            ProcessService::resolve_executor_config(None, None, &global),
+            ExecutorConfig::Claude
+        ));
+    }
+
+    #[test]
+    fn test_resolve_executor_skips_blank_and_unknown_names() {
+        let global = ExecutorConfig::Claude;
+
+        assert!(matches!(
+            ProcessService::resolve_executor_config(Some(""), Some("gemini"), &global),
+            ExecutorConfig::Gemini
+        ));
+        assert!(matches!(
+            ProcessService::resolve_executor_config(Some("not-an-executor"), None, &global),
+            ExecutorConfig::Claude
+        ));
+    }
+}
@@ -2,7 +2,12 @@ use std::{env, sync::OnceLock};
 
 use directories::ProjectDirs;
 
+pub mod output_log;
 pub mod path;
+pub mod process_env;
+#[cfg(unix)]
+pub mod process_group;
+pub mod sandbox;
 pub mod shell;
 pub mod text;
 pub mod worktree_manager;
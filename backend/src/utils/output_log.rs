@@ -0,0 +1,220 @@
+//! Log files that executions write their output to
+//!
+//! A process spawned for an execution writes stdout and stderr to files rather than pipes, so
+//! it keeps running when the server stops. Tail threads copy the files into pipes the
+//! executors read as before, and a restarted server picks a process up by tailing its files
+//! again.
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+};
+
+use uuid::Uuid;
+
+use crate::models::execution_output::OutputStream;
+
+fn log_dir() -> PathBuf {
+    super::asset_dir().join("process-logs")
+}
+
+pub fn log_path(execution_process_id: Uuid, stream: OutputStream) -> PathBuf {
+    log_dir().join(format!("{}.{}.log", execution_process_id, stream.as_str()))
+}
+
+/// Create the stdout and stderr log files of an execution about to be spawned
+pub fn create(execution_process_id: Uuid) -> io::Result<(File, File)> {
+    fs::create_dir_all(log_dir())?;
+    Ok((
+        create_log(&log_path(execution_process_id, OutputStream::Stdout))?,
+        create_log(&log_path(execution_process_id, OutputStream::Stderr))?,
+    ))
+}
+
+/// Logs hold output before its secrets are redacted, so only the owner may read them
+fn create_log(path: &Path) -> io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)
+}
+
+/// Remove the logs of executions no tail is reading, e.g. those left behind by a server
+/// that stopped before it had copied them
+pub fn remove_leftovers(tailed: impl Fn(Uuid) -> bool) -> io::Result<()> {
+    remove_leftovers_in(&log_dir(), tailed)
+}
+
+fn remove_leftovers_in(dir: &Path, tailed: impl Fn(Uuid) -> bool) -> io::Result<()> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    for entry in entries {
+        let path = entry?.path();
+        let execution_process_id = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.split('.').next())
+            .and_then(|id| Uuid::parse_str(id).ok());
+        if execution_process_id.is_some_and(|id| !tailed(id)) {
+            fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Byte offset just past the first `lines` lines of a log, or its end if it has fewer
+pub fn line_offset(log: impl Read, lines: usize) -> io::Result<u64> {
+    let mut reader = BufReader::new(log);
+    let mut line = Vec::new();
+    let mut offset = 0;
+    for _ in 0..lines {
+        line.clear();
+        match reader.read_until(b'\n', &mut line)? {
+            0 => break,
+            read => offset += read as u64,
+        }
+    }
+    Ok(offset)
+}
+
+#[cfg(unix)]
+pub use tail::{attach, tail};
+
+#[cfg(unix)]
+mod tail {
+    use std::{
+        io::{self, Seek, SeekFrom},
+        os::fd::OwnedFd,
+        time::Duration,
+    };
+
+    use command_group::AsyncGroupChild;
+    use tokio::process::{ChildStderr, ChildStdout};
+    use uuid::Uuid;
+
+    use super::log_path;
+    use crate::{models::execution_output::OutputStream, utils::process_group};
+
+    /// How often a tail looks for output added to its log
+    const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+    /// Copy a log from `start` into a pipe until `exited` says the process is done, then
+    /// remove the log. The pipe reads like the output of the process itself.
+    pub fn tail(
+        execution_process_id: Uuid,
+        stream: OutputStream,
+        start: SeekFrom,
+        exited: impl Fn() -> bool + Send + 'static,
+    ) -> io::Result<OwnedFd> {
+        let path = log_path(execution_process_id, stream);
+        let mut log = std::fs::File::open(&path)?;
+        log.seek(start)?;
+        let (reader, mut writer) = io::pipe()?;
+
+        std::thread::Builder::new()
+            .name(format!("{}-{}", stream.as_str(), execution_process_id))
+            .spawn(move || {
+                loop {
+                    // Checked before copying, so everything written up to the exit is copied
+                    let exited = exited();
+                    // Failing to write means nobody reads the output any more
+                    if io::copy(&mut log, &mut writer).is_err() || exited {
+                        break;
+                    }
+                    std::thread::sleep(POLL_INTERVAL);
+                }
+                drop(writer);
+                if let Err(e) = std::fs::remove_file(&path) {
+                    tracing::warn!("Failed to remove output log {}: {}", path.display(), e);
+                }
+            })?;
+
+        Ok(reader.into())
+    }
+
+    /// Give a child spawned with its output in log files pipes that tail those files, where
+    /// its stdout and stderr pipes would be
+    pub fn attach(child: &mut AsyncGroupChild, execution_process_id: Uuid) -> io::Result<()> {
+        let pgid = child
+            .id()
+            .ok_or_else(|| io::Error::other("child exited before its output was attached"))?
+            as i32;
+        let stdout = tail(
+            execution_process_id,
+            OutputStream::Stdout,
+            SeekFrom::Start(0),
+            move || process_group::has_exited(pgid),
+        )?;
+        let stderr = tail(
+            execution_process_id,
+            OutputStream::Stderr,
+            SeekFrom::Start(0),
+            move || process_group::has_exited(pgid),
+        )?;
+
+        child.inner().stdout = Some(ChildStdout::from_std(std::process::ChildStdout::from(
+            stdout,
+        ))?);
+        child.inner().stderr = Some(ChildStderr::from_std(std::process::ChildStderr::from(
+            stderr,
+        ))?);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_offset_skips_stored_lines() {
+        let log = "first\nsecond\nthird, still being written";
+        assert_eq!(line_offset(log.as_bytes(), 0).unwrap(), 0);
+        assert_eq!(line_offset(log.as_bytes(), 2).unwrap(), 13);
+        assert_eq!(line_offset(log.as_bytes(), 5).unwrap(), log.len() as u64);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_create_log_is_only_readable_by_its_owner() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let log = create_log(&dir.path().join("log")).unwrap();
+        assert_eq!(log.metadata().unwrap().permissions().mode() & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_remove_leftovers_leaves_no_unread_secret_behind() {
+        let secret = "sk-live-1234";
+        let dir = tempfile::tempdir().unwrap();
+        let tailed_id = Uuid::new_v4();
+        let leftover_id = Uuid::new_v4();
+        for id in [tailed_id, leftover_id] {
+            for stream in ["stdout", "stderr"] {
+                let path = dir.path().join(format!("{}.{}.log", id, stream));
+                fs::write(path, format!("token={}\n", secret)).unwrap();
+            }
+        }
+
+        remove_leftovers_in(dir.path(), |id| id == tailed_id).unwrap();
+
+        let mut remaining = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        remaining.sort();
+        assert_eq!(
+            remaining,
+            [
+                format!("{}.stderr.log", tailed_id),
+                format!("{}.stdout.log", tailed_id)
+            ]
+        );
+    }
+}
@@ -0,0 +1,321 @@
+//! Project environment variables and sandbox for spawned processes, and redaction of their
+//! secrets
+//!
+//! Executors build their own commands, so the environment travels as a task-local set
+//! around the spawn. Secret values are registered per execution process so its output
+//! can be redacted before it's stored or streamed.
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    future::Future,
+    sync::{Mutex, RwLock},
+    time::Duration,
+};
+
+use command_group::{AsyncCommandGroup, AsyncGroupChild};
+use serde_json::Value;
+use tokio::process::Command;
+use uuid::Uuid;
+
+#[cfg(unix)]
+use super::output_log;
+use super::sandbox::SandboxPolicy;
+use crate::models::execution_output::OutputStream;
+
+/// Replaces secret values in stored and streamed output
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Shorter secrets would redact unrelated output
+const MIN_REDACTED_LENGTH: usize = 4;
+
+/// How long a finished process keeps its secrets, so output flushed after the process is
+/// marked completed is still redacted
+const SECRET_RETENTION_AFTER_COMPLETION: Duration = Duration::from_secs(30);
+
+lazy_static::lazy_static! {
+    static ref PROCESS_SECRETS: RwLock<HashMap<Uuid, Vec<String>>> = RwLock::new(HashMap::new());
+    /// Output held back because it ends with the start of a secret, per process and stream
+    static ref HELD_OUTPUT: Mutex<HashMap<(Uuid, OutputStream), String>> =
+        Mutex::new(HashMap::new());
+}
+
+tokio::task_local! {
+    static PROCESS_ENV: ProcessEnvironment;
+}
+
+/// Environment applied to every process spawned for a project
+#[derive(Debug, Clone, Default)]
+pub struct ProcessEnvironment {
+    pub vars: Vec<(String, String)>,
+    /// Values that must not appear in stored output
+    pub secrets: Vec<String>,
+    /// Confinement for the spawned processes, if the project is sandboxed
+    pub sandbox: Option<SandboxPolicy>,
+    /// Execution the spawned process runs as, whose output then goes through log files
+    pub execution_process_id: Option<Uuid>,
+}
+
+impl ProcessEnvironment {
+    /// Run `future` with this environment applied to the commands it spawns
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        PROCESS_ENV.scope(self, future).await
+    }
+}
+
+/// Add the current project's environment variables and sandbox to a command about to be spawned
+fn apply_process_env(command: &mut Command) {
+    // Commands spawned outside a project scope (e.g. in tests) keep the inherited environment
+    let _ = PROCESS_ENV.try_with(|env| {
+        command.envs(env.vars.iter().map(|(key, value)| (key, value)));
+        if let Some(sandbox) = &env.sandbox {
+            sandbox.apply(command);
+        }
+    });
+}
+
+/// Spawn a command in its own process group with the current project's environment and
+/// sandbox. An execution's process writes its output to log files, so it survives a server
+/// restart; the child still gets stdout and stderr pipes to read it from.
+pub fn group_spawn(command: &mut Command) -> std::io::Result<AsyncGroupChild> {
+    apply_process_env(command);
+
+    #[cfg(unix)]
+    if let Ok(Some(execution_process_id)) = PROCESS_ENV.try_with(|env| env.execution_process_id) {
+        let (stdout, stderr) = output_log::create(execution_process_id)?;
+        command.stdout(stdout).stderr(stderr);
+        let mut child = command.group_spawn()?;
+        if let Err(e) = output_log::attach(&mut child, execution_process_id) {
+            child.start_kill().ok();
+            return Err(e);
+        }
+        return Ok(child);
+    }
+
+    command.group_spawn()
+}
+
+/// Remember the secrets whose values must be redacted from a process's output
+pub fn register_secrets(execution_process_id: Uuid, secrets: Vec<String>) {
+    let mut secrets = secrets
+        .into_iter()
+        .filter(|secret| secret.len() >= MIN_REDACTED_LENGTH)
+        .collect::<Vec<_>>();
+    if secrets.is_empty() {
+        return;
+    }
+    // Longest first, so a secret containing another is replaced whole
+    secrets.sort_by_key(|secret| std::cmp::Reverse(secret.len()));
+    PROCESS_SECRETS
+        .write()
+        .unwrap()
+        .insert(execution_process_id, secrets);
+}
+
+/// Forget a finished process's secrets once its last output has been flushed
+pub fn release_secrets(execution_process_id: Uuid) {
+    if !PROCESS_SECRETS
+        .read()
+        .unwrap()
+        .contains_key(&execution_process_id)
+    {
+        return;
+    }
+    tokio::spawn(async move {
+        tokio::time::sleep(SECRET_RETENTION_AFTER_COMPLETION).await;
+        PROCESS_SECRETS
+            .write()
+            .unwrap()
+            .remove(&execution_process_id);
+        HELD_OUTPUT
+            .lock()
+            .unwrap()
+            .retain(|(id, _), _| *id != execution_process_id);
+    });
+}
+
+/// Redact a process's secrets from the next piece of one of its output streams.
+///
+/// A secret can be split across pieces, so output ending with the start of a secret is
+/// held back and redacted together with the next piece. `flush_process_output` returns
+/// whatever is still held once the stream ends.
+pub fn redact_process_output(
+    execution_process_id: Uuid,
+    stream: OutputStream,
+    text: &str,
+) -> Cow<'_, str> {
+    let secrets = PROCESS_SECRETS.read().unwrap();
+    let Some(secrets) = secrets.get(&execution_process_id) else {
+        return Cow::Borrowed(text);
+    };
+
+    let mut held_output = HELD_OUTPUT.lock().unwrap();
+    let key = (execution_process_id, stream);
+    let mut output = held_output.remove(&key).unwrap_or_default();
+    output.push_str(text);
+    let mut output = redact(&output, secrets).into_owned();
+
+    let held_len = unfinished_secret_len(&output, secrets);
+    if held_len > 0 {
+        held_output.insert(key, output.split_off(output.len() - held_len));
+    }
+    Cow::Owned(output)
+}
+
+/// Output of a finished stream that was held back waiting for the rest of a secret
+pub fn flush_process_output(execution_process_id: Uuid, stream: OutputStream) -> Option<String> {
+    HELD_OUTPUT
+        .lock()
+        .unwrap()
+        .remove(&(execution_process_id, stream))
+}
+
+/// How many bytes at the end of `text` could be the start of one of a process's secrets,
+/// for callers that split output themselves and should not split inside one
+pub fn unfinished_process_secret_len(execution_process_id: Uuid, text: &str) -> usize {
+    PROCESS_SECRETS
+        .read()
+        .unwrap()
+        .get(&execution_process_id)
+        .map_or(0, |secrets| unfinished_secret_len(text, secrets))
+}
+
+/// Length of the longest suffix of `text` that starts, but doesn't complete, a secret
+fn unfinished_secret_len(text: &str, secrets: &[String]) -> usize {
+    secrets
+        .iter()
+        .filter_map(|secret| {
+            (1..secret.len().min(text.len() + 1))
+                .rev()
+                .filter(|&len| secret.is_char_boundary(len))
+                .find(|&len| text.ends_with(&secret[..len]))
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Redact a process's secrets from every string in a JSON value
+pub fn redact_process_value(execution_process_id: Uuid, value: &mut Value) {
+    if let Some(secrets) = PROCESS_SECRETS.read().unwrap().get(&execution_process_id) {
+        redact_value(value, secrets);
+    }
+}
+
+fn redact_value(value: &mut Value, secrets: &[String]) {
+    match value {
+        Value::String(text) => {
+            if let Cow::Owned(redacted) = redact(text, secrets) {
+                *text = redacted;
+            }
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| redact_value(item, secrets)),
+        Value::Object(map) => map
+            .values_mut()
+            .for_each(|item| redact_value(item, secrets)),
+        _ => {}
+    }
+}
+
+/// Replace every occurrence of the given secrets, leaving the text untouched if none occur
+pub fn redact<'a>(text: &'a str, secrets: &[String]) -> Cow<'a, str> {
+    let mut redacted = Cow::Borrowed(text);
+    for secret in secrets {
+        if !secret.is_empty() && redacted.contains(secret.as_str()) {
+            redacted = Cow::Owned(redacted.replace(secret.as_str(), REDACTED));
+        }
+    }
+    redacted
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_redact_replaces_every_secret() {
+        let secrets = vec!["sk-live-1234".to_string(), "hunter22".to_string()];
+        assert_eq!(
+            redact(
+                "key=sk-live-1234 pass=hunter22 again sk-live-1234",
+                &secrets
+            ),
+            "key=[REDACTED] pass=[REDACTED] again [REDACTED]"
+        );
+        assert!(matches!(
+            redact("nothing to see", &secrets),
+            Cow::Borrowed("nothing to see")
+        ));
+    }
+
+    #[test]
+    fn test_redact_value_reaches_nested_strings() {
+        let secrets = vec!["sk-live-1234".to_string()];
+        let mut value = json!({
+            "op": "add",
+            "value": { "content": "export KEY=sk-live-1234", "tags": ["sk-live-1234", 3] },
+        });
+        redact_value(&mut value, &secrets);
+        assert_eq!(
+            value,
+            json!({
+                "op": "add",
+                "value": { "content": "export KEY=[REDACTED]", "tags": ["[REDACTED]", 3] },
+            })
+        );
+    }
+
+    #[test]
+    fn test_short_secrets_are_not_registered() {
+        let process_id = Uuid::new_v4();
+        register_secrets(
+            process_id,
+            vec!["ab".to_string(), "long-secret".to_string()],
+        );
+        assert_eq!(
+            redact_process_output(process_id, OutputStream::Stdout, "ab long-secret"),
+            "ab [REDACTED]"
+        );
+    }
+
+    #[test]
+    fn test_secret_split_across_pieces_is_redacted() {
+        let process_id = Uuid::new_v4();
+        register_secrets(process_id, vec!["sk-live-1234".to_string()]);
+
+        // The start of the secret waits for the next piece instead of being stored
+        assert_eq!(
+            redact_process_output(process_id, OutputStream::Stdout, "key=sk-li"),
+            "key="
+        );
+        // Other streams are unaffected
+        assert_eq!(
+            redact_process_output(process_id, OutputStream::Stderr, "sk"),
+            ""
+        );
+        assert_eq!(
+            redact_process_output(process_id, OutputStream::Stdout, "ve-1234 done\n"),
+            "[REDACTED] done\n"
+        );
+
+        // What merely looked like a secret is returned when the stream ends
+        assert_eq!(
+            flush_process_output(process_id, OutputStream::Stderr),
+            Some("sk".to_string())
+        );
+        assert_eq!(flush_process_output(process_id, OutputStream::Stdout), None);
+    }
+
+    #[test]
+    fn test_unfinished_secret_len() {
+        let secrets = vec!["sk-live-1234".to_string(), "hunter22".to_string()];
+        assert_eq!(unfinished_secret_len("token sk-live", &secrets), 7);
+        assert_eq!(unfinished_secret_len("password hunt", &secrets), 4);
+        // Complete secrets are redacted, not held
+        assert_eq!(unfinished_secret_len("hunter22", &secrets), 0);
+        assert_eq!(unfinished_secret_len("nothing\n", &secrets), 0);
+    }
+}
@@ -0,0 +1,117 @@
+//! Checking on and stopping the process groups executions are spawned in
+//!
+//! Every execution leads its own process group, so a group outlives the server that spawned
+//! it and can still be signalled as a whole after a restart. Its pid may have been reused by
+//! then, so a group is only taken for the execution's while its leader's identity matches.
+
+use std::time::Duration;
+
+use nix::{
+    errno::Errno,
+    sys::signal::{killpg, Signal},
+    unistd::Pid,
+};
+
+/// How long a group gets to exit after each signal before the next, harsher one
+const SIGNAL_GRACE_PERIOD: Duration = Duration::from_secs(2);
+
+/// What tells a process apart from a later one given the same pid, e.g. after a reboot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessIdentity {
+    /// Clock ticks after boot the process started at, field 22 of `/proc/<pid>/stat`
+    pub start_time: i64,
+    /// Changes on every boot
+    pub boot_id: String,
+}
+
+impl ProcessIdentity {
+    /// Identity of a running process. Only Linux has a `/proc` to read it from.
+    pub fn of(pid: i32) -> Option<Self> {
+        let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+        let boot_id = std::fs::read_to_string("/proc/sys/kernel/random/boot_id").ok()?;
+        Some(Self {
+            start_time: parse_start_time(&stat)?,
+            boot_id: boot_id.trim().to_string(),
+        })
+    }
+}
+
+/// Field 22 of a `/proc/<pid>/stat` line. The command name before it is in parentheses and
+/// may contain spaces and parentheses itself, so fields are counted from the last `)`.
+fn parse_start_time(stat: &str) -> Option<i64> {
+    let fields = &stat[stat.rfind(')')? + 1..];
+    // `fields` starts with field 3
+    fields.split_whitespace().nth(22 - 3)?.parse().ok()
+}
+
+/// Whether the group led by `pgid` is still running as the process `identity` was taken of.
+/// A group whose leader exited can't be told apart from one that reused its id, so it
+/// counts as gone.
+pub fn is_running_as(pgid: i32, identity: &ProcessIdentity) -> bool {
+    ProcessIdentity::of(pgid).as_ref() == Some(identity) && is_alive(pgid)
+}
+
+/// Whether any process of the group is still around
+pub fn is_alive(pgid: i32) -> bool {
+    // EPERM means the group exists but belongs to someone else, which still counts
+    killpg(Pid::from_raw(pgid), None) != Err(Errno::ESRCH)
+}
+
+/// Whether the group leader `pid` has exited
+pub fn has_exited(pid: i32) -> bool {
+    // A child of this server stays waitable until the monitor reaps it, so only peek at it
+    let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+    let flags = libc::WEXITED | libc::WNOHANG | libc::WNOWAIT;
+    if unsafe { libc::waitid(libc::P_PID, pid as libc::id_t, &mut info, flags) } == 0 {
+        return info.si_signo != 0;
+    }
+    // Not a child (it was spawned before a restart) or already reaped: done once the group is
+    !is_alive(pid)
+}
+
+/// Stop every process of the group, escalating from SIGINT to SIGKILL
+pub async fn terminate(pgid: i32) -> nix::Result<()> {
+    for signal in [Signal::SIGINT, Signal::SIGTERM, Signal::SIGKILL] {
+        match killpg(Pid::from_raw(pgid), signal) {
+            Err(Errno::ESRCH) => return Ok(()),
+            result => result?,
+        }
+        tokio::time::sleep(SIGNAL_GRACE_PERIOD).await;
+        if !is_alive(pgid) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_start_time_skips_command_with_spaces_and_parens() {
+        let stat = "4242 (my (odd) cmd) S 1 4242 4242 0 -1 4194560 117 0 0 0 0 0 0 0 20 0 1 0 \
+                    987654 3366912 230 18446744073709551615";
+        assert_eq!(parse_start_time(stat), Some(987654));
+        assert_eq!(parse_start_time("4242 (cmd) S 1"), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_identity_of_running_process() {
+        let pid = std::process::id() as i32;
+        let identity = ProcessIdentity::of(pid).unwrap();
+        assert_eq!(ProcessIdentity::of(pid), Some(identity.clone()));
+
+        let rebooted = ProcessIdentity {
+            boot_id: "another-boot".to_string(),
+            ..identity.clone()
+        };
+        assert_ne!(ProcessIdentity::of(pid), Some(rebooted));
+        let reused = ProcessIdentity {
+            start_time: identity.start_time + 1,
+            ..identity
+        };
+        assert_ne!(ProcessIdentity::of(pid), Some(reused));
+    }
+}
@@ -0,0 +1,245 @@
+//! Confinement of coding agents and setup scripts to their worktree
+//!
+//! On Linux, Landlock limits writes to the worktree, the repository's git directory, the temp
+//! dir and the configured cache dirs, while the rest of the filesystem stays readable. Without
+//! network access the process also moves into its own user and network namespace, which has
+//! nothing but a loopback interface that is down.
+
+use std::{io, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+use ts_rs::TS;
+
+use crate::models::config::SandboxConfig;
+
+/// Writable on top of the policy's directories, so output can be discarded
+const DEVICE_PATHS: &[&str] = &["/dev/null", "/dev/tty"];
+
+/// Restrictions a sandboxed process runs under
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SandboxPolicy {
+    /// Paths the process may write beneath; everything else is read-only
+    pub writable_paths: Vec<String>,
+    pub network: bool,
+}
+
+impl SandboxPolicy {
+    /// Policy confining a process to `worktree_path`, or `None` where sandboxing isn't supported
+    pub fn for_worktree(
+        worktree_path: &str,
+        network: bool,
+        config: &SandboxConfig,
+    ) -> Option<Self> {
+        if !cfg!(target_os = "linux") {
+            tracing::warn!("Sandboxing is only supported on Linux, running unsandboxed");
+            return None;
+        }
+
+        let mut paths = vec![PathBuf::from(worktree_path)];
+        // Commits write to the main repository's object store, outside the worktree. A
+        // worktree's git dir names the repository's in its `commondir` file.
+        if let Ok(repo) = git2::Repository::open(worktree_path) {
+            let git_dir = repo.path();
+            paths.push(match std::fs::read_to_string(git_dir.join("commondir")) {
+                Ok(common_dir) => git_dir.join(common_dir.trim()),
+                Err(_) => git_dir.to_path_buf(),
+            });
+        }
+        paths.push(std::env::temp_dir());
+        // A file gets a rule of its own rather than its directory's, see `SandboxConfig`
+        paths.extend(config.cache_dirs.iter().map(|dir| expand_home(dir)));
+        paths.extend(DEVICE_PATHS.iter().map(PathBuf::from));
+
+        let mut writable_paths = Vec::new();
+        for path in paths {
+            // Rules attach to existing files, so a missing path couldn't be created anyway
+            if let Ok(path) = path.canonicalize() {
+                let path = path.to_string_lossy().into_owned();
+                if !writable_paths.contains(&path) {
+                    writable_paths.push(path);
+                }
+            }
+        }
+
+        Some(Self {
+            writable_paths,
+            network,
+        })
+    }
+
+    /// Check the running kernel can enforce this policy, before anything is spawned with it
+    pub fn verify(&self) -> io::Result<()> {
+        #[cfg(target_os = "linux")]
+        linux::Sandbox::new(self)?;
+        Ok(())
+    }
+
+    /// Confine a command about to be spawned to this policy
+    #[cfg(target_os = "linux")]
+    pub fn apply(&self, command: &mut Command) {
+        let mut sandbox = Some(linux::Sandbox::new(self));
+        // SAFETY: entering the sandbox only makes syscalls on state prepared before the fork
+        unsafe {
+            command.pre_exec(move || match sandbox.take() {
+                Some(Ok(sandbox)) => sandbox.enter(),
+                Some(Err(e)) => Err(e),
+                None => Err(io::Error::from_raw_os_error(libc::EINVAL)),
+            });
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn apply(&self, _command: &mut Command) {}
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ if path == "~" => dirs::home_dir().unwrap_or_default(),
+        _ => PathBuf::from(path),
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::{ffi::CStr, io};
+
+    use landlock::{
+        path_beneath_rules, Access, AccessFs, CompatLevel, Compatible, Ruleset, RulesetAttr,
+        RulesetCreated, RulesetCreatedAttr, RulesetError, ABI,
+    };
+
+    use super::SandboxPolicy;
+
+    /// The first ABI that lets files move between directories under the same rule
+    const LANDLOCK_ABI: ABI = ABI::V2;
+
+    /// A policy prepared in the parent, ready to be entered by the forked child
+    pub struct Sandbox {
+        ruleset: RulesetCreated,
+        /// uid and gid maps for a new user namespace, when the network is cut off
+        id_maps: Option<(String, String)>,
+    }
+
+    impl Sandbox {
+        pub fn new(policy: &SandboxPolicy) -> io::Result<Self> {
+            let ruleset = ruleset(policy).map_err(|e| {
+                io::Error::other(format!("Landlock (Linux 5.19+) is unavailable: {}", e))
+            })?;
+            let id_maps = (!policy.network).then(|| {
+                // SAFETY: getuid and getgid always succeed
+                let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+                (format!("{uid} {uid} 1"), format!("{gid} {gid} 1"))
+            });
+            Ok(Self { ruleset, id_maps })
+        }
+
+        /// Runs in the child between fork and exec, so it must not allocate
+        pub fn enter(self) -> io::Result<()> {
+            if let Some((uid_map, gid_map)) = &self.id_maps {
+                // SAFETY: the child is single-threaded, as unshare requires for a user namespace
+                if unsafe { libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) } != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                // Keep the user's own ids, so files they create stay theirs
+                write_proc_file(c"/proc/self/setgroups", b"deny")?;
+                write_proc_file(c"/proc/self/uid_map", uid_map.as_bytes())?;
+                write_proc_file(c"/proc/self/gid_map", gid_map.as_bytes())?;
+            }
+            self.ruleset
+                .restrict_self()
+                .map_err(|_| io::Error::from_raw_os_error(libc::EPERM))?;
+            Ok(())
+        }
+    }
+
+    fn ruleset(policy: &SandboxPolicy) -> Result<RulesetCreated, RulesetError> {
+        Ruleset::default()
+            .set_compatibility(CompatLevel::HardRequirement)
+            .handle_access(AccessFs::from_all(LANDLOCK_ABI))?
+            .create()?
+            .add_rules(path_beneath_rules(["/"], AccessFs::from_read(LANDLOCK_ABI)))?
+            .add_rules(path_beneath_rules(
+                &policy.writable_paths,
+                AccessFs::from_all(LANDLOCK_ABI),
+            ))
+    }
+
+    fn write_proc_file(path: &CStr, contents: &[u8]) -> io::Result<()> {
+        // SAFETY: `path` is nul-terminated and `contents` outlives the write
+        unsafe {
+            let fd = libc::open(path.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC);
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let written = libc::write(fd, contents.as_ptr().cast(), contents.len());
+            libc::close(fd);
+            if written != contents.len() as isize {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_home() {
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(expand_home("~/.npm"), home.join(".npm"));
+        assert_eq!(expand_home("~"), home);
+        assert_eq!(expand_home("/opt/cache"), PathBuf::from("/opt/cache"));
+        assert_eq!(expand_home("~other/x"), PathBuf::from("~other/x"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_policy_keeps_existing_writable_paths() {
+        let worktree = tempfile::tempdir().unwrap();
+        let cache = tempfile::tempdir().unwrap();
+        let config = SandboxConfig {
+            cache_dirs: vec![
+                cache.path().to_string_lossy().into_owned(),
+                "/does/not/exist".to_string(),
+            ],
+        };
+
+        let policy =
+            SandboxPolicy::for_worktree(worktree.path().to_str().unwrap(), false, &config).unwrap();
+
+        let canonical =
+            |path: &std::path::Path| path.canonicalize().unwrap().to_string_lossy().into_owned();
+        assert_eq!(policy.writable_paths[0], canonical(worktree.path()));
+        assert!(policy.writable_paths.contains(&canonical(cache.path())));
+        assert!(policy.writable_paths.contains(&"/dev/null".to_string()));
+        assert!(!policy.writable_paths.iter().any(|p| p.contains("does")));
+        assert!(!policy.network);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_policy_allows_cached_files_but_not_their_directory() {
+        let worktree = tempfile::tempdir().unwrap();
+        let home = tempfile::tempdir().unwrap();
+        let state_file = home.path().join(".agent.json");
+        std::fs::write(&state_file, "{}").unwrap();
+        let config = SandboxConfig {
+            cache_dirs: vec![state_file.to_string_lossy().into_owned()],
+        };
+
+        let policy =
+            SandboxPolicy::for_worktree(worktree.path().to_str().unwrap(), true, &config).unwrap();
+
+        // The file can be rewritten in place, but nothing can be created next to it, so
+        // saving it by renaming a temporary file over it fails
+        let canonical =
+            |path: &std::path::Path| path.canonicalize().unwrap().to_string_lossy().into_owned();
+        assert!(policy.writable_paths.contains(&canonical(&state_file)));
+        assert!(!policy.writable_paths.contains(&canonical(home.path())));
+    }
+}
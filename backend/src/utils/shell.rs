@@ -17,3 +17,24 @@ pub fn get_shell_command() -> (&'static str, &'static str) {
         }
     }
 }
+
+/// Append user-provided CLI arguments to a shell command line.
+///
+/// The arguments are passed through verbatim so they can contain quoting
+/// understood by the shell; blank values leave the command untouched.
+pub fn append_extra_args(command: &str, extra_args: Option<&str>) -> String {
+    match extra_args.map(str::trim) {
+        Some(args) if !args.is_empty() => format!("{} {}", command, args),
+        _ => command.to_string(),
+    }
+}
+
+/// Quote a value so the platform shell passes it through as a single argument
+pub fn shell_quote(value: &str) -> String {
+    let (shell_cmd, _) = get_shell_command();
+    if shell_cmd == "cmd" {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
@@ -13,6 +13,7 @@ import type {
   EditorType,
   ExecutionProcess,
   ExecutionProcessSummary,
+  ProjectEvent,
   Task,
   TaskAttempt,
   TaskAttemptState,
@@ -33,6 +34,7 @@ import {
   TaskSelectedAttemptContext,
 } from './taskDetailsContext.ts';
 import type { AttemptData } from '@/lib/types.ts';
+import useProjectEvents from '@/hooks/useProjectEvents';
 
 const TaskDetailsProvider: FC<{
   task: TaskWithAttemptStatus;
@@ -288,24 +290,24 @@ const TaskDetailsProvider: FC<{
     );
   }, [selectedAttempt, attemptData.processes, isStopping]);
 
-  useEffect(() => {
-    if (!isAttemptRunning || !task) return;
+  // Refresh the selected attempt when its processes, status or PR change,
+  // including a queued attempt starting on its own
+  const refreshSelectedAttempt = useCallback(() => {
+    if (selectedAttempt) {
+      fetchAttemptData(selectedAttempt.id, selectedAttempt.task_id);
+      fetchExecutionState(selectedAttempt.id, selectedAttempt.task_id);
+    }
+  }, [selectedAttempt, fetchAttemptData, fetchExecutionState]);
 
-    const interval = setInterval(() => {
-      if (selectedAttempt) {
-        fetchAttemptData(selectedAttempt.id, selectedAttempt.task_id);
-        fetchExecutionState(selectedAttempt.id, selectedAttempt.task_id);
+  const handleBoardEvent = useCallback(
+    ({ event }: ProjectEvent) => {
+      if ('attempt_id' in event && event.attempt_id === selectedAttempt?.id) {
+        refreshSelectedAttempt();
       }
-    }, 5000);
-
-    return () => clearInterval(interval);
-  }, [
-    isAttemptRunning,
-    task,
-    selectedAttempt,
-    fetchAttemptData,
-    fetchExecutionState,
-  ]);
+    },
+    [selectedAttempt?.id, refreshSelectedAttempt]
+  );
+  useProjectEvents(projectId, handleBoardEvent, refreshSelectedAttempt);
 
   // Refresh diff when coding agent is running and making changes
   useEffect(() => {
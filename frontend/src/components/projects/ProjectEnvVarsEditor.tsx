@@ -0,0 +1,138 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Loader2, Plus, Trash2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Input } from '@/components/ui/input';
+import { projectsApi } from '@/lib/api';
+import type { ProjectEnvVar } from 'shared/types';
+
+interface ProjectEnvVarsEditorProps {
+  projectId?: string;
+}
+
+export function ProjectEnvVarsEditor({ projectId }: ProjectEnvVarsEditorProps) {
+  const [vars, setVars] = useState<ProjectEnvVar[]>([]);
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [saved, setSaved] = useState(false);
+
+  const fetchVars = useCallback(async () => {
+    if (!projectId) return;
+    setLoading(true);
+    try {
+      setVars(await projectsApi.getEnvVars(projectId));
+    } catch (err) {
+      console.error('Failed to fetch environment variables:', err);
+    } finally {
+      setLoading(false);
+    }
+  }, [projectId]);
+
+  useEffect(() => {
+    fetchVars();
+  }, [fetchVars]);
+
+  const updateVar = (index: number, update: Partial<ProjectEnvVar>) => {
+    setSaved(false);
+    setVars((prev) =>
+      prev.map((v, i) => (i === index ? { ...v, ...update } : v))
+    );
+  };
+
+  const handleSave = async () => {
+    if (!projectId) return;
+    setSaving(true);
+    setError(null);
+    try {
+      setVars(await projectsApi.updateEnvVars(projectId, vars));
+      setSaved(true);
+    } catch (err: any) {
+      setError(err.message || 'Failed to save environment variables');
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  if (loading) {
+    return (
+      <div className="flex items-center justify-center py-8">
+        <Loader2 className="h-8 w-8 animate-spin" />
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-4">
+      <div className="flex justify-between items-center">
+        <h3 className="text-lg font-semibold">Environment Variables</h3>
+        <Button
+          size="sm"
+          onClick={() => {
+            setSaved(false);
+            setVars((prev) => [...prev, { key: '', value: '', secret: false }]);
+          }}
+        >
+          <Plus className="h-4 w-4 mr-2" />
+          Add Variable
+        </Button>
+      </div>
+      <p className="text-sm text-muted-foreground">
+        Passed to setup scripts, dev servers and coding agents. Secret values
+        are never shown again and are redacted from process output; leave a
+        secret empty to keep its current value.
+      </p>
+
+      {vars.length === 0 ? (
+        <div className="text-center py-8 text-muted-foreground">
+          No environment variables yet.
+        </div>
+      ) : (
+        <div className="space-y-2">
+          {vars.map((v, index) => (
+            <div key={index} className="flex items-center gap-2">
+              <Input
+                value={v.key}
+                placeholder="KEY"
+                className="font-mono"
+                onChange={(e) => updateVar(index, { key: e.target.value })}
+              />
+              <Input
+                value={v.value ?? ''}
+                type={v.secret ? 'password' : 'text'}
+                placeholder={v.secret ? '••••••••' : 'value'}
+                className="font-mono"
+                onChange={(e) => updateVar(index, { value: e.target.value })}
+              />
+              <label className="flex items-center gap-1 text-sm">
+                <Checkbox
+                  checked={v.secret}
+                  onCheckedChange={(secret) => updateVar(index, { secret })}
+                />
+                Secret
+              </label>
+              <Button
+                variant="ghost"
+                size="sm"
+                onClick={() => {
+                  setSaved(false);
+                  setVars((prev) => prev.filter((_, i) => i !== index));
+                }}
+              >
+                <Trash2 className="h-4 w-4" />
+              </Button>
+            </div>
+          ))}
+        </div>
+      )}
+
+      {error && <div className="text-sm text-destructive">{error}</div>}
+      <div className="flex justify-end items-center gap-2">
+        {saved && <span className="text-sm text-muted-foreground">Saved</span>}
+        <Button size="sm" disabled={saving} onClick={handleSave}>
+          {saving ? 'Saving...' : 'Save Variables'}
+        </Button>
+      </div>
+    </div>
+  );
+}
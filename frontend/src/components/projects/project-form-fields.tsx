@@ -1,8 +1,15 @@
 import { Label } from '@/components/ui/label';
 import { Input } from '@/components/ui/input';
 import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
 import { Alert, AlertDescription } from '@/components/ui/alert';
 import { AlertCircle, Folder } from 'lucide-react';
+import {
+  DEFAULT_PROMPT_TEMPLATE,
+  EXECUTOR_LABELS,
+  EXECUTOR_TYPES,
+  PROMPT_TEMPLATE_VARIABLES,
+} from 'shared/types';
 
 interface ProjectFormFieldsProps {
   isEditing: boolean;
@@ -21,6 +28,24 @@ interface ProjectFormFieldsProps {
   setSetupScript: (script: string) => void;
   devScript: string;
   setDevScript: (script: string) => void;
+  testScript: string;
+  setTestScript: (script: string) => void;
+  maxFixIterations: number;
+  setMaxFixIterations: (iterations: number) => void;
+  maxConcurrentAgents: number;
+  setMaxConcurrentAgents: (agents: number) => void;
+  executor: string;
+  setExecutor: (executor: string) => void;
+  executorArgs: string;
+  setExecutorArgs: (args: string) => void;
+  sandbox: boolean;
+  setSandbox: (sandbox: boolean) => void;
+  sandboxNetwork: boolean;
+  setSandboxNetwork: (network: boolean) => void;
+  promptTemplate: string;
+  setPromptTemplate: (template: string) => void;
+  instructions: string;
+  setInstructions: (instructions: string) => void;
   error: string;
 }
 
@@ -41,6 +66,24 @@ export function ProjectFormFields({
   setSetupScript,
   devScript,
   setDevScript,
+  testScript,
+  setTestScript,
+  maxFixIterations,
+  setMaxFixIterations,
+  maxConcurrentAgents,
+  setMaxConcurrentAgents,
+  executor,
+  setExecutor,
+  executorArgs,
+  setExecutorArgs,
+  sandbox,
+  setSandbox,
+  sandboxNetwork,
+  setSandboxNetwork,
+  promptTemplate,
+  setPromptTemplate,
+  instructions,
+  setInstructions,
   error,
 }: ProjectFormFieldsProps) {
   return (
@@ -206,6 +249,153 @@ export function ProjectFormFields({
         </p>
       </div>
 
+      <div className="space-y-2">
+        <Label htmlFor="test-script">Test Script (Optional)</Label>
+        <textarea
+          id="test-script"
+          value={testScript}
+          onChange={(e) => setTestScript(e.target.value)}
+          placeholder="#!/bin/bash&#10;npm test"
+          rows={4}
+          className="w-full px-3 py-2 border border-input bg-background text-foreground rounded-md resize-vertical focus:outline-none focus:ring-2 focus:ring-ring"
+        />
+        <p className="text-sm text-muted-foreground">
+          This script runs after each coding agent finishes to check the
+          attempt's changes. Its result is shown when comparing fan-out
+          attempts.
+        </p>
+      </div>
+
+      <div className="space-y-2">
+        <Label htmlFor="max-fix-iterations">Automatic Fix Attempts</Label>
+        <Input
+          id="max-fix-iterations"
+          type="number"
+          min={0}
+          max={10}
+          value={maxFixIterations}
+          onChange={(e) =>
+            setMaxFixIterations(Math.max(0, parseInt(e.target.value) || 0))
+          }
+          disabled={!testScript.trim()}
+        />
+        <p className="text-sm text-muted-foreground">
+          When the test script fails, its output is sent back to the coding
+          agent as a follow-up, up to this many times in a row. Set to 0 to
+          turn this off.
+        </p>
+      </div>
+
+      <div className="space-y-2">
+        <Label htmlFor="max-concurrent-agents">Max Concurrent Agents</Label>
+        <Input
+          id="max-concurrent-agents"
+          type="number"
+          min={0}
+          value={maxConcurrentAgents}
+          onChange={(e) =>
+            setMaxConcurrentAgents(Math.max(0, parseInt(e.target.value) || 0))
+          }
+        />
+        <p className="text-sm text-muted-foreground">
+          Attempts and follow-ups started beyond this many running attempts
+          wait in the execution queue. Set to 0 to only apply the global limit.
+        </p>
+      </div>
+
+      <div className="space-y-2">
+        <Label htmlFor="executor">Default Executor (Optional)</Label>
+        <select
+          id="executor"
+          value={executor}
+          onChange={(e) => setExecutor(e.target.value)}
+          className="w-full px-3 py-2 border border-input bg-background text-foreground rounded-md focus:outline-none focus:ring-2 focus:ring-ring"
+        >
+          <option value="">Use global default</option>
+          {EXECUTOR_TYPES.map((type) => (
+            <option key={type} value={type}>
+              {EXECUTOR_LABELS[type]}
+            </option>
+          ))}
+        </select>
+        <p className="text-sm text-muted-foreground">
+          Executor used for attempts in this project unless one is picked
+          explicitly when starting an attempt.
+        </p>
+      </div>
+
+      <div className="space-y-2">
+        <Label htmlFor="executor-args">Executor Arguments (Optional)</Label>
+        <Input
+          id="executor-args"
+          type="text"
+          value={executorArgs}
+          onChange={(e) => setExecutorArgs(e.target.value)}
+          placeholder="--model opus --max-turns 20"
+        />
+        <p className="text-sm text-muted-foreground">
+          Extra CLI arguments appended to the coding agent command for this
+          project.
+        </p>
+      </div>
+
+      <div className="space-y-2">
+        <div className="flex items-center space-x-2">
+          <Checkbox id="sandbox" checked={sandbox} onCheckedChange={setSandbox} />
+          <Label htmlFor="sandbox">Sandbox coding agents (Linux only)</Label>
+        </div>
+        <p className="text-sm text-muted-foreground">
+          Coding agents and setup scripts can only write to the attempt's
+          worktree, the temp directory and the cache directories from the
+          config file. Claude can't save changes to ~/.claude.json while
+          sandboxed.
+        </p>
+        {sandbox && (
+          <div className="flex items-center space-x-2">
+            <Checkbox
+              id="sandbox-network"
+              checked={sandboxNetwork}
+              onCheckedChange={setSandboxNetwork}
+            />
+            <Label htmlFor="sandbox-network">Allow network access</Label>
+          </div>
+        )}
+      </div>
+
+      <div className="space-y-2">
+        <Label htmlFor="instructions">Project Instructions (Optional)</Label>
+        <textarea
+          id="instructions"
+          value={instructions}
+          onChange={(e) => setInstructions(e.target.value)}
+          placeholder="Run the tests before finishing. Don't touch the generated files."
+          rows={3}
+          className="w-full px-3 py-2 border border-input bg-background text-foreground rounded-md resize-vertical focus:outline-none focus:ring-2 focus:ring-ring"
+        />
+        <p className="text-sm text-muted-foreground">
+          Added to the prompt of every task in this project.
+        </p>
+      </div>
+
+      <div className="space-y-2">
+        <Label htmlFor="prompt-template">Prompt Template (Optional)</Label>
+        <textarea
+          id="prompt-template"
+          value={promptTemplate}
+          onChange={(e) => setPromptTemplate(e.target.value)}
+          placeholder={DEFAULT_PROMPT_TEMPLATE}
+          rows={6}
+          className="w-full px-3 py-2 border border-input bg-background text-foreground rounded-md resize-vertical font-mono text-sm focus:outline-none focus:ring-2 focus:ring-ring"
+        />
+        <p className="text-sm text-muted-foreground">
+          Leave empty to use the template from the settings. Use{' '}
+          <code>{'{{variable}}'}</code> for a value and{' '}
+          <code>{'{{#variable}}...{{/variable}}'}</code> for text that is only
+          kept when the value isn't empty. Variables:{' '}
+          {PROMPT_TEMPLATE_VARIABLES.join(', ')}.
+        </p>
+      </div>
+
       {error && (
         <Alert variant="destructive">
           <AlertCircle className="h-4 w-4" />
@@ -11,6 +11,7 @@ import {
 import { Tabs, TabsContent, TabsList, TabsTrigger } from '@/components/ui/tabs';
 import { FolderPicker } from '@/components/ui/folder-picker';
 import { TaskTemplateManager } from '@/components/TaskTemplateManager';
+import { ProjectEnvVarsEditor } from './ProjectEnvVarsEditor';
 import { ProjectFormFields } from './project-form-fields';
 import { CreateProject, Project, UpdateProject } from 'shared/types';
 import { projectsApi } from '@/lib/api';
@@ -32,6 +33,27 @@ export function ProjectForm({
   const [gitRepoPath, setGitRepoPath] = useState(project?.git_repo_path || '');
   const [setupScript, setSetupScript] = useState(project?.setup_script ?? '');
   const [devScript, setDevScript] = useState(project?.dev_script ?? '');
+  const [testScript, setTestScript] = useState(project?.test_script ?? '');
+  const [maxFixIterations, setMaxFixIterations] = useState(
+    project?.max_fix_iterations ?? 0
+  );
+  const [maxConcurrentAgents, setMaxConcurrentAgents] = useState(
+    project?.max_concurrent_agents ?? 0
+  );
+  const [executor, setExecutor] = useState(project?.executor ?? '');
+  const [executorArgs, setExecutorArgs] = useState(
+    project?.executor_args ?? ''
+  );
+  const [sandbox, setSandbox] = useState(project?.sandbox ?? false);
+  const [sandboxNetwork, setSandboxNetwork] = useState(
+    project?.sandbox_network ?? true
+  );
+  const [promptTemplate, setPromptTemplate] = useState(
+    project?.prompt_template ?? ''
+  );
+  const [instructions, setInstructions] = useState(
+    project?.instructions ?? ''
+  );
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState('');
   const [showFolderPicker, setShowFolderPicker] = useState(false);
@@ -48,11 +70,29 @@ export function ProjectForm({
       setGitRepoPath(project.git_repo_path || '');
       setSetupScript(project.setup_script ?? '');
       setDevScript(project.dev_script ?? '');
+      setTestScript(project.test_script ?? '');
+      setMaxFixIterations(project.max_fix_iterations);
+      setMaxConcurrentAgents(project.max_concurrent_agents);
+      setExecutor(project.executor ?? '');
+      setExecutorArgs(project.executor_args ?? '');
+      setSandbox(project.sandbox);
+      setSandboxNetwork(project.sandbox_network);
+      setPromptTemplate(project.prompt_template ?? '');
+      setInstructions(project.instructions ?? '');
     } else {
       setName('');
       setGitRepoPath('');
       setSetupScript('');
       setDevScript('');
+      setTestScript('');
+      setMaxFixIterations(0);
+      setMaxConcurrentAgents(0);
+      setExecutor('');
+      setExecutorArgs('');
+      setSandbox(false);
+      setSandboxNetwork(true);
+      setPromptTemplate('');
+      setInstructions('');
     }
   }, [project]);
 
@@ -93,6 +133,16 @@ export function ProjectForm({
           git_repo_path: finalGitRepoPath,
           setup_script: setupScript.trim() || null,
           dev_script: devScript.trim() || null,
+          test_script: testScript,
+          max_fix_iterations: maxFixIterations,
+          max_concurrent_agents: maxConcurrentAgents,
+          // An empty string clears the project override
+          executor,
+          executor_args: executorArgs.trim(),
+          sandbox,
+          sandbox_network: sandboxNetwork,
+          prompt_template: promptTemplate,
+          instructions,
         };
 
         try {
@@ -108,6 +158,15 @@ export function ProjectForm({
           use_existing_repo: repoMode === 'existing',
           setup_script: setupScript.trim() || null,
           dev_script: devScript.trim() || null,
+          test_script: testScript.trim() || null,
+          max_fix_iterations: maxFixIterations,
+          max_concurrent_agents: maxConcurrentAgents,
+          executor: executor || null,
+          executor_args: executorArgs.trim() || null,
+          sandbox,
+          sandbox_network: sandboxNetwork,
+          prompt_template: promptTemplate.trim() || null,
+          instructions: instructions.trim() || null,
         };
 
         try {
@@ -137,11 +196,29 @@ export function ProjectForm({
       setGitRepoPath(project.git_repo_path || '');
       setSetupScript(project.setup_script ?? '');
       setDevScript(project.dev_script ?? '');
+      setTestScript(project.test_script ?? '');
+      setMaxFixIterations(project.max_fix_iterations);
+      setMaxConcurrentAgents(project.max_concurrent_agents);
+      setExecutor(project.executor ?? '');
+      setExecutorArgs(project.executor_args ?? '');
+      setSandbox(project.sandbox);
+      setSandboxNetwork(project.sandbox_network);
+      setPromptTemplate(project.prompt_template ?? '');
+      setInstructions(project.instructions ?? '');
     } else {
       setName('');
       setGitRepoPath('');
       setSetupScript('');
       setDevScript('');
+      setTestScript('');
+      setMaxFixIterations(0);
+      setMaxConcurrentAgents(0);
+      setExecutor('');
+      setExecutorArgs('');
+      setSandbox(false);
+      setSandboxNetwork(true);
+      setPromptTemplate('');
+      setInstructions('');
     }
     setParentPath('');
     setFolderName('');
@@ -167,9 +244,10 @@ export function ProjectForm({
 
         {isEditing ? (
           <Tabs defaultValue="general" className="w-full -mt-2">
-            <TabsList className="grid w-full grid-cols-2 mb-4">
+            <TabsList className="grid w-full grid-cols-3 mb-4">
               <TabsTrigger value="general">General</TabsTrigger>
               <TabsTrigger value="templates">Task Templates</TabsTrigger>
+              <TabsTrigger value="environment">Environment</TabsTrigger>
             </TabsList>
             <TabsContent value="general" className="space-y-4">
               <form onSubmit={handleSubmit} className="space-y-4">
@@ -190,6 +268,24 @@ export function ProjectForm({
                   setSetupScript={setSetupScript}
                   devScript={devScript}
                   setDevScript={setDevScript}
+                  testScript={testScript}
+                  setTestScript={setTestScript}
+                  maxFixIterations={maxFixIterations}
+                  setMaxFixIterations={setMaxFixIterations}
+                  maxConcurrentAgents={maxConcurrentAgents}
+                  setMaxConcurrentAgents={setMaxConcurrentAgents}
+                  executor={executor}
+                  setExecutor={setExecutor}
+                  executorArgs={executorArgs}
+                  setExecutorArgs={setExecutorArgs}
+                  sandbox={sandbox}
+                  setSandbox={setSandbox}
+                  sandboxNetwork={sandboxNetwork}
+                  setSandboxNetwork={setSandboxNetwork}
+                  promptTemplate={promptTemplate}
+                  setPromptTemplate={setPromptTemplate}
+                  instructions={instructions}
+                  setInstructions={setInstructions}
                   error={error}
                 />
                 <DialogFooter>
@@ -213,6 +309,9 @@ export function ProjectForm({
             <TabsContent value="templates" className="mt-0 pt-0">
               <TaskTemplateManager projectId={project?.id} />
             </TabsContent>
+            <TabsContent value="environment" className="mt-0 pt-0">
+              <ProjectEnvVarsEditor projectId={project?.id} />
+            </TabsContent>
           </Tabs>
         ) : (
           <form onSubmit={handleSubmit} className="space-y-4">
@@ -233,6 +332,24 @@ export function ProjectForm({
               setSetupScript={setSetupScript}
               devScript={devScript}
               setDevScript={setDevScript}
+              testScript={testScript}
+              setTestScript={setTestScript}
+              maxFixIterations={maxFixIterations}
+              setMaxFixIterations={setMaxFixIterations}
+              maxConcurrentAgents={maxConcurrentAgents}
+              setMaxConcurrentAgents={setMaxConcurrentAgents}
+              executor={executor}
+              setExecutor={setExecutor}
+              executorArgs={executorArgs}
+              setExecutorArgs={setExecutorArgs}
+              sandbox={sandbox}
+              setSandbox={setSandbox}
+              sandboxNetwork={sandboxNetwork}
+              setSandboxNetwork={setSandboxNetwork}
+              promptTemplate={promptTemplate}
+              setPromptTemplate={setPromptTemplate}
+              instructions={instructions}
+              setInstructions={setInstructions}
               error={error}
             />
             <DialogFooter>
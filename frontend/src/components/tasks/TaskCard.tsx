@@ -10,6 +10,7 @@ import {
 import { KanbanCard } from '@/components/ui/shadcn-io/kanban';
 import {
   CheckCircle,
+  Clock,
   Edit,
   Loader2,
   MoreHorizontal,
@@ -97,6 +98,10 @@ export function TaskCard({
             {task.has_in_progress_attempt && (
               <Loader2 className="h-3 w-3 animate-spin text-blue-500" />
             )}
+            {/* Queued Indicator */}
+            {task.has_queued_attempt && !task.has_in_progress_attempt && (
+              <Clock className="h-3 w-3 text-muted-foreground" />
+            )}
             {/* Merged Indicator */}
             {task.has_merged_attempt && (
               <CheckCircle className="h-3 w-3 text-green-500" />
@@ -46,16 +46,24 @@ const getEntryIcon = (entryType: NormalizedEntryType) => {
   if (entryType.type === 'error_message') {
     return <AlertCircle className="h-4 w-4 text-red-600" />;
   }
+  if (entryType.type === 'tool_result') {
+    return entryType.is_error ? (
+      <AlertCircle className="h-4 w-4 text-red-400" />
+    ) : (
+      <Terminal className="h-4 w-4 text-gray-400" />
+    );
+  }
   if (entryType.type === 'tool_use') {
     const { action_type, tool_name } = entryType;
 
     // Special handling for TODO tools
     if (
-      tool_name &&
-      (tool_name.toLowerCase() === 'todowrite' ||
-        tool_name.toLowerCase() === 'todoread' ||
-        tool_name.toLowerCase() === 'todo_write' ||
-        tool_name.toLowerCase() === 'todo_read')
+      action_type.action === 'todo_update' ||
+      (tool_name &&
+        (tool_name.toLowerCase() === 'todowrite' ||
+          tool_name.toLowerCase() === 'todoread' ||
+          tool_name.toLowerCase() === 'todo_write' ||
+          tool_name.toLowerCase() === 'todo_read'))
     ) {
       return <CheckSquare className="h-4 w-4 text-purple-600" />;
     }
@@ -63,7 +71,10 @@ const getEntryIcon = (entryType: NormalizedEntryType) => {
     if (action_type.action === 'file_read') {
       return <Eye className="h-4 w-4 text-orange-600" />;
     }
-    if (action_type.action === 'file_write') {
+    if (
+      action_type.action === 'file_write' ||
+      action_type.action === 'file_edit'
+    ) {
       return <Edit className="h-4 w-4 text-red-600" />;
     }
     if (action_type.action === 'command_run') {
@@ -100,14 +111,23 @@ const getContentClassName = (entryType: NormalizedEntryType) => {
     return `${baseClasses} text-red-600 font-mono bg-red-50 dark:bg-red-950/20 px-2 py-1 rounded`;
   }
 
+  // Tool output can be long, so keep it compact and scrollable
+  if (entryType.type === 'tool_result') {
+    const color = entryType.is_error
+      ? 'text-red-600 dark:text-red-400'
+      : 'text-muted-foreground';
+    return `${baseClasses} font-mono text-xs ${color} max-h-48 overflow-y-auto`;
+  }
+
   // Special styling for TODO lists
   if (
     entryType.type === 'tool_use' &&
-    entryType.tool_name &&
-    (entryType.tool_name.toLowerCase() === 'todowrite' ||
-      entryType.tool_name.toLowerCase() === 'todoread' ||
-      entryType.tool_name.toLowerCase() === 'todo_write' ||
-      entryType.tool_name.toLowerCase() === 'todo_read')
+    (entryType.action_type.action === 'todo_update' ||
+      (entryType.tool_name &&
+        (entryType.tool_name.toLowerCase() === 'todowrite' ||
+          entryType.tool_name.toLowerCase() === 'todoread' ||
+          entryType.tool_name.toLowerCase() === 'todo_write' ||
+          entryType.tool_name.toLowerCase() === 'todo_read')))
   ) {
     return `${baseClasses} font-mono text-purple-700 dark:text-purple-300 bg-purple-50 dark:bg-purple-950/20 px-2 py-1 rounded`;
   }
@@ -142,6 +162,26 @@ const parseFilePathFromContent = (content: string): string | null => {
   return null;
 };
 
+const formatDuration = (ms: number): string => {
+  if (ms < 1000) return `${ms}ms`;
+  if (ms < 60_000) return `${(ms / 1000).toFixed(1)}s`;
+  const minutes = Math.floor(ms / 60_000);
+  const seconds = Math.round((ms % 60_000) / 1000);
+  return `${minutes}m ${seconds}s`;
+};
+
+// How long a tool call ran, or how long any other entry took to arrive when noticeable
+const getDurationLabel = (entry: NormalizedEntry): string | null => {
+  // Entries streamed live carry no durations yet
+  if (typeof entry.tool_duration_ms === 'number') {
+    return `ran ${formatDuration(entry.tool_duration_ms)}`;
+  }
+  if (typeof entry.duration_ms === 'number' && entry.duration_ms >= 1000) {
+    return `+${formatDuration(entry.duration_ms)}`;
+  }
+  return null;
+};
+
 // Helper function to determine if a tool call modifies files
 const isFileModificationToolCall = (
   entryType: NormalizedEntryType
@@ -150,8 +190,11 @@ const isFileModificationToolCall = (
     return false;
   }
 
-  // Check for direct file write action
-  if (entryType.action_type.action === 'file_write') {
+  // Check for direct file write or edit action
+  if (
+    entryType.action_type.action === 'file_write' ||
+    entryType.action_type.action === 'file_edit'
+  ) {
     return true;
   }
 
@@ -181,7 +224,10 @@ const extractFilePathFromToolCall = (entry: NormalizedEntry): string | null => {
   const { action_type, tool_name } = entry.entry_type;
 
   // Direct path extraction from action_type
-  if (action_type.action === 'file_write') {
+  if (
+    action_type.action === 'file_write' ||
+    action_type.action === 'file_edit'
+  ) {
     return action_type.path || null;
   }
 
@@ -309,6 +355,8 @@ function DisplayConversationEntry({ entry, index, diffDeletable }: Props) {
   const shouldShowDiff =
     isFileModification && incrementalDiff && incrementalDiff.files.length > 0;
 
+  const durationLabel = getDurationLabel(entry);
+
   return (
     <div key={index}>
       <div className="flex items-start gap-3">
@@ -373,6 +421,14 @@ function DisplayConversationEntry({ entry, index, diffDeletable }: Props) {
             </div>
           )}
         </div>
+        {durationLabel && (
+          <div
+            className="flex-shrink-0 mt-1 text-xs text-muted-foreground tabular-nums"
+            title={entry.timestamp || undefined}
+          >
+            {durationLabel}
+          </div>
+        )}
       </div>
 
       {/* Render incremental diff card inline after file modification entries */}
@@ -1,5 +1,5 @@
 import { useContext } from 'react';
-import { MessageSquare } from 'lucide-react';
+import { Clock, MessageSquare } from 'lucide-react';
 import { NormalizedConversationViewer } from '@/components/tasks/TaskDetails/LogsTab/NormalizedConversationViewer.tsx';
 import {
   TaskAttemptDataContext,
@@ -55,6 +55,7 @@ function LogsTab() {
   const isSetupComplete = executionState.execution_state === 'SetupComplete';
   const isSetupFailed = executionState.execution_state === 'SetupFailed';
   const isSetupStopped = executionState.execution_state === 'SetupStopped';
+  const isSetupTimedOut = executionState.execution_state === 'SetupTimedOut';
   const isCodingAgentRunning =
     executionState.execution_state === 'CodingAgentRunning';
   const isCodingAgentComplete =
@@ -63,9 +64,28 @@ function LogsTab() {
     executionState.execution_state === 'CodingAgentFailed';
   const isCodingAgentStopped =
     executionState.execution_state === 'CodingAgentStopped';
+  const isCodingAgentTimedOut =
+    executionState.execution_state === 'CodingAgentTimedOut';
   const isComplete = executionState.execution_state === 'Complete';
   const hasChanges = executionState.has_changes;
 
+  // While waiting for a free coding agent slot
+  if (executionState.execution_state === 'Queued') {
+    return (
+      <div className="text-center py-8 text-muted-foreground">
+        <Clock className="h-12 w-12 mx-auto mb-4 opacity-50" />
+        <p className="text-lg font-medium mb-2">
+          Queued
+          {executionState.queue_position !== null &&
+            ` (position ${executionState.queue_position})`}
+        </p>
+        <p className="text-sm">
+          The attempt starts once a coding agent slot is free
+        </p>
+      </div>
+    );
+  }
+
   // When setup script is running, show setup execution stdio
   if (isSetupRunning) {
     return (
@@ -76,8 +96,8 @@ function LogsTab() {
     );
   }
 
-  // When setup failed or was stopped
-  if (isSetupFailed || isSetupStopped) {
+  // When setup failed, was stopped or timed out
+  if (isSetupFailed || isSetupStopped || isSetupTimedOut) {
     let setupProcess = executionState.setup_process_id
       ? attemptData.runningProcessDetails[executionState.setup_process_id]
       : Object.values(attemptData.runningProcessDetails).find(
@@ -96,11 +116,7 @@ function LogsTab() {
         );
 
         if (!setupProcess) {
-          setupProcess = {
-            ...setupSummary,
-            stdout: null,
-            stderr: null,
-          } as any;
+          setupProcess = setupSummary as any;
         }
       }
     }
@@ -109,15 +125,25 @@ function LogsTab() {
       <div className="h-full overflow-y-auto">
         <div className="mb-4">
           <p
-            className={`text-lg font-semibold mb-2 ${isSetupFailed ? 'text-destructive' : ''}`}
+            className={`text-lg font-semibold mb-2 ${isSetupFailed ? 'text-destructive' : isSetupTimedOut ? 'text-orange-600' : ''}`}
           >
-            {isSetupFailed ? 'Setup Script Failed' : 'Setup Script Stopped'}
+            {isSetupFailed
+              ? 'Setup Script Failed'
+              : isSetupTimedOut
+                ? 'Setup Script Timed Out'
+                : 'Setup Script Stopped'}
           </p>
           {isSetupFailed && (
             <p className="text-muted-foreground mb-4">
               The setup script encountered an error. Error details below:
             </p>
           )}
+          {isSetupTimedOut && (
+            <p className="text-muted-foreground mb-4">
+              The setup script exceeded its configured time limit and was
+              stopped.
+            </p>
+          )}
         </div>
 
         {setupProcess && (
@@ -127,12 +153,13 @@ function LogsTab() {
     );
   }
 
-  // When coding agent is in any state (running, complete, failed, stopped)
+  // When coding agent is in any state (running, complete, failed, stopped, timed out)
   if (
     isCodingAgentRunning ||
     isCodingAgentComplete ||
     isCodingAgentFailed ||
     isCodingAgentStopped ||
+    isCodingAgentTimedOut ||
     hasChanges
   ) {
     return <Conversation />;
@@ -145,6 +172,7 @@ function LogsTab() {
     !isCodingAgentComplete &&
     !isCodingAgentFailed &&
     !isCodingAgentStopped &&
+    !isCodingAgentTimedOut &&
     !hasChanges
   ) {
     return (
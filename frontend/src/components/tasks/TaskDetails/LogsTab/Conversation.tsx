@@ -130,7 +130,7 @@ function Conversation() {
     () =>
       visibleEntries.map((entry, index) => (
         <ConversationEntry
-          key={entry.entry.timestamp || index}
+          key={`${entry.processId}-${entry.entryIndex}`}
           idx={index}
           item={entry}
           handleConversationUpdate={handleConversationUpdate}
@@ -176,7 +176,7 @@ function Conversation() {
     visibleCount,
   ]);
 
-  // Check if we should show the status banner - only if the most recent process failed/stopped
+  // Check if we should show the status banner - only if the most recent process failed/stopped/timed out
   const getMostRecentProcess = () => {
     if (followUpLogs.length > 0) {
       // Sort by creation time or use last in array as most recent
@@ -189,7 +189,8 @@ function Conversation() {
   const showStatusBanner =
     mostRecentProcess &&
     (mostRecentProcess.status === 'failed' ||
-      mostRecentProcess.status === 'killed');
+      mostRecentProcess.status === 'killed' ||
+      mostRecentProcess.status === 'timedout');
 
   return (
     <div
@@ -240,12 +241,16 @@ function Conversation() {
           >
             {mostRecentProcess.status === 'failed'
               ? 'Coding Agent Failed'
-              : 'Coding Agent Stopped'}
+              : mostRecentProcess.status === 'timedout'
+                ? 'Coding Agent Timed Out'
+                : 'Coding Agent Stopped'}
           </p>
           <p className="text-muted-foreground">
             {mostRecentProcess.status === 'failed'
               ? 'The coding agent encountered an error.'
-              : 'The coding agent was stopped.'}
+              : mostRecentProcess.status === 'timedout'
+                ? 'The coding agent exceeded its configured time limit and was stopped.'
+                : 'The coding agent was stopped.'}
           </p>
         </div>
       )}
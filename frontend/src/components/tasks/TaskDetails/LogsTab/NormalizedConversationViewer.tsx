@@ -80,7 +80,7 @@ export function NormalizedConversationViewer({
       <div className="space-y-2">
         {displayEntries.map((entry, index) => (
           <DisplayConversationEntry
-            key={entry.timestamp || index}
+            key={index}
             entry={entry}
             index={index}
             diffDeletable={diffDeletable}
@@ -1,5 +1,6 @@
 import { useEffect, useMemo, useRef } from 'react';
 import { ExecutionProcess } from 'shared/types.ts';
+import useProcessOutput from '@/hooks/useProcessOutput';
 
 type Props = {
   setupProcessId: string | null;
@@ -9,13 +10,6 @@ type Props = {
 function SetupScriptRunning({ setupProcessId, runningProcessDetails }: Props) {
   const setupScrollRef = useRef<HTMLDivElement>(null);
 
-  // Auto-scroll setup script logs to bottom
-  useEffect(() => {
-    if (setupScrollRef.current) {
-      setupScrollRef.current.scrollTop = setupScrollRef.current.scrollHeight;
-    }
-  }, [runningProcessDetails]);
-
   const setupProcess = useMemo(
     () =>
       setupProcessId
@@ -26,6 +20,15 @@ function SetupScriptRunning({ setupProcessId, runningProcessDetails }: Props) {
     [setupProcessId, runningProcessDetails]
   );
 
+  const output = useProcessOutput(setupProcess?.id);
+
+  // Auto-scroll setup script logs to bottom
+  useEffect(() => {
+    if (setupScrollRef.current) {
+      setupScrollRef.current.scrollTop = setupScrollRef.current.scrollHeight;
+    }
+  }, [output]);
+
   return (
     <div ref={setupScrollRef} className="h-full overflow-y-auto">
       <div className="mb-4">
@@ -37,7 +40,7 @@ function SetupScriptRunning({ setupProcessId, runningProcessDetails }: Props) {
 
       {setupProcess && (
         <div className="font-mono text-sm whitespace-pre-wrap text-muted-foreground">
-          {[setupProcess.stdout || '', setupProcess.stderr || '']
+          {[output.stdout, output.stderr]
             .filter(Boolean)
             .join('\n') || 'Waiting for setup script output...'}
         </div>
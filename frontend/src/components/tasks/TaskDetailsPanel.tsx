@@ -1,6 +1,8 @@
 import { useEffect, useState } from 'react';
 import TaskDetailsHeader from './TaskDetailsHeader';
 import { TaskFollowUpSection } from './TaskFollowUpSection';
+import { TaskToolApprovals } from './TaskToolApprovals';
+import { TaskPlanEditor } from './TaskPlanEditor';
 import { EditorSelectionDialog } from './EditorSelectionDialog';
 import {
   getBackdropClasses,
@@ -106,6 +108,8 @@ export function TaskDetailsPanel({
                 )}
               </div>
 
+              <TaskToolApprovals />
+              <TaskPlanEditor />
               <TaskFollowUpSection />
             </div>
           </div>
@@ -5,7 +5,11 @@ import { Button } from '@/components/ui/button';
 import { useConfig } from '@/components/config-provider';
 import { attemptsApi, projectsApi } from '@/lib/api';
 import type { GitBranch, TaskAttempt } from 'shared/types';
-import { EXECUTOR_LABELS, EXECUTOR_TYPES } from 'shared/types';
+import {
+  EXECUTOR_LABELS,
+  EXECUTOR_TYPES,
+  PLAN_EXECUTOR_TYPES,
+} from 'shared/types';
 import {
   TaskAttemptDataContext,
   TaskAttemptLoadingContext,
@@ -17,10 +21,12 @@ import CreatePRDialog from '@/components/tasks/Toolbar/CreatePRDialog.tsx';
 import CreateAttempt from '@/components/tasks/Toolbar/CreateAttempt.tsx';
 import CurrentAttempt from '@/components/tasks/Toolbar/CurrentAttempt.tsx';
 
-const availableExecutors = EXECUTOR_TYPES.map((id) => ({
-  id,
-  name: EXECUTOR_LABELS[id] || id,
-}));
+const availableExecutors = [...EXECUTOR_TYPES, ...PLAN_EXECUTOR_TYPES].map(
+  (id) => ({
+    id,
+    name: EXECUTOR_LABELS[id] || id,
+  })
+);
 
 function TaskDetailsToolbar() {
   const { task, projectId } = useContext(TaskDetailsContext);
@@ -10,6 +10,7 @@ import {
   TaskSelectedAttemptContext,
 } from '@/components/context/taskDetailsContext.ts';
 import { Loader } from '@/components/ui/loader';
+import { executor_capabilities } from '@/lib/utils';
 
 export function TaskFollowUpSection() {
   const { task, projectId } = useContext(TaskDetailsContext);
@@ -22,9 +23,37 @@ export function TaskFollowUpSection() {
   const [isSendingFollowUp, setIsSendingFollowUp] = useState(false);
   const [followUpError, setFollowUpError] = useState<string | null>(null);
 
+  // While a coding agent runs, messages steer it instead of starting a follow-up
+  const runningCodingAgent = useMemo(
+    () =>
+      attemptData.processes.find(
+        (process) =>
+          process.process_type === 'codingagent' &&
+          process.status === 'running'
+      ),
+    [attemptData.processes]
+  );
+
+  const capabilities = useMemo(
+    () =>
+      executor_capabilities(
+        runningCodingAgent?.executor_type ?? selectedAttempt?.executor
+      ),
+    [runningCodingAgent, selectedAttempt]
+  );
+
   const canSendFollowUp = useMemo(() => {
+    if (runningCodingAgent) {
+      return (
+        !!selectedAttempt &&
+        !isSendingFollowUp &&
+        capabilities?.send_input !== false
+      );
+    }
+
     if (
       !selectedAttempt ||
+      capabilities?.follow_up === false ||
       attemptData.processes.length === 0 ||
       isAttemptRunning ||
       isSendingFollowUp
@@ -35,20 +64,47 @@ export function TaskFollowUpSection() {
     const completedOrKilledCodingAgentProcesses = attemptData.processes.filter(
       (process) =>
         process.process_type === 'codingagent' &&
-        (process.status === 'completed' || process.status === 'killed')
+        (process.status === 'completed' ||
+          process.status === 'killed' ||
+          process.status === 'timedout')
     );
 
     return completedOrKilledCodingAgentProcesses.length > 0;
   }, [
     selectedAttempt,
     attemptData.processes,
+    runningCodingAgent,
     isAttemptRunning,
     isSendingFollowUp,
+    capabilities,
   ]);
 
   const onSendFollowUp = async () => {
     if (!task || !selectedAttempt || !followUpMessage.trim()) return;
 
+    if (runningCodingAgent) {
+      try {
+        setIsSendingFollowUp(true);
+        setFollowUpError(null);
+        await attemptsApi.sendExecutionInput(
+          projectId!,
+          selectedAttempt.task_id,
+          selectedAttempt.id,
+          runningCodingAgent.id,
+          {
+            message: followUpMessage.trim(),
+          }
+        );
+        setFollowUpMessage('');
+      } catch (error: unknown) {
+        // @ts-expect-error it is type ApiError
+        setFollowUpError(`Failed to send message: ${error.message}`);
+      } finally {
+        setIsSendingFollowUp(false);
+      }
+      return;
+    }
+
     try {
       setIsSendingFollowUp(true);
       setFollowUpError(null);
@@ -82,7 +138,11 @@ export function TaskFollowUpSection() {
           )}
           <div className="flex gap-2 items-start">
             <FileSearchTextarea
-              placeholder="Continue working on this task... Type @ to search files."
+              placeholder={
+                runningCodingAgent
+                  ? 'Steer the running agent... Type @ to search files.'
+                  : 'Continue working on this task... Type @ to search files.'
+              }
               value={followUpMessage}
               onChange={(value) => {
                 setFollowUpMessage(value);
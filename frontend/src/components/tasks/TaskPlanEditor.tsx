@@ -0,0 +1,132 @@
+import { FileText, Pencil } from 'lucide-react';
+import { useCallback, useContext, useEffect, useState } from 'react';
+import { Button } from '@/components/ui/button';
+import { Textarea } from '@/components/ui/textarea';
+import { attemptsApi } from '@/lib/api.ts';
+import { is_planning_executor_type } from '@/lib/utils';
+import {
+  TaskAttemptDataContext,
+  TaskDetailsContext,
+  TaskSelectedAttemptContext,
+} from '@/components/context/taskDetailsContext.ts';
+import type { PlanPresentation } from 'shared/types';
+
+export function TaskPlanEditor() {
+  const { projectId } = useContext(TaskDetailsContext);
+  const { selectedAttempt } = useContext(TaskSelectedAttemptContext);
+  const { isAttemptRunning } = useContext(TaskAttemptDataContext);
+
+  const [plans, setPlans] = useState<PlanPresentation[]>([]);
+  const [draft, setDraft] = useState<string | null>(null);
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const isPlanAttempt =
+    !!selectedAttempt?.executor &&
+    is_planning_executor_type(selectedAttempt.executor);
+
+  const fetchPlans = useCallback(async () => {
+    if (!projectId || !selectedAttempt) return;
+    try {
+      const result = await attemptsApi.getPlans(
+        projectId,
+        selectedAttempt.task_id,
+        selectedAttempt.id
+      );
+      setPlans(result);
+    } catch (err) {
+      console.error('Failed to fetch plans:', err);
+    }
+  }, [projectId, selectedAttempt]);
+
+  useEffect(() => {
+    setDraft(null);
+    setError(null);
+    if (!isPlanAttempt || isAttemptRunning) {
+      setPlans([]);
+      return;
+    }
+    fetchPlans();
+  }, [isPlanAttempt, isAttemptRunning, fetchPlans]);
+
+  const latest = plans.length > 0 ? plans[plans.length - 1] : null;
+
+  const savePlan = async () => {
+    if (!projectId || !selectedAttempt || draft === null) return;
+    try {
+      setSaving(true);
+      setError(null);
+      await attemptsApi.updatePlan(
+        projectId,
+        selectedAttempt.task_id,
+        selectedAttempt.id,
+        { plan: draft }
+      );
+      setDraft(null);
+      await fetchPlans();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to save plan');
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  if (!latest) return null;
+
+  const approved = plans.some((p) => p.approved_at !== null);
+
+  return (
+    <div className="border-t p-4 space-y-2 text-sm">
+      <div className="flex items-center gap-2">
+        <FileText className="h-4 w-4 text-muted-foreground" />
+        <span className="font-medium flex-1">
+          Plan v{latest.version}
+          {latest.author === 'user' && ' (edited)'}
+          {approved && ' · approved'}
+        </span>
+        {!approved && draft === null && (
+          <Button
+            size="sm"
+            variant="outline"
+            onClick={() => setDraft(latest.plan)}
+          >
+            <Pencil className="h-4 w-4 mr-1" />
+            Edit plan
+          </Button>
+        )}
+      </div>
+      {draft !== null ? (
+        <>
+          <Textarea
+            value={draft}
+            onChange={(e) => setDraft(e.target.value)}
+            className="min-h-[200px] font-mono text-xs"
+            disabled={saving}
+          />
+          {error && <div className="text-destructive">{error}</div>}
+          <div className="flex justify-end gap-2">
+            <Button
+              size="sm"
+              variant="outline"
+              disabled={saving}
+              onClick={() => setDraft(null)}
+            >
+              Cancel
+            </Button>
+            <Button
+              size="sm"
+              disabled={saving || draft.trim() === ''}
+              onClick={savePlan}
+            >
+              {saving ? 'Saving...' : 'Save version'}
+            </Button>
+          </div>
+        </>
+      ) : (
+        <pre className="max-h-48 overflow-y-auto text-xs text-muted-foreground whitespace-pre-wrap break-words">
+          {latest.plan}
+        </pre>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,102 @@
+import { Check, ShieldQuestion, X } from 'lucide-react';
+import { useCallback, useContext, useEffect, useState } from 'react';
+import { Button } from '@/components/ui/button';
+import { attemptsApi } from '@/lib/api.ts';
+import {
+  TaskAttemptDataContext,
+  TaskDetailsContext,
+  TaskSelectedAttemptContext,
+} from '@/components/context/taskDetailsContext.ts';
+import type { ToolApproval } from 'shared/types';
+
+const POLL_INTERVAL_MS = 2000;
+
+export function TaskToolApprovals() {
+  const { projectId } = useContext(TaskDetailsContext);
+  const { selectedAttempt } = useContext(TaskSelectedAttemptContext);
+  const { isAttemptRunning } = useContext(TaskAttemptDataContext);
+
+  const [pending, setPending] = useState<ToolApproval[]>([]);
+  const [respondingId, setRespondingId] = useState<string | null>(null);
+
+  const fetchApprovals = useCallback(async () => {
+    if (!projectId || !selectedAttempt) return;
+    try {
+      const approvals = await attemptsApi.getApprovals(
+        projectId,
+        selectedAttempt.task_id,
+        selectedAttempt.id
+      );
+      setPending(approvals.filter((a) => a.status === 'pending'));
+    } catch (err) {
+      console.error('Failed to fetch tool approvals:', err);
+    }
+  }, [projectId, selectedAttempt]);
+
+  useEffect(() => {
+    if (!isAttemptRunning) {
+      setPending([]);
+      return;
+    }
+    fetchApprovals();
+    const interval = setInterval(fetchApprovals, POLL_INTERVAL_MS);
+    return () => clearInterval(interval);
+  }, [isAttemptRunning, fetchApprovals]);
+
+  const respond = async (approval: ToolApproval, approved: boolean) => {
+    if (!projectId || !selectedAttempt) return;
+    try {
+      setRespondingId(approval.id);
+      await attemptsApi.respondToApproval(
+        projectId,
+        selectedAttempt.task_id,
+        selectedAttempt.id,
+        approval.id,
+        { approved, reason: null }
+      );
+      await fetchApprovals();
+    } catch (err) {
+      console.error('Failed to respond to tool approval:', err);
+    } finally {
+      setRespondingId(null);
+    }
+  };
+
+  if (pending.length === 0) return null;
+
+  return (
+    <div className="border-t p-4 space-y-2">
+      {pending.map((approval) => (
+        <div
+          key={approval.id}
+          className="flex items-start gap-2 rounded-md border p-2 text-sm"
+        >
+          <ShieldQuestion className="h-4 w-4 mt-0.5 shrink-0 text-amber-500" />
+          <div className="flex-1 min-w-0">
+            <div className="font-medium">Allow {approval.tool_name}?</div>
+            <pre className="text-xs text-muted-foreground whitespace-pre-wrap break-all">
+              {approval.tool_input}
+            </pre>
+          </div>
+          <Button
+            size="sm"
+            variant="outline"
+            disabled={respondingId === approval.id}
+            onClick={() => respond(approval, false)}
+          >
+            <X className="h-4 w-4 mr-1" />
+            Deny
+          </Button>
+          <Button
+            size="sm"
+            disabled={respondingId === approval.id}
+            onClick={() => respond(approval, true)}
+          >
+            <Check className="h-4 w-4 mr-1" />
+            Allow
+          </Button>
+        </div>
+      ))}
+    </div>
+  );
+}
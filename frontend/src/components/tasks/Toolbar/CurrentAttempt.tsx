@@ -1,4 +1,5 @@
 import {
+  Download,
   ExternalLink,
   GitBranch as GitBranchIcon,
   GitPullRequest,
@@ -19,8 +20,10 @@ import {
 import { Button } from '@/components/ui/button.tsx';
 import {
   DropdownMenu,
+  DropdownMenuCheckboxItem,
   DropdownMenuContent,
   DropdownMenuItem,
+  DropdownMenuSeparator,
   DropdownMenuTrigger,
 } from '@/components/ui/dropdown-menu.tsx';
 import {
@@ -34,10 +37,10 @@ import {
 import BranchSelector from '@/components/tasks/BranchSelector.tsx';
 import {
   attemptsApi,
-  executionProcessesApi,
   makeRequest,
   FollowUpResponse,
   ApiResponse,
+  TranscriptFormat,
 } from '@/lib/api.ts';
 import {
   Dispatch,
@@ -50,7 +53,6 @@ import {
 } from 'react';
 import type {
   BranchStatus,
-  ExecutionProcess,
   GitBranch,
   TaskAttempt,
 } from 'shared/types.ts';
@@ -65,6 +67,7 @@ import {
 import { useConfig } from '@/components/config-provider.tsx';
 import { useKeyboardShortcuts } from '@/lib/keyboard-shortcuts.ts';
 import { useNavigate } from 'react-router-dom';
+import useProcessOutput from '@/hooks/useProcessOutput';
 
 // Helper function to get the display name for different editor types
 function getEditorDisplayName(editorType: string): string {
@@ -129,8 +132,6 @@ function CurrentAttempt({
   const [isStartingDevServer, setIsStartingDevServer] = useState(false);
   const [merging, setMerging] = useState(false);
   const [rebasing, setRebasing] = useState(false);
-  const [devServerDetails, setDevServerDetails] =
-    useState<ExecutionProcess | null>(null);
   const [isHoveringDevServer, setIsHoveringDevServer] = useState(false);
   const [branchStatus, setBranchStatus] = useState<BranchStatus | null>(null);
   const [branchStatusLoading, setBranchStatusLoading] = useState(false);
@@ -138,17 +139,7 @@ function CurrentAttempt({
   const [selectedRebaseBranch, setSelectedRebaseBranch] = useState<string>('');
   const [showStopConfirmation, setShowStopConfirmation] = useState(false);
   const [isApprovingPlan, setIsApprovingPlan] = useState(false);
-
-  const processedDevServerLogs = useMemo(() => {
-    if (!devServerDetails) return 'No output yet...';
-
-    const stdout = devServerDetails.stdout || '';
-    const stderr = devServerDetails.stderr || '';
-    const allOutput = stdout + (stderr ? '\n' + stderr : '');
-    const lines = allOutput.split('\n').filter((line) => line.trim());
-    const lastLines = lines.slice(-10);
-    return lastLines.length > 0 ? lastLines.join('\n') : 'No output yet...';
-  }, [devServerDetails]);
+  const [redactTranscript, setRedactTranscript] = useState(true);
 
   // Find running dev server in current project
   const runningDevServer = useMemo(() => {
@@ -158,6 +149,19 @@ function CurrentAttempt({
     );
   }, [attemptData.processes]);
 
+  const devServerOutput = useProcessOutput(
+    runningDevServer?.id,
+    isHoveringDevServer
+  );
+
+  const processedDevServerLogs = useMemo(() => {
+    const { stdout, stderr } = devServerOutput;
+    const allOutput = stdout + (stderr ? '\n' + stderr : '');
+    const lines = allOutput.split('\n').filter((line) => line.trim());
+    const lastLines = lines.slice(-10);
+    return lastLines.length > 0 ? lastLines.join('\n') : 'No output yet...';
+  }, [devServerOutput]);
+
   // Check if plan approval is needed
   const isPlanTask = useMemo(() => {
     return !!(
@@ -166,29 +170,19 @@ function CurrentAttempt({
     );
   }, [selectedAttempt.executor]);
 
-  const fetchDevServerDetails = useCallback(async () => {
-    if (!runningDevServer || !task || !selectedAttempt) return;
-
-    try {
-      const result = await executionProcessesApi.getDetails(
-        runningDevServer.id
-      );
-      setDevServerDetails(result);
-    } catch (err) {
-      console.error('Failed to fetch dev server details:', err);
-    }
-  }, [runningDevServer, task, selectedAttempt, projectId]);
-
-  useEffect(() => {
-    if (!isHoveringDevServer || !runningDevServer) {
-      setDevServerDetails(null);
-      return;
-    }
-
-    fetchDevServerDetails();
-    const interval = setInterval(fetchDevServerDetails, 2000);
-    return () => clearInterval(interval);
-  }, [isHoveringDevServer, runningDevServer, fetchDevServerDetails]);
+  const exportTranscript = (format: TranscriptFormat) => {
+    if (!projectId || !selectedAttempt) return;
+    // Served as an attachment, so the browser downloads it without navigating
+    window.location.assign(
+      attemptsApi.getTranscriptUrl(
+        projectId,
+        selectedAttempt.task_id,
+        selectedAttempt.id,
+        format,
+        redactTranscript
+      )
+    );
+  };
 
   const startDevServer = async () => {
     if (!task || !selectedAttempt) return;
@@ -626,7 +620,7 @@ function CurrentAttempt({
                     Add a dev server script in project settings to enable this
                     feature
                   </p>
-                ) : runningDevServer && devServerDetails ? (
+                ) : runningDevServer && isHoveringDevServer ? (
                   <div className="space-y-2">
                     <p className="text-sm font-medium">
                       Dev Server Logs (Last 10 lines):
@@ -687,6 +681,34 @@ function CurrentAttempt({
             </DropdownMenu>
           )}
 
+          <DropdownMenu>
+            <DropdownMenuTrigger asChild>
+              <Button variant="outline" size="sm" className="gap-2">
+                <Download className="h-4 w-4" />
+                Export
+              </Button>
+            </DropdownMenuTrigger>
+            <DropdownMenuContent align="start" className="w-56">
+              <DropdownMenuItem onClick={() => exportTranscript('markdown')}>
+                Markdown
+              </DropdownMenuItem>
+              <DropdownMenuItem onClick={() => exportTranscript('html')}>
+                HTML
+              </DropdownMenuItem>
+              <DropdownMenuItem onClick={() => exportTranscript('json')}>
+                JSON
+              </DropdownMenuItem>
+              <DropdownMenuSeparator />
+              <DropdownMenuCheckboxItem
+                checked={redactTranscript}
+                onCheckedChange={(checked) => setRedactTranscript(!!checked)}
+                onSelect={(event) => event.preventDefault()}
+              >
+                Redact secrets and paths
+              </DropdownMenuCheckboxItem>
+            </DropdownMenuContent>
+          </DropdownMenu>
+
           {/* Git Operations */}
           {selectedAttempt && branchStatus && (
             <>
@@ -715,7 +737,9 @@ function CurrentAttempt({
                   disabled={
                     isAttemptRunning ||
                     executionState?.execution_state === 'CodingAgentFailed' ||
-                    executionState?.execution_state === 'SetupFailed'
+                    executionState?.execution_state === 'CodingAgentTimedOut' ||
+                    executionState?.execution_state === 'SetupFailed' ||
+                    executionState?.execution_state === 'SetupTimedOut'
                   }
                   size="sm"
                   className="bg-green-600 hover:bg-green-700 disabled:bg-gray-400 gap-1"
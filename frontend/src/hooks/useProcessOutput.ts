@@ -0,0 +1,74 @@
+import { useCallback, useEffect, useRef, useState } from 'react';
+import { executionProcessesApi } from '@/lib/api';
+
+type ProcessOutput = {
+  stdout: string;
+  stderr: string;
+};
+
+const EMPTY_OUTPUT: ProcessOutput = { stdout: '', stderr: '' };
+
+// Polls a process's output, only fetching what was appended since the last poll
+const useProcessOutput = (
+  processId: string | null | undefined,
+  enabled = true,
+  intervalMs = 2000
+) => {
+  const [output, setOutput] = useState<ProcessOutput>(EMPTY_OUTPUT);
+  const offsetsRef = useRef({ stdout: 0, stderr: 0 });
+
+  const fetchOutput = useCallback(async () => {
+    if (!processId) return;
+
+    try {
+      const [stdout, stderr] = await Promise.all([
+        executionProcessesApi.getOutput(
+          processId,
+          'stdout',
+          offsetsRef.current.stdout
+        ),
+        executionProcessesApi.getOutput(
+          processId,
+          'stderr',
+          offsetsRef.current.stderr
+        ),
+      ]);
+      // Ignore responses for reads that were overtaken by another one
+      if (
+        stdout.offset !== offsetsRef.current.stdout ||
+        stderr.offset !== offsetsRef.current.stderr
+      ) {
+        return;
+      }
+      offsetsRef.current = {
+        stdout: stdout.next_offset,
+        stderr: stderr.next_offset,
+      };
+      if (stdout.content || stderr.content) {
+        setOutput((prev) => ({
+          stdout: prev.stdout + stdout.content,
+          stderr: prev.stderr + stderr.content,
+        }));
+      }
+    } catch (err) {
+      console.error('Failed to fetch process output:', err);
+    }
+  }, [processId]);
+
+  useEffect(() => {
+    offsetsRef.current = { stdout: 0, stderr: 0 };
+    setOutput(EMPTY_OUTPUT);
+  }, [processId]);
+
+  useEffect(() => {
+    if (!processId || !enabled) return;
+
+    fetchOutput();
+    const interval = setInterval(fetchOutput, intervalMs);
+    return () => clearInterval(interval);
+  }, [processId, enabled, intervalMs, fetchOutput]);
+
+  return output;
+};
+
+export default useProcessOutput;
@@ -0,0 +1,35 @@
+import { useEffect, useRef } from 'react';
+import type { ProjectEvent } from 'shared/types';
+
+// Subscribes to a project's board changes. `onResync` is called when events
+// were missed (e.g. after a server restart) and the caller has to reload.
+// The browser reconnects on its own and resumes after the last event it saw.
+const useProjectEvents = (
+  projectId: string | null | undefined,
+  onEvent: (event: ProjectEvent) => void,
+  onResync: () => void
+) => {
+  // Kept in refs so new callbacks don't reopen the connection
+  const onEventRef = useRef(onEvent);
+  const onResyncRef = useRef(onResync);
+  onEventRef.current = onEvent;
+  onResyncRef.current = onResync;
+
+  useEffect(() => {
+    if (!projectId) return;
+
+    const source = new EventSource(`/api/projects/${projectId}/events`);
+    source.addEventListener('board', (message) => {
+      try {
+        onEventRef.current(JSON.parse((message as MessageEvent).data));
+      } catch (err) {
+        console.error('Failed to parse board event:', err);
+      }
+    });
+    source.addEventListener('resync', () => onResyncRef.current());
+
+    return () => source.close();
+  }, [projectId]);
+};
+
+export default useProjectEvents;
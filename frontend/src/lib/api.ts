@@ -2,6 +2,7 @@
 import {
   BranchStatus,
   Config,
+  CreateFanOut,
   CreateFollowUpAttempt,
   CreateProject,
   CreateTask,
@@ -13,18 +14,32 @@ import {
   type EditorType,
   ExecutionProcess,
   ExecutionProcessSummary,
+  FanOutComparison,
   GitBranch,
+  OutputRange,
+  OutputStream,
+  PlanPresentation,
   ProcessLogsResponse,
   Project,
+  ProjectEnvVar,
   ProjectWithBranch,
+  RespondToolApproval,
+  SearchHit,
+  SendExecutionInput,
   Task,
   TaskAttempt,
   TaskAttemptState,
+  TaskStatus,
   TaskTemplate,
   TaskWithAttemptStatus,
+  ToolAllowlist,
+  ToolApproval,
+  UpdatePlanPresentation,
   UpdateProject,
   UpdateTask,
   UpdateTaskTemplate,
+  UsageTotals,
+  VerificationRun,
   WorktreeDiff,
 } from 'shared/types';
 
@@ -177,6 +192,43 @@ export const projectsApi = {
     );
     return handleApiResponse<FileSearchResult[]>(response);
   },
+
+  getUsage: async (id: string): Promise<UsageTotals> => {
+    const response = await makeRequest(`/api/projects/${id}/usage`);
+    return handleApiResponse<UsageTotals>(response);
+  },
+
+  getToolAllowlist: async (id: string): Promise<ToolAllowlist> => {
+    const response = await makeRequest(`/api/projects/${id}/tool-allowlist`);
+    return handleApiResponse<ToolAllowlist>(response);
+  },
+
+  updateToolAllowlist: async (
+    id: string,
+    data: ToolAllowlist
+  ): Promise<ToolAllowlist> => {
+    const response = await makeRequest(`/api/projects/${id}/tool-allowlist`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ToolAllowlist>(response);
+  },
+
+  getEnvVars: async (id: string): Promise<ProjectEnvVar[]> => {
+    const response = await makeRequest(`/api/projects/${id}/env`);
+    return handleApiResponse<ProjectEnvVar[]>(response);
+  },
+
+  updateEnvVars: async (
+    id: string,
+    data: ProjectEnvVar[]
+  ): Promise<ProjectEnvVar[]> => {
+    const response = await makeRequest(`/api/projects/${id}/env`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectEnvVar[]>(response);
+  },
 };
 
 // Task Management APIs
@@ -240,6 +292,16 @@ export const tasksApi = {
     return handleApiResponse<void>(response);
   },
 
+  getUsage: async (
+    projectId: string,
+    taskId: string
+  ): Promise<UsageTotals> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/usage`
+    );
+    return handleApiResponse<UsageTotals>(response);
+  },
+
   getChildren: async (
     projectId: string,
     taskId: string,
@@ -276,6 +338,46 @@ export const attemptsApi = {
     return handleApiResponse<TaskAttempt>(response);
   },
 
+  fanOut: async (
+    projectId: string,
+    taskId: string,
+    data: CreateFanOut
+  ): Promise<FanOutComparison> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/fan-out`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<FanOutComparison>(response);
+  },
+
+  getFanOut: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<FanOutComparison> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/fan-out`
+    );
+    return handleApiResponse<FanOutComparison>(response);
+  },
+
+  pickWinner: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<FanOutComparison> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/pick-winner`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<FanOutComparison>(response);
+  },
+
   getState: async (
     projectId: string,
     taskId: string,
@@ -452,6 +554,83 @@ export const attemptsApi = {
     return handleApiResponse<ExecutionProcessSummary[]>(response);
   },
 
+  getUsage: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<UsageTotals> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/usage`
+    );
+    return handleApiResponse<UsageTotals>(response);
+  },
+
+  getVerificationRuns: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<VerificationRun[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/verification-runs`
+    );
+    return handleApiResponse<VerificationRun[]>(response);
+  },
+
+  getApprovals: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<ToolApproval[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/approvals`
+    );
+    return handleApiResponse<ToolApproval[]>(response);
+  },
+
+  respondToApproval: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string,
+    approvalId: string,
+    data: RespondToolApproval
+  ): Promise<ToolApproval> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/approvals/${approvalId}/respond`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ToolApproval>(response);
+  },
+
+  getPlans: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<PlanPresentation[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/plans`
+    );
+    return handleApiResponse<PlanPresentation[]>(response);
+  },
+
+  updatePlan: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string,
+    data: UpdatePlanPresentation
+  ): Promise<PlanPresentation> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/plans`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<PlanPresentation>(response);
+  },
+
   stopExecutionProcess: async (
     projectId: string,
     taskId: string,
@@ -467,6 +646,23 @@ export const attemptsApi = {
     return handleApiResponse<void>(response);
   },
 
+  sendExecutionInput: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string,
+    processId: string,
+    data: SendExecutionInput
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/execution-processes/${processId}/input`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
   getDetails: async (attemptId: string): Promise<TaskAttempt> => {
     const response = await makeRequest(`/api/attempts/${attemptId}/details`);
     return handleApiResponse<TaskAttempt>(response);
@@ -482,14 +678,65 @@ export const attemptsApi = {
     );
     return handleApiResponse(response);
   },
+
+  // Download link for the attempt's transcript, served as an attachment
+  getTranscriptUrl: (
+    projectId: string,
+    taskId: string,
+    attemptId: string,
+    format: TranscriptFormat,
+    redact: boolean
+  ): string => {
+    return `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/transcript?format=${format}&redact=${redact}`;
+  },
 };
 
+export type TranscriptFormat = 'markdown' | 'html' | 'json';
+
 // Execution Process APIs
 export const executionProcessesApi = {
   getDetails: async (processId: string): Promise<ExecutionProcess> => {
     const response = await makeRequest(`/api/execution-processes/${processId}`);
     return handleApiResponse<ExecutionProcess>(response);
   },
+
+  getOutput: async (
+    processId: string,
+    stream: OutputStream,
+    offset: number
+  ): Promise<OutputRange> => {
+    const response = await makeRequest(
+      `/api/execution-processes/${processId}/output?stream=${stream}&offset=${offset}`
+    );
+    return handleApiResponse<OutputRange>(response);
+  },
+};
+
+export type SearchFilters = {
+  projectId?: string;
+  executor?: string;
+  status?: TaskStatus;
+  from?: Date;
+  to?: Date;
+  limit?: number;
+};
+
+// Search APIs
+export const searchApi = {
+  search: async (
+    query: string,
+    filters: SearchFilters = {}
+  ): Promise<SearchHit[]> => {
+    const params = new URLSearchParams({ q: query });
+    if (filters.projectId) params.set('project_id', filters.projectId);
+    if (filters.executor) params.set('executor', filters.executor);
+    if (filters.status) params.set('status', filters.status);
+    if (filters.from) params.set('from', filters.from.toISOString());
+    if (filters.to) params.set('to', filters.to.toISOString());
+    if (filters.limit) params.set('limit', String(filters.limit));
+    const response = await makeRequest(`/api/search?${params}`);
+    return handleApiResponse<SearchHit[]>(response);
+  },
 };
 
 // File System APIs
@@ -1,10 +1,21 @@
 import { type ClassValue, clsx } from 'clsx';
 import { twMerge } from 'tailwind-merge';
+import {
+  EXECUTOR_CAPABILITIES,
+  type ExecutorCapabilities,
+} from 'shared/types';
 
 export function cn(...inputs: ClassValue[]) {
   return twMerge(clsx(inputs));
 }
 
 export function is_planning_executor_type(executorType: string): boolean {
-  return executorType === 'claude-plan';
+  return executorType === 'claude-plan' || executorType.startsWith('plan:');
+}
+
+// Unknown executors (e.g. custom ones) return undefined and aren't restricted
+export function executor_capabilities(
+  executorType: string | null | undefined
+): ExecutorCapabilities | undefined {
+  return executorType ? EXECUTOR_CAPABILITIES[executorType] : undefined;
 }
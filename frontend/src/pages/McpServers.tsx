@@ -18,7 +18,11 @@ import { Label } from '@/components/ui/label';
 import { Alert, AlertDescription } from '@/components/ui/alert';
 import { Textarea } from '@/components/ui/textarea';
 import { Loader2 } from 'lucide-react';
-import { EXECUTOR_TYPES, EXECUTOR_LABELS } from 'shared/types';
+import {
+  EXECUTOR_CAPABILITIES,
+  EXECUTOR_LABELS,
+  EXECUTOR_TYPES,
+} from 'shared/types';
 import { useConfig } from '@/components/config-provider';
 import { mcpServersApi } from '../lib/api';
 
@@ -314,7 +318,9 @@ export function McpServers() {
                   <SelectValue placeholder="Select executor" />
                 </SelectTrigger>
                 <SelectContent>
-                  {EXECUTOR_TYPES.map((type) => (
+                  {EXECUTOR_TYPES.filter(
+                    (type) => EXECUTOR_CAPABILITIES[type]?.mcp
+                  ).map((type) => (
                     <SelectItem key={type} value={type}>
                       {EXECUTOR_LABELS[type]}
                     </SelectItem>
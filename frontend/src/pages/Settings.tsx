@@ -18,13 +18,16 @@ import { Label } from '@/components/ui/label';
 import { Alert, AlertDescription } from '@/components/ui/alert';
 import { Checkbox } from '@/components/ui/checkbox';
 import { Input } from '@/components/ui/input';
+import { Textarea } from '@/components/ui/textarea';
 import { Key, Loader2, Volume2 } from 'lucide-react';
 import type { EditorType, SoundFile, ThemeMode } from 'shared/types';
 import {
+  DEFAULT_PROMPT_TEMPLATE,
   EDITOR_LABELS,
   EDITOR_TYPES,
   EXECUTOR_LABELS,
   EXECUTOR_TYPES,
+  PROMPT_TEMPLATE_VARIABLES,
   SOUND_FILES,
   SOUND_LABELS,
 } from 'shared/types';
@@ -220,6 +223,49 @@ export function Settings() {
                   Choose the default executor for running tasks.
                 </p>
               </div>
+
+              <div className="space-y-2">
+                <Label htmlFor="prompt-template">Prompt Template</Label>
+                <Textarea
+                  id="prompt-template"
+                  value={config.prompt_template ?? ''}
+                  onChange={(e) =>
+                    updateConfig({ prompt_template: e.target.value || null })
+                  }
+                  placeholder={DEFAULT_PROMPT_TEMPLATE}
+                  rows={8}
+                  className="font-mono text-sm"
+                />
+                <p className="text-sm text-muted-foreground">
+                  The prompt coding agents start a task with, for projects
+                  without their own template. Leave empty to use the built-in
+                  one. Variables: {PROMPT_TEMPLATE_VARIABLES.join(', ')}.
+                </p>
+              </div>
+
+              <div className="space-y-2">
+                <Label htmlFor="max-concurrent-agents">
+                  Max Concurrent Agents
+                </Label>
+                <Input
+                  id="max-concurrent-agents"
+                  type="number"
+                  min={1}
+                  value={config.max_concurrent_agents ?? ''}
+                  onChange={(e) => {
+                    const agents = parseInt(e.target.value);
+                    updateConfig({
+                      max_concurrent_agents: agents > 0 ? agents : null,
+                    });
+                  }}
+                  placeholder="No limit"
+                />
+                <p className="text-sm text-muted-foreground">
+                  Attempts and follow-ups started beyond this many running
+                  attempts, across all projects, wait in the execution queue.
+                  Leave empty for no limit.
+                </p>
+              </div>
             </CardContent>
           </Card>
 
@@ -10,6 +10,7 @@ import { TaskFormDialog } from '@/components/tasks/TaskFormDialog';
 import { ProjectForm } from '@/components/projects/project-form';
 import { TaskTemplateManager } from '@/components/TaskTemplateManager';
 import { useKeyboardShortcuts } from '@/lib/keyboard-shortcuts';
+import useProjectEvents from '@/hooks/useProjectEvents';
 import {
   DropdownMenu,
   DropdownMenuContent,
@@ -199,6 +200,7 @@ export function ProjectTasks() {
           description: description || null,
           parent_task_attempt: null,
           executor: executor || null,
+          priority: null,
         };
         const result = await tasksApi.createAndStart(projectId!, payload);
         await fetchTasks();
@@ -326,17 +328,15 @@ export function ProjectTasks() {
       fetchProject();
       fetchTasks();
       fetchTemplates();
-
-      // Set up polling to refresh tasks every 5 seconds
-      const interval = setInterval(() => {
-        fetchTasks(true); // Skip loading spinner for polling
-      }, 2000);
-
-      // Cleanup interval on unmount
-      return () => clearInterval(interval);
     }
   }, [projectId]);
 
+  // Refresh tasks whenever something changes on the board
+  const refreshTasks = useCallback(() => {
+    fetchTasks(true); // Skip loading spinner for background refreshes
+  }, [fetchTasks]);
+  useProjectEvents(projectId, refreshTasks, refreshTasks);
+
   // Handle direct navigation to task URLs
   useEffect(() => {
     if (taskId && tasks.length > 0) {
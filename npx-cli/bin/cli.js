@@ -116,6 +116,14 @@ if (isMcpMode) {
     process.on("SIGTERM", () => proc.kill("SIGTERM"));
   });
 } else {
+  // The MCP server also answers tool permission prompts for coding agents
+  try {
+    extractAndRun("vibe-kanban-mcp", () => {});
+  } catch (e) {
+    // Still in use by a running MCP session, keep the extracted copy
+    console.warn(`⚠️ Could not refresh vibe-kanban-mcp: ${e.message}`);
+  }
+
   console.log(`📦 Extracting vibe-kanban...`);
   extractAndRun("vibe-kanban", (bin) => {
     console.log(`🚀 Launching vibe-kanban...`);
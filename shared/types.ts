@@ -4,7 +4,17 @@
 
 export type ApiResponse<T> = { success: boolean, data: T | null, message: string | null, };
 
-export type Config = { theme: ThemeMode, executor: ExecutorConfig, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, github_login_acknowledged: boolean, telemetry_acknowledged: boolean, sound_alerts: boolean, sound_file: SoundFile, push_notifications: boolean, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean | null, };
+export type Config = { theme: ThemeMode, executor: ExecutorConfig, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, github_login_acknowledged: boolean, telemetry_acknowledged: boolean, sound_alerts: boolean, sound_file: SoundFile, push_notifications: boolean, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean | null, custom_executors: Array<CustomExecutorConfig>, execution_timeouts: ExecutionTimeouts, tool_approvals: ToolApprovalConfig, sandbox: SandboxConfig, 
+/**
+ * Prompt template for projects without their own, `None` uses the built-in one
+ */
+prompt_template: string | null, 
+/**
+ * Most attempts running at once across all projects, from their setup script through the
+ * coding agent and tests; further attempts and follow-ups wait in the execution queue.
+ * `None` for no limit
+ */
+max_concurrent_agents: number | null, };
 
 export type ThemeMode = "light" | "dark" | "system" | "purple" | "green" | "blue" | "orange" | "red";
 
@@ -12,6 +22,37 @@ export type EditorConfig = { editor_type: EditorType, custom_command: string | n
 
 export type GitHubConfig = { pat: string | null, token: string | null, username: string | null, primary_email: string | null, default_pr_base: string | null, };
 
+export type ExecutionTimeouts = { setup_script: ProcessTimeouts, coding_agent: ProcessTimeouts, dev_server: ProcessTimeouts, test_script: ProcessTimeouts, };
+
+export type ProcessTimeouts = { 
+/**
+ * Kill the process once it has been running for this long
+ */
+max_runtime_minutes: number | null, 
+/**
+ * Kill the process if it writes no output and receives no input for this long. Time
+ * spent waiting for a tool approval doesn't count.
+ */
+idle_minutes: number | null, };
+
+export type ToolApprovalConfig = { enabled: boolean, 
+/**
+ * Requests nobody answers within this time are denied
+ */
+timeout_minutes: number, };
+
+export type SandboxConfig = { 
+/**
+ * Directories outside the worktree that sandboxed processes may write to, such as
+ * package caches and agent state. `~` expands to the home directory.
+ *
+ * Files can be listed too, but they can only be rewritten in place. Allowing a file to
+ * be created next to them would allow it anywhere beneath their directory, so tools
+ * that save by renaming a new copy over the file can't save it. Claude saves
+ * `~/.claude.json` that way, so changes it makes there while sandboxed are lost.
+ */
+cache_dirs: Array<string>, };
+
 export type EditorType = "vscode" | "cursor" | "windsurf" | "intellij" | "zed" | "custom";
 
 export type EditorConstants = { editor_types: Array<EditorType>, editor_labels: Array<string>, };
@@ -20,19 +61,180 @@ export type SoundFile = "abstract-sound1" | "abstract-sound2" | "abstract-sound3
 
 export type SoundConstants = { sound_files: Array<SoundFile>, sound_labels: Array<string>, };
 
-export type ConfigConstants = { editor: EditorConstants, sound: SoundConstants, };
-
-export type ExecutorConfig = { "type": "echo" } | { "type": "claude" } | { "type": "claude-plan" } | { "type": "amp" } | { "type": "gemini" } | { "type": "setup-script", script: string, } | { "type": "claude-code-router" } | { "type": "charm-opencode" } | { "type": "sst-opencode" };
-
-export type ExecutorConstants = { executor_types: Array<ExecutorConfig>, executor_labels: Array<string>, };
-
-export type CreateProject = { name: string, git_repo_path: string, use_existing_repo: boolean, setup_script: string | null, dev_script: string | null, };
-
-export type Project = { id: string, name: string, git_repo_path: string, setup_script: string | null, dev_script: string | null, created_at: Date, updated_at: Date, };
-
-export type ProjectWithBranch = { id: string, name: string, git_repo_path: string, setup_script: string | null, dev_script: string | null, current_branch: string | null, created_at: Date, updated_at: Date, };
-
-export type UpdateProject = { name: string | null, git_repo_path: string | null, setup_script: string | null, dev_script: string | null, };
+export type ConfigConstants = { editor: EditorConstants, sound: SoundConstants, executor: ExecutorConstants, };
+
+export type ExecutorConfig = { "type": "echo" } | { "type": "claude" } | { "type": "claude-plan" } | { "type": "amp" } | { "type": "gemini" } | { "type": "setup-script", script: string, } | { "type": "claude-code-router" } | { "type": "charm-opencode" } | { "type": "sst-opencode" } | { "type": "custom", name: string, } | { "type": "plan", executor: ExecutorConfig, } | { "type": "replay", fixture: string, 
+/**
+ * How many times faster than recorded to play back; 0 plays back without waiting
+ */
+speed: number | null, };
+
+export type PlanModeSupport = "native" | "scaffolded" | "unsupported";
+
+export type McpConfigFormat = "nested" | "dotted_key";
+
+export type McpConfigLocation = { 
+/**
+ * Config file on this machine. Left out of the generated TypeScript constants.
+ */
+config_path: string | null, attribute_path: Array<string>, format: McpConfigFormat, };
+
+export type ExecutorCapabilities = { 
+/**
+ * Executor string accepted wherever an executor is configured, e.g. `claude` or `plan:amp`
+ */
+executor_type: string, label: string, 
+/**
+ * Follow-up prompts can be sent once an execution finishes
+ */
+follow_up: boolean, 
+/**
+ * Output is normalized into a conversation while the execution runs
+ */
+streaming: boolean, 
+/**
+ * Messages can be sent to the agent while it runs
+ */
+send_input: boolean, plan_mode: PlanModeSupport, 
+/**
+ * The agent can read images referenced in the prompt
+ */
+image_input: boolean, 
+/**
+ * None if the executor has no MCP configuration we can edit
+ */
+mcp: McpConfigLocation | null, 
+/**
+ * Programs that must be on the PATH to start the executor
+ */
+required_binaries: Array<string>, };
+
+export type CustomExecutorConfig = { 
+/**
+ * Unique identifier, referenced as `custom:<name>`
+ */
+name: string, 
+/**
+ * Optional human readable label shown in the UI
+ */
+label: string | null, 
+/**
+ * Shell command template. `{prompt}` and `{prompt_file}` are substituted
+ * depending on `prompt_delivery`.
+ */
+command: string, prompt_delivery: PromptDelivery, 
+/**
+ * Shell command template used for follow-ups. Must contain `{session_id}`, which is
+ * substituted shell-quoted.
+ */
+follow_up_command: string | null, session_id: SessionIdExtractor | null, normalizer: CustomLogNormalizer, };
+
+export type PromptDelivery = "stdin" | "argument" | "file";
+
+export type SessionIdExtractor = { "type": "regex", pattern: string, } | { "type": "json_path", path: string, };
+
+export type CustomLogNormalizer = "claude" | "amp" | "gemini" | "plain_text";
+
+export type ExecutorConstants = { executors: Array<ExecutorCapabilities>, };
+
+export type CreateProject = { name: string, git_repo_path: string, use_existing_repo: boolean, setup_script: string | null, dev_script: string | null, test_script: string | null, 
+/**
+ * `None` turns the verify-and-fix loop off
+ */
+max_fix_iterations: bigint | null, 
+/**
+ * `None` leaves only the global limit
+ */
+max_concurrent_agents: bigint | null, executor: string | null, executor_args: string | null, 
+/**
+ * `None` leaves the project unsandboxed
+ */
+sandbox: boolean | null, 
+/**
+ * `None` allows network access
+ */
+sandbox_network: boolean | null, prompt_template: string | null, instructions: string | null, };
+
+export type Project = { id: string, name: string, git_repo_path: string, setup_script: string | null, dev_script: string | null, 
+/**
+ * Command run after each coding agent to check the attempt's changes (e.g. `cargo test`)
+ */
+test_script: string | null, 
+/**
+ * Follow-ups sent to the coding agent with the output of a failing test script, 0 turns the
+ * verify-and-fix loop off
+ */
+max_fix_iterations: bigint, 
+/**
+ * Most of the project's attempts running at once, from their setup script through the coding
+ * agent and tests; further attempts and follow-ups wait in the execution queue. 0 leaves
+ * only the global limit
+ */
+max_concurrent_agents: bigint, 
+/**
+ * Default executor for attempts in this project, overriding the global config
+ */
+executor: string | null, 
+/**
+ * Extra CLI arguments appended to the coding agent command (e.g. `--model opus`)
+ */
+executor_args: string | null, 
+/**
+ * Confine coding agents and setup scripts to their worktree (Linux only)
+ */
+sandbox: boolean, 
+/**
+ * Whether sandboxed processes may use the network
+ */
+sandbox_network: boolean, 
+/**
+ * Template for the prompt coding agents start tasks with, overriding the global config
+ */
+prompt_template: string | null, 
+/**
+ * House rules for coding agents, rendered into prompts as `{{project_instructions}}`
+ */
+instructions: string | null, created_at: Date, updated_at: Date, };
+
+export type ProjectWithBranch = { id: string, name: string, git_repo_path: string, setup_script: string | null, dev_script: string | null, test_script: string | null, max_fix_iterations: bigint, max_concurrent_agents: bigint, executor: string | null, executor_args: string | null, sandbox: boolean, sandbox_network: boolean, prompt_template: string | null, instructions: string | null, current_branch: string | null, created_at: Date, updated_at: Date, };
+
+export type UpdateProject = { name: string | null, git_repo_path: string | null, setup_script: string | null, dev_script: string | null, 
+/**
+ * `None` keeps the current value, an empty string clears it
+ */
+test_script: string | null, 
+/**
+ * `None` keeps the current value
+ */
+max_fix_iterations: bigint | null, 
+/**
+ * `None` keeps the current value
+ */
+max_concurrent_agents: bigint | null, 
+/**
+ * `None` keeps the current value, an empty string clears it
+ */
+executor: string | null, 
+/**
+ * `None` keeps the current value, an empty string clears it
+ */
+executor_args: string | null, 
+/**
+ * `None` keeps the current value
+ */
+sandbox: boolean | null, 
+/**
+ * `None` keeps the current value
+ */
+sandbox_network: boolean | null, 
+/**
+ * `None` keeps the current value, an empty string clears it
+ */
+prompt_template: string | null, 
+/**
+ * `None` keeps the current value, an empty string clears it
+ */
+instructions: string | null, };
 
 export type SearchResult = { path: string, is_file: boolean, match_type: SearchMatchType, };
 
@@ -44,13 +246,21 @@ export type CreateBranch = { name: string, base_branch: string | null, };
 
 export type CreateTask = { project_id: string, title: string, description: string | null, parent_task_attempt: string | null, };
 
-export type CreateTaskAndStart = { project_id: string, title: string, description: string | null, parent_task_attempt: string | null, executor: ExecutorConfig | null, };
+export type CreateTaskAndStart = { project_id: string, title: string, description: string | null, parent_task_attempt: string | null, executor: ExecutorConfig | null, 
+/**
+ * Queue priority of the started attempt, higher starts first
+ */
+priority: bigint | null, };
 
 export type TaskStatus = "todo" | "inprogress" | "inreview" | "done" | "cancelled";
 
 export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_task_attempt: string | null, created_at: string, updated_at: string, };
 
-export type TaskWithAttemptStatus = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_task_attempt: string | null, created_at: string, updated_at: string, has_in_progress_attempt: boolean, has_merged_attempt: boolean, last_attempt_failed: boolean, latest_attempt_executor: string | null, };
+export type TaskWithAttemptStatus = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_task_attempt: string | null, created_at: string, updated_at: string, has_in_progress_attempt: boolean, 
+/**
+ * An attempt is waiting in the execution queue
+ */
+has_queued_attempt: boolean, has_merged_attempt: boolean, last_attempt_failed: boolean, latest_attempt_executor: string | null, };
 
 export type UpdateTask = { title: string | null, description: string | null, status: TaskStatus | null, parent_task_attempt: string | null, };
 
@@ -60,16 +270,52 @@ export type CreateTaskTemplate = { project_id: string | null, title: string, des
 
 export type UpdateTaskTemplate = { title: string | null, description: string | null, template_name: string | null, };
 
-export type TaskAttemptStatus = "setuprunning" | "setupcomplete" | "setupfailed" | "executorrunning" | "executorcomplete" | "executorfailed";
+export type TaskAttemptStatus = "queued" | "setuprunning" | "setupcomplete" | "setupfailed" | "executorrunning" | "executorcomplete" | "executorfailed";
 
-export type TaskAttempt = { id: string, task_id: string, worktree_path: string, branch: string, base_branch: string, merge_commit: string | null, executor: string | null, pr_url: string | null, pr_number: bigint | null, pr_status: string | null, pr_merged_at: string | null, worktree_deleted: boolean, setup_completed_at: string | null, created_at: string, updated_at: string, };
+export type TaskAttempt = { id: string, task_id: string, worktree_path: string, branch: string, base_branch: string, merge_commit: string | null, executor: string | null, pr_url: string | null, pr_number: bigint | null, pr_status: string | null, pr_merged_at: string | null, worktree_deleted: boolean, setup_completed_at: string | null, fan_out_id: string | null, created_at: string, updated_at: string, };
 
-export type CreateTaskAttempt = { executor: string | null, base_branch: string | null, };
+export type CreateTaskAttempt = { executor: string | null, base_branch: string | null, 
+/**
+ * Queued attempts with a higher priority start first when agents are at their limit
+ */
+priority: bigint | null, };
 
 export type UpdateTaskAttempt = Record<string, never>;
 
 export type CreateFollowUpAttempt = { prompt: string, };
 
+export type CreateFanOut = { 
+/**
+ * One attempt per entry; repeat an executor to run it several times
+ */
+executors: Array<string>, base_branch: string | null, 
+/**
+ * Queue priority shared by every attempt of the fan-out
+ */
+priority: bigint | null, };
+
+export type DiffStats = { files_changed: number, lines_added: number, lines_removed: number, };
+
+export type AttemptComparison = { attempt: TaskAttempt, 
+/**
+ * Status of the latest coding agent run, `None` until one started
+ */
+status: ExecutionProcessStatus | null, exit_code: bigint | null, 
+/**
+ * Time spent in coding agent runs, counting running ones up to now
+ */
+duration_seconds: bigint, 
+/**
+ * `None` once the worktree of an unmerged attempt was cleaned up
+ */
+diff_stats: DiffStats | null, 
+/**
+ * Result of the latest test script run, `None` without a finished run
+ */
+tests_passed: boolean | null, usage: UsageTotals, };
+
+export type FanOutComparison = { fan_out_id: string, attempts: Array<AttemptComparison>, };
+
 export type DirectoryEntry = { name: string, path: string, is_directory: boolean, is_git_repo: boolean, };
 
 export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_path: string, };
@@ -88,22 +334,94 @@ export type WorktreeDiff = { files: Array<FileDiff>, };
 
 export type BranchStatus = { is_behind: boolean, commits_behind: number, commits_ahead: number, up_to_date: boolean, merged: boolean, has_uncommitted_changes: boolean, base_branch_name: string, };
 
-export type ExecutionState = "NotStarted" | "SetupRunning" | "SetupComplete" | "SetupFailed" | "SetupStopped" | "CodingAgentRunning" | "CodingAgentComplete" | "CodingAgentFailed" | "CodingAgentStopped" | "Complete";
-
-export type TaskAttemptState = { execution_state: ExecutionState, has_changes: boolean, has_setup_script: boolean, setup_process_id: string | null, coding_agent_process_id: string | null, };
-
-export type ExecutionProcess = { id: string, task_attempt_id: string, process_type: ExecutionProcessType, executor_type: string | null, status: ExecutionProcessStatus, command: string, args: string | null, working_directory: string, stdout: string | null, stderr: string | null, exit_code: bigint | null, started_at: string, completed_at: string | null, created_at: string, updated_at: string, };
+export type ExecutionState = "NotStarted" | "Queued" | "SetupRunning" | "SetupComplete" | "SetupFailed" | "SetupStopped" | "SetupTimedOut" | "CodingAgentRunning" | "CodingAgentComplete" | "CodingAgentFailed" | "CodingAgentStopped" | "CodingAgentTimedOut" | "Complete";
+
+export type TaskAttemptState = { execution_state: ExecutionState, has_changes: boolean, has_setup_script: boolean, setup_process_id: string | null, coding_agent_process_id: string | null, 
+/**
+ * 1-based place in the execution queue while the attempt is queued
+ */
+queue_position: number | null, };
+
+export type OutputStream = "stdout" | "stderr";
+
+export type OutputRange = { content: string, offset: number, 
+/**
+ * Where the next read continues
+ */
+next_offset: number, };
+
+export type ExecutionProcess = { id: string, task_attempt_id: string, process_type: ExecutionProcessType, executor_type: string | null, status: ExecutionProcessStatus, command: string, args: string | null, working_directory: string, exit_code: bigint | null, sandbox_policy: string | null, 
+/**
+ * OS process id of the spawned command, which leads its own process group
+ */
+pid: bigint | null, pgid: bigint | null, 
+/**
+ * Start time and boot of the process, which tell it apart from a later process that
+ * reuses its pid; `None` where they can't be read (off Linux)
+ */
+process_start_time: bigint | null, boot_id: string | null, started_at: string, completed_at: string | null, created_at: string, updated_at: string, };
 
 export type ExecutionProcessSummary = { id: string, task_attempt_id: string, process_type: ExecutionProcessType, executor_type: string | null, status: ExecutionProcessStatus, command: string, args: string | null, working_directory: string, exit_code: bigint | null, started_at: string, completed_at: string | null, created_at: string, updated_at: string, };
 
-export type ExecutionProcessStatus = "running" | "completed" | "failed" | "killed";
+export type ExecutionProcessStatus = "running" | "completed" | "failed" | "killed" | "timedout";
 
-export type ExecutionProcessType = "setupscript" | "codingagent" | "devserver";
+export type ExecutionProcessType = "setupscript" | "codingagent" | "devserver" | "testscript";
 
-export type CreateExecutionProcess = { task_attempt_id: string, process_type: ExecutionProcessType, executor_type: string | null, command: string, args: string | null, working_directory: string, };
+export type CreateExecutionProcess = { task_attempt_id: string, process_type: ExecutionProcessType, executor_type: string | null, command: string, args: string | null, working_directory: string, sandbox_policy: string | null, };
 
 export type UpdateExecutionProcess = { status: ExecutionProcessStatus | null, exit_code: bigint | null, completed_at: string | null, };
 
+export type SendExecutionInput = { message: string, };
+
+export type ExecutionUsage = { id: string, execution_process_id: string, model: string | null, input_tokens: bigint, output_tokens: bigint, cache_creation_input_tokens: bigint, cache_read_input_tokens: bigint, cost_usd: number | null, created_at: string, updated_at: string, };
+
+export type UsageTotals = { execution_count: bigint, input_tokens: bigint, output_tokens: bigint, cache_creation_input_tokens: bigint, cache_read_input_tokens: bigint, cost_usd: number | null, };
+
+export type ToolApproval = { id: string, execution_process_id: string, tool_name: string, tool_input: string, status: ToolApprovalStatus, reason: string | null, decided_at: string | null, created_at: string, updated_at: string, };
+
+export type ToolApprovalStatus = "pending" | "approved" | "denied" | "timedout";
+
+export type RespondToolApproval = { approved: boolean, reason: string | null, };
+
+export type ToolAllowlist = { 
+/**
+ * Commands, or command prefixes followed by arguments, that Bash may run
+ */
+commands: Array<string>, 
+/**
+ * Paths relative to the worktree that file tools may read and write under
+ */
+paths: Array<string>, };
+
+export type VerificationRun = { id: string, task_attempt_id: string, execution_process_id: string, 
+/**
+ * 1 for the run after an agent the user started, counting up with each requested fix
+ */
+iteration: bigint, passed: boolean, 
+/**
+ * Whether the failing output was sent back to the coding agent
+ */
+fix_requested: boolean, created_at: string, };
+
+export type ProjectEnvVar = { key: string, 
+/**
+ * Never returned for secrets. When saving, leaving a secret's value empty keeps
+ * the stored one.
+ */
+value: string | null, secret: boolean, };
+
+export type SandboxPolicy = { 
+/**
+ * Paths the process may write beneath; everything else is read-only
+ */
+writable_paths: Array<string>, network: boolean, };
+
+export type PlanPresentation = { id: string, task_attempt_id: string, execution_process_id: string | null, version: bigint, plan: string, author: PlanAuthor, approved_at: string | null, created_at: string, };
+
+export type PlanAuthor = "agent" | "user";
+
+export type UpdatePlanPresentation = { plan: string, };
+
 export type ExecutorSession = { id: string, task_attempt_id: string, execution_process_id: string, session_id: string | null, prompt: string | null, summary: string | null, created_at: string, updated_at: string, };
 
 export type CreateExecutorSession = { task_attempt_id: string, execution_process_id: string, prompt: string | null, };
@@ -112,11 +430,108 @@ export type UpdateExecutorSession = { session_id: string | null, prompt: string
 
 export type NormalizedConversation = { entries: Array<NormalizedEntry>, session_id: string | null, executor_type: string, prompt: string | null, summary: string | null, };
 
-export type NormalizedEntry = { timestamp: string | null, entry_type: NormalizedEntryType, content: string, };
-
-export type NormalizedEntryType = { "type": "user_message" } | { "type": "assistant_message" } | { "type": "tool_use", tool_name: string, action_type: ActionType, } | { "type": "system_message" } | { "type": "error_message" } | { "type": "thinking" };
-
-export type ActionType = { "action": "file_read", path: string, } | { "action": "file_write", path: string, } | { "action": "command_run", command: string, } | { "action": "search", query: string, } | { "action": "web_fetch", url: string, } | { "action": "task_create", description: string, } | { "action": "plan_presentation", plan: string, } | { "action": "other", description: string, };
+export type NormalizedEntry = { timestamp: string | null, entry_type: NormalizedEntryType, content: string, 
+/**
+ * Time since the previous entry, i.e. how long this one took to arrive
+ */
+duration_ms: number | null, 
+/**
+ * For a tool call, how long it ran
+ */
+tool_duration_ms: number | null, };
+
+export type NormalizedEntryType = { "type": "user_message" } | { "type": "assistant_message" } | { "type": "tool_use", tool_name: string, action_type: ActionType, 
+/**
+ * The ID the CLI gave the call, which its `ToolResult` refers to
+ */
+tool_use_id: string | null, } | { "type": "tool_result", tool_use_id: string, is_error: boolean, exit_code: number | null, } | { "type": "system_message" } | { "type": "error_message" } | { "type": "thinking" };
+
+export type ActionType = { "action": "file_read", path: string, } | { "action": "file_write", path: string, } | { "action": "file_edit", path: string, edits: Array<FileEditHunk>, } | { "action": "command_run", command: string, output: string | null, exit_code: number | null, } | { "action": "search", query: string, } | { "action": "web_fetch", url: string, } | { "action": "task_create", description: string, } | { "action": "plan_presentation", plan: string, } | { "action": "todo_update", todos: Array<TodoItem>, } | { "action": "other", description: string, };
+
+export type FileEditHunk = { old: string, new: string, };
+
+export type TodoItem = { content: string, 
+/**
+ * As the CLI reports it, e.g. `pending`, `in_progress` or `completed`
+ */
+status: string, priority: string | null, };
+
+export type Transcript = { schema_version: number, exported_at: string, 
+/**
+ * Whether secrets and worktree paths were replaced
+ */
+redacted: boolean, project: TranscriptProject, task: TranscriptTask, attempt: TranscriptAttempt, 
+/**
+ * Oldest first
+ */
+processes: Array<TranscriptProcess>, 
+/**
+ * `None` when the diff couldn't be computed, e.g. after the worktree was cleaned up
+ */
+diff: Array<TranscriptFileDiff> | null, };
+
+export type TranscriptProject = { id: string, name: string, };
+
+export type TranscriptTask = { id: string, title: string, description: string | null, status: TaskStatus, };
+
+export type TranscriptAttempt = { id: string, executor: string | null, branch: string, base_branch: string, created_at: string, };
+
+export type TranscriptProcess = { id: string, process_type: ExecutionProcessType, status: ExecutionProcessStatus, executor_type: string | null, command: string, exit_code: number | null, started_at: string, completed_at: string | null, 
+/**
+ * What a coding agent was asked: the task for the first run, the follow-up after that
+ */
+prompt: string | null, 
+/**
+ * A coding agent's final message
+ */
+summary: string | null, 
+/**
+ * A coding agent's conversation; empty for scripts
+ */
+entries: Array<TranscriptEntry>, 
+/**
+ * Output of scripts, stdout then stderr; `None` for coding agents
+ */
+output: string | null, };
+
+export type TranscriptEntry = { kind: TranscriptEntryKind, 
+/**
+ * Set for tool calls
+ */
+tool_name: string | null, content: string, timestamp: string | null, };
+
+export type TranscriptEntryKind = "user_message" | "assistant_message" | "tool_use" | "tool_result" | "system_message" | "error_message" | "thinking";
+
+export type TranscriptFileDiff = { path: string, 
+/**
+ * Changed lines prefixed with `+`, `-` or a space, like a unified diff without headers
+ */
+patch: string, };
+
+export type SearchHitKind = "task" | "prompt" | "summary" | "conversation";
+
+export type SearchHit = { kind: SearchHitKind, project_id: string, task_id: string, task_title: string, task_status: TaskStatus, 
+/**
+ * Set for every hit but tasks
+ */
+attempt_id: string | null, process_id: string | null, executor: string | null, 
+/**
+ * Matching excerpt, with matches between `<mark>` and `</mark>`. The rest is not
+ * escaped, so render it as text.
+ */
+snippet: string, 
+/**
+ * Lower is better
+ */
+score: number, created_at: Date, };
+
+export type BoardEvent = { "type": "task_created", task_id: string, } | { "type": "task_updated", task_id: string, status: TaskStatus, } | { "type": "task_deleted", task_id: string, } | { "type": "attempt_status_changed", task_id: string, attempt_id: string, status: TaskAttemptStatus, } | { "type": "process_started", task_id: string, attempt_id: string, process_id: string, process_type: ExecutionProcessType, } | { "type": "process_completed", task_id: string, attempt_id: string, process_id: string, process_type: ExecutionProcessType, status: ExecutionProcessStatus, } | { "type": "pr_status_changed", task_id: string, attempt_id: string, pr_status: string, merged: boolean, } | { "type": "worktree_cleaned_up", task_id: string, attempt_id: string, };
+
+export type ProjectEvent = { 
+/**
+ * Cursor to resume the stream after this event
+ */
+id: number, project_id: string, event: BoardEvent, created_at: Date, };
 
 // Generated constants
 export const EXECUTOR_TYPES: string[] = [
@@ -130,13 +545,12 @@ export const EXECUTOR_TYPES: string[] = [
     "sst-opencode"
 ];
 
-export const EDITOR_TYPES: EditorType[] = [
-    "vscode",
-    "cursor", 
-    "windsurf",
-    "intellij",
-    "zed",
-    "custom"
+// Executors run in plan mode, selectable when starting an attempt
+export const PLAN_EXECUTOR_TYPES: string[] = [
+    "plan:amp",
+    "plan:gemini",
+    "plan:charm-opencode",
+    "plan:sst-opencode"
 ];
 
 export const EXECUTOR_LABELS: Record<string, string> = {
@@ -147,9 +561,37 @@ export const EXECUTOR_LABELS: Record<string, string> = {
     "gemini": "Gemini",
     "charm-opencode": "Charm Opencode",
     "claude-code-router": "Claude Code Router",
-    "sst-opencode": "SST Opencode"
+    "sst-opencode": "SST Opencode",
+    "plan:amp": "Amp Plan",
+    "plan:gemini": "Gemini Plan",
+    "plan:charm-opencode": "Charm Opencode Plan",
+    "plan:sst-opencode": "SST Opencode Plan"
 };
 
+export const EXECUTOR_CAPABILITIES: Record<string, ExecutorCapabilities> = {
+    "echo": {"executor_type":"echo","label":"Echo (Test Mode)","follow_up":false,"streaming":false,"send_input":false,"plan_mode":"unsupported","image_input":false,"mcp":null,"required_binaries":[]},
+    "claude": {"executor_type":"claude","label":"Claude","follow_up":true,"streaming":true,"send_input":true,"plan_mode":"native","image_input":true,"mcp":{"config_path":null,"attribute_path":["mcpServers"],"format":"nested"},"required_binaries":["npx"]},
+    "claude-plan": {"executor_type":"claude-plan","label":"Claude Plan","follow_up":true,"streaming":true,"send_input":false,"plan_mode":"native","image_input":true,"mcp":{"config_path":null,"attribute_path":["mcpServers"],"format":"nested"},"required_binaries":["npx"]},
+    "amp": {"executor_type":"amp","label":"Amp","follow_up":true,"streaming":true,"send_input":false,"plan_mode":"scaffolded","image_input":false,"mcp":{"config_path":null,"attribute_path":["amp","mcpServers"],"format":"dotted_key"},"required_binaries":["npx"]},
+    "gemini": {"executor_type":"gemini","label":"Gemini","follow_up":true,"streaming":true,"send_input":false,"plan_mode":"scaffolded","image_input":true,"mcp":{"config_path":null,"attribute_path":["mcpServers"],"format":"nested"},"required_binaries":["npx"]},
+    "charm-opencode": {"executor_type":"charm-opencode","label":"Charm Opencode","follow_up":true,"streaming":true,"send_input":false,"plan_mode":"scaffolded","image_input":false,"mcp":{"config_path":null,"attribute_path":["mcpServers"],"format":"nested"},"required_binaries":["opencode"]},
+    "claude-code-router": {"executor_type":"claude-code-router","label":"Claude Code Router","follow_up":true,"streaming":true,"send_input":false,"plan_mode":"unsupported","image_input":true,"mcp":{"config_path":null,"attribute_path":["mcpServers"],"format":"nested"},"required_binaries":["npx"]},
+    "sst-opencode": {"executor_type":"sst-opencode","label":"SST Opencode","follow_up":true,"streaming":true,"send_input":false,"plan_mode":"scaffolded","image_input":false,"mcp":{"config_path":null,"attribute_path":["mcp"],"format":"nested"},"required_binaries":["npx"]},
+    "plan:amp": {"executor_type":"plan:amp","label":"Amp Plan","follow_up":true,"streaming":true,"send_input":false,"plan_mode":"scaffolded","image_input":false,"mcp":{"config_path":null,"attribute_path":["amp","mcpServers"],"format":"dotted_key"},"required_binaries":["npx"]},
+    "plan:gemini": {"executor_type":"plan:gemini","label":"Gemini Plan","follow_up":true,"streaming":true,"send_input":false,"plan_mode":"scaffolded","image_input":true,"mcp":{"config_path":null,"attribute_path":["mcpServers"],"format":"nested"},"required_binaries":["npx"]},
+    "plan:charm-opencode": {"executor_type":"plan:charm-opencode","label":"Charm Opencode Plan","follow_up":true,"streaming":true,"send_input":false,"plan_mode":"scaffolded","image_input":false,"mcp":{"config_path":null,"attribute_path":["mcpServers"],"format":"nested"},"required_binaries":["opencode"]},
+    "plan:sst-opencode": {"executor_type":"plan:sst-opencode","label":"SST Opencode Plan","follow_up":true,"streaming":true,"send_input":false,"plan_mode":"scaffolded","image_input":false,"mcp":{"config_path":null,"attribute_path":["mcp"],"format":"nested"},"required_binaries":["npx"]}
+};
+
+export const EDITOR_TYPES: EditorType[] = [
+    "vscode",
+    "cursor", 
+    "windsurf",
+    "intellij",
+    "zed",
+    "custom"
+];
+
 export const EDITOR_LABELS: Record<string, string> = {
     "vscode": "VS Code",
     "cursor": "Cursor",
@@ -177,4 +619,8 @@ export const SOUND_LABELS: Record<string, string> = {
     "cow-mooing": "Cow Mooing",
     "phone-vibration": "Phone Vibration",
     "rooster": "Rooster Call"
-};
\ No newline at end of file
+};
+
+export const DEFAULT_PROMPT_TEMPLATE: string = "project_id: {{project_id}}\n\nTask title: {{task_title}}\n{{#task_description}}\nTask description: {{task_description}}\n{{/task_description}}\n{{#linked_files}}\n\nFiles referenced by the task:\n{{linked_files}}\n{{/linked_files}}\n{{#project_instructions}}\n\n{{project_instructions}}\n{{/project_instructions}}\n";
+
+export const PROMPT_TEMPLATE_VARIABLES: string[] = ["project_id","task_id","task_title","task_description","parent_task","branch","base_branch","project_instructions","linked_files"];
\ No newline at end of file